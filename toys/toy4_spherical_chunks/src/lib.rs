@@ -196,6 +196,16 @@ impl Chunk {
 
 // === Camera System ===
 
+/// Angular velocity (rad/s) that covers `speed_m_s` along an orbit of `radius` (v = r·ω)
+pub fn angular_velocity_for_speed(radius: f32, speed_m_s: f32) -> f32 {
+    speed_m_s / radius
+}
+
+/// Linear speed (m/s) along an orbit of `radius` at `angular_velocity` (v = r·ω)
+pub fn speed_for_angular_velocity(radius: f32, angular_velocity: f32) -> f32 {
+    angular_velocity * radius
+}
+
 pub struct OrbitCamera {
     pub altitude: f32,
     pub angular_pos: f32,
@@ -205,13 +215,10 @@ pub struct OrbitCamera {
 
 impl OrbitCamera {
     pub fn new(altitude: f32, speed_m_s: f32) -> Self {
-        let r = PLANET_RADIUS + altitude;
-        let angular_velocity = speed_m_s / r;
-
         Self {
             altitude,
             angular_pos: 0.0,
-            angular_velocity,
+            angular_velocity: angular_velocity_for_speed(PLANET_RADIUS + altitude, speed_m_s),
             time: 0.0,
         }
     }
@@ -230,6 +237,23 @@ impl OrbitCamera {
         self.time += dt;
     }
 
+    /// Distance from the planet center (m)
+    pub fn radius(&self) -> f32 {
+        PLANET_RADIUS + self.altitude
+    }
+
+    /// Tangential speed over the surface at the current altitude (m/s)
+    pub fn linear_speed(&self) -> f32 {
+        speed_for_angular_velocity(self.radius(), self.angular_velocity)
+    }
+
+    /// Move to `altitude`, rescaling angular velocity so the linear speed stays the same
+    pub fn set_altitude(&mut self, altitude: f32) {
+        let speed = self.linear_speed();
+        self.altitude = altitude;
+        self.angular_velocity = angular_velocity_for_speed(self.radius(), speed);
+    }
+
     pub fn position(&self) -> glam::Vec3 {
         let r = PLANET_RADIUS + self.altitude;
 
@@ -415,3 +439,40 @@ pub fn generate_grid_indices(grid_size: u32) -> Vec<u32> {
 
     indices
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speed_and_angular_velocity_round_trip() {
+        // 100 m/s on a 1 km orbit: 0.1 rad/s
+        assert_eq!(angular_velocity_for_speed(1_000.0, 100.0), 0.1);
+        assert_eq!(speed_for_angular_velocity(1_000.0, 0.1), 100.0);
+
+        let radius = PLANET_RADIUS + DEFAULT_ALTITUDE;
+        let omega = angular_velocity_for_speed(radius, DEFAULT_SPEED);
+
+        let speed = speed_for_angular_velocity(radius, omega);
+        assert!((speed - DEFAULT_SPEED).abs() <= DEFAULT_SPEED * f32::EPSILON);
+    }
+
+    #[test]
+    fn test_set_altitude_preserves_linear_speed() {
+        let mut camera = OrbitCamera::new(DEFAULT_ALTITUDE, DEFAULT_SPEED);
+        let speed = camera.linear_speed();
+
+        // Climb and dive, including a clamped-to-surface altitude
+        for altitude in [130.0, 1.0, 5_000.0, 30.0, 250_000.0] {
+            camera.set_altitude(altitude);
+            assert_eq!(camera.altitude, altitude);
+            assert!(
+                (camera.linear_speed() - speed).abs() <= speed * f32::EPSILON,
+                "Altitude {}: {} m/s (was {} m/s)",
+                altitude,
+                camera.linear_speed(),
+                speed
+            );
+        }
+    }
+}
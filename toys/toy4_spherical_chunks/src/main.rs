@@ -26,13 +26,10 @@ struct OrbitCamera {
 
 impl OrbitCamera {
     fn new(altitude: f32, speed_m_s: f32) -> Self {
-        let r = PLANET_RADIUS + altitude;
-        let angular_velocity = speed_m_s / r;
-
         Self {
             altitude,
             angular_pos: 0.0,
-            angular_velocity,
+            angular_velocity: angular_velocity_for_speed(PLANET_RADIUS + altitude, speed_m_s),
             paused: false,
             time: 0.0,
         }
@@ -61,9 +58,11 @@ impl OrbitCamera {
     }
 
     fn adjust_altitude(&mut self, delta: f32) {
-        self.altitude = (self.altitude + delta).max(1.0);
-        let linear_speed = self.angular_velocity * (PLANET_RADIUS + self.altitude - delta);
-        self.angular_velocity = linear_speed / (PLANET_RADIUS + self.altitude);
+        // Keep linear speed (the old radius is read before the clamped altitude is applied)
+        let mut camera = self.as_lib_camera();
+        camera.set_altitude((self.altitude + delta).max(1.0));
+        self.altitude = camera.altitude;
+        self.angular_velocity = camera.angular_velocity;
         println!(
             "Altitude: {:.1}m (radius: {:.1}m)",
             self.altitude,
@@ -72,9 +71,10 @@ impl OrbitCamera {
     }
 
     fn adjust_speed(&mut self, delta_m_s: f32) {
-        let current_speed = self.angular_velocity * (PLANET_RADIUS + self.altitude);
+        let radius = PLANET_RADIUS + self.altitude;
+        let current_speed = speed_for_angular_velocity(radius, self.angular_velocity);
         let new_speed = (current_speed + delta_m_s).max(1.0);
-        self.angular_velocity = new_speed / (PLANET_RADIUS + self.altitude);
+        self.angular_velocity = angular_velocity_for_speed(radius, new_speed);
         println!("Orbital speed: {:.1} m/s", new_speed);
     }
 }
@@ -250,8 +250,10 @@ async fn render_frame(time: f32, chunk_size: u32) {
     queue.submit(std::iter::once(encoder.finish()));
 
     // Read back pixels
-    let bytes_per_row = WIDTH * 4;
+    // Rows in the copy must be padded to COPY_BYTES_PER_ROW_ALIGNMENT
     let unpadded_bytes_per_row = WIDTH * 4;
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let bytes_per_row = unpadded_bytes_per_row.div_ceil(align) * align;
 
     let output_buffer = device.create_buffer(&wgpu::BufferDescriptor {
         label: Some("Output Buffer"),
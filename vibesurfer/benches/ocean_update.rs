@@ -0,0 +1,69 @@
+//! `OceanGrid::update` throughput across grid sizes, reported as vertices per second.
+//!
+//! Run with `cargo bench -p vibesurfer --bench ocean_update`. Seed and audio-modulated detail
+//! parameters are fixed, and the camera flies forward each update so wrapped rows recompute
+//! their base terrain like in flight. Each size also checks every height stays finite, so the
+//! bench doubles as a smoke test.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use glam::Vec3;
+use vibesurfer::ocean::OceanGrid;
+use vibesurfer::params::OceanPhysics;
+
+/// Vertices per side
+const GRID_SIZES: [usize; 4] = [128, 256, 512, 1024];
+
+/// Detail layer as modulated by moderate bass and mids
+const DETAIL_AMPLITUDE_M: f32 = 4.0;
+const DETAIL_FREQUENCY: f32 = 0.3;
+
+/// Simulated frame rate and camera speed (one update per frame)
+const FPS: f32 = 60.0;
+const CAMERA_SPEED_M_PER_S: f32 = 100.0;
+
+fn bench_ocean_update(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ocean_grid_update");
+    group.sample_size(10); // A 1024² update takes hundreds of milliseconds
+
+    for grid_size in GRID_SIZES {
+        let physics = OceanPhysics {
+            grid_size,
+            noise_seed: 42,
+            ..OceanPhysics::default()
+        };
+        let mut grid = OceanGrid::new(&physics);
+        let mut frame = 0u32;
+
+        group.throughput(Throughput::Elements((grid_size * grid_size) as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(grid_size),
+            &physics,
+            |b, physics| {
+                b.iter(|| {
+                    frame += 1;
+                    let time_s = frame as f32 / FPS;
+                    let camera_pos = Vec3::new(0.0, 101.0, time_s * CAMERA_SPEED_M_PER_S);
+                    grid.update(
+                        time_s,
+                        DETAIL_AMPLITUDE_M,
+                        DETAIL_FREQUENCY,
+                        camera_pos,
+                        physics,
+                    );
+                })
+            },
+        );
+
+        assert!(
+            grid.vertices.iter().all(|v| v.position[1].is_finite()),
+            "Non-finite heights after {} updates of a {}² grid",
+            frame,
+            grid_size
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_ocean_update);
+criterion_main!(benches);
@@ -0,0 +1,19 @@
+// Motion blur: sub-frames summed into an HDR target (weights come from the blend constant),
+// then the sum written to the output. Both passes draw a fullscreen triangle reading one texel
+// per pixel.
+
+@group(0) @binding(0)
+var source: texture_2d<f32>;
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    // Fullscreen triangle
+    let x = f32((vertex_index << 1u) & 2u);
+    let y = f32(vertex_index & 2u);
+    return vec4<f32>(x * 2.0 - 1.0, y * 2.0 - 1.0, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    return textureLoad(source, vec2<i32>(position.xy), 0);
+}
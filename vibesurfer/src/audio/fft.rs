@@ -1,70 +1,325 @@
 //! FFT analysis thread and utilities.
 
-use rustfft::{num_complex::Complex, FftPlanner};
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+use std::collections::VecDeque;
 use std::f32::consts::PI;
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crate::ocean::AudioBands;
 use crate::params::FFTConfig;
 
-/// Spawn FFT analysis thread
+/// Windowed FFT that reduces a block of samples to bass/mid/high band energies
+pub struct BandAnalyzer {
+    config: FFTConfig,
+    fft: Arc<dyn Fft<f32>>,
+    buffer: Vec<Complex<f32>>,
+    /// Bin magnitudes of the last analysis (bins 0..fft_size/2)
+    spectrum: Vec<f32>,
+}
+
+impl BandAnalyzer {
+    pub fn new(config: FFTConfig) -> Self {
+        let fft = FftPlanner::new().plan_fft_forward(config.fft_size);
+        let buffer = vec![Complex::new(0.0, 0.0); config.fft_size];
+        let spectrum = vec![0.0; config.fft_size / 2];
+        Self {
+            config,
+            fft,
+            buffer,
+            spectrum,
+        }
+    }
+
+    /// Samples per analysis window
+    pub fn fft_size(&self) -> usize {
+        self.config.fft_size
+    }
+
+    /// Bin magnitudes of the last analyzed window (bins 0..fft_size/2; zeros before the first)
+    pub fn spectrum(&self) -> &[f32] {
+        &self.spectrum
+    }
+
+    /// Band energies of the first `fft_size` samples (None if there are fewer)
+    pub fn analyze(&mut self, samples: &[f32]) -> Option<AudioBands> {
+        let fft_size = self.config.fft_size;
+        if samples.len() < fft_size {
+            return None;
+        }
+
+        // Remove the DC offset (it would fill bin 0 and leak into the bass band), then
+        // apply the Hann window
+        let samples = &samples[..fft_size];
+        let mean = samples.iter().sum::<f32>() / fft_size as f32;
+        for (i, (bin, &sample)) in self.buffer.iter_mut().zip(samples).enumerate() {
+            *bin = Complex::new((sample - mean) * hann_window(i, fft_size), 0.0);
+        }
+
+        // Perform FFT
+        self.fft.process(&mut self.buffer);
+        for (magnitude, bin) in self.spectrum.iter_mut().zip(&self.buffer) {
+            *magnitude = bin.norm();
+        }
+
+        // Extract frequency bands (mean magnitude), noise-gated by the floor
+        let band = |bins: Range<usize>| {
+            let mean = self.buffer[bins.clone()]
+                .iter()
+                .map(|c| c.norm())
+                .sum::<f32>()
+                / bins.len() as f32;
+            (mean - self.config.gate_floor).max(0.0)
+        };
+
+        Some(AudioBands {
+            low: band(self.config.bass_bins()),
+            mid: band(self.config.mid_bins()),
+            high: band(self.config.high_bins()),
+        })
+    }
+}
+
+/// Automatic gain control: divides each band by a slowly decaying running peak
+///
+/// Peaks jump up to any louder value and otherwise decay as exp(-rate × t), so each
+/// passage normalizes toward 1 at its own loudest point. Output is clamped to 0..1.
+pub struct BandNormalizer {
+    decay_per_s: f32,
+    left_peak: AudioBands,
+    right_peak: AudioBands,
+}
+
+impl BandNormalizer {
+    pub fn new(decay_per_s: f32) -> Self {
+        Self {
+            decay_per_s,
+            left_peak: AudioBands::default(),
+            right_peak: AudioBands::default(),
+        }
+    }
+
+    /// Normalize both channels, `dt_s` seconds after the previous call
+    pub fn normalize(&mut self, bands: StereoBands, dt_s: f32) -> StereoBands {
+        let decay = (-self.decay_per_s * dt_s).exp();
+        StereoBands {
+            left: normalize_channel(&mut self.left_peak, bands.left, decay),
+            right: normalize_channel(&mut self.right_peak, bands.right, decay),
+            width: bands.width,
+        }
+    }
+}
+
+/// Update one channel's running peaks and scale its bands by them
+fn normalize_channel(peak: &mut AudioBands, bands: AudioBands, decay: f32) -> AudioBands {
+    let band = |peak: &mut f32, value: f32| {
+        *peak = (*peak * decay).max(value);
+        if *peak > f32::EPSILON {
+            (value / *peak).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    };
+    AudioBands {
+        low: band(&mut peak.low, bands.low),
+        mid: band(&mut peak.mid, bands.mid),
+        high: band(&mut peak.high, bands.high),
+    }
+}
+
+/// Band energies per channel plus stereo width
+///
+/// In mono analysis both sides hold the left-channel bands and `width` is 0.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StereoBands {
+    pub left: AudioBands,
+    pub right: AudioBands,
+    /// Side (L − R) share of total energy: 0 = mono, 0.5 = hard-panned, 1 = out of phase
+    pub width: f32,
+}
+
+/// How far back `BandHistory` keeps analyzed bands
+pub const BAND_HISTORY: Duration = Duration::from_secs(2);
+
+/// Recent analysis results with the time each was produced, oldest first
+///
+/// Lets the visuals sample the bands from a moment ago (`FFTConfig::visual_latency_ms`).
+#[derive(Clone, Debug, Default)]
+pub struct BandHistory {
+    samples: VecDeque<(Instant, StereoBands)>,
+}
+
+impl BandHistory {
+    /// Record bands analyzed at `at`, forgetting samples older than `BAND_HISTORY`
+    pub fn push(&mut self, at: Instant, bands: StereoBands) {
+        self.samples.push_back((at, bands));
+        while let Some(&(oldest, _)) = self.samples.front() {
+            if at.saturating_duration_since(oldest) <= BAND_HISTORY {
+                break;
+            }
+            self.samples.pop_front();
+        }
+    }
+
+    /// Most recent bands (silence before the first analysis)
+    pub fn latest(&self) -> StereoBands {
+        self.samples.back().map(|&(_, b)| b).unwrap_or_default()
+    }
+
+    /// Bands that were current at `at`: the newest sample not after it
+    ///
+    /// Times before the oldest kept sample get the oldest one.
+    pub fn at(&self, at: Instant) -> StereoBands {
+        self.samples
+            .iter()
+            .rev()
+            .find(|&&(time, _)| time <= at)
+            .or(self.samples.front())
+            .map(|&(_, b)| b)
+            .unwrap_or_default()
+    }
+}
+
+/// Mono mix of a stereo sample (what mono mode analyzes)
+pub fn downmix(left: f32, right: f32) -> f32 {
+    (left + right) * 0.5
+}
+
+/// Samples waiting for FFT analysis
+///
+/// In stereo mode `left` and `right` hold the channels; otherwise `left` holds the mono
+/// downmix and `right` stays empty.
+pub struct FftBuffer {
+    pub left: Vec<f32>,
+    pub right: Vec<f32>,
+    stereo: bool,
+    /// Most samples held per channel (unbounded unless set via `with_cap`)
+    cap: usize,
+}
+
+impl FftBuffer {
+    pub fn new(stereo: bool) -> Self {
+        Self {
+            left: Vec::new(),
+            right: Vec::new(),
+            stereo,
+            cap: usize::MAX,
+        }
+    }
+
+    /// Bound the buffer to `cap` samples per channel (see `FFTConfig::fft_buffer_cap`)
+    pub fn with_cap(mut self, cap: usize) -> Self {
+        self.cap = cap.max(1);
+        self
+    }
+
+    /// Accumulate one stereo sample
+    ///
+    /// At the cap, the oldest quarter of the buffer is dropped first (a stalled FFT thread
+    /// then costs bounded memory and latency, without shifting the buffer every sample).
+    pub fn push(&mut self, left: f32, right: f32) {
+        if self.left.len() >= self.cap {
+            self.drain((self.cap / 4).max(1));
+        }
+        if self.stereo {
+            self.left.push(left);
+            self.right.push(right);
+        } else {
+            self.left.push(downmix(left, right));
+        }
+    }
+
+    /// Drop the oldest `count` samples from both channels
+    pub fn drain(&mut self, count: usize) {
+        self.left.drain(..count.min(self.left.len()));
+        self.right.drain(..count.min(self.right.len()));
+    }
+}
+
+/// Analyze the first FFT window of each channel (None if the left channel is short)
+///
+/// With an empty `right` (mono mode) both sides get the `left` (downmix) bands and width 0.
+/// The left channel is analyzed last, so `BandAnalyzer::spectrum` is the left spectrum.
+pub fn analyze_stereo(
+    analyzer: &mut BandAnalyzer,
+    left: &[f32],
+    right: &[f32],
+) -> Option<StereoBands> {
+    if left.len() < analyzer.fft_size() {
+        return None;
+    }
+    let right_bands = analyzer.analyze(right);
+    let left_bands = analyzer.analyze(left)?;
+    let Some(right_bands) = right_bands else {
+        return Some(StereoBands {
+            left: left_bands,
+            right: left_bands,
+            width: 0.0,
+        });
+    };
+
+    let fft_size = analyzer.fft_size();
+    Some(StereoBands {
+        left: left_bands,
+        right: right_bands,
+        width: stereo_width(&left[..fft_size], &right[..fft_size]),
+    })
+}
+
+/// Side energy over total (mid + side) energy of a stereo block (0 when silent)
+pub fn stereo_width(left: &[f32], right: &[f32]) -> f32 {
+    let (mid, side) = left
+        .iter()
+        .zip(right)
+        .fold((0.0, 0.0), |(mid, side), (l, r)| {
+            let (m, s) = ((l + r) * 0.5, (l - r) * 0.5);
+            (mid + m * m, side + s * s)
+        });
+    if mid + side > 0.0 {
+        side / (mid + side)
+    } else {
+        0.0
+    }
+}
+
+/// Spawn FFT analysis thread (runs until `running` is cleared)
 pub fn spawn_fft_thread(
     config: FFTConfig,
-    fft_buffer: Arc<Mutex<Vec<f32>>>,
-    audio_bands: Arc<Mutex<AudioBands>>,
+    fft_buffer: Arc<Mutex<FftBuffer>>,
+    band_history: Arc<Mutex<BandHistory>>,
+    spectrum: Arc<Mutex<Vec<f32>>>,
+    running: Arc<AtomicBool>,
 ) -> thread::JoinHandle<()> {
     thread::spawn(move || {
-        let mut planner = FftPlanner::new();
-        let fft = planner.plan_fft_forward(config.fft_size);
-        let mut fft_input = vec![Complex::new(0.0, 0.0); config.fft_size];
-        let mut fft_output = vec![Complex::new(0.0, 0.0); config.fft_size];
+        let update_interval = Duration::from_millis(config.update_interval_ms);
+        let mut normalizer = config
+            .normalize
+            .then(|| BandNormalizer::new(config.normalize_decay_per_s));
+        let hop_size = config.hop_size();
+        let mut analyzer = BandAnalyzer::new(config);
 
-        loop {
-            thread::sleep(Duration::from_millis(config.update_interval_ms));
+        while running.load(Ordering::Relaxed) {
+            thread::sleep(update_interval);
 
             let mut fft_buf = fft_buffer.lock().unwrap();
 
-            if fft_buf.len() >= config.fft_size {
-                // Apply Hann window
-                for i in 0..config.fft_size {
-                    let window = hann_window(i, config.fft_size);
-                    fft_input[i] = Complex::new(fft_buf[i] * window, 0.0);
+            if let Some(mut bands) = analyze_stereo(&mut analyzer, &fft_buf.left, &fft_buf.right) {
+                if let Some(ref mut normalizer) = normalizer {
+                    bands = normalizer.normalize(bands, update_interval.as_secs_f32());
                 }
 
-                // Perform FFT
-                fft_output.copy_from_slice(&fft_input);
-                fft.process(&mut fft_output);
-
-                // Extract frequency bands with normalization
-                let bass_bins = config.bass_bins();
-                let mid_bins = config.mid_bins();
-                let high_bins = config.high_bins();
-
-                let low: f32 = fft_output[bass_bins.clone()]
-                    .iter()
-                    .map(|c| c.norm())
-                    .sum::<f32>()
-                    / bass_bins.len() as f32;
-
-                let mid: f32 = fft_output[mid_bins.clone()]
-                    .iter()
-                    .map(|c| c.norm())
-                    .sum::<f32>()
-                    / mid_bins.len() as f32;
-
-                let high: f32 = fft_output[high_bins.clone()]
-                    .iter()
-                    .map(|c| c.norm())
-                    .sum::<f32>()
-                    / high_bins.len() as f32;
-
                 // Update shared bands
-                *audio_bands.lock().unwrap() = AudioBands { low, mid, high };
+                band_history.lock().unwrap().push(Instant::now(), bands);
+                spectrum
+                    .lock()
+                    .unwrap()
+                    .clone_from_slice(analyzer.spectrum());
 
-                // 50% overlap (drain half the buffer)
-                fft_buf.drain(0..config.fft_size / 2);
+                // Advance one hop (the overlap stays for the next window)
+                fft_buf.drain(hop_size);
             }
         }
     })
@@ -79,6 +334,201 @@ pub fn hann_window(index: usize, size: usize) -> f32 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_band_analyzer_finds_tone_band() {
+        let config = FFTConfig::default();
+        let sample_rate = config.sample_rate_hz as f32;
+        let mut analyzer = BandAnalyzer::new(config.clone());
+        assert!(analyzer.analyze(&[0.0; 16]).is_none());
+
+        // 100 Hz sine: bass dominates
+        let tone: Vec<f32> = (0..config.fft_size)
+            .map(|i| (2.0 * PI * 100.0 * i as f32 / sample_rate).sin())
+            .collect();
+        let bands = analyzer.analyze(&tone).unwrap();
+        assert!(
+            bands.low > bands.mid * 5.0 && bands.low > bands.high * 5.0,
+            "{:?}",
+            bands
+        );
+    }
+
+    #[test]
+    fn test_gate_floor_zeroes_quiet_bands_and_offsets_loud_ones() {
+        let config = FFTConfig::default();
+        let sample_rate = config.sample_rate_hz as f32;
+
+        // 100 Hz + a quiet 2 kHz: loud bass, weak mid and high
+        let samples: Vec<f32> = (0..config.fft_size)
+            .map(|i| {
+                let t = i as f32 / sample_rate;
+                (2.0 * PI * 100.0 * t).sin() + 0.1 * (2.0 * PI * 2000.0 * t).sin()
+            })
+            .collect();
+        let gated = |gate_floor| {
+            BandAnalyzer::new(FFTConfig {
+                gate_floor,
+                ..config.clone()
+            })
+            .analyze(&samples)
+            .unwrap()
+        };
+        let raw = gated(0.0);
+        let quiet = raw.mid.max(raw.high);
+        assert!(raw.low > quiet, "{:?}", raw);
+
+        // Floor above every band: silence
+        let silent = gated(raw.low * 1.01);
+        assert_eq!((silent.low, silent.mid, silent.high), (0.0, 0.0, 0.0));
+
+        // Floor between: louder bands pass reduced by it, quieter ones are zero
+        let floor = (quiet + raw.low) / 2.0;
+        let bands = gated(floor);
+        assert!((bands.low - (raw.low - floor)).abs() < 1e-5, "{:?}", bands);
+        assert_eq!((bands.mid, bands.high), (0.0, 0.0));
+
+        assert!(FFTConfig {
+            gate_floor: -0.1,
+            ..FFTConfig::default()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_hard_panned_tone_is_one_sided() {
+        let config = FFTConfig {
+            stereo: true,
+            ..FFTConfig::default()
+        };
+        let sample_rate = config.sample_rate_hz as f32;
+        let mut analyzer = BandAnalyzer::new(config.clone());
+
+        // 100 Hz + 2 kHz on the left, silence on the right
+        let left: Vec<f32> = (0..config.fft_size)
+            .map(|i| {
+                let t = i as f32 / sample_rate;
+                (2.0 * PI * 100.0 * t).sin() + 0.5 * (2.0 * PI * 2000.0 * t).sin()
+            })
+            .collect();
+        let right = vec![0.0; config.fft_size];
+
+        let bands = analyze_stereo(&mut analyzer, &left, &right).unwrap();
+        assert!(
+            bands.left.low > 1.0 && bands.left.high > 0.1,
+            "{:?}",
+            bands.left
+        );
+        assert_eq!(
+            (bands.right.low, bands.right.mid, bands.right.high),
+            (0.0, 0.0, 0.0)
+        );
+        assert!((bands.width - 0.5).abs() < 1e-5, "Width {}", bands.width);
+
+        // Swapped channels mirror the result
+        let swapped = analyze_stereo(&mut analyzer, &right, &left).unwrap();
+        assert_eq!(swapped.right.low, bands.left.low);
+        assert_eq!(swapped.left.low, 0.0);
+
+        // Identical channels: no width; mono analysis copies the left bands
+        let centered = analyze_stereo(&mut analyzer, &left, &left).unwrap();
+        assert_eq!(centered.width, 0.0);
+        let mono = analyze_stereo(&mut analyzer, &left, &[]).unwrap();
+        assert_eq!(mono.right.low, mono.left.low);
+        assert_eq!(mono.width, 0.0);
+    }
+
+    #[test]
+    fn test_spectrum_peaks_at_tone_bin() {
+        let config = FFTConfig::default();
+        let sample_rate = config.sample_rate_hz as f32;
+        let mut analyzer = BandAnalyzer::new(config.clone());
+        assert_eq!(analyzer.spectrum(), vec![0.0; config.fft_size / 2]);
+
+        // 2 kHz sine on the left, silence on the right
+        let left: Vec<f32> = (0..config.fft_size)
+            .map(|i| (2.0 * PI * 2000.0 * i as f32 / sample_rate).sin())
+            .collect();
+        let right = vec![0.0; config.fft_size];
+        analyze_stereo(&mut analyzer, &left, &right).unwrap();
+
+        // The left channel's spectrum is kept, peaking at the tone's bin
+        let spectrum = analyzer.spectrum();
+        assert_eq!(spectrum.len(), config.fft_size / 2);
+        let peak = (0..spectrum.len())
+            .max_by(|&a, &b| spectrum[a].total_cmp(&spectrum[b]))
+            .unwrap();
+        assert_eq!(peak, config.hz_to_bin(2000.0));
+    }
+
+    #[test]
+    fn test_dc_offset_is_removed_before_transform() {
+        let config = FFTConfig::default();
+        let mut analyzer = BandAnalyzer::new(config.clone());
+
+        // Sine centered on bin 48 (~2 kHz), riding on a DC offset ten times its amplitude
+        let cycles_per_sample = 48.0 / config.fft_size as f32;
+        let samples: Vec<f32> = (0..config.fft_size)
+            .map(|i| 10.0 + (2.0 * PI * cycles_per_sample * i as f32).sin())
+            .collect();
+        let bands = analyzer.analyze(&samples).unwrap();
+
+        let spectrum = analyzer.spectrum();
+        let peak = spectrum[48];
+        assert!(spectrum[0] < peak * 1e-3, "{} vs {}", spectrum[0], peak);
+        assert!(bands.low < bands.high, "{:?}", bands);
+    }
+
+    #[test]
+    fn test_mono_mode_buffers_the_downmix() {
+        let mut mono = FftBuffer::new(false);
+        mono.push(1.0, 0.0);
+        mono.push(0.25, 0.75);
+        assert_eq!(mono.left, vec![0.5, 0.5]);
+        assert!(mono.right.is_empty());
+
+        let mut stereo = FftBuffer::new(true);
+        stereo.push(1.0, 0.0);
+        assert_eq!((stereo.left[0], stereo.right[0]), (1.0, 0.0));
+    }
+
+    #[test]
+    fn test_normalizer_tracks_loud_and_quiet_peaks() {
+        let config = FFTConfig::default();
+        let sample_rate = config.sample_rate_hz as f32;
+        let mut analyzer = BandAnalyzer::new(config.clone());
+        let mut normalizer = BandNormalizer::new(config.normalize_decay_per_s);
+        let dt_s = config.update_interval_ms as f32 / 1000.0;
+
+        let tone = |amplitude: f32| -> Vec<f32> {
+            (0..config.fft_size)
+                .map(|i| amplitude * (2.0 * PI * 100.0 * i as f32 / sample_rate).sin())
+                .collect()
+        };
+        let loud = tone(1.0);
+        let quiet = tone(0.01);
+        let mut step = |samples: &[f32]| {
+            let bands = analyze_stereo(&mut analyzer, samples, &[]).unwrap();
+            normalizer.normalize(bands, dt_s)
+        };
+
+        let loud_bands = step(&loud);
+        assert!((loud_bands.left.low - 1.0).abs() < 1e-5, "{:?}", loud_bands);
+
+        // Right after the loud passage, the quiet one sits far below the held peak
+        let first_quiet = step(&quiet);
+        assert!(first_quiet.left.low < 0.05, "{:?}", first_quiet);
+
+        // The peak decays until the quiet passage fills the range again
+        let seconds = 15.0;
+        let mut last = first_quiet;
+        for _ in 0..(seconds / dt_s) as usize {
+            last = step(&quiet);
+            assert!(last.left.low <= 1.0);
+        }
+        assert!((last.left.low - 1.0).abs() < 1e-5, "{:?}", last);
+    }
+
     #[test]
     fn test_hann_window() {
         let size = 1024;
@@ -88,4 +538,88 @@ mod tests {
         assert!((hann_window(size - 1, size) - 0.0).abs() < 0.01);
         assert!((hann_window(size / 2, size) - 1.0).abs() < 0.01);
     }
+
+    #[test]
+    fn test_buffer_cap_bounds_a_stalled_buffer() {
+        let config = FFTConfig::default();
+        let cap = config.fft_buffer_cap();
+        assert_eq!(cap, config.fft_size * 4);
+
+        // Nothing drains (stalled FFT thread): stays within the cap, keeping the newest
+        let mut buffer = FftBuffer::new(true).with_cap(cap);
+        let pushed = cap * 20 + 7;
+        for i in 0..pushed {
+            buffer.push(i as f32, -(i as f32));
+            assert!(buffer.left.len() <= cap);
+        }
+        assert_eq!(buffer.left.len(), buffer.right.len());
+        assert!(buffer.left.len() >= config.fft_size);
+        assert_eq!(*buffer.left.last().unwrap(), (pushed - 1) as f32);
+    }
+
+    #[test]
+    fn test_overlap_sets_samples_drained_per_hop() {
+        let hop = |overlap| {
+            let config = FFTConfig {
+                overlap,
+                ..FFTConfig::default()
+            };
+            config.validate().unwrap();
+            let mut buffer = FftBuffer::new(true);
+            for i in 0..config.fft_size {
+                buffer.push(i as f32, -(i as f32));
+            }
+            buffer.drain(config.hop_size());
+            config.fft_size - buffer.left.len()
+        };
+
+        assert_eq!(hop(0.75), 256);
+        assert_eq!(hop(0.5), 512);
+        assert_eq!(hop(0.0), 1024);
+
+        let full = FFTConfig {
+            overlap: 1.0,
+            ..FFTConfig::default()
+        };
+        assert!(full.validate().is_err());
+    }
+
+    #[test]
+    fn test_history_returns_older_bands_for_a_delay() {
+        let bands = |low| StereoBands {
+            left: AudioBands {
+                low,
+                ..AudioBands::default()
+            },
+            ..StereoBands::default()
+        };
+        let start = Instant::now();
+        let mut history = BandHistory::default();
+        assert_eq!(history.latest().left.low, 0.0);
+
+        // One analysis every 50 ms: low = 0, 1, 2, 3
+        for n in 0..4 {
+            history.push(start + Duration::from_millis(50 * n), bands(n as f32));
+        }
+        let now = start + Duration::from_millis(160);
+        assert_eq!(history.latest().left.low, 3.0);
+        assert_eq!(history.at(now).left.low, 3.0);
+
+        // 80 ms ago (t = 80 ms) the 50 ms analysis was the newest
+        assert_eq!(history.at(now - Duration::from_millis(80)).left.low, 1.0);
+        // Before the first analysis: the oldest sample
+        assert_eq!(history.at(start - Duration::from_millis(10)).left.low, 0.0);
+
+        // Samples older than BAND_HISTORY are dropped
+        history.push(start + BAND_HISTORY + Duration::from_millis(60), bands(9.0));
+        assert_eq!(history.at(start).left.low, 2.0);
+        // Visuals can lag the analysis but not lead it
+        for (visual_latency_ms, valid) in [(0.0, true), (120.0, true), (-50.0, false)] {
+            let config = FFTConfig {
+                visual_latency_ms,
+                ..FFTConfig::default()
+            };
+            assert_eq!(config.validate().is_ok(), valid, "{}", visual_latency_ms);
+        }
+    }
 }
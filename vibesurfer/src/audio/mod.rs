@@ -6,6 +6,9 @@
 mod fft;
 mod synthesis;
 mod system;
+mod wav;
 
 // Re-export public types
-pub use system::AudioSystem;
+pub use fft::StereoBands;
+pub use system::{AudioBackend, AudioSystem};
+pub use wav::{analyze_wav, BandTrack};
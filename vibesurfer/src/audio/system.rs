@@ -1,157 +1,702 @@
 //! Audio system managing synthesis and FFT analysis.
+//!
+//! Live mode plays the synthesis through the output device and analyzes it on a background
+//! FFT thread. Recording mode instead synthesizes audio offline, in lockstep with captured
+//! frames, so the WAV track and the audio bands line up exactly with simulated time; the
+//! device keeps playing the composition meanwhile, from its own engine.
 
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use glicol::Engine;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::{Duration, Instant};
 
-use super::fft::spawn_fft_thread;
+use super::fft::{
+    analyze_stereo, downmix, spawn_fft_thread, BandAnalyzer, BandHistory, BandNormalizer,
+    FftBuffer, StereoBands,
+};
 use super::synthesis::GLICOL_COMPOSITION;
 use crate::ocean::AudioBands;
-use crate::params::{audio_constants::BLOCK_SIZE, FFTConfig, RecordingConfig};
+use crate::params::{audio_constants::BLOCK_SIZE, FFTConfig, RecordingConfig, WavSampleFormat};
+
+type WavWriter = hound::WavWriter<std::io::BufWriter<std::fs::File>>;
+
+/// Audio synthesized frame by frame for a recording (the played audio is separate)
+struct OfflineAudio {
+    engine: Engine<BLOCK_SIZE>,
+    recording_config: RecordingConfig,
+    sample_rate_hz: usize,
+    /// Synthesized stereo samples not yet written (rest of the last block)
+    leftover: VecDeque<[f32; 2]>,
+    /// Most recent samples (up to one FFT window; the mono downmix unless stereo, and the
+    /// right channel only in stereo mode)
+    window: VecDeque<f32>,
+    window_right: VecDeque<f32>,
+    stereo: bool,
+    analyzer: BandAnalyzer,
+    /// Running-peak normalization (only with `FFTConfig::normalize`)
+    normalizer: Option<BandNormalizer>,
+    /// None once finalized
+    wav_writer: Option<WavWriter>,
+    samples_written: u64,
+    bands: StereoBands,
+}
+
+impl OfflineAudio {
+    /// Synthesize and record audio up to the start of `frame_num`, then analyze its tail
+    fn advance_to_frame(&mut self, frame_num: usize) -> StereoBands {
+        let target = self
+            .recording_config
+            .samples_until_frame(frame_num, self.sample_rate_hz);
+        if target <= self.samples_written || self.wav_writer.is_none() {
+            return self.bands;
+        }
+        let dt_s = (target - self.samples_written) as f32 / self.sample_rate_hz as f32;
+
+        while self.samples_written < target {
+            if self.leftover.is_empty() {
+                let (buffers, _) = self.engine.next_block(vec![]);
+                self.leftover
+                    .extend((0..BLOCK_SIZE).map(|i| [buffers[0][i], buffers[1][i]]));
+            }
+            let Some([left, right]) = self.leftover.pop_front() else {
+                break;
+            };
+
+            // Same safety limiter as live playback
+            let (left, right) = (left.clamp(-0.5, 0.5), right.clamp(-0.5, 0.5));
+            if let Some(ref mut writer) = self.wav_writer {
+                write_wav_frame(writer, left, right);
+            }
+
+            let fft_size = self.analyzer.fft_size();
+            self.window.push_back(if self.stereo {
+                left
+            } else {
+                downmix(left, right)
+            });
+            if self.window.len() > fft_size {
+                self.window.pop_front();
+            }
+            if self.stereo {
+                self.window_right.push_back(right);
+                if self.window_right.len() > fft_size {
+                    self.window_right.pop_front();
+                }
+            }
+            self.samples_written += 1;
+        }
+
+        if let Some(bands) = analyze_stereo(
+            &mut self.analyzer,
+            self.window.make_contiguous(),
+            self.window_right.make_contiguous(),
+        ) {
+            self.bands = match self.normalizer {
+                Some(ref mut normalizer) => normalizer.normalize(bands, dt_s),
+                None => bands,
+            };
+        }
+        self.bands
+    }
+
+    /// Pad the track to the full recording length and finalize the WAV file
+    fn finish(&mut self) -> Result<(), String> {
+        self.advance_to_frame(self.recording_config.total_frames());
+        self.finalize()
+    }
+
+    /// Finalize the WAV file with the audio written so far (no-op once finalized)
+    fn finalize(&mut self) -> Result<(), String> {
+        match self.wav_writer.take() {
+            Some(writer) => writer
+                .finalize()
+                .map_err(|e| format!("Failed to finalize WAV file: {}", e)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Live audio output backend
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AudioBackend {
+    /// Default output device (null backend if there is none)
+    #[default]
+    Device,
+    /// No output device: synthesis is paced by a timer thread (CI/headless)
+    Null,
+}
+
+/// How often the null backend synthesizes (roughly one device buffer)
+const NULL_AUDIO_PERIOD: Duration = Duration::from_millis(10);
+
+/// Timer thread running the engine in real time without an output device
+///
+/// Feeds the FFT buffer like the device callback, so bands behave as if audio were playing.
+struct NullAudio {
+    running: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl NullAudio {
+    fn start(
+        mut engine: Engine<BLOCK_SIZE>,
+        fft_buffer: Arc<Mutex<FftBuffer>>,
+        sample_rate_hz: usize,
+    ) -> Self {
+        let running = Arc::new(AtomicBool::new(true));
+        let running_thread = Arc::clone(&running);
+
+        let thread = thread::spawn(move || {
+            let start = Instant::now();
+            let mut frames_done = 0;
+            let mut data = Vec::new();
+
+            while running_thread.load(Ordering::Relaxed) {
+                thread::sleep(NULL_AUDIO_PERIOD);
+
+                // Whole blocks due by now (the rest waits for the next tick)
+                let frames_due = (start.elapsed().as_secs_f64() * sample_rate_hz as f64) as usize;
+                let frames = (frames_due - frames_done) / BLOCK_SIZE * BLOCK_SIZE;
+                data.resize(frames * 2, 0.0);
+                fill_output(&mut engine, &mut fft_buffer.lock().unwrap(), &mut data);
+                frames_done += frames;
+            }
+        });
+
+        Self {
+            running,
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for NullAudio {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
 
 /// Audio system managing synthesis and FFT analysis
 pub struct AudioSystem {
-    /// Shared FFT frequency bands (thread-safe)
-    audio_bands: Arc<Mutex<AudioBands>>,
+    /// Recent FFT frequency bands per channel, timestamped (thread-safe, live mode)
+    audio_bands: Arc<Mutex<BandHistory>>,
 
-    /// Audio output stream (kept alive)
-    _stream: cpal::Stream,
+    /// Latest FFT bin magnitudes of the left channel or mono downmix (thread-safe, live mode)
+    spectrum: Arc<Mutex<Vec<f32>>>,
 
-    /// FFT analysis thread handle (optional, for cleanup)
-    _fft_thread: Option<thread::JoinHandle<()>>,
+    /// How long ago the bands `bands_for_frame` returns live were analyzed (milliseconds)
+    visual_latency_ms: f32,
+
+    /// Audio output stream (kept alive; None without a device)
+    _stream: Option<cpal::Stream>,
+
+    /// Timer-driven synthesis when there is no output device (stopped on drop)
+    null_audio: Option<NullAudio>,
+
+    /// Frame-locked synthesis (recording mode only)
+    offline: Option<Mutex<OfflineAudio>>,
+
+    /// FFT analysis thread handle (live mode; joined on shutdown)
+    fft_thread: Option<thread::JoinHandle<()>>,
+
+    /// Cleared to stop the FFT thread
+    running: Arc<AtomicBool>,
 }
 
 impl AudioSystem {
     /// Create and start audio system with specified configuration
+    ///
+    /// With a recording config, audio is synthesized offline per frame (see `bands_for_frame`)
+    /// and written to the recording's WAV file, while a second engine plays the composition on
+    /// `backend` in real time (the null backend records silently). Otherwise it is played on
+    /// `backend` (falling back to the null backend when there is no output device).
     pub fn new(
         fft_config: FFTConfig,
         recording_config: Option<RecordingConfig>,
+        backend: AudioBackend,
     ) -> Result<Self, String> {
         // Validate FFT configuration
         fft_config
             .validate()
             .map_err(|e| format!("Invalid FFT config: {}", e))?;
 
-        // Create WAV writer if recording
-        let wav_writer: Option<Arc<Mutex<hound::WavWriter<std::io::BufWriter<std::fs::File>>>>> =
-            recording_config.as_ref().map(|config| {
-                let spec = hound::WavSpec {
-                    channels: 2,
-                    sample_rate: fft_config.sample_rate_hz as u32,
-                    bits_per_sample: 32,
-                    sample_format: hound::SampleFormat::Float,
-                };
-                let writer = hound::WavWriter::create(&config.audio_path(), spec)
-                    .expect("Failed to create WAV writer");
-                Arc::new(Mutex::new(writer))
+        let engine = create_engine(fft_config.sample_rate_hz)?;
+        let audio_bands = Arc::new(Mutex::new(BandHistory::default()));
+        let spectrum = Arc::new(Mutex::new(vec![0.0; fft_config.fft_size / 2]));
+        let visual_latency_ms = fft_config.visual_latency_ms;
+
+        if let Some(recording_config) = recording_config {
+            recording_config
+                .validate_audio_format()
+                .map_err(|e| format!("Invalid recording config: {}", e))?;
+            let spec = hound::WavSpec {
+                channels: recording_config.audio_channels,
+                sample_rate: fft_config.sample_rate_hz as u32,
+                bits_per_sample: recording_config.audio_bits_per_sample,
+                sample_format: match recording_config.audio_sample_format {
+                    WavSampleFormat::Float => hound::SampleFormat::Float,
+                    WavSampleFormat::Int => hound::SampleFormat::Int,
+                },
+            };
+            let wav_writer = hound::WavWriter::create(recording_config.audio_path(), spec)
+                .map_err(|e| format!("Failed to create WAV writer: {}", e))?;
+            println!(
+                "Audio: offline @ {}Hz, {} fps",
+                fft_config.sample_rate_hz, recording_config.fps
+            );
+
+            // Keep playing live, paced by the device rather than by captured frames (nothing
+            // analyzes this stream, so its FFT buffer just holds one window)
+            let stream = output_device(backend).and_then(|device| {
+                let fft_buffer = Arc::new(Mutex::new(
+                    FftBuffer::new(false).with_cap(fft_config.fft_size),
+                ));
+                create_engine(fft_config.sample_rate_hz)
+                    .and_then(|engine| {
+                        start_output_stream(
+                            &device,
+                            engine,
+                            fft_buffer,
+                            fft_config.expected_block_latency_ms(),
+                        )
+                    })
+                    .map_err(|e| eprintln!("Warning: {}, recording without playback", e))
+                    .ok()
             });
 
-        let wav_writer_clone = wav_writer.clone();
+            let offline = OfflineAudio {
+                engine,
+                recording_config,
+                sample_rate_hz: fft_config.sample_rate_hz,
+                leftover: VecDeque::with_capacity(BLOCK_SIZE),
+                window: VecDeque::with_capacity(fft_config.fft_size),
+                window_right: VecDeque::with_capacity(fft_config.fft_size),
+                stereo: fft_config.stereo,
+                normalizer: fft_config
+                    .normalize
+                    .then(|| BandNormalizer::new(fft_config.normalize_decay_per_s)),
+                analyzer: BandAnalyzer::new(fft_config),
+                wav_writer: Some(wav_writer),
+                samples_written: 0,
+                bands: StereoBands::default(),
+            };
+            return Ok(Self {
+                audio_bands,
+                spectrum,
+                visual_latency_ms,
+                _stream: stream,
+                null_audio: None,
+                offline: Some(Mutex::new(offline)),
+                fft_thread: None,
+                running: Arc::new(AtomicBool::new(false)),
+            });
+        }
+
+        // Shared sample buffer between audio output and FFT thread
+        let fft_buffer = Arc::new(Mutex::new(
+            FftBuffer::new(fft_config.stereo).with_cap(fft_config.fft_buffer_cap()),
+        ));
+
+        let (stream, null_audio) = match output_device(backend) {
+            Some(device) => {
+                let stream = start_output_stream(
+                    &device,
+                    engine,
+                    Arc::clone(&fft_buffer),
+                    fft_config.expected_block_latency_ms(),
+                )?;
+                (Some(stream), None)
+            }
+            None => {
+                println!(
+                    "Audio: null backend @ {}Hz (not played)",
+                    fft_config.sample_rate_hz
+                );
+                let null_audio =
+                    NullAudio::start(engine, Arc::clone(&fft_buffer), fft_config.sample_rate_hz);
+                (None, Some(null_audio))
+            }
+        };
 
-        // Create Glicol engine
-        let mut engine = Engine::<BLOCK_SIZE>::new();
-        engine.set_sr(fft_config.sample_rate_hz);
-        engine.update_with_code(GLICOL_COMPOSITION);
-        engine
-            .update()
-            .map_err(|e| format!("Glicol engine init failed: {:?}", e))?;
+        // Start FFT analysis thread
+        let running = Arc::new(AtomicBool::new(true));
+        let fft_thread = spawn_fft_thread(
+            fft_config,
+            fft_buffer,
+            Arc::clone(&audio_bands),
+            Arc::clone(&spectrum),
+            Arc::clone(&running),
+        );
 
-        // Shared state between audio callback and FFT thread
-        let engine = Arc::new(Mutex::new(engine));
-        let engine_clone = Arc::clone(&engine);
+        Ok(Self {
+            audio_bands,
+            spectrum,
+            visual_latency_ms,
+            _stream: stream,
+            null_audio,
+            offline: None,
+            fft_thread: Some(fft_thread),
+            running,
+        })
+    }
 
-        let fft_buffer = Arc::new(Mutex::new(Vec::<f32>::new()));
-        let fft_buffer_clone = Arc::clone(&fft_buffer);
+    /// Get current audio frequency bands (thread-safe)
+    pub fn get_bands(&self) -> AudioBands {
+        self.stereo_bands().left
+    }
 
-        let audio_bands = Arc::new(Mutex::new(AudioBands::default()));
-        let audio_bands_fft = Arc::clone(&audio_bands);
+    /// Get current (left, right) frequency bands
+    ///
+    /// Without `FFTConfig::stereo`, both sides are the left-channel bands.
+    pub fn get_bands_stereo(&self) -> (AudioBands, AudioBands) {
+        let bands = self.stereo_bands();
+        (bands.left, bands.right)
+    }
 
-        // Setup audio output device
-        let host = cpal::default_host();
-        let device = host
-            .default_output_device()
-            .ok_or("No audio output device found")?;
+    /// Current stereo width (side energy share: 0 = mono, 0.5 = hard-panned)
+    pub fn get_stereo_width(&self) -> f32 {
+        self.stereo_bands().width
+    }
 
-        let config = device
-            .default_output_config()
-            .map_err(|e| format!("Failed to get audio config: {}", e))?;
+    /// Latest FFT bin magnitudes of the left channel, or the mono downmix unless stereo
+    /// (bins 0..fft_size/2, raw like the bands)
+    ///
+    /// When recording, the spectrum of the current frame's analysis window.
+    pub fn get_spectrum(&self) -> Vec<f32> {
+        match self.offline {
+            Some(ref offline) => offline.lock().unwrap().analyzer.spectrum().to_vec(),
+            None => self.spectrum.lock().unwrap().clone(),
+        }
+    }
 
-        println!(
-            "Audio: {} @ {}Hz",
-            device.name().unwrap_or_else(|_| "Unknown".to_string()),
-            config.sample_rate().0
-        );
+    /// Bands analyzed `delay_ms` ago (from a short history; latest for a zero delay)
+    ///
+    /// The bands can't lead the analysis, so negative delays also get the latest bands
+    /// (`FFTConfig::validate` rejects a negative `visual_latency_ms`).
+    ///
+    /// When recording, always the current frame's bands (audio and frames are in lockstep).
+    pub fn get_bands_at(&self, delay_ms: f32) -> AudioBands {
+        if self.offline.is_some() || delay_ms <= 0.0 {
+            return self.get_bands();
+        }
+        let delay = Duration::from_secs_f32(delay_ms / 1000.0);
+        let history = self.audio_bands.lock().unwrap();
+        match Instant::now().checked_sub(delay) {
+            Some(at) => history.at(at).left,
+            None => history.latest().left,
+        }
+    }
 
-        // Build audio output stream
-        let stream = device
-            .build_output_stream(
-                &config.into(),
-                move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
-                    let mut engine = engine_clone.lock().unwrap();
-                    let mut fft_buf = fft_buffer_clone.lock().unwrap();
-
-                    let frames_needed = data.len() / 2; // Stereo frames
-                    let mut frame_idx = 0;
-
-                    // Generate multiple blocks if needed to fill the entire buffer
-                    while frame_idx < frames_needed {
-                        let (buffers, _) = engine.next_block(vec![]);
-
-                        let samples_to_copy = (frames_needed - frame_idx).min(BLOCK_SIZE);
-
-                        for i in 0..samples_to_copy {
-                            // Safety limiter: hard clip to ±0.5 to prevent ear damage
-                            let left = buffers[0][i].clamp(-0.5, 0.5);
-                            let right = buffers[1][i].clamp(-0.5, 0.5);
-
-                            let out_idx = (frame_idx + i) * 2;
-                            data[out_idx] = left;
-                            data[out_idx + 1] = right;
-
-                            fft_buf.push(left); // Accumulate for FFT analysis
-
-                            // Record to WAV if recording
-                            if let Some(ref writer) = wav_writer_clone {
-                                if let Ok(mut w) = writer.lock() {
-                                    let _ = w.write_sample(left);
-                                    let _ = w.write_sample(right);
-                                }
-                            }
-                        }
-
-                        frame_idx += samples_to_copy;
-                    }
-                },
-                |err| eprintln!("Audio stream error: {}", err),
-                None,
-            )
-            .map_err(|e| format!("Failed to build audio stream: {}", e))?;
+    /// Latest per-channel analysis (offline bands when recording)
+    fn stereo_bands(&self) -> StereoBands {
+        match self.offline {
+            Some(ref offline) => offline.lock().unwrap().bands,
+            None => self.audio_bands.lock().unwrap().latest(),
+        }
+    }
 
-        stream
-            .play()
-            .map_err(|e| format!("Failed to start audio stream: {}", e))?;
+    /// Audio bands for rendering frame `frame_num`
+    ///
+    /// When recording, first synthesizes audio up to the frame's simulated time, so bands
+    /// and the WAV track advance exactly one frame period per frame. Live, the bands from
+    /// `FFTConfig::visual_latency_ms` ago (see `get_bands_at`).
+    pub fn bands_for_frame(&self, frame_num: usize) -> AudioBands {
+        match self.offline {
+            Some(ref offline) => offline.lock().unwrap().advance_to_frame(frame_num).left,
+            None => self.get_bands_at(self.visual_latency_ms),
+        }
+    }
 
-        // Start FFT analysis thread
-        let fft_thread = spawn_fft_thread(fft_config, fft_buffer, audio_bands_fft);
+    /// Stop recording audio and finalize the WAV file (no-op when not recording)
+    pub fn finish_recording(&self) -> Result<(), String> {
+        let Some(ref offline) = self.offline else {
+            return Ok(());
+        };
+        offline
+            .lock()
+            .map_err(|_| "Offline audio lock poisoned".to_string())?
+            .finish()
+    }
 
-        Ok(Self {
-            audio_bands,
-            _stream: stream,
-            _fft_thread: Some(fft_thread),
-        })
+    /// Stop and join the audio threads, then finalize the WAV file
+    ///
+    /// Same as dropping, but reports errors. An unfinished recording keeps the audio
+    /// written so far (use `finish_recording` to pad it to full length first).
+    pub fn shutdown(mut self) -> Result<(), String> {
+        self.stop()
     }
 
-    /// Get current audio frequency bands (thread-safe)
-    pub fn get_bands(&self) -> AudioBands {
-        *self.audio_bands.lock().unwrap()
+    fn stop(&mut self) -> Result<(), String> {
+        // Stop feeding the FFT buffer, then the analysis itself
+        self.null_audio = None;
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.fft_thread.take() {
+            thread
+                .join()
+                .map_err(|_| "FFT thread panicked".to_string())?;
+        }
+
+        let Some(ref offline) = self.offline else {
+            return Ok(());
+        };
+        offline
+            .lock()
+            .map_err(|_| "Offline audio lock poisoned".to_string())?
+            .finalize()
+    }
+}
+
+impl Drop for AudioSystem {
+    fn drop(&mut self) {
+        if let Err(e) = self.stop() {
+            eprintln!("Warning: Audio shutdown failed: {}", e);
+        }
+    }
+}
+
+/// Output device for `backend` (None for the null backend, or with a warning if there is none)
+fn output_device(backend: AudioBackend) -> Option<cpal::Device> {
+    match backend {
+        AudioBackend::Device => {
+            let device = cpal::default_host().default_output_device();
+            if device.is_none() {
+                eprintln!("Warning: No audio output device found, using null audio backend");
+            }
+            device
+        }
+        AudioBackend::Null => None,
+    }
+}
+
+/// Play the engine on an output device, feeding the FFT buffer from the audio callback
+///
+/// `block_latency_ms` (`FFTConfig::expected_block_latency_ms`) is logged at startup.
+fn start_output_stream(
+    device: &cpal::Device,
+    mut engine: Engine<BLOCK_SIZE>,
+    fft_buffer: Arc<Mutex<FftBuffer>>,
+    block_latency_ms: f32,
+) -> Result<cpal::Stream, String> {
+    let config = device
+        .default_output_config()
+        .map_err(|e| format!("Failed to get audio config: {}", e))?;
+
+    println!(
+        "Audio: {} @ {}Hz, {}-sample blocks ({:.1} ms synthesis latency)",
+        device.name().unwrap_or_else(|_| "Unknown".to_string()),
+        config.sample_rate().0,
+        BLOCK_SIZE,
+        block_latency_ms
+    );
+
+    let stream = device
+        .build_output_stream(
+            &config.into(),
+            move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                fill_output(&mut engine, &mut fft_buffer.lock().unwrap(), data);
+            },
+            |err| eprintln!("Audio stream error: {}", err),
+            None,
+        )
+        .map_err(|e| format!("Failed to build audio stream: {}", e))?;
+
+    stream
+        .play()
+        .map_err(|e| format!("Failed to start audio stream: {}", e))?;
+    Ok(stream)
+}
+
+/// Synthesize into an interleaved stereo buffer and accumulate the samples for FFT analysis
+///
+/// A trailing sample of an odd-length buffer (half a stereo frame) is filled with silence.
+fn fill_output(engine: &mut Engine<BLOCK_SIZE>, fft_buf: &mut FftBuffer, data: &mut [f32]) {
+    let mut frames = data.chunks_exact_mut(2); // Stereo frames
+    let frames_needed = frames.len();
+    let mut frame_idx = 0;
+
+    // Generate multiple blocks if needed to fill the entire buffer
+    while frame_idx < frames_needed {
+        let (buffers, _) = engine.next_block(vec![]);
+
+        let samples_to_copy = (frames_needed - frame_idx).min(BLOCK_SIZE);
+
+        for (i, frame) in frames.by_ref().take(samples_to_copy).enumerate() {
+            // Safety limiter: hard clip to ±0.5 to prevent ear damage
+            let left = buffers[0][i].clamp(-0.5, 0.5);
+            let right = buffers[1][i].clamp(-0.5, 0.5);
+
+            frame.copy_from_slice(&[left, right]);
+
+            fft_buf.push(left, right); // Accumulate for FFT analysis
+        }
+
+        frame_idx += samples_to_copy;
     }
+    frames.into_remainder().fill(0.0);
+}
+
+/// Write one stereo frame in the writer's format (mono sums the channels, ints are scaled)
+fn write_wav_frame(writer: &mut WavWriter, left: f32, right: f32) {
+    let spec = writer.spec();
+    let frame = [left, right];
+    let mono = [left + right];
+    let samples: &[f32] = if spec.channels == 1 { &mono } else { &frame };
+
+    for &sample in samples {
+        let _ = match spec.sample_format {
+            hound::SampleFormat::Float => writer.write_sample(sample),
+            hound::SampleFormat::Int => {
+                let max = ((1_i64 << (spec.bits_per_sample - 1)) - 1) as f32;
+                writer.write_sample((sample.clamp(-1.0, 1.0) * max).round() as i32)
+            }
+        };
+    }
+}
+
+/// Glicol engine running the composition at `sample_rate_hz`
+fn create_engine(sample_rate_hz: usize) -> Result<Engine<BLOCK_SIZE>, String> {
+    let mut engine = Engine::<BLOCK_SIZE>::new();
+    engine.set_sr(sample_rate_hz);
+    engine.update_with_code(GLICOL_COMPOSITION);
+    engine
+        .update()
+        .map_err(|e| format!("Glicol engine init failed: {:?}", e))?;
+    Ok(engine)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_null_backend_produces_bands() {
+        let audio = AudioSystem::new(FFTConfig::default(), None, AudioBackend::Null)
+            .expect("Null backend needs no output device");
+
+        // Bands appear once the timer thread has synthesized a few FFT windows
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut bands = audio.get_bands();
+        while bands.low + bands.mid + bands.high <= 0.0 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(50));
+            bands = audio.get_bands();
+        }
+        assert!(bands.low + bands.mid + bands.high > 0.0, "{:?}", bands);
+    }
+
+    #[test]
+    fn test_shutdown_joins_fft_thread() {
+        let audio = AudioSystem::new(FFTConfig::default(), None, AudioBackend::Null).unwrap();
+        let audio_bands = Arc::clone(&audio.audio_bands);
+
+        audio.shutdown().unwrap();
+
+        // The FFT thread's handle to the bands is released once it has exited
+        assert_eq!(Arc::strong_count(&audio_bands), 1);
+    }
+
+    /// Recording config writing into a fresh temp directory named after the test
+    fn temp_recording_config(name: &str, duration_secs: f32) -> RecordingConfig {
+        let output_dir =
+            std::env::temp_dir().join(format!("vibesurfer_audio_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&output_dir).unwrap();
+        RecordingConfig {
+            output_dir: output_dir.to_string_lossy().into_owned(),
+            fps: 30,
+            ..RecordingConfig::new(duration_secs)
+        }
+    }
+
+    #[test]
+    fn test_finish_recording_writes_complete_wav() {
+        let recording_config = temp_recording_config("finish", 1.0);
+        let fft_config = FFTConfig::default();
+        let sample_rate_hz = fft_config.sample_rate_hz;
+
+        let audio = AudioSystem::new(
+            fft_config,
+            Some(recording_config.clone()),
+            AudioBackend::Null,
+        )
+        .unwrap();
+        for frame in 0..recording_config.total_frames() {
+            audio.bands_for_frame(frame);
+        }
+        audio.finish_recording().unwrap();
+
+        // Header lengths are final: one second of stereo samples reads back in full
+        let mut reader = hound::WavReader::open(recording_config.audio_path()).unwrap();
+        assert_eq!(reader.duration() as usize, sample_rate_hz);
+        assert_eq!(reader.len() as usize, sample_rate_hz * 2);
+        assert_eq!(
+            reader.samples::<f32>().filter_map(Result::ok).count(),
+            sample_rate_hz * 2
+        );
+
+        drop(audio);
+        let _ = std::fs::remove_dir_all(&recording_config.output_dir);
+    }
+
+    #[test]
+    fn test_mono_16_bit_recording_header() {
+        let recording_config = RecordingConfig {
+            audio_channels: 1,
+            audio_bits_per_sample: 16,
+            audio_sample_format: WavSampleFormat::Int,
+            ..temp_recording_config("mono16", 0.5)
+        };
+        let fft_config = FFTConfig::default();
+        let sample_rate_hz = fft_config.sample_rate_hz;
+
+        let audio = AudioSystem::new(
+            fft_config,
+            Some(recording_config.clone()),
+            AudioBackend::Null,
+        )
+        .unwrap();
+        audio.finish_recording().unwrap();
+
+        let mut reader = hound::WavReader::open(recording_config.audio_path()).unwrap();
+        let spec = reader.spec();
+        assert_eq!(spec.channels, 1);
+        assert_eq!(spec.bits_per_sample, 16);
+        assert_eq!(spec.sample_format, hound::SampleFormat::Int);
+        assert_eq!(reader.len() as usize, sample_rate_hz / 2);
+        assert!(reader.samples::<i16>().all(|sample| sample.is_ok()));
+
+        drop(audio);
+        let _ = std::fs::remove_dir_all(&recording_config.output_dir);
+    }
+
+    #[test]
+    fn test_shutdown_finalizes_partial_recording() {
+        let recording_config = temp_recording_config("shutdown", 1.0);
+        let fft_config = FFTConfig::default();
+        let sample_rate_hz = fft_config.sample_rate_hz;
+
+        let audio = AudioSystem::new(
+            fft_config,
+            Some(recording_config.clone()),
+            AudioBackend::Null,
+        )
+        .unwrap();
+        audio.bands_for_frame(10);
+        audio.shutdown().unwrap();
+
+        // Readable WAV holding the frames synthesized before shutdown
+        let reader = hound::WavReader::open(recording_config.audio_path()).unwrap();
+        let frames = recording_config.samples_until_frame(10, sample_rate_hz);
+        assert_eq!(reader.duration() as u64, frames);
+        assert_eq!(reader.spec().channels, 2);
+
+        let _ = std::fs::remove_dir_all(&recording_config.output_dir);
+    }
+
     #[test]
     fn test_fft_config_hz_to_bin() {
         let config = FFTConfig::default();
@@ -163,6 +708,42 @@ mod tests {
         assert_eq!(config.hz_to_bin(100.0), 2); // ~100 Hz ≈ bin 2
     }
 
+    #[test]
+    fn test_expected_block_latency() {
+        // 128 samples at 44.1 kHz ≈ 2.9 ms; halving the rate doubles it
+        let config = FFTConfig::default();
+        assert!((config.expected_block_latency_ms() - 2.9025).abs() < 1e-3);
+        let half_rate = FFTConfig {
+            sample_rate_hz: 22050,
+            ..FFTConfig::default()
+        };
+        assert!(
+            (half_rate.expected_block_latency_ms() - 2.0 * config.expected_block_latency_ms())
+                .abs()
+                < 1e-4
+        );
+    }
+
+    #[test]
+    fn test_fill_output_handles_odd_buffer_lengths() {
+        let mut engine = create_engine(44100).unwrap();
+        let mut fft_buf = FftBuffer::new(true);
+
+        // 301 samples: 150 stereo frames (more than one block) plus half a frame
+        let mut data = vec![f32::NAN; 301];
+        fill_output(&mut engine, &mut fft_buf, &mut data);
+        assert!(data.iter().all(|s| s.is_finite() && s.abs() <= 0.5));
+        assert_eq!(data[300], 0.0);
+        assert_eq!(fft_buf.left.len(), 150);
+
+        // A lone sample is silence; an empty buffer is a no-op
+        let mut single = [f32::NAN];
+        fill_output(&mut engine, &mut fft_buf, &mut single);
+        assert_eq!(single, [0.0]);
+        fill_output(&mut engine, &mut fft_buf, &mut []);
+        assert_eq!(fft_buf.left.len(), 150);
+    }
+
     #[test]
     fn test_fft_config_band_ranges() {
         let config = FFTConfig::default();
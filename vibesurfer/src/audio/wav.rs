@@ -0,0 +1,198 @@
+//! Offline band extraction from a WAV file.
+//!
+//! For rendering a video to a fixed song: the song is analyzed once up front, and frame N
+//! uses the bands of the audio just before frame N's start, independent of render speed.
+
+use std::path::Path;
+
+use super::fft::{BandAnalyzer, BandNormalizer, StereoBands};
+use crate::ocean::AudioBands;
+use crate::params::FFTConfig;
+
+/// Bands for every frame of a WAV file at `fps` (mono downmix, like live analysis)
+///
+/// Frame N analyzes the FFT window ending where frame N starts (zero-padded at the start
+/// of the song), at the file's own sample rate. Honors `FFTConfig::normalize`.
+pub fn analyze_wav(
+    path: &Path,
+    fft_config: &FFTConfig,
+    fps: u32,
+) -> Result<Vec<AudioBands>, String> {
+    let mut reader = hound::WavReader::open(path)
+        .map_err(|e| format!("Failed to open WAV '{}': {}", path.display(), e))?;
+    let spec = reader.spec();
+    let channels = spec.channels.max(1) as usize;
+
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>(),
+        hound::SampleFormat::Int => {
+            let scale = 1.0 / (1_i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|sample| sample.map(|s| s as f32 * scale))
+                .collect::<Result<_, _>>()
+        }
+    }
+    .map_err(|e| format!("Failed to read WAV '{}': {}", path.display(), e))?;
+    let mono: Vec<f32> = samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+
+    let config = FFTConfig {
+        sample_rate_hz: spec.sample_rate as usize,
+        ..fft_config.clone()
+    };
+    let fft_size = config.fft_size;
+    let fps = fps.max(1) as usize;
+    let mut normalizer = config
+        .normalize
+        .then(|| BandNormalizer::new(config.normalize_decay_per_s));
+    let mut analyzer = BandAnalyzer::new(config.clone());
+
+    // Zero padding so early frames still see a full window
+    let mut padded = vec![0.0; fft_size];
+    padded.extend_from_slice(&mono);
+
+    let frame_count = (mono.len() * fps).div_ceil(config.sample_rate_hz);
+    Ok((0..frame_count)
+        .map(|frame| {
+            let end = frame * config.sample_rate_hz / fps;
+            let bands = analyzer
+                .analyze(&padded[end..end + fft_size])
+                .unwrap_or_default();
+            match normalizer {
+                Some(ref mut normalizer) => {
+                    let stereo = StereoBands {
+                        left: bands,
+                        right: bands,
+                        width: 0.0,
+                    };
+                    normalizer.normalize(stereo, 1.0 / fps as f32).left
+                }
+                None => bands,
+            }
+        })
+        .collect())
+}
+
+/// Precomputed per-frame bands that stand in for the live `AudioSystem`
+#[derive(Clone, Debug, Default)]
+pub struct BandTrack {
+    bands: Vec<AudioBands>,
+}
+
+impl BandTrack {
+    pub fn new(bands: Vec<AudioBands>) -> Self {
+        Self { bands }
+    }
+
+    /// Analyze a WAV file for rendering at `fps` (see `analyze_wav`)
+    pub fn from_wav(path: &Path, fft_config: &FFTConfig, fps: u32) -> Result<Self, String> {
+        analyze_wav(path, fft_config, fps).map(Self::new)
+    }
+
+    /// Number of frames covered
+    pub fn len(&self) -> usize {
+        self.bands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bands.is_empty()
+    }
+
+    /// Bands for frame `frame_num` (silence past the end of the track)
+    pub fn bands_for_frame(&self, frame_num: usize) -> AudioBands {
+        self.bands.get(frame_num).copied().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ocean::OceanSystem;
+    use crate::params::{AudioReactiveMapping, OceanPhysics, ParamTarget};
+    use std::f32::consts::PI;
+
+    #[test]
+    fn test_analyze_wav_follows_song_per_frame() {
+        let path =
+            std::env::temp_dir().join(format!("vibesurfer_bands_{}.wav", std::process::id()));
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 44100,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+
+        // 1 s of silence, then 1 s of a 100 Hz tone (left channel only)
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for i in 0..2 * 44100 {
+            let tone = if i >= 44100 {
+                (2.0 * PI * 100.0 * i as f32 / 44100.0).sin() * 0.5
+            } else {
+                0.0
+            };
+            writer
+                .write_sample((tone * i16::MAX as f32) as i16)
+                .unwrap();
+            writer.write_sample(0_i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let bands = analyze_wav(&path, &FFTConfig::default(), 10).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(bands.len(), 20);
+        assert!(
+            bands[..=10].iter().all(|b| b.low == 0.0),
+            "{:?}",
+            &bands[..=10]
+        );
+        assert!(
+            bands[11..].iter().all(|b| b.low > 1.0),
+            "{:?}",
+            &bands[11..]
+        );
+    }
+
+    #[test]
+    fn test_frame_parameters_derive_from_track_bands() {
+        // Rising energy, so the gust envelope follows each frame's target exactly
+        let bands: Vec<AudioBands> = (0..30)
+            .map(|n| AudioBands {
+                low: n as f32 * 0.1,
+                mid: n as f32 * 0.2,
+                high: n as f32 * 0.3,
+            })
+            .collect();
+        let track = BandTrack::new(bands.clone());
+
+        let physics = OceanPhysics {
+            grid_size: 2,
+            ..OceanPhysics::default()
+        };
+        let mapping = AudioReactiveMapping::default();
+        let mut ocean = OceanSystem::new(physics.clone(), mapping.clone()).unwrap();
+
+        for (frame, expected) in bands.iter().enumerate() {
+            let (amplitude, frequency, line_width) =
+                ocean.modulate(frame as f32 / 30.0, &track.bands_for_frame(frame));
+            assert_eq!(
+                amplitude,
+                physics.detail_amplitude_m + mapping.offset(ParamTarget::Amplitude, expected)
+            );
+            assert_eq!(
+                frequency,
+                physics.detail_frequency + mapping.offset(ParamTarget::Frequency, expected)
+            );
+            assert_eq!(
+                line_width,
+                physics.base_line_width + mapping.offset(ParamTarget::LineWidth, expected)
+            );
+        }
+
+        // Past the end of the song the ocean gets silence
+        assert_eq!(track.bands_for_frame(bands.len()).low, 0.0);
+    }
+}
@@ -0,0 +1,100 @@
+// Bloom post-process: bright-pass, separable Gaussian blur and composite.
+// Every pass draws a fullscreen triangle sampling the previous stage's texture.
+
+struct BloomUniforms {
+    threshold: f32,       // Luminance where glow starts
+    intensity: f32,       // Glow strength added in the composite
+    radius: f32,          // Gaussian sigma (texels)
+    exposure: f32,        // Linear exposure scale applied in the composite
+    direction: vec2<f32>, // One texel step along the blur axis (UV units)
+    tone_mapping: u32,    // 0 = passthrough, 1 = Reinhard, 2 = ACES
+    _padding: f32,
+}
+
+@group(0) @binding(0)
+var<uniform> params: BloomUniforms;
+
+@group(0) @binding(1)
+var source: texture_2d<f32>;
+
+@group(0) @binding(2)
+var source_sampler: sampler;
+
+// Blurred glow (composite pass only)
+@group(0) @binding(3)
+var glow: texture_2d<f32>;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    var output: VertexOutput;
+
+    // Fullscreen triangle
+    let x = f32((vertex_index << 1u) & 2u);
+    let y = f32(vertex_index & 2u);
+
+    output.position = vec4<f32>(x * 2.0 - 1.0, y * 2.0 - 1.0, 0.0, 1.0);
+    output.uv = vec2<f32>(x, 1.0 - y);
+
+    return output;
+}
+
+// Keep only the part of each pixel brighter than the threshold
+@fragment
+fn fs_bright(input: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(source, source_sampler, input.uv).rgb;
+    let luma = dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+    let excess = max(luma - params.threshold, 0.0);
+    return vec4<f32>(color * (excess / max(luma, 0.0001)), 1.0);
+}
+
+// One axis of the separable Gaussian blur (3 sigma, at most 32 taps each side)
+@fragment
+fn fs_blur(input: VertexOutput) -> @location(0) vec4<f32> {
+    let sigma = max(params.radius, 0.5);
+    let taps = i32(min(ceil(sigma * 3.0), 32.0));
+
+    var sum = vec3<f32>(0.0);
+    var weight_sum = 0.0;
+    for (var i = -taps; i <= taps; i++) {
+        let offset = f32(i);
+        let weight = exp(-offset * offset / (2.0 * sigma * sigma));
+        sum += textureSample(source, source_sampler, input.uv + params.direction * offset).rgb * weight;
+        weight_sum += weight;
+    }
+
+    return vec4<f32>(sum / weight_sum, 1.0);
+}
+
+// Exposure, then the selected curve from HDR down to display range
+fn tone_map(hdr: vec3<f32>) -> vec3<f32> {
+    let color = hdr * params.exposure;
+    switch params.tone_mapping {
+        case 1u: {
+            return color / (1.0 + color);
+        }
+        case 2u: {
+            // Narkowicz ACES filmic fit
+            return clamp(
+                (color * (2.51 * color + 0.03)) / (color * (2.43 * color + 0.59) + 0.14),
+                vec3<f32>(0.0),
+                vec3<f32>(1.0),
+            );
+        }
+        default: {
+            return color;
+        }
+    }
+}
+
+// Scene plus glow, tone mapped
+@fragment
+fn fs_composite(input: VertexOutput) -> @location(0) vec4<f32> {
+    let scene = textureSample(source, source_sampler, input.uv).rgb;
+    let bloom = textureSample(glow, source_sampler, input.uv).rgb;
+    return vec4<f32>(tone_map(scene + bloom * params.intensity), 1.0);
+}
@@ -1,23 +1,353 @@
 //! Procedural camera journey system with parameterized cinematic paths.
 
 use glam::{Mat4, Vec3};
+use serde::Serialize;
 
 use crate::params::{
-    BasicCameraPath, CameraJourney, CameraPreset, FixedCamera, FloatingCamera, RenderConfig,
+    BasicCameraPath, CameraJourney, CameraKeyframe, CameraPreset, CameraShakeConfig, FixedCamera,
+    FloatingCamera, ManualCameraState, OrbitCamera, RenderConfig,
 };
 
+/// Time step for finite-difference speed estimate (seconds)
+const SPEED_ESTIMATE_DT_S: f32 = 1.0 / 60.0;
+
+/// Pitch limit for manual camera (radians, ±89° avoids look-at singularity at the poles)
+const MANUAL_PITCH_LIMIT_RAD: f32 = 89.0 * std::f32::consts::PI / 180.0;
+
+/// Far plane headroom over the farthest grid corner (covers terrain relief below the eye)
+const FAR_PLANE_FIT_MARGIN: f32 = 1.25;
+
+/// Rate the running bass average follows the bass level (per second, for onset detection)
+const BASS_AVERAGE_RATE_PER_S: f32 = 4.0;
+
+/// Strongest shake a bass onset triggers (intensity, 1.0 = configured amplitudes)
+const MAX_ONSET_INTENSITY: f32 = 2.0;
+
 /// Type alias for terrain height query function (saves boilerplate in tests)
 type TerrainFn = fn(f32, f32) -> f32;
 
+/// What a blend fades out from
+enum BlendSource {
+    /// Previous preset, still moving
+    Preset(CameraPreset),
+
+    /// Pose held still (eye, target), captured when a blend was re-triggered mid-way
+    Pose(Vec3, Vec3),
+}
+
+/// Active cross-fade from a previous preset to the current one
+struct CameraBlend {
+    /// View being faded out
+    from: BlendSource,
+
+    /// Time the blend started (seconds)
+    start_time_s: f32,
+
+    /// Blend duration (seconds)
+    duration_s: f32,
+}
+
+impl CameraBlend {
+    /// Smoothstep weight of the new preset at given time (0 = old, 1 = new)
+    fn weight(&self, time_s: f32) -> f32 {
+        if self.duration_s <= 0.0 {
+            return 1.0;
+        }
+        let u = ((time_s - self.start_time_s) / self.duration_s).clamp(0.0, 1.0);
+        u * u * (3.0 - 2.0 * u)
+    }
+}
+
+/// Transient camera jitter with exponentially decaying amplitude
+pub struct CameraShake {
+    config: CameraShakeConfig,
+
+    /// Time of the most recent trigger (seconds, None = never triggered)
+    trigger_time_s: Option<f32>,
+
+    /// Intensity at trigger time (dimensionless, 1.0 = configured amplitudes)
+    intensity: f32,
+
+    /// Running average of the bass level (for onset detection)
+    bass_average: f32,
+
+    /// Bass was above the onset threshold at the previous update
+    bass_above: bool,
+}
+
+impl CameraShake {
+    /// Create idle shake with specified configuration
+    pub fn new(config: CameraShakeConfig) -> Self {
+        Self {
+            config,
+            trigger_time_s: None,
+            intensity: 0.0,
+            bass_average: 0.0,
+            bass_above: false,
+        }
+    }
+
+    /// Follow the bass level over `dt_s` and shake on an onset
+    ///
+    /// An onset is the bass rising `bass_onset_threshold` above its running average; the
+    /// shake intensity is the rise in thresholds (capped at `MAX_ONSET_INTENSITY`). A held
+    /// note triggers once.
+    pub fn on_bass(&mut self, low: f32, dt_s: f32, time_s: f32) {
+        let threshold = self.config.bass_onset_threshold;
+        let rise = low - self.bass_average;
+        let above = threshold > 0.0 && rise > threshold;
+        if above && !self.bass_above {
+            self.trigger((rise / threshold).min(MAX_ONSET_INTENSITY), time_s);
+        }
+        self.bass_above = above;
+        self.bass_average += rise * (1.0 - (-BASS_AVERAGE_RATE_PER_S * dt_s).exp());
+    }
+
+    /// Start a new shake (ignored if a stronger shake is still decaying)
+    pub fn trigger(&mut self, intensity: f32, time_s: f32) {
+        if intensity >= self.amplitude(time_s) {
+            self.trigger_time_s = Some(time_s);
+            self.intensity = intensity;
+        }
+    }
+
+    /// Current decayed intensity
+    pub fn amplitude(&self, time_s: f32) -> f32 {
+        let Some(trigger_time_s) = self.trigger_time_s else {
+            return 0.0;
+        };
+        let elapsed = time_s - trigger_time_s;
+        if elapsed < 0.0 {
+            return 0.0;
+        }
+        self.intensity * (-elapsed / self.config.decay_time_constant_s).exp()
+    }
+
+    /// Jitter offsets to add to (eye, target) at given time
+    pub fn offset(&self, time_s: f32) -> (Vec3, Vec3) {
+        let amplitude = self.amplitude(time_s);
+        if amplitude == 0.0 {
+            return (Vec3::ZERO, Vec3::ZERO);
+        }
+
+        // Incommensurate per-axis sines with phase offsets (never all zero at once)
+        let phase = (time_s - self.trigger_time_s.unwrap_or(time_s))
+            * self.config.frequency_hz
+            * std::f32::consts::TAU;
+        let jitter = |a: f32, b: f32, c: f32| {
+            Vec3::new(
+                (phase + a).sin(),
+                (phase * 1.31 + b).sin(),
+                (phase * 0.77 + c).sin(),
+            )
+        };
+
+        let eye_offset = jitter(0.5, 1.3, 2.1) * amplitude * self.config.position_amplitude_m;
+        let target_offset = jitter(2.7, 0.9, 1.7) * amplitude * self.config.target_amplitude_m;
+
+        (eye_offset, target_offset)
+    }
+}
+
+/// One exported camera sample (JSON: `{"t", "eye", "target", "fov"}`)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CameraPathSample {
+    /// Time (seconds)
+    pub t: f32,
+
+    /// Camera position (meters)
+    pub eye: [f32; 3],
+
+    /// Look-at target (meters)
+    pub target: [f32; 3],
+
+    /// Vertical field of view (degrees, speed-dependent as rendered)
+    pub fov: f32,
+}
+
+/// Boxed terrain height query at (x, z) world position
+pub type TerrainSource = Box<dyn Fn(f32, f32) -> f32 + Send>;
+
 /// Camera system with procedural journey path
 pub struct CameraSystem {
     preset: CameraPreset,
+    blend: Option<CameraBlend>,
+    shake: CameraShake,
+    /// Stored terrain query, used when no explicit `terrain_height_fn` is passed
+    terrain_source: Option<TerrainSource>,
+    /// Side length of the ocean grid window (meters, for far plane auto-fit)
+    grid_extent_m: Option<f32>,
 }
 
 impl CameraSystem {
     /// Create new camera system with specified preset
     pub fn new(preset: CameraPreset) -> Self {
-        Self { preset }
+        Self {
+            preset,
+            blend: None,
+            shake: CameraShake::new(CameraShakeConfig::default()),
+            terrain_source: None,
+            grid_extent_m: None,
+        }
+    }
+
+    /// Set the side length of the grid window centered under the camera
+    ///
+    /// Used by `RenderConfig::far_plane_auto_fit` (e.g. `OceanPhysics::grid_extent_m`).
+    pub fn set_grid_extent(&mut self, extent_m: f32) {
+        self.grid_extent_m = Some(extent_m);
+    }
+
+    /// Far clipping plane for a camera at `eye` looking at `target` (meters)
+    ///
+    /// With `far_plane_auto_fit` and a grid extent set, fits the grid window (see
+    /// `fitted_far_plane_m`); otherwise `far_plane_m`.
+    pub fn far_plane_m(&self, eye: Vec3, target: Vec3, render_config: &RenderConfig) -> f32 {
+        match self.grid_extent_m {
+            Some(extent_m) if render_config.far_plane_auto_fit => {
+                fitted_far_plane_m(eye, target, extent_m).max(render_config.near_plane_m * 2.0)
+            }
+            _ => render_config.far_plane_m,
+        }
+    }
+
+    /// Store a terrain height query for the Floating preset
+    ///
+    /// Consulted whenever a call passes `None` as `terrain_height_fn`; an explicit
+    /// function still takes precedence.
+    pub fn set_terrain_source(&mut self, source: impl Fn(f32, f32) -> f32 + Send + 'static) {
+        self.terrain_source = Some(Box::new(source));
+    }
+
+    /// Drop the stored terrain query (Floating falls back to the fixed path again)
+    pub fn clear_terrain_source(&mut self) {
+        self.terrain_source = None;
+    }
+
+    /// Explicit terrain query if given, otherwise the stored source
+    fn terrain_query<'a, F>(
+        &'a self,
+        terrain_height_fn: Option<&'a F>,
+    ) -> Option<&'a dyn Fn(f32, f32) -> f32>
+    where
+        F: Fn(f32, f32) -> f32,
+    {
+        match terrain_height_fn {
+            Some(get_height) => Some(get_height),
+            None => self
+                .terrain_source
+                .as_deref()
+                .map(|source| source as &dyn Fn(f32, f32) -> f32),
+        }
+    }
+
+    /// Active camera preset (the blend target while blending)
+    pub fn preset(&self) -> &CameraPreset {
+        &self.preset
+    }
+
+    /// Active camera preset, for tweaking its parameters live (takes effect next frame)
+    pub fn preset_mut(&mut self) -> &mut CameraPreset {
+        &mut self.preset
+    }
+
+    /// Switch preset immediately (no blend)
+    pub fn set_preset(&mut self, preset: CameraPreset) {
+        self.preset = preset;
+        self.blend = None;
+    }
+
+    /// Replace camera shake configuration (resets any active shake)
+    pub fn set_shake_config(&mut self, config: CameraShakeConfig) {
+        self.shake = CameraShake::new(config);
+    }
+
+    /// Trigger a transient camera shake (e.g. on a detected beat)
+    ///
+    /// # Arguments
+    /// * `intensity` - Shake strength (1.0 = configured amplitudes)
+    /// * `time_s` - Trigger time (seconds, same clock as `create_view_proj_matrix`)
+    pub fn trigger_shake(&mut self, intensity: f32, time_s: f32) {
+        self.shake.trigger(intensity, time_s);
+    }
+
+    /// Shake on bass onsets (`CameraShakeConfig::bass_onset_threshold`)
+    ///
+    /// Call once per simulation step of `dt_s` with the bass band level.
+    pub fn react_to_bass(&mut self, low: f32, dt_s: f32, time_s: f32) {
+        self.shake.on_bass(low, dt_s, time_s);
+    }
+
+    /// Current shake offsets to add to (eye, target)
+    pub fn shake_offset(&self, time_s: f32) -> (Vec3, Vec3) {
+        self.shake.offset(time_s)
+    }
+
+    /// Switch to a new preset, cross-fading from the current one
+    ///
+    /// # Arguments
+    /// * `preset` - Preset to settle on once the blend completes
+    /// * `start_time_s` - Time the blend starts (seconds, same clock as `compute_position_and_target`)
+    /// * `duration_s` - Blend duration (seconds, 0 = instant switch)
+    ///
+    /// Re-triggering while a blend is still running fades from the pose the camera
+    /// has at `start_time_s` (held still), so the view never jumps.
+    pub fn blend_to(&mut self, preset: CameraPreset, start_time_s: f32, duration_s: f32) {
+        let blending = self
+            .blend
+            .as_ref()
+            .is_some_and(|blend| blend.weight(start_time_s) < 1.0);
+        let from = if blending {
+            let (eye, target) = self.compute_position_and_target(start_time_s, None::<TerrainFn>);
+            self.preset = preset;
+            BlendSource::Pose(eye, target)
+        } else {
+            BlendSource::Preset(std::mem::replace(&mut self.preset, preset))
+        };
+        self.blend = Some(CameraBlend {
+            from,
+            start_time_s,
+            duration_s,
+        });
+    }
+
+    /// Integrate user input into the manual camera (no-op for other presets)
+    ///
+    /// # Arguments
+    /// * `forward` / `right` / `up` - Movement input (-1 to 1, scaled by camera speed)
+    /// * `yaw_delta` - Heading change (radians, positive turns toward +X)
+    /// * `pitch_delta` - Elevation change (radians, positive looks up)
+    /// * `dt` - Frame time (seconds)
+    pub fn apply_input(
+        &mut self,
+        forward: f32,
+        right: f32,
+        up: f32,
+        yaw_delta: f32,
+        pitch_delta: f32,
+        dt: f32,
+    ) {
+        let CameraPreset::Manual(state) = &mut self.preset else {
+            return;
+        };
+
+        // Rotate first so movement follows the new view direction
+        state.yaw_rad += yaw_delta;
+        state.pitch_rad =
+            (state.pitch_rad + pitch_delta).clamp(-MANUAL_PITCH_LIMIT_RAD, MANUAL_PITCH_LIMIT_RAD);
+
+        // Forward flies along the view direction, strafing stays level, up is world Y
+        let view_dir = manual_view_direction(state);
+        let right_dir = view_dir.cross(Vec3::Y).normalize_or_zero();
+        let velocity =
+            (view_dir * forward + right_dir * right + Vec3::Y * up) * state.speed_m_per_s;
+
+        state.position = (Vec3::from_array(state.position) + velocity * dt).to_array();
+    }
+
+    /// Camera position and look-at target using the stored terrain source
+    pub fn position_and_target(&self, time_s: f32) -> (Vec3, Vec3) {
+        self.compute_position_and_target(time_s, None::<fn(f32, f32) -> f32>)
     }
 
     /// Compute camera position and look-at target for given time
@@ -25,6 +355,7 @@ impl CameraSystem {
     /// # Arguments
     /// * `time_s` - Current time in seconds
     /// * `terrain_height_fn` - Optional function to query terrain height at (x, z) world position
+    ///   (falls back to the stored terrain source if None)
     ///
     /// # Returns
     /// Tuple of (eye_position, target_position)
@@ -36,22 +367,163 @@ impl CameraSystem {
     where
         F: Fn(f32, f32) -> f32,
     {
-        match &self.preset {
+        let terrain = self.terrain_query(terrain_height_fn.as_ref());
+        let (eye, target) = Self::compute_preset(&self.preset, time_s, terrain.as_ref());
+
+        // Cross-fade from the previous preset while a blend is active
+        if let Some(ref blend) = self.blend {
+            let w = blend.weight(time_s);
+            if w < 1.0 {
+                let (from_eye, from_target) = match blend.from {
+                    BlendSource::Preset(ref from) => {
+                        Self::compute_preset(from, time_s, terrain.as_ref())
+                    }
+                    BlendSource::Pose(eye, target) => (eye, target),
+                };
+                return (from_eye.lerp(eye, w), from_target.lerp(target, w));
+            }
+        }
+
+        (eye, target)
+    }
+
+    /// Sample the camera path at evenly spaced times (e.g. for trajectory previews)
+    ///
+    /// # Arguments
+    /// * `start_s` / `end_s` - Time range in seconds (both endpoints included)
+    /// * `steps` - Number of samples (1 = start only, 0 = empty)
+    /// * `terrain_height_fn` - Optional terrain query (required for Floating preset)
+    ///
+    /// # Returns
+    /// Vector of (eye_position, target_position) pairs
+    pub fn sample_path<F>(
+        &self,
+        start_s: f32,
+        end_s: f32,
+        steps: usize,
+        terrain_height_fn: Option<F>,
+    ) -> Vec<(Vec3, Vec3)>
+    where
+        F: Fn(f32, f32) -> f32,
+    {
+        let step_s = if steps > 1 {
+            (end_s - start_s) / (steps - 1) as f32
+        } else {
+            0.0
+        };
+
+        (0..steps)
+            .map(|i| {
+                let time_s = start_s + step_s * i as f32;
+                self.compute_position_and_target(time_s, terrain_height_fn.as_ref())
+            })
+            .collect()
+    }
+
+    /// Export the camera path as a JSON array for external tools (e.g. Blender)
+    ///
+    /// Samples `(end_s - start_s) × fps` frames starting at `start_s`, one frame period apart,
+    /// as `[{"t": s, "eye": [x, y, z], "target": [x, y, z], "fov": degrees}, ...]`.
+    /// The Floating preset follows `terrain_height_fn`, else the stored terrain source,
+    /// else flat terrain at height 0.
+    /// Camera shake is not included.
+    pub fn export_path<F>(
+        &self,
+        start_s: f32,
+        end_s: f32,
+        fps: u32,
+        render_config: &RenderConfig,
+        terrain_height_fn: Option<F>,
+    ) -> String
+    where
+        F: Fn(f32, f32) -> f32,
+    {
+        let samples = match self.terrain_query(terrain_height_fn.as_ref()) {
+            Some(get_height) => self.path_samples(start_s, end_s, fps, render_config, get_height),
+            None => self.path_samples(start_s, end_s, fps, render_config, |_x: f32, _z: f32| 0.0),
+        };
+        serde_json::to_string_pretty(&samples).expect("Camera samples always serialize")
+    }
+
+    /// Camera samples one frame period apart over [start_s, end_s)
+    fn path_samples<F>(
+        &self,
+        start_s: f32,
+        end_s: f32,
+        fps: u32,
+        render_config: &RenderConfig,
+        get_height: F,
+    ) -> Vec<CameraPathSample>
+    where
+        F: Fn(f32, f32) -> f32,
+    {
+        let fps = fps.max(1);
+        let count = ((end_s - start_s) * fps as f32).round().max(0.0) as usize;
+
+        (0..count)
+            .map(|i| {
+                let t = start_s + i as f32 / fps as f32;
+                let (eye, target) = self.compute_position_and_target(t, Some(&get_height));
+                CameraPathSample {
+                    t,
+                    eye: eye.to_array(),
+                    target: target.to_array(),
+                    fov: self.fov_at(t, eye, render_config, Some(&get_height)),
+                }
+            })
+            .collect()
+    }
+
+    /// Speed-dependent FOV at `time_s` for camera position `eye` (degrees)
+    ///
+    /// Speed is estimated by finite-differencing the path over the previous frame.
+    fn fov_at<F>(
+        &self,
+        time_s: f32,
+        eye: Vec3,
+        render_config: &RenderConfig,
+        terrain_height_fn: Option<&F>,
+    ) -> f32
+    where
+        F: Fn(f32, f32) -> f32,
+    {
+        let (prev_eye, _) =
+            self.compute_position_and_target(time_s - SPEED_ESTIMATE_DT_S, terrain_height_fn);
+        let speed_m_per_s = eye.distance(prev_eye) / SPEED_ESTIMATE_DT_S;
+        render_config.fov_for_speed(speed_m_per_s)
+    }
+
+    /// Compute position and target for a single preset (no blending)
+    fn compute_preset<F>(
+        preset: &CameraPreset,
+        time_s: f32,
+        terrain_height_fn: Option<&F>,
+    ) -> (Vec3, Vec3)
+    where
+        F: Fn(f32, f32) -> f32,
+    {
+        match preset {
             CameraPreset::Cinematic(params) => Self::compute_cinematic_path(params, time_s),
             CameraPreset::Basic(params) => Self::compute_basic_path(params, time_s),
             CameraPreset::Fixed(params) => Self::compute_fixed_path(params, time_s),
             CameraPreset::Floating(params) => {
-                if let Some(ref get_height) = terrain_height_fn {
+                if let Some(get_height) = terrain_height_fn {
                     Self::compute_floating_path(params, time_s, get_height)
                 } else {
                     // Fallback if no terrain query available
                     Self::compute_fixed_path(&FixedCamera::default(), time_s)
                 }
             }
+            CameraPreset::Orbit(params) => Self::compute_orbit_path(params, time_s),
+            CameraPreset::Keyframed(keyframes) => Self::compute_keyframed_path(keyframes, time_s),
+            CameraPreset::Manual(state) => Self::compute_manual_path(state),
         }
     }
 
     /// Compute fixed camera path (moves forward at constant velocity)
+    ///
+    /// Moving the eye itself (rather than reporting a separate velocity) is what flows the
+    /// grid: the terrain follows the returned position like any other preset's.
     fn compute_fixed_path(p: &FixedCamera, time_s: f32) -> (Vec3, Vec3) {
         // Camera moves forward through world space
         let eye = Vec3::new(
@@ -127,19 +599,106 @@ impl CameraSystem {
         (eye, target)
     }
 
-    /// Compute basic camera path (straight line, constant altitude)
+    /// Compute basic camera path (straight line with optional sway, constant altitude)
     fn compute_basic_path(p: &BasicCameraPath, time_s: f32) -> (Vec3, Vec3) {
-        // Simple straight-line motion
-        let x = 0.0; // Stay centered
+        // Lateral sway around the center line (centered when the amplitude is 0)
+        let sway_x =
+            |t: f32| (t * p.sway_freq_hz * std::f32::consts::TAU).sin() * p.sway_amplitude_m;
+
+        let x = sway_x(time_s);
         let y = p.altitude_m; // Constant altitude
         let z = time_s * p.forward_speed_m_per_s; // Linear forward motion
 
         let eye = Vec3::new(x, y, z);
 
         // Look slightly down toward the ocean surface to see motion
-        // Target is ahead and below eye level (creates ~15-20 degree downward angle)
-        let target_y = y * 0.6; // Look at point 40% lower than camera
-        let target = Vec3::new(x, target_y, z + p.look_ahead_m);
+        let target_y = y * (1.0 - p.look_down_fraction);
+
+        // Aim at where the path will be after look_ahead_m, so the view leans into the curve
+        let look_ahead_s = if p.forward_speed_m_per_s > 0.0 {
+            p.look_ahead_m / p.forward_speed_m_per_s
+        } else {
+            0.0
+        };
+        let target = Vec3::new(sway_x(time_s + look_ahead_s), target_y, z + p.look_ahead_m);
+
+        (eye, target)
+    }
+
+    /// Compute orbit camera path (circles center point, looking inward)
+    fn compute_orbit_path(p: &OrbitCamera, time_s: f32) -> (Vec3, Vec3) {
+        let center = Vec3::from_array(p.center);
+        let angle = time_s * p.angular_speed_rad_per_s;
+
+        // Circle in XZ plane at constant radius, raised by altitude
+        let eye = center
+            + Vec3::new(
+                angle.cos() * p.radius_m,
+                p.altitude_m,
+                angle.sin() * p.radius_m,
+            );
+
+        // Always look at the center point
+        (eye, center)
+    }
+
+    /// Compute keyframed camera path (Catmull-Rom spline through authored keyframes)
+    ///
+    /// Keyframes must be sorted by time. Holds the first keyframe before it starts
+    /// and the last keyframe after it ends.
+    fn compute_keyframed_path(keyframes: &[CameraKeyframe], time_s: f32) -> (Vec3, Vec3) {
+        let (Some(first), Some(last)) = (keyframes.first(), keyframes.last()) else {
+            // Fallback if no keyframes authored
+            return Self::compute_fixed_path(&FixedCamera::default(), time_s);
+        };
+
+        // Clamp outside the authored time range
+        if time_s <= first.time_s {
+            return (
+                Vec3::from_array(first.position),
+                Vec3::from_array(first.target),
+            );
+        }
+        if time_s >= last.time_s {
+            return (
+                Vec3::from_array(last.position),
+                Vec3::from_array(last.target),
+            );
+        }
+
+        // Segment [k1, k2] containing time_s (k1.time_s <= time_s < k2.time_s)
+        let i = keyframes.partition_point(|k| k.time_s <= time_s) - 1;
+        let k1 = &keyframes[i];
+        let k2 = &keyframes[i + 1];
+
+        // Neighbor control points (duplicate endpoints at the ends of the path)
+        let k0 = &keyframes[i.saturating_sub(1)];
+        let k3 = &keyframes[(i + 2).min(keyframes.len() - 1)];
+
+        let u = (time_s - k1.time_s) / (k2.time_s - k1.time_s);
+
+        let eye = catmull_rom(
+            Vec3::from_array(k0.position),
+            Vec3::from_array(k1.position),
+            Vec3::from_array(k2.position),
+            Vec3::from_array(k3.position),
+            u,
+        );
+        let target = catmull_rom(
+            Vec3::from_array(k0.target),
+            Vec3::from_array(k1.target),
+            Vec3::from_array(k2.target),
+            Vec3::from_array(k3.target),
+            u,
+        );
+
+        (eye, target)
+    }
+
+    /// Compute manual camera path (user-driven position, target from yaw/pitch)
+    fn compute_manual_path(state: &ManualCameraState) -> (Vec3, Vec3) {
+        let eye = Vec3::from_array(state.position);
+        let target = eye + manual_view_direction(state);
 
         (eye, target)
     }
@@ -153,6 +712,10 @@ impl CameraSystem {
     ///
     /// # Returns
     /// Tuple of (view_proj_matrix, camera_position)
+    ///
+    /// Camera shake is applied to the view only; the returned position is unshaken
+    /// so the terrain grid stays steady. FOV widens with camera speed
+    /// (see `RenderConfig::fov_for_speed`).
     pub fn create_view_proj_matrix<F>(
         &self,
         time_s: f32,
@@ -162,23 +725,69 @@ impl CameraSystem {
     where
         F: Fn(f32, f32) -> f32,
     {
-        let (eye, target) = self.compute_position_and_target(time_s, terrain_height_fn);
+        let (eye, target) = self.compute_position_and_target(time_s, terrain_height_fn.as_ref());
+        let (eye_shake, target_shake) = self.shake.offset(time_s);
+
+        let fov_degrees = self.fov_at(time_s, eye, render_config, terrain_height_fn.as_ref());
 
         // Always keep Y as up vector (camera never rolls)
         let up = Vec3::Y;
 
-        let view = Mat4::look_at_rh(eye, target, up);
-        let proj = Mat4::perspective_rh(
-            render_config.fov_degrees.to_radians(),
-            render_config.aspect_ratio(),
-            render_config.near_plane_m,
-            render_config.far_plane_m,
-        );
+        let view = Mat4::look_at_rh(eye + eye_shake, target + target_shake, up);
+        let far_plane_m = self.far_plane_m(eye, target, render_config);
+        let proj = perspective(fov_degrees, render_config, far_plane_m);
 
         (proj * view, eye)
     }
 }
 
+/// Perspective projection at vertical `fov_degrees`, with X scaled by `anamorphic_squeeze`
+pub fn projection_matrix(fov_degrees: f32, render_config: &RenderConfig) -> Mat4 {
+    perspective(fov_degrees, render_config, render_config.far_plane_m)
+}
+
+/// `projection_matrix` with an explicit far plane
+fn perspective(fov_degrees: f32, render_config: &RenderConfig, far_plane_m: f32) -> Mat4 {
+    let mut proj = Mat4::perspective_rh(
+        fov_degrees.to_radians(),
+        render_config.aspect_ratio(),
+        render_config.near_plane_m,
+        far_plane_m,
+    );
+    proj.x_axis *= render_config.anamorphic_squeeze;
+    proj
+}
+
+/// Far plane just past the visible grid window (meters)
+///
+/// The grid is a `grid_extent_m` square centered under the eye at sea level, so its
+/// farthest corner is half the diagonal away horizontally and the eye's altitude
+/// vertically; the look-at `target` is kept in range too. Scaled by a fixed margin.
+pub fn fitted_far_plane_m(eye: Vec3, target: Vec3, grid_extent_m: f32) -> f32 {
+    let half_diagonal_m = grid_extent_m * std::f32::consts::FRAC_1_SQRT_2;
+    let corner_m = half_diagonal_m.hypot(eye.y);
+    corner_m.max(eye.distance(target)) * FAR_PLANE_FIT_MARGIN
+}
+
+/// Unit view direction for manual camera yaw/pitch
+fn manual_view_direction(state: &ManualCameraState) -> Vec3 {
+    let (sin_yaw, cos_yaw) = state.yaw_rad.sin_cos();
+    let (sin_pitch, cos_pitch) = state.pitch_rad.sin_cos();
+
+    Vec3::new(sin_yaw * cos_pitch, sin_pitch, cos_yaw * cos_pitch)
+}
+
+/// Uniform Catmull-Rom spline between p1 (u=0) and p2 (u=1)
+fn catmull_rom(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3, u: f32) -> Vec3 {
+    let u2 = u * u;
+    let u3 = u2 * u;
+
+    0.5 * (2.0 * p1
+        + (p2 - p0) * u
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * u2
+        + (3.0 * p1 - p0 - 3.0 * p2 + p3) * u3)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -221,6 +830,70 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_preset_mut_changes_the_path_live() {
+        let mut camera = CameraSystem::new(CameraPreset::Cinematic(CameraJourney::default()));
+        let (before, _) = camera.compute_position_and_target(1.0, None::<TerrainFn>);
+
+        let CameraPreset::Cinematic(ref mut journey) = camera.preset_mut() else {
+            panic!("Expected the cinematic preset");
+        };
+        journey.z_forward_speed_m_per_s *= 2.0;
+        let speed = journey.z_forward_speed_m_per_s;
+
+        let (after, _) = camera.compute_position_and_target(1.0, None::<TerrainFn>);
+        assert!(
+            (after.z - before.z - speed / 2.0).abs() < 1e-3,
+            "{} -> {}",
+            before.z,
+            after.z
+        );
+        let CameraPreset::Cinematic(journey) = camera.preset() else {
+            panic!("Expected the cinematic preset");
+        };
+        assert_eq!(journey.z_forward_speed_m_per_s, speed);
+    }
+
+    #[test]
+    fn test_fixed_camera_velocity_flows_the_waves() {
+        use crate::ocean::OceanGrid;
+        use crate::params::OceanPhysics;
+
+        let physics = OceanPhysics {
+            grid_size: 16,
+            ..OceanPhysics::default()
+        };
+        // Camera-relative Z of one vertex over five 60 Hz frames
+        let flow = |simulated_velocity| {
+            let camera = CameraSystem::new(CameraPreset::Fixed(FixedCamera {
+                simulated_velocity,
+                ..FixedCamera::default()
+            }));
+            let mut grid = OceanGrid::new(&physics);
+            (0..5)
+                .map(|frame| {
+                    let time_s = frame as f32 / 60.0;
+                    let (eye, _) = camera.compute_position_and_target(time_s, None::<TerrainFn>);
+                    grid.update(time_s, 2.0, 0.1, eye, &physics);
+                    grid.vertices[100].position[2]
+                })
+                .collect::<Vec<f32>>()
+        };
+
+        let still = flow(0.0);
+        assert!(still.iter().all(|&z| z == still[0]), "Moved: {:?}", still);
+
+        // Waves stream toward the camera at the simulated speed (2.5 m per frame)
+        let moving = flow(150.0);
+        assert!(
+            moving
+                .windows(2)
+                .all(|pair| (pair[0] - pair[1] - 2.5).abs() < 1e-3),
+            "Surface didn't flow: {:?}",
+            moving
+        );
+    }
+
     #[test]
     fn test_basic_camera_straight_line() {
         let params = BasicCameraPath::default();
@@ -243,6 +916,564 @@ mod tests {
         assert_eq!(target1.z, eye1.z + params.look_ahead_m);
     }
 
+    #[test]
+    fn test_basic_camera_defaults_match_fixed_tilt() {
+        // No sway and 40% look-down: the original straight path aimed 40% below the camera
+        let params = BasicCameraPath {
+            look_down_fraction: 0.4,
+            sway_amplitude_m: 0.0,
+            ..BasicCameraPath::default()
+        };
+        let camera = CameraSystem::new(CameraPreset::Basic(params.clone()));
+        for t in [0.0, 0.5, 3.0, 20.0] {
+            let (eye, target) = camera.compute_position_and_target(t, None::<TerrainFn>);
+            let y = params.altitude_m;
+            let z = t * params.forward_speed_m_per_s;
+            assert_eq!(eye, Vec3::new(0.0, y, z));
+            assert!(
+                target.abs_diff_eq(Vec3::new(0.0, y * 0.6, z + params.look_ahead_m), 1e-4),
+                "Target {} at t={}",
+                target,
+                t
+            );
+        }
+
+        // Swaying: the camera leaves the center line and aims at the path ahead
+        let swaying = BasicCameraPath {
+            sway_amplitude_m: 12.0,
+            sway_freq_hz: 0.25,
+            ..params
+        };
+        let camera = CameraSystem::new(CameraPreset::Basic(swaying.clone()));
+        let (eye, target) = camera.compute_position_and_target(1.0, None::<TerrainFn>);
+        assert!((eye.x - 12.0).abs() < 1e-4, "Quarter period: {}", eye.x);
+        let look_ahead_s = swaying.look_ahead_m / swaying.forward_speed_m_per_s;
+        let (ahead, _) = camera.compute_position_and_target(1.0 + look_ahead_s, None::<TerrainFn>);
+        assert!((target.x - ahead.x).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_orbit_camera_returns_after_period() {
+        let params = OrbitCamera::default();
+        let camera = CameraSystem::new(CameraPreset::Orbit(params.clone()));
+        let center = Vec3::from_array(params.center);
+        let period = params.period_s();
+
+        for t in [0.0, 1.7, 12.5] {
+            let (eye_a, target_a) = camera.compute_position_and_target(t, None::<TerrainFn>);
+            let (eye_b, target_b) =
+                camera.compute_position_and_target(t + period, None::<TerrainFn>);
+
+            // Eye returns to the same place after one revolution
+            assert!(
+                eye_a.distance(eye_b) < 0.01,
+                "Eye {:?} != {:?} after one period at t={}",
+                eye_a,
+                eye_b,
+                t
+            );
+
+            // Always looking at the center, from the configured radius and altitude
+            assert_eq!(target_a, center);
+            assert_eq!(target_b, center);
+            let offset = eye_a - center;
+            assert!((offset.y - params.altitude_m).abs() < 1e-3);
+            assert!((Vec3::new(offset.x, 0.0, offset.z).length() - params.radius_m).abs() < 1e-2);
+        }
+    }
+
+    fn test_keyframes() -> Vec<CameraKeyframe> {
+        vec![
+            CameraKeyframe {
+                time_s: 0.0,
+                position: [0.0, 50.0, 0.0],
+                target: [0.0, 0.0, 100.0],
+            },
+            CameraKeyframe {
+                time_s: 2.0,
+                position: [40.0, 80.0, 200.0],
+                target: [0.0, 20.0, 300.0],
+            },
+            CameraKeyframe {
+                time_s: 5.0,
+                position: [-30.0, 60.0, 500.0],
+                target: [-50.0, 10.0, 650.0],
+            },
+            CameraKeyframe {
+                time_s: 6.0,
+                position: [0.0, 40.0, 600.0],
+                target: [0.0, 0.0, 800.0],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_keyframed_camera_exact_at_keyframes() {
+        let keyframes = test_keyframes();
+        let camera = CameraSystem::new(CameraPreset::Keyframed(keyframes.clone()));
+
+        for k in &keyframes {
+            let (eye, target) = camera.compute_position_and_target(k.time_s, None::<TerrainFn>);
+            assert!(eye.distance(Vec3::from_array(k.position)) < 1e-3);
+            assert!(target.distance(Vec3::from_array(k.target)) < 1e-3);
+        }
+
+        // Clamped before the first and after the last keyframe
+        let first = &keyframes[0];
+        let last = &keyframes[keyframes.len() - 1];
+        let (eye, target) = camera.compute_position_and_target(-3.0, None::<TerrainFn>);
+        assert_eq!(eye, Vec3::from_array(first.position));
+        assert_eq!(target, Vec3::from_array(first.target));
+        let (eye, target) = camera.compute_position_and_target(100.0, None::<TerrainFn>);
+        assert_eq!(eye, Vec3::from_array(last.position));
+        assert_eq!(target, Vec3::from_array(last.target));
+    }
+
+    #[test]
+    fn test_keyframed_camera_monotonic_time() {
+        // Keyframes given out of order are sorted by time on load
+        let json = r#"[
+            {"time_s": 2.0, "position": [0.0, 50.0, 200.0], "target": [0.0, 0.0, 300.0]},
+            {"time_s": 0.0, "position": [0.0, 50.0, 0.0], "target": [0.0, 0.0, 100.0]},
+            {"time_s": 1.0, "position": [0.0, 50.0, 100.0], "target": [0.0, 0.0, 200.0]},
+            {"time_s": 3.0, "position": [0.0, 50.0, 300.0], "target": [0.0, 0.0, 400.0]}
+        ]"#;
+        let keyframes = CameraKeyframe::parse_json(json).unwrap();
+        assert!(keyframes.windows(2).all(|w| w[0].time_s <= w[1].time_s));
+
+        // Evenly spaced keyframes along +Z: camera only ever moves forward
+        let camera = CameraSystem::new(CameraPreset::Keyframed(keyframes));
+        let mut last_z = f32::NEG_INFINITY;
+        for step in 0..=60 {
+            let t = step as f32 * 0.05;
+            let (eye, _) = camera.compute_position_and_target(t, None::<TerrainFn>);
+            assert!(eye.z >= last_z, "Camera moved backward at t={}", t);
+            last_z = eye.z;
+        }
+
+        assert!(CameraKeyframe::parse_json("[]").is_err());
+    }
+
+    #[test]
+    fn test_blend_retrigger_is_continuous() {
+        let mut camera = CameraSystem::new(CameraPreset::Fixed(FixedCamera::default()));
+        camera.blend_to(CameraPreset::Orbit(OrbitCamera::default()), 0.0, 2.0);
+
+        // Halfway through, switch again: the view must not jump
+        let t = 1.0;
+        let before = camera.compute_position_and_target(t, None::<TerrainFn>);
+        camera.blend_to(CameraPreset::Basic(BasicCameraPath::default()), t, 2.0);
+        let after = camera.compute_position_and_target(t, None::<TerrainFn>);
+        assert!(before.0.distance(after.0) < 1e-3);
+        assert!(before.1.distance(after.1) < 1e-3);
+
+        // And it still settles on the newest preset
+        let basic = CameraSystem::new(CameraPreset::Basic(BasicCameraPath::default()));
+        let end = camera.compute_position_and_target(t + 2.0, None::<TerrainFn>);
+        let expected = basic.compute_position_and_target(t + 2.0, None::<TerrainFn>);
+        assert!(end.0.distance(expected.0) < 1e-3);
+    }
+
+    #[test]
+    fn test_preset_blend() {
+        let old = CameraPreset::Fixed(FixedCamera::default());
+        let new = CameraPreset::Orbit(OrbitCamera::default());
+        let old_camera = CameraSystem::new(old.clone());
+        let new_camera = CameraSystem::new(new.clone());
+
+        let mut camera = CameraSystem::new(old);
+        let (start, duration) = (3.0, 2.0);
+        camera.blend_to(new, start, duration);
+
+        // At blend start: old preset
+        let (eye, target) = camera.compute_position_and_target(start, None::<TerrainFn>);
+        let (old_eye, old_target) =
+            old_camera.compute_position_and_target(start, None::<TerrainFn>);
+        assert!(eye.distance(old_eye) < 1e-3);
+        assert!(target.distance(old_target) < 1e-3);
+
+        // At blend end (and after): new preset
+        for t in [start + duration, start + duration + 5.0] {
+            let (eye, target) = camera.compute_position_and_target(t, None::<TerrainFn>);
+            let (new_eye, new_target) =
+                new_camera.compute_position_and_target(t, None::<TerrainFn>);
+            assert!(eye.distance(new_eye) < 1e-3);
+            assert!(target.distance(new_target) < 1e-3);
+        }
+
+        // In between: bounded by the two presets
+        for step in 1..10 {
+            let t = start + duration * step as f32 / 10.0;
+            let (eye, target) = camera.compute_position_and_target(t, None::<TerrainFn>);
+            let (a_eye, a_target) = old_camera.compute_position_and_target(t, None::<TerrainFn>);
+            let (b_eye, b_target) = new_camera.compute_position_and_target(t, None::<TerrainFn>);
+            let eps = Vec3::splat(1e-3);
+            assert!(
+                eye.cmpge(a_eye.min(b_eye) - eps).all() && eye.cmple(a_eye.max(b_eye) + eps).all()
+            );
+            assert!(
+                target.cmpge(a_target.min(b_target) - eps).all()
+                    && target.cmple(a_target.max(b_target) + eps).all()
+            );
+        }
+    }
+
+    #[test]
+    fn test_manual_camera_forward_follows_view() {
+        let state = ManualCameraState {
+            yaw_rad: 0.7,
+            pitch_rad: -0.3,
+            ..ManualCameraState::default()
+        };
+        let mut camera = CameraSystem::new(CameraPreset::Manual(state));
+
+        let (eye0, target0) = camera.compute_position_and_target(0.0, None::<TerrainFn>);
+        let view_dir = (target0 - eye0).normalize();
+
+        camera.apply_input(1.0, 0.0, 0.0, 0.0, 0.0, 0.5);
+        let (eye1, _) = camera.compute_position_and_target(0.0, None::<TerrainFn>);
+
+        // Moved speed * dt along the view direction
+        let moved = eye1 - eye0;
+        assert!((moved.length() - 100.0 * 0.5).abs() < 1e-2);
+        assert!(moved.normalize().dot(view_dir) > 0.9999);
+    }
+
+    #[test]
+    fn test_manual_camera_pitch_clamp() {
+        let mut camera = CameraSystem::new(CameraPreset::Manual(ManualCameraState::default()));
+
+        camera.apply_input(0.0, 0.0, 0.0, 0.0, 10.0, 0.016);
+        let (eye, target) = camera.compute_position_and_target(0.0, None::<TerrainFn>);
+        let pitch = (target - eye).normalize().y.asin();
+        assert!((pitch - MANUAL_PITCH_LIMIT_RAD).abs() < 1e-3);
+
+        camera.apply_input(0.0, 0.0, 0.0, 0.0, -20.0, 0.016);
+        let (eye, target) = camera.compute_position_and_target(0.0, None::<TerrainFn>);
+        let pitch = (target - eye).normalize().y.asin();
+        assert!((pitch + MANUAL_PITCH_LIMIT_RAD).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_bass_onset_triggers_shake_once() {
+        let config = CameraShakeConfig {
+            bass_onset_threshold: 0.3,
+            ..CameraShakeConfig::default()
+        };
+        let dt = 1.0 / 120.0;
+        let run = |config: &CameraShakeConfig, levels: &[(f32, usize)]| {
+            let mut camera = CameraSystem::new(CameraPreset::default());
+            camera.set_shake_config(config.clone());
+            // Steps at which a shake was triggered
+            let steps = levels
+                .iter()
+                .flat_map(|&(low, steps)| std::iter::repeat_n(low, steps));
+            let mut triggers = Vec::new();
+            for (step, low) in steps.enumerate() {
+                let before = camera.shake.trigger_time_s;
+                camera.react_to_bass(low, dt, step as f32 * dt);
+                if camera.shake.trigger_time_s != before {
+                    triggers.push(step);
+                }
+            }
+            triggers
+        };
+
+        // Steady bass settles the average without shaking
+        assert!(run(&config, &[(0.2, 600)]).is_empty());
+
+        // A kick over the settled level shakes once, even if held
+        let kick = [(0.2, 600), (1.0, 120)];
+        assert_eq!(run(&config, &kick), vec![600]);
+
+        // Two kicks with a gap between them shake twice
+        let kicks = [(0.2, 600), (1.0, 12), (0.2, 120), (1.0, 12)];
+        assert_eq!(run(&config, &kicks).len(), 2);
+
+        // Default config: no audio-triggered shake
+        assert!(run(&CameraShakeConfig::default(), &kick).is_empty());
+    }
+
+    #[test]
+    fn test_camera_shake_config_validation() {
+        assert_eq!(CameraShakeConfig::default().validate(), Ok(()));
+        for invalid in [
+            CameraShakeConfig {
+                decay_time_constant_s: 0.0,
+                ..CameraShakeConfig::default()
+            },
+            CameraShakeConfig {
+                decay_time_constant_s: -0.1,
+                ..CameraShakeConfig::default()
+            },
+            CameraShakeConfig {
+                bass_onset_threshold: -1.0,
+                ..CameraShakeConfig::default()
+            },
+        ] {
+            assert!(invalid.validate().is_err(), "Accepted {:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_camera_shake_decay() {
+        let config = CameraShakeConfig::default();
+        let mut camera = CameraSystem::new(CameraPreset::default());
+        camera.set_shake_config(config.clone());
+
+        // Idle before trigger
+        let (eye, target) = camera.shake_offset(1.0);
+        assert_eq!(eye, Vec3::ZERO);
+        assert_eq!(target, Vec3::ZERO);
+
+        camera.trigger_shake(1.0, 2.0);
+
+        // Still idle before the trigger time, active immediately after
+        assert_eq!(camera.shake_offset(1.9).0, Vec3::ZERO);
+        let (eye, target) = camera.shake_offset(2.0);
+        assert!(eye.length() > 0.1);
+        assert!(target.length() > 0.1);
+
+        // Decayed below 1% of full amplitude after five time constants
+        let settled = 2.0 + 5.0 * config.decay_time_constant_s;
+        let (eye, target) = camera.shake_offset(settled);
+        assert!(eye.length() < 0.01 * config.position_amplitude_m * 3f32.sqrt());
+        assert!(target.length() < 0.01 * config.target_amplitude_m * 3f32.sqrt());
+    }
+
+    #[test]
+    fn test_anamorphic_squeeze_scales_only_horizontal() {
+        let plain = RenderConfig::default();
+        let squeezed = RenderConfig {
+            anamorphic_squeeze: 2.0,
+            ..RenderConfig::default()
+        };
+        let fov = plain.fov_degrees;
+
+        // Default squeeze reproduces the plain perspective projection
+        assert_eq!(
+            projection_matrix(fov, &plain),
+            Mat4::perspective_rh(
+                fov.to_radians(),
+                plain.aspect_ratio(),
+                plain.near_plane_m,
+                plain.far_plane_m,
+            )
+        );
+
+        let base = projection_matrix(fov, &plain);
+        let proj = projection_matrix(fov, &squeezed);
+        assert_eq!(proj.x_axis, base.x_axis * 2.0);
+        assert_eq!(proj.y_axis, base.y_axis);
+        assert_eq!(proj.z_axis, base.z_axis);
+        assert_eq!(proj.w_axis, base.w_axis);
+
+        // Same through the camera: only the clip-space X row of view-proj doubles
+        let camera = CameraSystem::new(CameraPreset::Fixed(FixedCamera::default()));
+        let (base_view_proj, _) = camera.create_view_proj_matrix(1.0, &plain, None::<TerrainFn>);
+        let (view_proj, _) = camera.create_view_proj_matrix(1.0, &squeezed, None::<TerrainFn>);
+        assert!(view_proj
+            .row(0)
+            .abs_diff_eq(base_view_proj.row(0) * 2.0, 1e-6));
+        for row in 1..4 {
+            assert_eq!(view_proj.row(row), base_view_proj.row(row));
+        }
+    }
+
+    #[test]
+    fn test_far_plane_auto_fit_covers_grid_corners() {
+        let extent = 2048.0;
+        let half = extent / 2.0;
+        let mut render_config = RenderConfig {
+            far_plane_auto_fit: true,
+            ..RenderConfig::default()
+        };
+
+        for eye in [
+            Vec3::new(0.0, 101.0, 0.0),
+            Vec3::new(350.0, 20.0, -4000.0),
+            Vec3::new(-12.0, 900.0, 75.0),
+        ] {
+            let target = eye + Vec3::new(0.0, -30.0, 200.0);
+            let farthest = [(-1.0, -1.0), (-1.0, 1.0), (1.0, -1.0), (1.0, 1.0)]
+                .iter()
+                .map(|(sx, sz)| eye.distance(Vec3::new(eye.x + sx * half, 0.0, eye.z + sz * half)))
+                .fold(0.0, f32::max);
+
+            let mut camera = CameraSystem::new(CameraPreset::Fixed(FixedCamera::default()));
+            assert_eq!(
+                camera.far_plane_m(eye, target, &render_config),
+                render_config.far_plane_m,
+                "No grid extent yet: manual far plane"
+            );
+            camera.set_grid_extent(extent);
+            let far = camera.far_plane_m(eye, target, &render_config);
+            assert!(far >= farthest, "{} < {} at {:?}", far, farthest, eye);
+            assert!(
+                far <= farthest * 1.5,
+                "{} > 1.5 × {} at {:?}",
+                far,
+                farthest,
+                eye
+            );
+        }
+
+        // Auto-fit off keeps the manual far plane
+        render_config.far_plane_auto_fit = false;
+        let mut camera = CameraSystem::new(CameraPreset::Fixed(FixedCamera::default()));
+        camera.set_grid_extent(extent);
+        assert_eq!(
+            camera.far_plane_m(Vec3::new(0.0, 101.0, 0.0), Vec3::ZERO, &render_config),
+            render_config.far_plane_m
+        );
+    }
+
+    #[test]
+    fn test_dynamic_fov() {
+        // Default FOV does not widen with speed
+        let default_config = RenderConfig::default();
+        assert_eq!(
+            default_config.fov_for_speed(default_config.speed_for_max_fov_m_per_s),
+            default_config.fov_degrees
+        );
+
+        let render_config = RenderConfig {
+            fov_max_degrees: 115.0,
+            ..default_config
+        };
+        let base_matrix = |eye: Vec3, target: Vec3| {
+            Mat4::perspective_rh(
+                render_config.fov_degrees.to_radians(),
+                render_config.aspect_ratio(),
+                render_config.near_plane_m,
+                render_config.far_plane_m,
+            ) * Mat4::look_at_rh(eye, target, Vec3::Y)
+        };
+
+        // Stationary fixed camera: base FOV
+        let stationary = CameraSystem::new(CameraPreset::Fixed(FixedCamera {
+            simulated_velocity: 0.0,
+            ..FixedCamera::default()
+        }));
+        let (view_proj, _) =
+            stationary.create_view_proj_matrix(5.0, &render_config, None::<TerrainFn>);
+        let (eye, target) = stationary.compute_position_and_target(5.0, None::<TerrainFn>);
+        assert!(view_proj.abs_diff_eq(base_matrix(eye, target), 1e-5));
+
+        // Fast basic camera: wider FOV, different matrix
+        let params = BasicCameraPath::default();
+        assert!(
+            render_config.fov_for_speed(params.forward_speed_m_per_s) > render_config.fov_degrees
+        );
+        let fast = CameraSystem::new(CameraPreset::Basic(params));
+        let (view_proj, _) = fast.create_view_proj_matrix(5.0, &render_config, None::<TerrainFn>);
+        let (eye, target) = fast.compute_position_and_target(5.0, None::<TerrainFn>);
+        assert!(!view_proj.abs_diff_eq(base_matrix(eye, target), 1e-5));
+
+        // Clamped at max FOV beyond reference speed
+        assert_eq!(
+            render_config.fov_for_speed(10.0 * render_config.speed_for_max_fov_m_per_s),
+            render_config.fov_max_degrees
+        );
+    }
+
+    #[test]
+    fn test_sample_path() {
+        let camera = CameraSystem::new(CameraPreset::Cinematic(CameraJourney::default()));
+        let samples = camera.sample_path(2.0, 10.0, 33, None::<TerrainFn>);
+
+        assert_eq!(samples.len(), 33);
+        assert_eq!(
+            samples[0],
+            camera.compute_position_and_target(2.0, None::<TerrainFn>)
+        );
+        assert_eq!(
+            samples[32],
+            camera.compute_position_and_target(10.0, None::<TerrainFn>)
+        );
+
+        // Floating preset follows supplied terrain
+        let terrain = |_x: f32, _z: f32| 7.0;
+        let floating = CameraSystem::new(CameraPreset::Floating(FloatingCamera::default()));
+        let samples = floating.sample_path(0.0, 4.0, 5, Some(terrain));
+        assert_eq!(samples.len(), 5);
+        for (eye, _) in &samples {
+            assert_eq!(
+                eye.y,
+                7.0 + FloatingCamera::default().height_above_terrain_m
+            );
+        }
+
+        assert!(camera
+            .sample_path(0.0, 1.0, 0, None::<TerrainFn>)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_terrain_source_drives_floating_preset() {
+        let height = FloatingCamera::default().height_above_terrain_m;
+        let mut camera = CameraSystem::new(CameraPreset::Floating(FloatingCamera::default()));
+        let (without_source, _) = camera.position_and_target(2.0);
+
+        camera.set_terrain_source(|x: f32, z: f32| 0.01 * x + 0.02 * z + 12.0);
+        let (eye, _) = camera.position_and_target(2.0);
+        assert!((eye.y - (0.01 * eye.x + 0.02 * eye.z + 12.0 + height)).abs() < 1e-4);
+        assert_ne!(eye, without_source);
+
+        // Generic calls without a query consult the source too; an explicit one wins
+        let samples = camera.sample_path(2.0, 2.0, 1, None::<TerrainFn>);
+        assert_eq!(samples[0].0, eye);
+        let (explicit, _) = camera.compute_position_and_target(2.0, Some(|_x: f32, _z: f32| 3.0));
+        assert_eq!(explicit.y, 3.0 + height);
+
+        camera.clear_terrain_source();
+        assert_eq!(camera.position_and_target(2.0).0, without_source);
+    }
+
+    #[test]
+    fn test_export_path_samples_every_frame() {
+        let camera = CameraSystem::new(CameraPreset::Cinematic(CameraJourney::default()));
+        let render_config = RenderConfig::default();
+        let json = camera.export_path(2.0, 7.0, 30, &render_config, None::<TerrainFn>);
+
+        let samples: Vec<serde_json::Value> = serde_json::from_str(&json).unwrap();
+        assert_eq!(samples.len(), 5 * 30);
+        for (i, sample) in samples.iter().enumerate() {
+            let t = sample["t"].as_f64().unwrap();
+            assert!(
+                (t - (2.0 + i as f64 / 30.0)).abs() < 1e-4,
+                "Sample {} at t={}",
+                i,
+                t
+            );
+
+            let values = ["eye", "target"]
+                .iter()
+                .flat_map(|key| sample[key].as_array().unwrap().clone())
+                .chain([sample["fov"].clone()]);
+            for value in values {
+                let v = value.as_f64().expect("Non-numeric value");
+                assert!(v.is_finite(), "Sample {} has non-finite value {}", i, v);
+            }
+        }
+
+        // Matches the live camera at the first sample
+        let (eye, target) = camera.compute_position_and_target(2.0, None::<TerrainFn>);
+        let first = &samples[0];
+        assert_eq!(first["eye"][1].as_f64().unwrap() as f32, eye.y);
+        assert_eq!(first["target"][2].as_f64().unwrap() as f32, target.z);
+
+        // Floating preset without terrain: flat ground at height 0
+        let floating = CameraSystem::new(CameraPreset::Floating(FloatingCamera::default()));
+        let json = floating.export_path(0.0, 1.0, 10, &render_config, None::<TerrainFn>);
+        let samples: Vec<serde_json::Value> = serde_json::from_str(&json).unwrap();
+        assert_eq!(samples.len(), 10);
+        let height = FloatingCamera::default().height_above_terrain_m as f64;
+        for sample in &samples {
+            assert!((sample["eye"][1].as_f64().unwrap() - height).abs() < 1e-4);
+        }
+    }
+
     #[test]
     fn test_view_proj_matrix_generation() {
         let camera = CameraSystem::new(CameraPreset::default());
@@ -1,10 +1,14 @@
 //! Command-line argument parsing.
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
+use crate::audio::AudioBackend;
 use crate::params::{
-    BasicCameraPath, CameraJourney, CameraPreset, FixedCamera, FloatingCamera, RecordingConfig,
+    load_from_toml, AppConfig, BasicCameraPath, CameraJourney, CameraKeyframe, CameraPreset,
+    FixedCamera, FloatingCamera, ManualCameraState, NoiseKind, OrbitCamera, OutputFormat,
+    RecordingConfig,
 };
+use crate::rendering::ffmpeg_available;
 
 /// Command line arguments
 #[derive(Parser, Debug)]
@@ -15,7 +19,11 @@ pub struct Args {
     #[arg(long, value_name = "SECONDS")]
     pub record: Option<f32>,
 
-    /// Camera preset: fixed (default), basic, cinematic, floating
+    /// Encode the recording straight to MP4 with ffmpeg instead of saving PNG frames
+    #[arg(long)]
+    pub mp4: bool,
+
+    /// Camera preset: fixed (default), basic, cinematic, floating, orbit, manual
     #[arg(long, value_name = "PRESET", default_value = "fixed")]
     pub camera_preset: String,
 
@@ -26,11 +34,159 @@ pub struct Args {
     /// Height above terrain for floating preset (meters)
     #[arg(long, value_name = "METERS", default_value = "20")]
     pub float_height: f32,
+
+    /// Orbit radius for orbit preset (meters from center)
+    #[arg(long, value_name = "METERS", default_value = "300")]
+    pub orbit_radius: f32,
+
+    /// Fly an authored keyframe path loaded from JSON (overrides --camera-preset)
+    #[arg(long, value_name = "PATH")]
+    pub camera_keyframes: Option<String>,
+
+    /// Terrain noise: perlin, simplex, fbm (overrides the config file)
+    #[arg(long, value_name = "KIND")]
+    pub noise: Option<String>,
+
+    /// Terrain noise seed for CPU and GPU terrain (overrides the config file)
+    #[arg(long, value_name = "SEED")]
+    pub seed: Option<u32>,
+
+    /// Start the ocean and camera this many seconds in (overrides the config file)
+    #[arg(long, value_name = "SECONDS", allow_negative_numbers = true)]
+    pub time_offset: Option<f32>,
+
+    /// Window size as WIDTHxHEIGHT, e.g. 1600x900 (overrides the config file)
+    #[arg(long, value_name = "WxH")]
+    pub resolution: Option<String>,
+
+    /// Open the window borderless fullscreen
+    #[arg(long)]
+    pub fullscreen: bool,
+
+    /// Drive the ocean from a pre-analyzed WAV (frame N uses the song at frame N's time;
+    /// use with --record for an offline render, the song becomes the audio track)
+    #[arg(long, value_name = "WAV")]
+    pub audio_file: Option<String>,
+
+    /// Don't open an audio output device (synthesize silently; for CI/headless machines)
+    #[arg(long)]
+    pub no_audio_device: bool,
+
+    /// Reload shader.wgsl and skybox.wgsl from src/ when edited (invalid edits are logged
+    /// and skipped)
+    #[arg(long)]
+    pub watch_shaders: bool,
+
+    /// Load tuning parameters from a TOML file (missing values keep defaults)
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<String>,
+
+    /// Run a tool instead of the simulator
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Subcommands (tools that run without opening a window, and session replay)
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Export the selected camera path as JSON (`[{t, eye, target, fov}, ...]`)
+    ExportCamera {
+        /// Output JSON file
+        #[arg(long, value_name = "PATH", default_value = "camera_path.json")]
+        output: String,
+
+        /// First sample time (seconds)
+        #[arg(long, value_name = "SECONDS", default_value = "0")]
+        start: f32,
+
+        /// End time (seconds, exclusive)
+        #[arg(long, value_name = "SECONDS", default_value = "60")]
+        end: f32,
+
+        /// Samples per second
+        #[arg(long, default_value = "60")]
+        fps: u32,
+    },
+
+    /// Translate the crate's WGSL shaders with naga (validated first) and write the output
+    ExportShaders {
+        /// Target: msl, spirv, hlsl
+        #[arg(long, value_name = "BACKEND", default_value = "msl")]
+        backend: String,
+
+        /// Output directory (one file per shader, e.g. shader.metal)
+        #[arg(long, value_name = "DIR", default_value = "shaders")]
+        out: String,
+    },
+
+    /// Write the base terrain under the camera as a top-down grayscale heightmap PNG
+    Heightmap {
+        /// Output PNG file
+        #[arg(long, value_name = "PATH", default_value = "heightmap.png")]
+        output: String,
+
+        /// Center the map under the selected camera at this time (seconds)
+        #[arg(long, value_name = "SECONDS", default_value = "0")]
+        time: f32,
+
+        /// Side length of the mapped square (meters)
+        #[arg(long, value_name = "METERS", default_value = "2048")]
+        extent: f32,
+
+        /// Image width and height (pixels)
+        #[arg(long, value_name = "PIXELS", default_value = "512")]
+        resolution: u32,
+    },
+
+    /// Re-render a recorded session from its config.toml and audio.wav into DIR/replay
+    /// (camera flags are taken from this command line, as when recording)
+    Replay {
+        /// Session directory (a recording's output directory)
+        #[arg(long, value_name = "DIR", default_value = "recording")]
+        dir: String,
+    },
 }
 
 impl Args {
+    /// Load app configuration from `--config`, or defaults if not given
+    pub fn load_config(&self) -> AppConfig {
+        let Some(ref path) = self.config else {
+            return AppConfig::default();
+        };
+
+        match load_from_toml(path) {
+            Ok(config) => {
+                println!("Config: loaded {}", path);
+                config
+            }
+            Err(e) => {
+                eprintln!("Warning: {}, using defaults", e);
+                AppConfig::default()
+            }
+        }
+    }
+
     /// Parse camera preset from command-line arguments
-    pub fn parse_camera_preset(&self) -> CameraPreset {
+    ///
+    /// `journey` is the cinematic path used by the cinematic preset.
+    pub fn parse_camera_preset(&self, journey: &CameraJourney) -> CameraPreset {
+        if let Some(ref path) = self.camera_keyframes {
+            match Self::load_camera_keyframes(path) {
+                Ok(keyframes) => {
+                    println!(
+                        "Camera: Keyframed ({} keyframes from {})",
+                        keyframes.len(),
+                        path
+                    );
+                    return CameraPreset::Keyframed(keyframes);
+                }
+                Err(e) => {
+                    eprintln!("Warning: {}, using fixed", e);
+                    return CameraPreset::Fixed(FixedCamera::default());
+                }
+            }
+        }
+
         match self.camera_preset.to_lowercase().as_str() {
             "basic" => {
                 println!("Camera: Basic (straight-line flight)");
@@ -38,7 +194,7 @@ impl Args {
             }
             "cinematic" => {
                 println!("Camera: Cinematic (procedural journey)");
-                CameraPreset::Cinematic(CameraJourney::default())
+                CameraPreset::Cinematic(journey.clone())
             }
             "fixed" => {
                 println!("Camera: Fixed (elevation: {}m)", self.elevation);
@@ -52,6 +208,17 @@ impl Args {
                 floating.height_above_terrain_m = self.float_height;
                 CameraPreset::Floating(floating)
             }
+            "orbit" => {
+                println!("Camera: Orbit (radius: {}m)", self.orbit_radius);
+                CameraPreset::Orbit(OrbitCamera {
+                    radius_m: self.orbit_radius,
+                    ..OrbitCamera::default()
+                })
+            }
+            "manual" => {
+                println!("Camera: Manual (WASD + Space/Shift to fly, hold left mouse to look)");
+                CameraPreset::Manual(ManualCameraState::default())
+            }
             other => {
                 eprintln!("Warning: Unknown camera preset '{}', using fixed", other);
                 CameraPreset::Fixed(FixedCamera::default())
@@ -59,11 +226,90 @@ impl Args {
         }
     }
 
+    /// Parse `--noise` into a noise kind (None if not given or unknown)
+    pub fn parse_noise_kind(&self) -> Option<NoiseKind> {
+        let kind = match self.noise.as_ref()?.to_lowercase().as_str() {
+            "perlin" => NoiseKind::Perlin,
+            "simplex" => NoiseKind::Simplex,
+            "fbm" => NoiseKind::Fbm,
+            other => {
+                eprintln!("Warning: Unknown noise kind '{}', using config", other);
+                return None;
+            }
+        };
+        println!("Noise: {:?}", kind);
+        Some(kind)
+    }
+
+    /// Parse `--resolution` into a window size (None if not given or invalid)
+    pub fn parse_resolution(&self) -> Option<(u32, u32)> {
+        match parse_resolution(self.resolution.as_ref()?) {
+            Ok(size) => Some(size),
+            Err(e) => {
+                eprintln!("Warning: {}, using config", e);
+                None
+            }
+        }
+    }
+
+    /// Live audio backend (`--no-audio-device` selects the null backend)
+    pub fn audio_backend(&self) -> AudioBackend {
+        if self.no_audio_device {
+            AudioBackend::Null
+        } else {
+            AudioBackend::Device
+        }
+    }
+
+    /// Load camera keyframes from a JSON file
+    fn load_camera_keyframes(path: &str) -> Result<Vec<CameraKeyframe>, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read camera keyframes '{}': {}", path, e))?;
+        CameraKeyframe::parse_json(&json)
+    }
+
     /// Create recording configuration if recording mode is enabled
-    pub fn create_recording_config(&self) -> Option<RecordingConfig> {
-        self.record.map(|duration| {
-            let config = RecordingConfig::new(duration);
+    ///
+    /// `configured` is the recording section of the config file, if any. `--record` enables
+    /// recording on its own and overrides the configured duration. MP4 output falls back to a
+    /// PNG sequence when ffmpeg is not installed.
+    pub fn create_recording_config(
+        &self,
+        configured: Option<RecordingConfig>,
+    ) -> Option<RecordingConfig> {
+        let config = match (self.record, configured) {
+            (Some(duration), Some(configured)) => Some(RecordingConfig {
+                duration_secs: duration,
+                ..configured
+            }),
+            (Some(duration), None) => Some(RecordingConfig::new(duration)),
+            (None, configured) => configured,
+        };
 
+        let config = config.map(|mut config| {
+            if let Err(e) = config.validate_audio_format() {
+                eprintln!("Warning: {}, recording stereo 32-bit float audio", e);
+                let defaults = RecordingConfig::new(config.duration_secs);
+                config.audio_channels = defaults.audio_channels;
+                config.audio_bits_per_sample = defaults.audio_bits_per_sample;
+                config.audio_sample_format = defaults.audio_sample_format;
+            }
+            if let Err(e) = config.validate_resolution() {
+                eprintln!("Warning: {}, recording at the window size", e);
+                config.width = None;
+                config.height = None;
+            }
+            if self.mp4 && config.output_format == OutputFormat::PngSequence {
+                config.output_format = OutputFormat::mp4();
+            }
+            if config.output_format != OutputFormat::PngSequence && !ffmpeg_available() {
+                eprintln!("Warning: ffmpeg not found on PATH, recording a PNG sequence instead");
+                config.output_format = OutputFormat::PngSequence;
+            }
+            config
+        });
+
+        config.inspect(|config| {
             // Create output directories
             std::fs::create_dir_all(&config.frames_dir())
                 .expect("Failed to create frames directory");
@@ -80,9 +326,39 @@ impl Args {
 
             // Remove old audio and video files
             let _ = std::fs::remove_file(config.audio_path());
-            let _ = std::fs::remove_file(format!("{}/output.mp4", config.output_dir));
-
-            config
+            let _ = std::fs::remove_file(config.video_path());
+            let _ = std::fs::remove_file(config.silent_video_path());
         })
     }
 }
+
+/// Parse a `WIDTHxHEIGHT` window size (both non-zero)
+fn parse_resolution(s: &str) -> Result<(u32, u32), String> {
+    let invalid = || format!("Invalid resolution '{}', expected WIDTHxHEIGHT", s);
+    let (width, height) = s.trim().split_once(['x', 'X']).ok_or_else(invalid)?;
+    let width: u32 = width.parse().map_err(|_| invalid())?;
+    let height: u32 = height.parse().map_err(|_| invalid())?;
+    if width == 0 || height == 0 {
+        return Err(format!("Invalid resolution '{}', must be non-zero", s));
+    }
+    Ok((width, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_resolution() {
+        assert_eq!(parse_resolution("1600x900"), Ok((1600, 900)));
+        assert_eq!(parse_resolution("1920X1080"), Ok((1920, 1080)));
+
+        // Missing separator, non-numeric or zero components
+        assert!(parse_resolution("1600").is_err());
+        assert!(parse_resolution("1600*900").is_err());
+        assert!(parse_resolution("widex900").is_err());
+        assert!(parse_resolution("1600x").is_err());
+        assert!(parse_resolution("1600x900x2").is_err());
+        assert!(parse_resolution("0x900").is_err());
+    }
+}
@@ -0,0 +1,38 @@
+// Debug HUD: one textured quad in the top-left corner, drawn over the finished frame
+
+struct HudUniforms {
+    rect: vec4<f32>,  // Quad corners in NDC: (left, top, right, bottom)
+}
+
+@group(0) @binding(0)
+var<uniform> hud: HudUniforms;
+
+@group(0) @binding(1)
+var hud_texture: texture_2d<f32>;
+
+@group(0) @binding(2)
+var hud_sampler: sampler;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) corner: u32) -> VertexOutput {
+    // Two triangles covering the unit square (uv origin at the top-left)
+    var us = array<f32, 6>(0.0, 0.0, 1.0, 1.0, 0.0, 1.0);
+    var vs = array<f32, 6>(0.0, 1.0, 0.0, 0.0, 1.0, 1.0);
+    let uv = vec2<f32>(us[corner], vs[corner]);
+
+    var out: VertexOutput;
+    let position = mix(hud.rect.xy, hud.rect.zw, uv);
+    out.clip_position = vec4<f32>(position, 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(hud_texture, hud_sampler, in.uv);
+}
@@ -7,3 +7,5 @@ pub mod noise;
 pub mod ocean;
 pub mod params;
 pub mod rendering;
+pub mod replay;
+pub mod timestep;
@@ -0,0 +1,160 @@
+// Thick grid lines: each lattice edge is one instance, expanded into a screen-space quad
+
+struct Uniforms {
+    view_proj: mat4x4<f32>,
+    line_width: f32,
+    amplitude: f32,
+    frequency: f32,
+    time: f32,
+    fog_color: vec4<f32>,
+    fog_density: f32,
+    color_height_range_m: f32,
+    color_low: vec4<f32>,   // Tint at troughs (audio-reactive palette)
+    color_high: vec4<f32>,  // Tint at peaks
+    light_direction: vec4<f32>,  // xyz: toward the key light, w: Lambert intensity (0 = unlit)
+    edge_fade_start: f32,
+    edge_fade_end: f32,
+    depth_color_range_m: f32,
+    depth_color_trough: vec4<f32>,
+    depth_color_crest: vec4<f32>,
+    camera_xz: vec2<f32>,
+}
+
+struct Vertex {
+    position: vec3<f32>,
+    base_height: f32,  // Base terrain height under the vertex
+    uv: vec2<f32>,
+    _padding2: vec2<f32>,  // Align normal to 16 bytes
+    normal: vec3<f32>,
+    foam: f32,
+}
+
+struct LineUniforms {
+    viewport_size: vec2<f32>,  // Render target size (pixels)
+    pixels_per_width: f32,     // Screen pixels per unit of uniforms.line_width
+    _padding: f32,
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+@group(1) @binding(0)
+var<storage, read> vertices: array<Vertex>;
+
+@group(1) @binding(1)
+var<storage, read> edges: array<u32>;  // Line-list index pairs
+
+@group(1) @binding(2)
+var<uniform> line_params: LineUniforms;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) world_pos: vec3<f32>,
+    @location(2) foam: f32,
+    @location(3) view_depth: f32,
+    @location(4) across: f32,  // -1..1 across the line width
+    @location(5) wave_height: f32,  // Height above the base terrain
+    @location(6) normal: vec3<f32>,
+}
+
+// Endpoints closer than this (clip w) are pulled onto the near side of the camera
+const NEAR_W: f32 = 0.001;
+
+// Move `p` along the edge toward `q` until it is in front of the camera
+fn clip_to_near(p: vec4<f32>, q: vec4<f32>) -> vec4<f32> {
+    if p.w >= NEAR_W || q.w < NEAR_W {
+        return p;
+    }
+    let t = (NEAR_W - p.w) / (q.w - p.w);
+    return mix(p, q, t);
+}
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) corner: u32,
+    @builtin(instance_index) edge: u32,
+) -> VertexOutput {
+    let a = vertices[edges[edge * 2u]];
+    let b = vertices[edges[edge * 2u + 1u]];
+
+    // Two triangles per quad: which endpoint (0 = a, 1 = b) and which side of the line
+    var ends = array<f32, 6>(0.0, 0.0, 1.0, 0.0, 1.0, 1.0);
+    var sides = array<f32, 6>(-1.0, 1.0, -1.0, 1.0, 1.0, -1.0);
+    let t = ends[corner];
+    let side = sides[corner];
+
+    var out: VertexOutput;
+    let clip_a_raw = uniforms.view_proj * vec4<f32>(a.position, 1.0);
+    let clip_b_raw = uniforms.view_proj * vec4<f32>(b.position, 1.0);
+    if clip_a_raw.w < NEAR_W && clip_b_raw.w < NEAR_W {
+        return out;  // Entirely behind the camera: degenerate quad
+    }
+    let clip_a = clip_to_near(clip_a_raw, clip_b_raw);
+    let clip_b = clip_to_near(clip_b_raw, clip_a_raw);
+
+    // Perpendicular to the edge in pixels (any direction for degenerate edges)
+    let half_viewport = line_params.viewport_size * 0.5;
+    var dir = (clip_b.xy / clip_b.w - clip_a.xy / clip_a.w) * half_viewport;
+    if length(dir) < 1e-6 {
+        dir = vec2<f32>(1.0, 0.0);
+    }
+    let normal = normalize(vec2<f32>(-dir.y, dir.x));
+
+    // Offset in NDC scaled by w, so the width stays constant in pixels at any distance
+    let clip = mix(clip_a, clip_b, t);
+    let half_width_px = 0.5 * uniforms.line_width * line_params.pixels_per_width;
+    let offset = normal * side * half_width_px / half_viewport * clip.w;
+
+    out.clip_position = vec4<f32>(clip.xy + offset, clip.zw);
+    out.uv = mix(a.uv, b.uv, t);
+    out.world_pos = mix(a.position, b.position, t);
+    out.foam = mix(a.foam, b.foam, t);
+    out.view_depth = clip.w;
+    out.across = side;
+    out.wave_height = mix(a.position.y - a.base_height, b.position.y - b.base_height, t);
+    out.normal = mix(a.normal, b.normal, t);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let hot_pink = vec3<f32>(1.0, 0.16, 0.46);
+    let deep_purple = vec3<f32>(0.55, 0.12, 1.0);
+
+    // Bright core fading out toward the edges of the quad
+    let core_intensity = 1.0 - smoothstep(0.0, 0.5, abs(in.across));
+    let glow_intensity = 1.0 - smoothstep(0.0, 1.0, abs(in.across));
+    var brightness = core_intensity * 2.5 + glow_intensity * 0.8;
+
+    // Same palette as the filled ocean: gradient, peak/trough and depth tints, foam, key light
+    var color = mix(hot_pink, deep_purple, in.uv.y);
+    let height_range = max(uniforms.color_height_range_m, 0.001);
+    let height_t = smoothstep(-height_range, height_range, in.world_pos.y);
+    color = color * mix(uniforms.color_low.rgb, uniforms.color_high.rgb, height_t);
+    let depth_range = max(uniforms.depth_color_range_m, 0.001);
+    let depth_t = smoothstep(-depth_range, depth_range, in.wave_height);
+    color = color * mix(uniforms.depth_color_trough.rgb, uniforms.depth_color_crest.rgb, depth_t);
+
+    let foam_white = vec3<f32>(1.0, 0.95, 1.0);
+    color = mix(color, foam_white, in.foam * 0.7);
+    brightness = brightness * (1.0 + in.foam);
+
+    let lambert = max(dot(normalize(in.normal), uniforms.light_direction.xyz), 0.0);
+    color = color * mix(1.0, lambert, uniforms.light_direction.w);
+
+    color = color * brightness;
+
+    // Exponential-squared distance fog toward the configured color
+    let fog_distance = in.view_depth * uniforms.fog_density;
+    let fog_amount = 1.0 - exp(-fog_distance * fog_distance);
+    color = mix(color, uniforms.fog_color.rgb, fog_amount);
+
+    // Circular fade hiding the wrap boundary (as shader.wgsl)
+    let dist_from_center = length(in.world_pos.xz - uniforms.camera_xz);
+    let distance_fade =
+        1.0 - smoothstep(uniforms.edge_fade_start, uniforms.edge_fade_end, dist_from_center);
+
+    let alpha = clamp(brightness, 0.0, 1.0) * distance_fade;
+    return vec4<f32>(color, alpha);
+}
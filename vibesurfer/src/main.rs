@@ -4,8 +4,10 @@
 //! currents shimmer with color, and your motion becomes rhythm.
 
 use clap::Parser;
+use std::io::Write;
+use std::path::Path;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use winit::{
     application::ApplicationHandler,
     event::*,
@@ -15,12 +17,74 @@ use winit::{
 };
 
 use glam::Mat4;
-use vibesurfer::audio::AudioSystem;
+use vibesurfer::audio::{AudioBackend, AudioSystem, BandTrack};
 use vibesurfer::camera::CameraSystem;
-use vibesurfer::cli::Args;
-use vibesurfer::ocean::OceanSystem;
+use vibesurfer::cli::{Args, Command};
+use vibesurfer::ocean::AudioBands;
+use vibesurfer::ocean::{OceanGrid, OceanSystem};
 use vibesurfer::params::*;
-use vibesurfer::rendering::{RenderSystem, SkyboxUniforms, Uniforms};
+use vibesurfer::rendering::{
+    export_shaders, mux_audio, FrameTimings, HudState, RenderSystem, ShaderBackend, ShaderWatcher,
+    SkyboxUniforms, Uniforms, SHADER_SOURCE_DIR,
+};
+use vibesurfer::replay::{save_session_config, Session};
+use vibesurfer::timestep::{frame_interval, frame_pacing_sleep, FixedTimestep, SimClock};
+
+/// Blend duration when cycling camera presets (seconds)
+const CAMERA_BLEND_DURATION_S: f32 = 2.0;
+
+/// Presets visited (in order) when cycling with the C key
+fn camera_cycle_presets(journey: &CameraJourney) -> Vec<CameraPreset> {
+    vec![
+        CameraPreset::Fixed(FixedCamera::default()),
+        CameraPreset::Basic(BasicCameraPath::default()),
+        CameraPreset::Cinematic(journey.clone()),
+        CameraPreset::Floating(FloatingCamera::default()),
+        CameraPreset::Orbit(OrbitCamera::default()),
+    ]
+}
+
+/// How often the config file is checked for changes (hot reload)
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How often watched shader files are checked for changes (`--watch-shaders`)
+const SHADER_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Simulation time one Right-arrow step advances while paused (seconds)
+const PAUSED_STEP_S: f64 = 1.0 / 60.0;
+
+/// Mouse-look sensitivity for manual camera (radians per pixel of mouse motion)
+const MOUSE_LOOK_SENSITIVITY_RAD_PER_PX: f32 = 0.003;
+
+/// Held movement keys and accumulated mouse motion for the manual camera
+#[derive(Default)]
+struct ManualInput {
+    forward: bool,
+    back: bool,
+    left: bool,
+    right: bool,
+    up: bool,
+    down: bool,
+    /// Mouse-look active (left button held)
+    mouse_look: bool,
+    /// Mouse motion since last frame (pixels)
+    mouse_delta: (f64, f64),
+}
+
+impl ManualInput {
+    /// Record key press/release for movement keys (ignores other keys)
+    fn set_key(&mut self, code: KeyCode, pressed: bool) {
+        match code {
+            KeyCode::KeyW => self.forward = pressed,
+            KeyCode::KeyS => self.back = pressed,
+            KeyCode::KeyA => self.left = pressed,
+            KeyCode::KeyD => self.right = pressed,
+            KeyCode::Space => self.up = pressed,
+            KeyCode::ShiftLeft => self.down = pressed,
+            _ => {}
+        }
+    }
+}
 
 /// Main application state
 struct App {
@@ -31,14 +95,32 @@ struct App {
     // Simulation systems
     ocean: OceanSystem,
     camera: CameraSystem,
+    camera_cycle_index: usize,
+    manual_input: ManualInput,
     audio: Option<AudioSystem>,
+    /// Pre-analyzed song bands, used instead of `audio` (offline render)
+    band_track: Option<BandTrack>,
 
     // Configuration
+    audio_backend: AudioBackend,
     render_config: RenderConfig,
     recording_config: Option<RecordingConfig>,
+    fft_config: FFTConfig,
+    camera_journey: CameraJourney,
+    config_watcher: Option<ConfigWatcher>,
+    shader_watcher: Option<ShaderWatcher>,
 
     // Time tracking
-    start_time: Instant,
+    timestep: FixedTimestep,
+    /// Pause/step control feeding `timestep` (interactive mode)
+    sim_clock: SimClock,
+    /// Audio-modulated (amplitude, frequency, line width) at the previous and latest step
+    modulation: [(f32, f32, f32); 2],
+    /// Detail layer phase (`OceanSystem::detail_phase`) at the previous and latest step
+    detail_phase: [f32; 2],
+    last_frame_time: Instant,
+    /// When recording started (for the progress line)
+    recording_start: Option<Instant>,
     frame_count: usize,
     last_fps_update: Instant,
     last_fps_frame_count: usize,
@@ -46,15 +128,50 @@ struct App {
 }
 
 impl App {
-    fn new(camera_preset: CameraPreset, recording_config: Option<RecordingConfig>) -> Self {
-        // Create default parameters
-        let ocean_physics = OceanPhysics::default();
-        let audio_mapping = AudioReactiveMapping::default();
-        let render_config = RenderConfig::default();
+    fn new(
+        config: AppConfig,
+        config_watcher: Option<ConfigWatcher>,
+        shader_watcher: Option<ShaderWatcher>,
+        camera_preset: CameraPreset,
+        recording_config: Option<RecordingConfig>,
+        audio_backend: AudioBackend,
+        band_track: Option<BandTrack>,
+    ) -> Self {
+        // Parameters from config file (defaults for anything not set)
+        let AppConfig {
+            ocean: ocean_physics,
+            audio_mapping,
+            fft: fft_config,
+            camera_journey,
+            camera_shake,
+            render: render_config,
+            recording: _, // Already merged into recording_config by the CLI
+            automation,
+        } = config;
 
         // Initialize systems
-        let ocean = OceanSystem::new(ocean_physics, audio_mapping);
-        let camera = CameraSystem::new(camera_preset);
+        let mut ocean = OceanSystem::new(ocean_physics, audio_mapping).unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        });
+        if let Err(e) = ocean.set_automation(automation) {
+            eprintln!("Warning: {}, automation ignored", e);
+        }
+        // Cycling continues from the starting preset (past the end if it isn't in the list)
+        let cycle_presets = camera_cycle_presets(&camera_journey);
+        let camera_cycle_index = cycle_presets
+            .iter()
+            .position(|preset| preset.name() == camera_preset.name())
+            .unwrap_or(cycle_presets.len() - 1);
+        let mut camera = CameraSystem::new(camera_preset);
+        camera.set_grid_extent(ocean.physics.grid_extent_m());
+        match camera_shake.validate() {
+            Ok(()) => camera.set_shake_config(camera_shake),
+            Err(e) => eprintln!("Warning: {}, camera shake uses defaults", e),
+        }
+        let timestep = FixedTimestep::new(render_config.sim_hz as f64);
+        let modulation = ocean.modulate(0.0, &AudioBands::default());
+        let detail_phase = ocean.detail_phase();
 
         let now = Instant::now();
         Self {
@@ -62,10 +179,23 @@ impl App {
             render_system: None,
             ocean,
             camera,
+            camera_cycle_index,
+            manual_input: ManualInput::default(),
             audio: None,
+            band_track,
+            audio_backend,
             render_config,
             recording_config,
-            start_time: now,
+            fft_config,
+            camera_journey,
+            config_watcher,
+            shader_watcher,
+            timestep,
+            sim_clock: SimClock::new(PAUSED_STEP_S),
+            modulation: [modulation; 2],
+            detail_phase: [detail_phase; 2],
+            last_frame_time: now,
+            recording_start: None,
             frame_count: 0,
             last_fps_update: now,
             last_fps_frame_count: 0,
@@ -76,15 +206,250 @@ impl App {
     fn is_recording(&self) -> bool {
         self.recording_config.is_some()
     }
+
+    /// Simulation time to render (seconds), between the last two fixed steps
+    fn sim_time_s(&self) -> f32 {
+        self.timestep.render_time_s() as f32
+    }
+
+    /// Advance the fixed-step simulation by the time since the last frame
+    ///
+    /// Real frame time live; exactly one frame period per frame while recording.
+    fn step_simulation(&mut self, frame_dt: f32, audio_bands: &AudioBands) {
+        match self.recording_config {
+            // Exact frame timestamps (`RecordingConfig::frame_time_s`, kept in f64), so
+            // low recording rates never hit the stall cap
+            Some(ref cfg) => self
+                .timestep
+                .advance_to(self.frame_count as f64 / cfg.fps.max(1) as f64),
+            None => self
+                .timestep
+                .advance(self.sim_clock.elapsed_s(frame_dt as f64)),
+        }
+
+        let step_s = self.timestep.step_s() as f32;
+        while let Some(step_time_s) = self.timestep.next_step() {
+            self.apply_manual_input(step_s);
+            let world_time_s = self.ocean.physics.world_time_s(step_time_s as f32);
+            self.camera
+                .react_to_bass(audio_bands.low, step_s, world_time_s);
+            self.modulation = [
+                self.modulation[1],
+                self.ocean.modulate(step_time_s as f32, audio_bands),
+            ];
+            self.detail_phase = [self.detail_phase[1], self.ocean.detail_phase()];
+        }
+    }
+
+    /// Detail layer phase at app time `time_s`, along the latest step (extrapolated past it)
+    fn detail_phase_at(&self, time_s: f32) -> f32 {
+        let [p0, p1] = self.detail_phase;
+        let previous_step_s = self.timestep.render_time_s() - self.timestep.remainder_s();
+        let u = (time_s as f64 - previous_step_s) / self.timestep.step_s();
+        p0 + (p1 - p0) * u as f32
+    }
+
+    /// Audio-modulated (amplitude, frequency, line width), interpolated to the render time
+    fn interpolated_modulation(&self) -> (f32, f32, f32) {
+        let alpha = self.timestep.alpha();
+        let lerp = |a: f32, b: f32| a + (b - a) * alpha;
+        let [(a0, f0, w0), (a1, f1, w1)] = self.modulation;
+        (lerp(a0, a1), lerp(f0, f1), lerp(w0, w1))
+    }
+
+    /// Write out outstanding frames and the audio track, then mux MP4 output
+    fn finish_recording(&mut self) {
+        if let Some(ref mut render_system) = self.render_system {
+            if let Err(e) = render_system.flush_capture() {
+                eprintln!("Frame capture failed: {}", e);
+            }
+        }
+        if let Some(ref audio) = self.audio {
+            if let Err(e) = audio.finish_recording() {
+                eprintln!("Audio recording failed: {}", e);
+            }
+        }
+
+        let Some(ref cfg) = self.recording_config else {
+            return;
+        };
+        if let OutputFormat::Mp4 { .. } = cfg.output_format {
+            match mux_audio(cfg) {
+                Ok(()) => println!("🎬 Video saved to {}", cfg.video_path()),
+                Err(e) => eprintln!("Failed to mux audio into video: {}", e),
+            }
+        }
+    }
+
+    /// Overwrite the progress line with frames done, percent and ETA (recording only)
+    fn print_recording_progress(&self) {
+        let (Some(cfg), Some(start)) = (&self.recording_config, self.recording_start) else {
+            return;
+        };
+        let total = cfg.total_frames().max(1);
+        let eta = cfg
+            .eta_s(self.frame_count, start.elapsed().as_secs_f32())
+            .map(|s| format!("{}:{:02}", s as u64 / 60, s as u64 % 60))
+            .unwrap_or_else(|| "--:--".to_string());
+        print!(
+            "\r   Frame {}/{} ({:.0}%) | ETA {}   ",
+            self.frame_count,
+            total,
+            100.0 * self.frame_count as f32 / total as f32,
+            eta
+        );
+        let _ = std::io::stdout().flush();
+    }
+
+    /// Switch to the next camera preset, blending from the current view
+    fn cycle_camera_preset(&mut self) {
+        let presets = camera_cycle_presets(&self.camera_journey);
+        self.camera_cycle_index = (self.camera_cycle_index + 1) % presets.len();
+        let preset = presets[self.camera_cycle_index].clone();
+        println!("Camera: blending to {}", preset.name());
+
+        let time_s = self.ocean.physics.world_time_s(self.sim_time_s());
+        self.camera
+            .blend_to(preset, time_s, CAMERA_BLEND_DURATION_S);
+    }
+
+    /// Apply a hot-reloaded config file, if one arrived since the last frame
+    ///
+    /// Ocean physics, audio mapping, cinematic journey, camera shake and render config are
+    /// swapped live.
+    /// Grid size, spacing or seed changes rebuild the ocean mesh and its GPU buffers.
+    /// FFT settings, window size and `sim_hz` only take effect on restart.
+    fn apply_config_reload(&mut self) {
+        let Some(config) = self
+            .config_watcher
+            .as_ref()
+            .and_then(ConfigWatcher::try_recv)
+        else {
+            return;
+        };
+
+        if let Err(e) = config.ocean.validate() {
+            eprintln!("Config: reload ignored, invalid ocean physics: {}", e);
+            return;
+        }
+
+        let old_physics = &self.ocean.physics;
+        let rebuild_grid = config.ocean.grid_size != old_physics.grid_size
+            || config.ocean.grid_spacing_m != old_physics.grid_spacing_m
+            || config.ocean.noise_seed != old_physics.noise_seed
+            || config.ocean.noise_kind != old_physics.noise_kind;
+
+        self.ocean.physics = config.ocean;
+        self.ocean.mapping = config.audio_mapping;
+        if let Err(e) = self.ocean.set_automation(config.automation) {
+            eprintln!("Config: automation not reloaded, {}", e);
+        }
+        if rebuild_grid {
+            self.ocean.grid = OceanGrid::new(&self.ocean.physics);
+            self.camera
+                .set_grid_extent(self.ocean.physics.grid_extent_m());
+            if let Some(ref mut render_system) = self.render_system {
+                render_system.recreate_ocean_buffers(self.ocean.physics.grid_size as u32);
+            }
+        }
+
+        if let CameraPreset::Cinematic(_) = self.camera.preset() {
+            self.camera
+                .set_preset(CameraPreset::Cinematic(config.camera_journey.clone()));
+        }
+        self.camera_journey = config.camera_journey;
+        match config.camera_shake.validate() {
+            Ok(()) => self.camera.set_shake_config(config.camera_shake),
+            Err(e) => eprintln!("Config: camera shake not reloaded, {}", e),
+        }
+        // The window keeps its current size (set by the CLI, config or a resize)
+        self.render_config = RenderConfig {
+            window_width: self.render_config.window_width,
+            window_height: self.render_config.window_height,
+            fullscreen: self.render_config.fullscreen,
+            ..config.render
+        };
+
+        println!(
+            "Config: reloaded{}",
+            if rebuild_grid {
+                " (ocean grid rebuilt)"
+            } else {
+                ""
+            }
+        );
+    }
+
+    /// Rebuild pipelines for shader files edited since the last frame (`--watch-shaders`)
+    ///
+    /// Edits that fail naga validation or pipeline creation are reported and the running
+    /// shader is kept.
+    fn apply_shader_reload(&mut self) {
+        let (Some(watcher), Some(render_system)) = (&self.shader_watcher, &mut self.render_system)
+        else {
+            return;
+        };
+
+        for (shader, source) in watcher.try_recv() {
+            match render_system.reload_shader(shader, &source) {
+                Ok(()) => println!("Shaders: reloaded {}", shader.file_name()),
+                Err(e) => eprintln!(
+                    "Warning: shader reload skipped, {} is invalid:\n{}",
+                    shader.file_name(),
+                    e
+                ),
+            }
+        }
+    }
+
+    /// Freeze or resume wave and camera time (audio keeps playing)
+    fn toggle_pause(&mut self) {
+        let paused = !self.sim_clock.paused();
+        self.sim_clock.set_paused(paused);
+        println!("Simulation {}", if paused { "paused" } else { "resumed" });
+    }
+
+    /// Feed held keys and mouse motion into the manual camera
+    fn apply_manual_input(&mut self, dt: f32) {
+        let input = &mut self.manual_input;
+        let axis = |positive: bool, negative: bool| positive as i32 as f32 - negative as i32 as f32;
+        let (dx, dy) = std::mem::take(&mut input.mouse_delta);
+
+        self.camera.apply_input(
+            axis(input.forward, input.back),
+            axis(input.right, input.left),
+            axis(input.up, input.down),
+            -dx as f32 * MOUSE_LOOK_SENSITIVITY_RAD_PER_PX, // Mouse right turns right
+            -dy as f32 * MOUSE_LOOK_SENSITIVITY_RAD_PER_PX, // Mouse up looks up
+            dt,
+        );
+    }
 }
 
 impl ApplicationHandler for App {
     fn about_to_wait(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {
+        // Frame rate cap: wait out the rest of the interval since the last frame started
+        if let Some(interval) = frame_interval(self.render_config.max_fps) {
+            let sleep = frame_pacing_sleep(self.last_frame_time, Instant::now(), interval);
+            if !sleep.is_zero() {
+                std::thread::sleep(sleep);
+            }
+        }
+
         if let Some(window) = &self.window {
             window.request_redraw();
         }
     }
 
+    fn exiting(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {
+        // Join the audio threads and finalize any WAV output before the process exits
+        if let Some(audio) = self.audio.take() {
+            if let Err(e) = audio.shutdown() {
+                eprintln!("Audio shutdown failed: {}", e);
+            }
+        }
+    }
+
     fn resumed(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
         if self.window.is_some() {
             return; // Already initialized
@@ -96,35 +461,64 @@ impl ApplicationHandler for App {
             .with_inner_size(winit::dpi::LogicalSize::new(
                 self.render_config.window_width,
                 self.render_config.window_height,
-            ));
+            ))
+            .with_fullscreen(
+                self.render_config
+                    .fullscreen
+                    .then_some(winit::window::Fullscreen::Borderless(None)),
+            );
 
         let window = Arc::new(event_loop.create_window(window_attributes).unwrap());
 
         // Initialize rendering system
         let render_system = pollster::block_on(RenderSystem::new(
             Arc::clone(&window),
-            &self.ocean.grid,
+            self.ocean.physics.grid_size as u32,
+            &self.render_config,
             self.recording_config.clone(),
         ))
         .unwrap();
+        (
+            self.render_config.window_width,
+            self.render_config.window_height,
+        ) = render_system.render_size();
 
-        // Initialize audio system
-        let fft_config = FFTConfig::default();
-        let audio = AudioSystem::new(fft_config, self.recording_config.clone()).unwrap();
+        // Initialize audio system (not needed when a band track drives the ocean)
+        if self.band_track.is_none() {
+            let audio = AudioSystem::new(
+                self.fft_config.clone(),
+                self.recording_config.clone(),
+                self.audio_backend,
+            )
+            .unwrap();
+            self.audio = Some(audio);
+        }
 
         if self.is_recording() {
             let cfg = self.recording_config.as_ref().unwrap();
             println!("\n🎬 Recording mode: {} seconds", cfg.duration_secs);
             println!("   Output: {}/", cfg.output_dir);
             println!("   Frames: {} @ {}fps", cfg.total_frames(), cfg.fps);
+            if cfg.capture_every_n > 1 {
+                println!(
+                    "   Time-lapse: every {} frames ({} captured)",
+                    cfg.capture_every_n,
+                    cfg.captured_frames()
+                );
+            }
+            if let Some((width, height)) = cfg.resolution() {
+                println!("   Resolution: {}x{}", width, height);
+            }
+            self.recording_start = Some(Instant::now());
         } else {
             println!("\nVibesurfer is running!");
+            println!("Press C to cycle camera presets");
+            println!("Press Space to pause, Right arrow to step a frame");
             println!("Press ESC to quit\n");
         }
 
         self.window = Some(window);
         self.render_system = Some(render_system);
-        self.audio = Some(audio);
     }
 
     fn window_event(
@@ -135,6 +529,16 @@ impl ApplicationHandler for App {
     ) {
         match event {
             WindowEvent::CloseRequested => event_loop.exit(),
+            WindowEvent::Resized(size) => {
+                if let Some(ref mut render_system) = self.render_system {
+                    render_system.resize(size.width, size.height);
+                    // Camera aspect ratio follows the rendered frames, not the window
+                    (
+                        self.render_config.window_width,
+                        self.render_config.window_height,
+                    ) = render_system.render_size();
+                }
+            }
             WindowEvent::KeyboardInput {
                 event:
                     KeyEvent {
@@ -144,8 +548,133 @@ impl ApplicationHandler for App {
                     },
                 ..
             } => event_loop.exit(),
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        state: ElementState::Pressed,
+                        physical_key: PhysicalKey::Code(KeyCode::KeyC),
+                        repeat: false,
+                        ..
+                    },
+                ..
+            } if !self.is_recording() => self.cycle_camera_preset(),
+            // Space ascends with the manual camera instead
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        state: ElementState::Pressed,
+                        physical_key: PhysicalKey::Code(KeyCode::Space),
+                        repeat: false,
+                        ..
+                    },
+                ..
+            } if !self.is_recording()
+                && !matches!(self.camera.preset(), CameraPreset::Manual(_)) =>
+            {
+                self.toggle_pause()
+            }
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        state: ElementState::Pressed,
+                        physical_key: PhysicalKey::Code(KeyCode::ArrowRight),
+                        ..
+                    },
+                ..
+            } if !self.is_recording() => self.sim_clock.step(),
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        state: ElementState::Pressed,
+                        physical_key: PhysicalKey::Code(KeyCode::KeyF),
+                        repeat: false,
+                        ..
+                    },
+                ..
+            } => {
+                if let Some(ref mut render_system) = self.render_system {
+                    let wireframe = !render_system.wireframe();
+                    render_system.set_wireframe(wireframe);
+                }
+            }
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        state: ElementState::Pressed,
+                        physical_key: PhysicalKey::Code(KeyCode::KeyL),
+                        repeat: false,
+                        ..
+                    },
+                ..
+            } => {
+                if let Some(ref mut render_system) = self.render_system {
+                    let thick_lines = !render_system.thick_lines();
+                    render_system.set_thick_lines(thick_lines);
+                }
+            }
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        state: ElementState::Pressed,
+                        physical_key: PhysicalKey::Code(KeyCode::F1),
+                        repeat: false,
+                        ..
+                    },
+                ..
+            } => {
+                if let Some(ref mut render_system) = self.render_system {
+                    let show_hud = !render_system.show_hud();
+                    render_system.set_show_hud(show_hud);
+                }
+            }
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        state: ElementState::Pressed,
+                        physical_key: PhysicalKey::Code(KeyCode::F2),
+                        repeat: false,
+                        ..
+                    },
+                ..
+            } => {
+                if let Some(ref mut render_system) = self.render_system {
+                    let show_spectrum = !render_system.show_spectrum();
+                    render_system.set_show_spectrum(show_spectrum);
+                }
+            }
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        state: ElementState::Pressed,
+                        physical_key: PhysicalKey::Code(KeyCode::F12),
+                        repeat: false,
+                        ..
+                    },
+                ..
+            } => self.save_screenshot(),
+            WindowEvent::KeyboardInput {
+                event:
+                    KeyEvent {
+                        state,
+                        physical_key: PhysicalKey::Code(code),
+                        ..
+                    },
+                ..
+            } => self
+                .manual_input
+                .set_key(code, state == ElementState::Pressed),
+            WindowEvent::MouseInput {
+                state,
+                button: MouseButton::Left,
+                ..
+            } => self.manual_input.mouse_look = state == ElementState::Pressed,
             WindowEvent::RedrawRequested => {
-                self.render_frame();
+                if let Err(e) = self.render_frame() {
+                    eprintln!("Error: {:?}, exiting", e);
+                    self.finish_recording();
+                    event_loop.exit();
+                    return;
+                }
 
                 // Check if recording is complete
                 if self.is_recording() {
@@ -153,8 +682,9 @@ impl ApplicationHandler for App {
                     if self.frame_count >= cfg.total_frames() {
                         println!(
                             "\n✅ Recording complete! {} frames captured",
-                            self.frame_count
+                            cfg.captured_frames()
                         );
+                        self.finish_recording();
                         event_loop.exit();
                     }
                 }
@@ -162,119 +692,226 @@ impl ApplicationHandler for App {
             _ => {}
         }
     }
+
+    fn device_event(
+        &mut self,
+        _event_loop: &winit::event_loop::ActiveEventLoop,
+        _device_id: DeviceId,
+        event: DeviceEvent,
+    ) {
+        // Raw mouse motion drives manual camera look while the left button is held
+        if let DeviceEvent::MouseMotion { delta } = event {
+            if self.manual_input.mouse_look {
+                self.manual_input.mouse_delta.0 += delta.0;
+                self.manual_input.mouse_delta.1 += delta.1;
+            }
+        }
+    }
 }
 
 impl App {
-    /// Render a single frame
-    fn render_frame(&mut self) {
-        let Some(ref render_system) = self.render_system else {
+    /// Save the next frame to `screenshot_<unix ms>.png` in the working directory
+    fn save_screenshot(&mut self) {
+        let Some(ref mut render_system) = self.render_system else {
             return;
         };
-        let Some(ref audio) = self.audio else {
-            return;
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis());
+        let path = format!("screenshot_{}.png", timestamp_ms);
+        if let Err(e) = render_system.capture_current_frame(Path::new(&path)) {
+            eprintln!("Screenshot failed: {}", e);
+        }
+    }
+
+    /// Render a single frame (Err: the GPU ran out of memory and the app should exit)
+    fn render_frame(&mut self) -> Result<(), wgpu::SurfaceError> {
+        let now = Instant::now();
+        let frame_dt = now.duration_since(self.last_frame_time).as_secs_f32();
+        self.last_frame_time = now;
+
+        // Pick up config file edits
+        self.apply_config_reload();
+        self.apply_shader_reload();
+
+        if self.render_system.is_none() {
+            return Ok(());
+        }
+
+        // Get audio frequency bands (synthesized up to this frame while recording, or
+        // frame N of the pre-analyzed song)
+        let audio_bands = match (&self.band_track, &self.audio) {
+            (Some(track), _) => track.bands_for_frame(self.frame_count),
+            (None, Some(audio)) => audio.bands_for_frame(self.frame_count),
+            (None, None) => return Ok(()),
         };
 
-        // Get current time
-        let time_s = self.start_time.elapsed().as_secs_f32();
+        // Step camera input and audio envelopes at the fixed rate, then render in between
+        self.step_simulation(frame_dt, &audio_bands);
+        let time_s = self.sim_time_s();
+        // Audio-modulated parameters (gust/recovery envelope)
+        let (amplitude, frequency, line_width) = self.interpolated_modulation();
+        // Fog and FOV automation, then the audio mapping routes
+        let render_config = self.ocean.mapping.modulate_render_config(
+            &automate_render_config(self.ocean.automation(), time_s, &self.render_config),
+            &audio_bands,
+        );
 
-        // Get audio frequency bands
-        let audio_bands = audio.get_bands();
+        // Motion blur averages sub-frames spread over the frame period (recording only)
+        let subframe_times: Vec<(f32, f32)> = match self.recording_config {
+            Some(ref cfg) => cfg.subframe_times(time_s),
+            None => vec![time_s],
+        }
+        .into_iter()
+        .map(|time_s| (time_s, self.detail_phase_at(time_s)))
+        .collect();
+        let subframes = subframe_times.len() as u32;
+        let mut index_count = 0;
+
+        let Some(ref mut render_system) = self.render_system else {
+            return Ok(());
+        };
 
         // Create terrain query function for floating camera
         let ocean_physics = self.ocean.physics.clone();
         let terrain_fn = |x: f32, z: f32| self.ocean.grid.query_base_terrain(x, z, &ocean_physics);
 
-        // Update camera position
-        let (view_proj, camera_pos) =
-            self.camera
-                .create_view_proj_matrix(time_s, &self.render_config, Some(terrain_fn));
-
-        // DEBUG: Log camera position every second
-        if self.frame_count % 60 == 0 {
-            println!(
-                "[{:.1}s] Camera: ({:.1}, {:.1}, {:.1})",
-                time_s, camera_pos.x, camera_pos.y, camera_pos.z
-            );
-        }
+        for (subframe, (time_s, detail_phase)) in subframe_times.into_iter().enumerate() {
+            // Camera, terrain and shaders all run on the offset world clock
+            let time_s = self.ocean.physics.world_time_s(time_s);
 
-        // === Terrain Generation: GPU only ===
-
-        let (amplitude, frequency, line_width, index_count) = {
-            // GPU path: Compute audio-modulated parameters
-            let amplitude = self.ocean.physics.detail_amplitude_m
-                + audio_bands.low * self.ocean.mapping.bass_to_amplitude_scale;
-            let frequency = self.ocean.physics.detail_frequency
-                + audio_bands.mid * self.ocean.mapping.mid_to_frequency_scale;
-            let line_width = self.ocean.physics.base_line_width
-                + audio_bands.high * self.ocean.mapping.high_to_glow_scale;
-
-            // Create terrain params for GPU (camera at actual world position)
-            let terrain_params = vibesurfer::params::TerrainParams {
-                base_amplitude: self.ocean.physics.base_terrain_amplitude_m,
-                base_frequency: self.ocean.physics.base_terrain_frequency,
-                detail_amplitude: amplitude,
-                detail_frequency: frequency,
-                camera_pos: [camera_pos.x, camera_pos.y, camera_pos.z],
-                _padding1: 0.0,
-                grid_size: self.ocean.physics.grid_size as u32,
-                grid_spacing: self.ocean.physics.grid_spacing_m,
-                time: time_s * self.ocean.physics.wave_speed,
-                _padding2: 0.0,
-            };
+            // Update camera position
+            let (view_proj, camera_pos) =
+                self.camera
+                    .create_view_proj_matrix(time_s, &render_config, Some(terrain_fn));
 
-            // DEBUG: Log terrain params every second
-            if self.frame_count % 60 == 0 {
+            // DEBUG: Log camera position every second (recording shows a progress line instead)
+            let debug_log = self.recording_config.is_none() && self.frame_count % 60 == 0;
+            if debug_log {
                 println!(
-                    "  TerrainParams: camera_pos=({:.1}, {:.1}, {:.1}) grid_size={} spacing={:.1}",
-                    terrain_params.camera_pos[0],
-                    terrain_params.camera_pos[1],
-                    terrain_params.camera_pos[2],
-                    terrain_params.grid_size,
-                    terrain_params.grid_spacing
+                    "[{:.1}s] Camera: ({:.1}, {:.1}, {:.1})",
+                    time_s, camera_pos.x, camera_pos.y, camera_pos.z
                 );
-                println!("  Dispatching compute shader (frame {})", self.frame_count);
             }
 
-            // Dispatch GPU compute shader
-            render_system
-                .dispatch_terrain_compute(&terrain_params, self.ocean.physics.grid_size as u32);
+            // Keep loud chop out of the camera: crests stay below the near plane
+            let amplitude = self.ocean.physics.clamp_detail_amplitude(
+                amplitude,
+                camera_pos.y,
+                terrain_fn(camera_pos.x, camera_pos.z),
+                render_config.near_plane_m,
+            );
 
-            if self.frame_count % 60 == 0 {
-                println!("  Compute shader dispatched");
-            }
+            // === Terrain Generation: GPU only ===
 
-            // Use all indices (no phantom line filtering in Phase 1)
-            let index_count = self.ocean.grid.indices.len() as u32;
+            index_count = {
+                // Altitude level of detail: coarser lattice higher up, same world extent
+                let grid_size = self.ocean.physics.grid_size_for_altitude(camera_pos.y) as u32;
+                if grid_size != render_system.grid_size() {
+                    render_system.recreate_ocean_buffers(grid_size);
+                }
 
-            (amplitude, frequency, line_width, index_count)
-        };
+                // Create terrain params for GPU (camera at actual world position)
+                let terrain_params = vibesurfer::params::TerrainParams {
+                    base_amplitude: self.ocean.physics.base_terrain_amplitude_m,
+                    base_frequency: self.ocean.physics.base_terrain_frequency,
+                    detail_amplitude: amplitude,
+                    detail_frequency: frequency,
+                    camera_pos: [camera_pos.x, camera_pos.y, camera_pos.z],
+                    _padding1: 0.0,
+                    grid_size,
+                    grid_spacing: self.ocean.physics.grid_spacing_for_size(grid_size as usize),
+                    time: detail_phase,
+                    noise_seed: self.ocean.physics.noise_seed,
+                    noise_kind: self.ocean.physics.noise_kind.shader_id(),
+                    base_octaves: self.ocean.physics.base_terrain_octaves,
+                    detail_octaves: self.ocean.physics.detail_octaves,
+                    noise_lacunarity: self.ocean.physics.noise_lacunarity,
+                    noise_gain: self.ocean.physics.noise_gain,
+                    _padding2: 0,
+                    swell_offset: self.ocean.physics.swell_offset_m(time_s).to_array(),
+                };
 
-        // Grid is local window around camera (camera moves through world space)
-        let model = Mat4::IDENTITY;
-        let mvp = view_proj * model;
-
-        // Update ocean uniforms
-        let uniforms = Uniforms {
-            view_proj: mvp.to_cols_array_2d(),
-            line_width,
-            amplitude,
-            frequency,
-            time: time_s,
-        };
-        render_system.update_uniforms(&uniforms);
-
-        // Update skybox uniforms
-        let inv_view_proj = view_proj.inverse();
-        let skybox_uniforms = SkyboxUniforms {
-            inv_view_proj: inv_view_proj.to_cols_array_2d(),
-            time: time_s,
-            _padding: [0.0; 3],
-        };
-        render_system.update_skybox_uniforms(&skybox_uniforms);
+                // DEBUG: Log terrain params every second
+                if debug_log {
+                    println!(
+                        "  TerrainParams: camera_pos=({:.1}, {:.1}, {:.1}) grid_size={} spacing={:.1}",
+                        terrain_params.camera_pos[0],
+                        terrain_params.camera_pos[1],
+                        terrain_params.camera_pos[2],
+                        terrain_params.grid_size,
+                        terrain_params.grid_spacing
+                    );
+                    println!("  Dispatching compute shader (frame {})", self.frame_count);
+                }
+
+                // Dispatch GPU compute shader
+                render_system.dispatch_terrain_compute(&terrain_params, grid_size);
+
+                if debug_log {
+                    println!("  Compute shader dispatched");
+                }
+
+                // Draw the compute output directly (all indices, no phantom line filtering)
+                render_system.index_count()
+            };
+
+            // Grid is local window around camera (camera moves through world space)
+            let model = Mat4::IDENTITY;
+            let mvp = view_proj * model;
+
+            // Update ocean uniforms
+            let uniforms = Uniforms::new(
+                mvp,
+                line_width,
+                amplitude,
+                frequency,
+                time_s,
+                &render_config,
+            )
+            .with_color_ramp(&self.ocean.mapping, audio_bands.mid)
+            .with_light(self.ocean.light_direction())
+            .with_edge_fade(&self.ocean.physics, [camera_pos.x, camera_pos.z]);
+            render_system.update_uniforms(&uniforms);
+
+            // Update skybox uniforms
+            let inv_view_proj = view_proj.inverse();
+            let skybox_uniforms =
+                SkyboxUniforms::new(inv_view_proj, time_s, &render_config, audio_bands.high);
+            render_system.update_skybox_uniforms(&skybox_uniforms);
+            render_system.update_bloom(&render_config, &audio_bands);
+
+            if render_system.show_hud() {
+                let hud = HudState::new(
+                    self.fps,
+                    audio_bands,
+                    &self.camera,
+                    time_s,
+                    Some(terrain_fn),
+                );
+                render_system.update_hud(&hud);
+            }
+            if render_system.show_spectrum() {
+                if let Some(ref audio) = self.audio {
+                    render_system.update_spectrum(&audio.get_spectrum());
+                }
+            }
+
+            if subframes > 1 {
+                render_system.accumulate_subframe(index_count, subframe as u32, subframes);
+            }
+        }
 
         // Render (and capture if recording)
-        if let Err(e) = render_system.render(self.frame_count, index_count) {
-            eprintln!("Render error: {:?}", e);
+        let result = if subframes > 1 {
+            render_system.render_accumulated(self.frame_count)
+        } else {
+            render_system.render(self.frame_count, index_count)
+        };
+        match result {
+            Err(e @ wgpu::SurfaceError::OutOfMemory) => return Err(e),
+            Err(e) => eprintln!("Render error: {:?}", e),
+            Ok(()) => {}
         }
 
         self.frame_count += 1;
@@ -288,14 +925,29 @@ impl App {
             self.last_fps_update = now;
             self.last_fps_frame_count = self.frame_count;
 
+            // GPU pass times, when the adapter supports timestamp queries
+            let timings = render_system.frame_timings();
+            let gpu_times = if timings == FrameTimings::default() {
+                String::new()
+            } else {
+                format!(
+                    " | compute {:.2} ms, render {:.2} ms",
+                    timings.compute_ms, timings.render_ms
+                )
+            };
+
+            self.print_recording_progress();
+
             if let Some(ref window) = self.window {
                 // TODO(Phase B): Add velocity display back using camera position delta
                 window.set_title(&format!(
-                    "Vibesurfer - Audio-Reactive Ocean | {:.0} FPS",
-                    self.fps
+                    "Vibesurfer - Audio-Reactive Ocean | {:.0} FPS{}",
+                    self.fps, gpu_times
                 ));
             }
         }
+
+        Ok(())
     }
 }
 
@@ -306,11 +958,196 @@ fn main() {
     println!("Vibesurfer - Fluid audio-reactive ocean surfing simulator");
     println!("Initializing systems...\n");
 
-    // Parse camera preset and recording config
-    let camera_preset = args.parse_camera_preset();
-    let recording_config = args.create_recording_config();
+    // Replaying a session: its saved config and audio stand in for --config and --audio-file
+    let session = match args.command {
+        Some(Command::Replay { ref dir }) => {
+            Some(Session::load(Path::new(dir)).unwrap_or_else(|e| {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }))
+        }
+        _ => None,
+    };
+
+    // Load tuning config, then parse camera preset and recording config
+    let mut config = match session {
+        Some(ref session) => {
+            println!("Replay: {}", session.dir.display());
+            AppConfig {
+                recording: session.replay_recording(),
+                ..session.config.clone()
+            }
+        }
+        None => args.load_config(),
+    };
+    if let Some(noise_kind) = args.parse_noise_kind() {
+        config.ocean.noise_kind = noise_kind;
+    }
+    if let Some(seed) = args.seed {
+        println!("Seed: {}", seed);
+        config.ocean.noise_seed = seed;
+    }
+    if let Some(time_offset_s) = args.time_offset {
+        config.ocean.time_offset_s = time_offset_s;
+    }
+    if let Some((width, height)) = args.parse_resolution() {
+        config.render.window_width = width;
+        config.render.window_height = height;
+    }
+    if args.fullscreen {
+        config.render.fullscreen = true;
+    }
+    let camera_preset = args.parse_camera_preset(&config.camera_journey);
+
+    if let Some(Command::ExportCamera {
+        ref output,
+        start,
+        end,
+        fps,
+    }) = args.command
+    {
+        let camera = CameraSystem::new(camera_preset);
+        // Base terrain only depends on the noise settings, so a minimal grid is enough
+        let terrain_physics = OceanPhysics {
+            grid_size: 2,
+            ..config.ocean.clone()
+        };
+        let terrain = OceanGrid::new(&terrain_physics);
+        let terrain_fn = |x: f32, z: f32| terrain.query_base_terrain(x, z, &terrain_physics);
+
+        let json = camera.export_path(start, end, fps, &config.render, Some(terrain_fn));
+        match std::fs::write(output, json) {
+            Ok(()) => println!("Camera path written to {}", output),
+            Err(e) => {
+                eprintln!("Error: Failed to write '{}': {}", output, e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if let Some(Command::Heightmap {
+        ref output,
+        time,
+        extent,
+        resolution,
+    }) = args.command
+    {
+        // Base terrain only depends on the noise settings, so a minimal grid is enough
+        let ocean = OceanSystem::new(
+            OceanPhysics {
+                grid_size: 2,
+                ..config.ocean.clone()
+            },
+            config.audio_mapping.clone(),
+        )
+        .unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        });
+        let camera = CameraSystem::new(camera_preset);
+        let terrain_fn = |x: f32, z: f32| ocean.grid.query_base_terrain(x, z, &ocean.physics);
+        let (eye, _) = camera.compute_position_and_target(time, Some(terrain_fn));
+
+        let heightmap = ocean.export_heightmap([eye.x, eye.z], extent, resolution);
+        match heightmap.save(output) {
+            Ok(()) => println!(
+                "Heightmap written to {} ({}×{} m around x={:.0}, z={:.0})",
+                output, extent, extent, eye.x, eye.z
+            ),
+            Err(e) => {
+                eprintln!("Error: Failed to write '{}': {}", output, e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if let Some(Command::ExportShaders {
+        ref backend,
+        ref out,
+    }) = args.command
+    {
+        let backend = ShaderBackend::parse(backend).unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        });
+        let mut failed = 0;
+        for (name, result) in export_shaders(backend, Path::new(out)) {
+            match result {
+                Ok(path) => println!("Shaders: {} -> {}", name, path.display()),
+                Err(e) => {
+                    eprintln!("Error: {} failed:\n{}", name, e);
+                    failed += 1;
+                }
+            }
+        }
+        if failed > 0 {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let recording_config = args.create_recording_config(config.recording.clone());
+
+    // Keep the effective config with the recording, so the session can be replayed
+    if let Some(ref cfg) = recording_config {
+        if let Err(e) = save_session_config(&config, cfg) {
+            eprintln!("Warning: {}", e);
+        }
+    }
+
+    // Analyze the song up front; when recording, it also becomes the audio track
+    let copy_audio_track = |path: &str| {
+        if let Some(ref cfg) = recording_config {
+            if let Err(e) = std::fs::copy(path, cfg.audio_path()) {
+                eprintln!(
+                    "Warning: Failed to copy {} to {}: {}",
+                    path,
+                    cfg.audio_path(),
+                    e
+                );
+            }
+        }
+    };
+    let band_track = match session {
+        Some(session) => {
+            copy_audio_track(&session.audio_path());
+            Some(session.band_track)
+        }
+        None => args.audio_file.as_ref().map(|path| {
+            let fps = recording_config.as_ref().map_or(60, |cfg| cfg.fps);
+            let track =
+                BandTrack::from_wav(Path::new(path), &config.fft, fps).unwrap_or_else(|e| {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                });
+            println!("Audio: {} frames of bands from {}", track.len(), path);
+            copy_audio_track(path);
+            track
+        }),
+    };
+
+    // Watch the config file for live edits
+    let config_watcher = args
+        .config
+        .as_ref()
+        .map(|path| ConfigWatcher::spawn(path, CONFIG_POLL_INTERVAL));
+
+    // Watch shader sources for live edits
+    let shader_watcher = args
+        .watch_shaders
+        .then(|| ShaderWatcher::spawn(SHADER_SOURCE_DIR, SHADER_POLL_INTERVAL));
 
-    let mut app = App::new(camera_preset, recording_config);
+    let mut app = App::new(
+        config,
+        config_watcher,
+        shader_watcher,
+        camera_preset,
+        recording_config,
+        args.audio_backend(),
+        band_track,
+    );
     let event_loop = EventLoop::new().unwrap();
     let _ = event_loop.run_app(&mut app);
 }
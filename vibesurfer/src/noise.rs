@@ -1,20 +1,60 @@
 //! Noise generation for ocean terrain.
 //!
 //! Provides consistent noise implementation for both CPU (Rust) and GPU (WGSL).
-//! Using OpenSimplex noise for smooth, artifact-free procedural terrain.
+//! `simplex3d` is an operation-for-operation port of the Stefan Gustavson simplex noise in
+//! `terrain_compute.wgsl`, so CPU and GPU terrain agree to within float rounding.
+//! `perlin3d` is a CPU-only alternative for comparing looks (see `NoiseKind`).
 
-use noise::{NoiseFn, OpenSimplex};
+use glam::{Vec3, Vec4, Vec4Swizzles};
+
+use crate::params::NoiseKind;
 
 /// Noise generator for ocean terrain
+///
+/// Fully determined by its seed: no random state, no lookup tables, and no external noise
+/// crate, so the same seed gives the same terrain on every run and platform.
 pub struct NoiseGenerator {
-    simplex: OpenSimplex,
+    seed: u32,
+    /// Noise function used by `sample`
+    kind: NoiseKind,
+    /// Offset along the third noise axis (selects an independent slice per seed)
+    seed_offset: f32,
 }
 
 impl NoiseGenerator {
-    /// Create new noise generator with seed
+    /// Create new noise generator with seed (fractal simplex sampling)
     pub fn new(seed: u32) -> Self {
+        Self::with_kind(seed, NoiseKind::Fbm)
+    }
+
+    /// Create new noise generator with seed and sampling strategy
+    pub fn with_kind(seed: u32, kind: NoiseKind) -> Self {
         Self {
-            simplex: OpenSimplex::new(seed),
+            seed,
+            kind,
+            seed_offset: seed_offset(seed),
+        }
+    }
+
+    /// Seed this generator was created with
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    /// Sampling strategy used by `sample`
+    pub fn kind(&self) -> NoiseKind {
+        self.kind
+    }
+
+    /// Sample terrain noise at (x, z) and time `t` with this generator's `NoiseKind`
+    ///
+    /// `octaves`, `lacunarity` and `gain` only apply to `NoiseKind::Fbm`.
+    /// Returns value in range [-1, 1]
+    pub fn sample(&self, x: f64, z: f64, t: f64, octaves: u32, lacunarity: f32, gain: f32) -> f32 {
+        match self.kind {
+            NoiseKind::Perlin => self.sample_perlin_3d(x, z, t),
+            NoiseKind::Simplex => self.sample_3d(x, z, t),
+            NoiseKind::Fbm => self.fbm(x, z, t, octaves, lacunarity, gain),
         }
     }
 
@@ -22,6 +62,368 @@ impl NoiseGenerator {
     ///
     /// Returns value in range [-1, 1]
     pub fn sample_3d(&self, x: f64, y: f64, z: f64) -> f32 {
-        self.simplex.get([x, y, z]) as f32
+        simplex3d(Vec3::new(x as f32, y as f32, z as f32 + self.seed_offset))
+    }
+
+    /// Sample 3D classic Perlin noise at position
+    ///
+    /// Returns value in range [-1, 1]
+    pub fn sample_perlin_3d(&self, x: f64, y: f64, z: f64) -> f32 {
+        perlin3d(Vec3::new(x as f32, y as f32, z as f32 + self.seed_offset))
+    }
+
+    /// Sample fractal Brownian motion (multi-octave simplex noise)
+    ///
+    /// Each octave multiplies spatial frequency by `lacunarity` and amplitude by `gain`.
+    /// The sum is divided by the total amplitude, so the result stays in [-1, 1].
+    /// With `octaves = 1` this is identical to `sample_3d(x, z, t)`.
+    /// Negative or NaN `gain` is treated as 0 (a negative gain can cancel the total amplitude).
+    pub fn fbm(&self, x: f64, z: f64, t: f64, octaves: u32, lacunarity: f32, gain: f32) -> f32 {
+        let gain = gain.max(0.0);
+        let mut sum = 0.0;
+        let mut total_amplitude = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+
+        for _ in 0..octaves.max(1) {
+            sum += self.sample_3d(x * frequency, z * frequency, t) * amplitude;
+            total_amplitude += amplitude;
+            amplitude *= gain;
+            frequency *= lacunarity as f64;
+        }
+
+        sum / total_amplitude
+    }
+}
+
+/// Offset along the third noise axis for a seed (must match `terrain_compute.wgsl`)
+///
+/// The full seed is hashed to 24 bits and spread over one simplex period (289), so every
+/// seed gets its own slice and the offset stays small enough for f32 precision.
+pub fn seed_offset(seed: u32) -> f32 {
+    let mut h = seed;
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x7feb_352d);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x846c_a68b);
+    h ^= h >> 16;
+    (h >> 8) as f32 * (289.0 / 16_777_216.0)
+}
+
+fn mod289_vec3(x: Vec3) -> Vec3 {
+    x - (x * (1.0 / 289.0)).floor() * 289.0
+}
+
+fn mod289_vec4(x: Vec4) -> Vec4 {
+    x - (x * (1.0 / 289.0)).floor() * 289.0
+}
+
+fn permute(x: Vec4) -> Vec4 {
+    mod289_vec4(((x * 34.0) + 1.0) * x)
+}
+
+fn taylor_inv_sqrt(r: Vec4) -> Vec4 {
+    Vec4::splat(1.792_842_9) - 0.853_734_7 * r
+}
+
+/// WGSL `step(edge, x)`: 1.0 where `edge <= x`, else 0.0
+fn step_vec3(edge: Vec3, x: Vec3) -> Vec3 {
+    Vec3::select(edge.cmple(x), Vec3::ONE, Vec3::ZERO)
+}
+
+fn step_vec4(edge: Vec4, x: Vec4) -> Vec4 {
+    Vec4::select(edge.cmple(x), Vec4::ONE, Vec4::ZERO)
+}
+
+/// 3D simplex noise (Stefan Gustavson), mirroring `simplex3d` in `terrain_compute.wgsl`
+///
+/// Returns value in range [-1, 1]
+pub fn simplex3d(v: Vec3) -> f32 {
+    let c = glam::Vec2::new(1.0 / 6.0, 1.0 / 3.0);
+
+    // First corner
+    let mut i = (v + v.dot(Vec3::splat(c.y))).floor();
+    let x0 = v - i + i.dot(Vec3::splat(c.x));
+
+    // Other corners
+    let g = step_vec3(Vec3::new(x0.y, x0.z, x0.x), Vec3::new(x0.x, x0.y, x0.z));
+    let l = Vec3::ONE - g;
+    let l_zxy = Vec3::new(l.z, l.x, l.y);
+    let i1 = g.min(l_zxy);
+    let i2 = g.max(l_zxy);
+
+    let x1 = x0 - i1 + c.x;
+    let x2 = x0 - i2 + c.y;
+    let x3 = x0 - 0.5;
+
+    // Permutations
+    i = mod289_vec3(i);
+    let p = permute(
+        permute(
+            permute(i.z + Vec4::new(0.0, i1.z, i2.z, 1.0)) + i.y + Vec4::new(0.0, i1.y, i2.y, 1.0),
+        ) + i.x
+            + Vec4::new(0.0, i1.x, i2.x, 1.0),
+    );
+
+    // Gradients
+    let n_ = 1.0 / 7.0;
+    let ns = n_ * Vec3::new(2.0, 0.5, 1.0) - Vec3::new(0.0, 1.0, 0.0);
+
+    let j = p - 49.0 * (p * ns.z * ns.z).floor();
+
+    let x_ = (j * ns.z).floor();
+    let y_ = (j - 7.0 * x_).floor();
+
+    let x = x_ * ns.x + ns.y;
+    let y = y_ * ns.x + ns.y;
+    let h = Vec4::ONE - x.abs() - y.abs();
+
+    let b0 = Vec4::new(x.x, x.y, y.x, y.y);
+    let b1 = Vec4::new(x.z, x.w, y.z, y.w);
+
+    let s0 = b0.floor() * 2.0 + 1.0;
+    let s1 = b1.floor() * 2.0 + 1.0;
+    let sh = -step_vec4(h, Vec4::ZERO);
+
+    let a0 = b0.xzyw() + s0.xzyw() * sh.xxyy();
+    let a1 = b1.xzyw() + s1.xzyw() * sh.zzww();
+
+    let mut p0 = Vec3::new(a0.x, a0.y, h.x);
+    let mut p1 = Vec3::new(a0.z, a0.w, h.y);
+    let mut p2 = Vec3::new(a1.x, a1.y, h.z);
+    let mut p3 = Vec3::new(a1.z, a1.w, h.w);
+
+    // Normalize gradients
+    let norm = taylor_inv_sqrt(Vec4::new(p0.dot(p0), p1.dot(p1), p2.dot(p2), p3.dot(p3)));
+    p0 *= norm.x;
+    p1 *= norm.y;
+    p2 *= norm.z;
+    p3 *= norm.w;
+
+    // Mix final noise value
+    let m = (Vec4::splat(0.6) - Vec4::new(x0.dot(x0), x1.dot(x1), x2.dot(x2), x3.dot(x3)))
+        .max(Vec4::ZERO);
+    let m = m * m;
+    42.0 * (m * m).dot(Vec4::new(p0.dot(x0), p1.dot(x1), p2.dot(x2), p3.dot(x3)))
+}
+
+/// Hash of an integer lattice point (no permutation table, so it is seed-free and tileless)
+fn hash_lattice(x: i32, y: i32, z: i32) -> u32 {
+    let mut h = (x as u32).wrapping_mul(0x8da6_b343)
+        ^ (y as u32).wrapping_mul(0xd816_3841)
+        ^ (z as u32).wrapping_mul(0xcb1a_b31f);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0x5bd1_e995);
+    h ^ (h >> 15)
+}
+
+/// Dot product of offset `d` with one of Perlin's 12 cube-edge gradients
+fn perlin_gradient(hash: u32, d: Vec3) -> f32 {
+    match hash % 12 {
+        0 => d.x + d.y,
+        1 => -d.x + d.y,
+        2 => d.x - d.y,
+        3 => -d.x - d.y,
+        4 => d.x + d.z,
+        5 => -d.x + d.z,
+        6 => d.x - d.z,
+        7 => -d.x - d.z,
+        8 => d.y + d.z,
+        9 => -d.y + d.z,
+        10 => d.y - d.z,
+        _ => -d.y - d.z,
+    }
+}
+
+/// 3D classic gradient noise (Ken Perlin's improved noise with a hashed lattice)
+///
+/// Returns value in range [-1, 1]
+pub fn perlin3d(v: Vec3) -> f32 {
+    let cell = v.floor();
+    let f = v - cell;
+    let (ix, iy, iz) = (cell.x as i32, cell.y as i32, cell.z as i32);
+
+    // Quintic fade curve (C2-continuous across cell boundaries)
+    let u = f * f * f * (f * (f * 6.0 - 15.0) + 10.0);
+
+    let corner = |dx: i32, dy: i32, dz: i32| {
+        let offset = Vec3::new(dx as f32, dy as f32, dz as f32);
+        perlin_gradient(hash_lattice(ix + dx, iy + dy, iz + dz), f - offset)
+    };
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+
+    let x00 = lerp(corner(0, 0, 0), corner(1, 0, 0), u.x);
+    let x10 = lerp(corner(0, 1, 0), corner(1, 1, 0), u.x);
+    let x01 = lerp(corner(0, 0, 1), corner(1, 0, 1), u.x);
+    let x11 = lerp(corner(0, 1, 1), corner(1, 1, 1), u.x);
+    let y0 = lerp(x00, x10, u.y);
+    let y1 = lerp(x01, x11, u.y);
+
+    lerp(y0, y1, u.z).clamp(-1.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Gradient energy relative to total variance along a line (share of high frequencies)
+    fn roughness(noise: &NoiseGenerator, octaves: u32) -> f32 {
+        let step = 0.02;
+        let samples: Vec<f32> = (0..4000)
+            .map(|i| noise.fbm(i as f64 * step, 3.7, 0.5, octaves, 2.0, 0.5))
+            .collect();
+        let n = samples.len() as f32;
+        let mean = samples.iter().sum::<f32>() / n;
+        let variance = samples.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / n;
+        let gradient_energy = samples
+            .windows(2)
+            .map(|w| (w[1] - w[0]).powi(2))
+            .sum::<f32>()
+            / n;
+        gradient_energy / variance
+    }
+
+    /// `sample_3d` with seed 42 at fixed coordinates: (x, y, z, value)
+    ///
+    /// Regenerate only for an intentional change to the noise (it changes every terrain).
+    const GOLDEN_SEED_42: [(f64, f64, f64, f32); 6] = [
+        (0.0, 0.0, 0.0, -0.62518555),
+        (0.5, 0.25, 0.0, 0.4300482),
+        (1.37, -2.11, 0.5, 0.14419329),
+        (-13.7, 42.9, 3.25, 0.2544651),
+        (123.456, -654.321, 7.5, 0.6504077),
+        (0.003, 0.007, 100.0, 0.12952872),
+    ];
+
+    #[test]
+    fn test_sample_matches_golden_values() {
+        let noise = NoiseGenerator::new(42);
+        assert_eq!(noise.seed(), 42);
+        for (x, y, z, expected) in GOLDEN_SEED_42 {
+            let value = noise.sample_3d(x, y, z);
+            // Tolerance only covers float rounding (e.g. fused multiply-add on some targets)
+            assert!(
+                (value - expected).abs() < 1e-5,
+                "sample_3d({}, {}, {}) = {}, golden {}",
+                x,
+                y,
+                z,
+                value,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_seed_determines_output() {
+        let (x, y, z) = (1.37, -2.11, 0.5);
+        let a = NoiseGenerator::new(7);
+        let b = NoiseGenerator::new(7);
+        assert_eq!(a.seed(), 7);
+        assert_eq!(a.sample_3d(x, y, z), b.sample_3d(x, y, z));
+        assert_ne!(
+            a.sample_3d(x, y, z),
+            NoiseGenerator::new(42).sample_3d(x, y, z)
+        );
+    }
+
+    #[test]
+    fn test_seeds_congruent_mod_1024_differ() {
+        // 1031 = 7 + 1024: the old `seed % 1024` offset gave both the same terrain
+        let (a, b) = (NoiseGenerator::new(7), NoiseGenerator::new(1031));
+        assert_ne!(seed_offset(7), seed_offset(1031));
+        let differing = (0..100)
+            .filter(|&i| {
+                let (x, z) = (i as f64 * 0.37, i as f64 * -0.21);
+                a.sample_3d(x, z, 0.0) != b.sample_3d(x, z, 0.0)
+            })
+            .count();
+        assert!(differing > 90, "Only {} of 100 samples differ", differing);
+
+        // Large seeds stay within one simplex period
+        for seed in [0, 1, 1024, u32::MAX - 1, u32::MAX] {
+            assert!((0.0..289.0).contains(&seed_offset(seed)));
+        }
+    }
+
+    #[test]
+    fn test_each_kind_is_finite_and_in_range() {
+        for kind in [NoiseKind::Perlin, NoiseKind::Simplex, NoiseKind::Fbm] {
+            let noise = NoiseGenerator::with_kind(42, kind);
+            assert_eq!(noise.kind(), kind);
+            for i in 0..5000 {
+                let (x, z, t) = (i as f64 * 0.173, i as f64 * -0.091, i as f64 * 0.01);
+                let v = noise.sample(x, z, t, 4, 2.0, 0.5);
+                assert!(
+                    v.is_finite() && (-1.0..=1.0).contains(&v),
+                    "{:?} value {} at ({}, {}, {})",
+                    kind,
+                    v,
+                    x,
+                    z,
+                    t
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_kind_changes_output() {
+        let (x, z, t) = (1.37, -2.11, 0.5);
+        let sample = |kind| NoiseGenerator::with_kind(42, kind).sample(x, z, t, 4, 2.0, 0.5);
+        let perlin = sample(NoiseKind::Perlin);
+        let simplex = sample(NoiseKind::Simplex);
+        let fbm = sample(NoiseKind::Fbm);
+
+        assert_ne!(perlin, simplex);
+        assert_ne!(simplex, fbm);
+        assert_ne!(perlin, fbm);
+
+        // Simplex kind is the plain single-octave sample (matches the GPU shader)
+        assert_eq!(simplex, NoiseGenerator::new(42).sample_3d(x, z, t));
+    }
+
+    #[test]
+    fn test_fbm_single_octave_matches_sample() {
+        let noise = NoiseGenerator::new(42);
+        for i in 0..100 {
+            let (x, z, t) = (i as f64 * 0.37, i as f64 * -0.21, i as f64 * 0.05);
+            assert_eq!(noise.fbm(x, z, t, 1, 2.0, 0.5), noise.sample_3d(x, z, t));
+        }
+    }
+
+    #[test]
+    fn test_fbm_octaves_add_detail_within_range() {
+        let noise = NoiseGenerator::new(42);
+
+        // More octaves: more high-frequency variation
+        let r1 = roughness(&noise, 1);
+        let r4 = roughness(&noise, 4);
+        assert!(
+            r4 > r1 * 2.0,
+            "4-octave roughness {} vs 1-octave {}",
+            r4,
+            r1
+        );
+
+        // Normalized: never exceeds single-octave range
+        for i in 0..5000 {
+            let v = noise.fbm(i as f64 * 0.173, i as f64 * 0.091, 0.0, 6, 2.0, 0.5);
+            assert!((-1.0..=1.0).contains(&v), "fbm value {} out of range", v);
+        }
+    }
+
+    #[test]
+    fn test_fbm_negative_gain_stays_finite() {
+        let noise = NoiseGenerator::new(42);
+        for i in 0..100 {
+            let (x, z, t) = (i as f64 * 0.37, i as f64 * -0.21, i as f64 * 0.05);
+
+            // Gain -1 over two octaves would divide by a zero total amplitude
+            let v = noise.fbm(x, z, t, 2, 2.0, -1.0);
+            assert!(v.is_finite(), "fbm value {} with negative gain", v);
+            assert_eq!(v, noise.fbm(x, z, t, 2, 2.0, 0.0));
+            assert!(noise.fbm(x, z, t, 4, 2.0, f32::NAN).is_finite());
+        }
     }
 }
@@ -1,65 +1,153 @@
 //! Ocean grid mesh with procedural noise animation and toroidal wrapping.
 
+use std::collections::{HashMap, HashSet};
+
 use bytemuck::{Pod, Zeroable};
 use glam::Vec3;
 
+use super::prefetch::{BaseTerrain, Cell, Lattice, TerrainPrefetcher};
 use crate::noise::NoiseGenerator;
-use crate::params::OceanPhysics;
+use crate::params::{GerstnerWave, OceanPhysics, WaveModel};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Fewest vertices per rayon job (smaller splits cost more than they save)
+#[cfg(feature = "parallel")]
+const PARALLEL_MIN_VERTICES: usize = 4096;
 
-/// Vertex data for ocean mesh (position + UV coordinates)
+/// Vertex data for ocean mesh (position + UV coordinates + surface normal + foam)
 /// Must match WGSL Vertex struct exactly (including padding for storage buffer alignment)
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Pod, Zeroable)]
 pub struct Vertex {
     pub position: [f32; 3],
-    pub _padding1: f32, // Align position to 16 bytes
+    /// Base terrain height under this vertex (m); `position[1]` minus this is the wave
+    /// height (detail + swell) above the hills (fills position's 16-byte slot)
+    pub base_height: f32,
     pub uv: [f32; 2],
-    pub _padding2: [f32; 2], // Pad to 32 bytes total for WGSL storage array alignment
+    pub _padding2: [f32; 2], // Align normal to 16 bytes
+    pub normal: [f32; 3],
+    pub foam: f32, // Whitecap intensity [0, 1] (fills the 48-byte WGSL stride)
 }
 
 /// Ocean grid mesh with procedural noise animation
 pub struct OceanGrid {
     pub vertices: Vec<Vertex>,
     pub indices: Vec<u32>,
+    /// Line-list indices for a wireframe of the grid (see `generate_line_indices`)
+    pub line_indices: Vec<u32>,
     /// Filtered indices (excludes stretched triangles from wrapping)
     pub filtered_indices: Vec<u32>,
     noise: NoiseGenerator,
     grid_size: usize,
     grid_spacing: f32,
+    /// Undisplaced vertex XZ positions (flow and wrap here; Gerstner offsets applied on top)
+    rest_xz: Vec<[f32; 2]>,
     /// Last camera position (for computing delta movement)
     last_camera_pos: Vec3,
     /// Base terrain heights (stable physics surface, not affected by audio)
     base_terrain_heights: Vec<f32>,
     /// Track which vertices have been wrapped (need base terrain recompute)
     dirty_base_terrain: Vec<bool>,
+    /// World XZ of lattice cell (0, 0): vertices stay on this lattice until the next `build`
+    lattice_origin: [f32; 2],
+    /// Worker precomputing base terrain for rows about to wrap (`prefetch_rows` > 0)
+    prefetch: Option<TerrainPrefetcher>,
+    /// Base terrain noise samples taken inside the last `update`'s vertex loop
+    base_terrain_samples: usize,
+    /// An invalid input or height has been reported (warn only once per grid)
+    warned_invalid: bool,
+    /// Split `update`'s vertex loop and triangle filter across threads
+    #[cfg(feature = "parallel")]
+    parallel: bool,
 }
 
 impl OceanGrid {
     /// Create a new ocean grid with specified parameters
     pub fn new(physics: &OceanPhysics) -> Self {
-        let grid_size = physics.grid_size;
-        let grid_spacing = physics.grid_spacing_m;
-        let half_size = (grid_size as f32 * grid_spacing) / 2.0;
+        let mut grid = Self {
+            vertices: Vec::new(),
+            indices: Vec::new(),
+            line_indices: Vec::new(),
+            filtered_indices: Vec::new(),
+            noise: NoiseGenerator::with_kind(physics.noise_seed, physics.noise_kind),
+            grid_size: physics.grid_size,
+            grid_spacing: physics.grid_spacing_m,
+            rest_xz: Vec::new(),
+            last_camera_pos: Vec3::ZERO,
+            base_terrain_heights: Vec::new(),
+            dirty_base_terrain: Vec::new(),
+            lattice_origin: [0.0, 0.0],
+            prefetch: None,
+            base_terrain_samples: 0,
+            warned_invalid: false,
+            #[cfg(feature = "parallel")]
+            parallel: true,
+        };
+        grid.build(physics.grid_size, physics.grid_spacing_m);
+        grid
+    }
+
+    /// Grid resolution (vertices per side minus one)
+    pub fn grid_size(&self) -> usize {
+        self.grid_size
+    }
+
+    /// Spacing between grid vertices (meters)
+    pub fn grid_spacing(&self) -> f32 {
+        self.grid_spacing
+    }
+
+    /// Rebuild at a new resolution covering the same world extent (level-of-detail switch)
+    ///
+    /// Vertex and index buffers are reused (no reallocation when shrinking). The grid is laid
+    /// out fresh around the camera and every vertex recomputes its base terrain on the next
+    /// `update`.
+    pub fn regenerate(&mut self, grid_size: usize) {
+        let grid_world_size = self.grid_size as f32 * self.grid_spacing;
+        self.build(grid_size, grid_world_size / grid_size as f32);
+    }
+
+    /// Return to the freshly built state at the current resolution
+    ///
+    /// Flat vertices centered on the origin, camera at the origin, and every vertex's base
+    /// terrain recomputed on the next `update` (which then matches the first `update` of a
+    /// new grid).
+    pub fn reset(&mut self) {
+        self.last_camera_pos = Vec3::ZERO;
+        self.prefetch = None;
+        self.base_terrain_samples = 0;
+        self.warned_invalid = false;
+        self.build(self.grid_size, self.grid_spacing);
+    }
 
-        let mut vertices = Vec::new();
-        let mut indices = Vec::new();
+    /// Lay out a flat `grid_size` × `grid_size` lattice centered on the camera
+    fn build(&mut self, grid_size: usize, grid_spacing: f32) {
+        let half_size = (grid_size as f32 * grid_spacing) / 2.0;
+        self.grid_size = grid_size;
+        self.grid_spacing = grid_spacing;
 
         // Generate flat XZ plane grid
+        self.vertices.clear();
         for z in 0..=grid_size {
             for x in 0..=grid_size {
                 let x_pos = x as f32 * grid_spacing - half_size;
                 let z_pos = z as f32 * grid_spacing - half_size;
 
-                vertices.push(Vertex {
+                self.vertices.push(Vertex {
                     position: [x_pos, 0.0, z_pos],
-                    _padding1: 0.0,
+                    base_height: 0.0,
                     uv: [x as f32 / grid_size as f32, z as f32 / grid_size as f32],
                     _padding2: [0.0, 0.0],
+                    normal: [0.0, 1.0, 0.0], // Flat plane faces up
+                    foam: 0.0,
                 });
             }
         }
 
         // Generate triangle indices (counter-clockwise winding)
+        self.indices.clear();
         for z in 0..grid_size {
             for x in 0..grid_size {
                 let top_left = (z * (grid_size + 1) + x) as u32;
@@ -67,7 +155,7 @@ impl OceanGrid {
                 let bottom_left = ((z + 1) * (grid_size + 1) + x) as u32;
                 let bottom_right = bottom_left + 1;
 
-                indices.extend_from_slice(&[
+                self.indices.extend_from_slice(&[
                     top_left,
                     bottom_left,
                     top_right,
@@ -78,20 +166,36 @@ impl OceanGrid {
             }
         }
 
-        let vertex_count = vertices.len();
-        let filtered_indices = indices.clone(); // Initially same as indices
+        // Initially same as indices
+        self.filtered_indices.clone_from(&self.indices);
 
-        Self {
-            vertices,
-            indices,
-            filtered_indices,
-            noise: NoiseGenerator::new(physics.noise_seed),
-            grid_size: physics.grid_size,
-            grid_spacing: physics.grid_spacing_m,
-            last_camera_pos: Vec3::ZERO,
-            base_terrain_heights: vec![0.0; vertex_count],
-            dirty_base_terrain: vec![true; vertex_count], // Initially all need computation
-        }
+        self.line_indices.clear();
+        self.line_indices
+            .extend(generate_line_indices(grid_size as u32 + 1));
+
+        self.rest_xz.clear();
+        self.rest_xz
+            .extend(self.vertices.iter().map(|v| [v.position[0], v.position[2]]));
+
+        let vertex_count = self.vertices.len();
+        self.base_terrain_heights.clear();
+        self.base_terrain_heights.resize(vertex_count, 0.0);
+        self.dirty_base_terrain.clear();
+        self.dirty_base_terrain.resize(vertex_count, true); // All need computation
+
+        // Rest positions are relative to the camera seen by the next `update`
+        self.lattice_origin = [
+            self.last_camera_pos.x - half_size,
+            self.last_camera_pos.z - half_size,
+        ];
+    }
+
+    /// Base terrain noise samples taken inside the last `update`'s vertex loop
+    ///
+    /// Wrapped vertices whose height was prefetched (see `OceanPhysics::prefetch_rows`)
+    /// don't count.
+    pub fn base_terrain_samples(&self) -> usize {
+        self.base_terrain_samples
     }
 
     /// Query base terrain height at world position (for physics)
@@ -102,15 +206,79 @@ impl OceanGrid {
     pub fn query_base_terrain(&self, world_x: f32, world_z: f32, physics: &OceanPhysics) -> f32 {
         let t = 0.0; // Base terrain is time-independent (static hills)
 
-        let noise_value = self.noise.sample_3d(
+        let noise_value = self.noise.sample(
             (world_x * physics.base_terrain_frequency) as f64,
             (world_z * physics.base_terrain_frequency) as f64,
             t as f64,
+            physics.base_terrain_octaves,
+            physics.noise_lacunarity,
+            physics.noise_gain,
         );
 
         noise_value * physics.base_terrain_amplitude_m
     }
 
+    /// Block until every requested prefetch has arrived (deterministic tests)
+    #[cfg(test)]
+    pub(crate) fn wait_for_prefetch(&mut self) {
+        if let Some(prefetch) = &mut self.prefetch {
+            prefetch.wait();
+        }
+    }
+
+    /// Rendered surface height at world position, bilinear between grid vertices (for physics)
+    ///
+    /// Interpolates the four surrounding vertex heights (base + detail + Gerstner, as drawn)
+    /// on the flowing grid lattice, so physics matches the mesh. Gerstner XZ displacement is
+    /// ignored when locating the cell. Positions outside the current grid window fall back
+    /// to `query_base_terrain`.
+    pub fn sample_height_bilinear(
+        &self,
+        world_x: f32,
+        world_z: f32,
+        physics: &OceanPhysics,
+    ) -> f32 {
+        let row = self.grid_size + 1;
+        let local_x = world_x - self.last_camera_pos.x;
+        let local_z = world_z - self.last_camera_pos.z;
+
+        // Every vertex in a grid column shares its rest X, every vertex in a grid row its rest Z
+        let cell_x = self.lattice_cell(local_x, |column| self.rest_xz[column][0]);
+        let cell_z = self.lattice_cell(local_z, |grid_row| self.rest_xz[grid_row * row][1]);
+        let (Some((x0, x1, tx)), Some((z0, z1, tz))) = (cell_x, cell_z) else {
+            return self.query_base_terrain(world_x, world_z, physics);
+        };
+
+        let height = |x: usize, z: usize| self.vertices[z * row + x].position[1];
+        let near = height(x0, z0) + (height(x1, z0) - height(x0, z0)) * tx;
+        let far = height(x0, z1) + (height(x1, z1) - height(x0, z1)) * tx;
+        near + (far - near) * tz
+    }
+
+    /// Grid lines bracketing `coord` along one axis: (lower index, upper index, fraction)
+    ///
+    /// `line(i)` is the rest coordinate of grid line `i`. None if `coord` is outside the grid.
+    fn lattice_cell(&self, coord: f32, line: impl Fn(usize) -> f32) -> Option<(usize, usize, f32)> {
+        let spacing = self.grid_spacing;
+        let tolerance = 1e-3; // In spacings: absorbs float drift from flowing
+
+        // Lines flow together, so line i sits i spacings past line 0 (modulo the wrap)
+        let steps = ((coord - line(0)) / spacing + tolerance).floor() as i64;
+        let lower = steps.rem_euclid(self.grid_size as i64) as usize;
+        let upper = (lower + 1) % self.grid_size;
+
+        // Near the wrap seam the neighbouring lines sit on the far side of the grid
+        let expected_lower = line(0) + steps as f32 * spacing;
+        let on_lattice =
+            |index: usize, expected: f32| (line(index) - expected).abs() < spacing * 0.5;
+        if !on_lattice(lower, expected_lower) || !on_lattice(upper, expected_lower + spacing) {
+            return None;
+        }
+
+        let offset = coord - line(lower);
+        Some((lower, upper, (offset / spacing).clamp(0.0, 1.0)))
+    }
+
     /// Update ocean surface with two-layer terrain system
     ///
     /// Layer 1 (Base terrain): Stable large-scale hills for skiing physics
@@ -119,8 +287,21 @@ impl OceanGrid {
     /// Uses flowing surface approach: grid vertices scroll backward as camera "moves" forward,
     /// with toroidal wrapping to create infinite extent illusion.
     ///
+    /// In `WaveModel::Gerstner` mode, vertices are additionally displaced (XZ and height) by
+    /// the configured Gerstner waves, with steepness scaled by audio-modulated amplitude.
+    ///
+    /// Non-finite or negative detail amplitude/frequency is treated as 0.0, and any
+    /// non-finite height (e.g. from a bad config value) is reset to 0.0, so the mesh never
+    /// vanishes; the first such problem is logged.
+    ///
+    /// With the `parallel` feature, vertices and triangles are processed on rayon's thread
+    /// pool; each vertex is independent, so the result is identical to the serial loop.
+    ///
+    /// With `prefetch_rows` > 0, wrapped vertices take their base terrain from the
+    /// background prefetch when it is ready, and the rows next in line are requested.
+    ///
     /// # Arguments
-    /// * `time_s` - Current time in seconds
+    /// * `time_s` - Current time in seconds (app clock; `physics.time_offset_s` is added)
     /// * `detail_amplitude_m` - Detail wave height (audio-modulated)
     /// * `detail_frequency` - Detail spatial frequency
     /// * `camera_pos` - Camera position (used to compute flow velocity)
@@ -133,7 +314,37 @@ impl OceanGrid {
         camera_pos: Vec3,
         physics: &OceanPhysics,
     ) {
-        let detail_t = time_s * physics.wave_speed;
+        let detail_phase = physics.world_time_s(time_s) * physics.wave_speed;
+        self.update_with_detail_phase(
+            time_s,
+            detail_phase,
+            detail_amplitude_m,
+            detail_frequency,
+            camera_pos,
+            physics,
+        );
+    }
+
+    /// `update` with an explicit detail layer phase instead of time × `wave_speed`
+    ///
+    /// For a wave speed that changes over time (see `OceanSystem::detail_phase`), where the
+    /// caller accumulates the phase so it never jumps.
+    pub fn update_with_detail_phase(
+        &mut self,
+        time_s: f32,
+        detail_phase: f32,
+        detail_amplitude_m: f32,
+        detail_frequency: f32,
+        camera_pos: Vec3,
+        physics: &OceanPhysics,
+    ) {
+        let time_s = physics.world_time_s(time_s);
+        let detail_amplitude_m = self.valid_input("detail_amplitude_m", detail_amplitude_m);
+        let detail_frequency = self.valid_input("detail_frequency", detail_frequency);
+        let detail_t = detail_phase;
+
+        // Swell carries the detail pattern along its direction, camera or not
+        let swell_offset = physics.swell_offset_m(time_s);
 
         // Compute camera delta (how much camera moved this frame)
         let camera_delta = camera_pos - self.last_camera_pos;
@@ -143,94 +354,541 @@ impl OceanGrid {
         let grid_world_size = self.grid_size as f32 * self.grid_spacing;
         let half_size = grid_world_size / 2.0;
 
-        // Flow grid backward opposite to camera motion
-        // (Camera moves forward → grid flows backward)
-        for (idx, vertex) in self.vertices.iter_mut().enumerate() {
-            // Move vertex opposite to camera motion
-            vertex.position[0] -= camera_delta.x;
-            vertex.position[2] -= camera_delta.z;
-
-            // Toroidal wrapping using modulo (branchless, better for SIMD/pipelining)
-            // Map to [0, grid_world_size) range, then shift to [-half_size, half_size)
-            let wrapped_x =
-                ((vertex.position[0] + half_size).rem_euclid(grid_world_size)) - half_size;
-            let wrapped_z =
-                ((vertex.position[2] + half_size).rem_euclid(grid_world_size)) - half_size;
-
-            let wrapped = (wrapped_x - vertex.position[0]).abs() > 0.01
-                || (wrapped_z - vertex.position[2]).abs() > 0.01;
-
-            vertex.position[0] = wrapped_x;
-            vertex.position[2] = wrapped_z;
-
-            // Get absolute world coordinates
-            let x_world = camera_pos.x + vertex.position[0];
-            let z_world = camera_pos.z + vertex.position[2];
-
-            // Layer 1: Base terrain (stable, time-independent hills)
-            // Only recompute if this vertex was just wrapped (changed position)
-            let base_height = if wrapped || self.dirty_base_terrain[idx] {
-                let base_noise = self.noise.sample_3d(
-                    (x_world * physics.base_terrain_frequency) as f64,
-                    (z_world * physics.base_terrain_frequency) as f64,
-                    0.0, // Time-independent for stable terrain
+        // Gerstner steepness follows audio: 1.0 at rest amplitude, larger with bass
+        let gerstner_waves: &[GerstnerWave] = match physics.wave_model {
+            WaveModel::Noise => &[],
+            WaveModel::Gerstner => &physics.gerstner_waves,
+        };
+        let steepness_scale = if physics.detail_amplitude_m > 0.0 {
+            detail_amplitude_m / physics.detail_amplitude_m
+        } else {
+            1.0
+        };
+
+        // Base terrain prefetched for this lattice (worker started on first use)
+        let terrain = BaseTerrain::new(physics);
+        let lattice = Lattice {
+            origin: self.lattice_origin,
+            spacing: self.grid_spacing,
+        };
+        if physics.prefetch_rows == 0 {
+            self.prefetch = None;
+        } else {
+            self.prefetch
+                .get_or_insert_with(TerrainPrefetcher::spawn)
+                .receive();
+        }
+
+        let step = VertexStep {
+            noise: &self.noise,
+            physics,
+            terrain,
+            lattice,
+            prefetched: self
+                .prefetch
+                .as_ref()
+                .and_then(|prefetch| prefetch.heights(&terrain, &lattice)),
+            time_s,
+            detail_amplitude_m,
+            detail_frequency,
+            detail_t,
+            swell_offset,
+            camera_pos,
+            camera_delta,
+            grid_world_size,
+            half_size,
+            gerstner_waves,
+            steepness_scale,
+        };
+
+        // Flow grid backward opposite to camera motion (vertices are independent)
+        #[cfg(feature = "parallel")]
+        let stats = if self.parallel {
+            step.apply_parallel(
+                &mut self.vertices,
+                &mut self.rest_xz,
+                &mut self.base_terrain_heights,
+                &mut self.dirty_base_terrain,
+            )
+        } else {
+            step.apply_serial(
+                &mut self.vertices,
+                &mut self.rest_xz,
+                &mut self.base_terrain_heights,
+                &mut self.dirty_base_terrain,
+            )
+        };
+        #[cfg(not(feature = "parallel"))]
+        let stats = step.apply_serial(
+            &mut self.vertices,
+            &mut self.rest_xz,
+            &mut self.base_terrain_heights,
+            &mut self.dirty_base_terrain,
+        );
+
+        self.base_terrain_samples = stats.base_terrain_samples;
+
+        // Request the rows that wrap next (the worker has a few frames to sample them)
+        let rows = physics.prefetch_rows.min(self.grid_size / 2);
+        let lookahead = self
+            .prefetch
+            .is_some()
+            .then(|| step.lookahead_cells(&self.rest_xz, rows as f32 * self.grid_spacing));
+        if let (Some(prefetch), Some(lookahead)) = (&mut self.prefetch, lookahead) {
+            prefetch.request(lookahead, terrain, lattice);
+        }
+
+        if stats.invalid_heights > 0 {
+            self.warn_invalid_once(&format!(
+                "{} non-finite ocean heights reset to 0.0",
+                stats.invalid_heights
+            ));
+        }
+        debug_assert!(self.vertices.iter().all(|v| v.position[1].is_finite()));
+
+        // Smooth normals from final heights
+        self.compute_normals();
+
+        // Whitecaps where the surface is steep
+        self.compute_foam(physics);
+
+        // Filter out stretched triangles (from toroidal wrapping)
+        self.filter_stretched_triangles(physics.wrap_edge_threshold_factor);
+    }
+
+    /// `value` if finite and non-negative, else 0.0 (logged once)
+    fn valid_input(&mut self, name: &str, value: f32) -> f32 {
+        if value.is_finite() && value >= 0.0 {
+            value
+        } else {
+            self.warn_invalid_once(&format!("Invalid {} {}, using 0.0", name, value));
+            0.0
+        }
+    }
+
+    /// Print a warning for the first invalid input or height this grid sees
+    fn warn_invalid_once(&mut self, message: &str) {
+        if !self.warned_invalid {
+            eprintln!(
+                "Warning: {} (further invalid ocean values are not reported)",
+                message
+            );
+            self.warned_invalid = true;
+        }
+    }
+
+    /// Compute smooth per-vertex normals from neighboring heights (central differences)
+    ///
+    /// Neighbors are taken in grid index space. A neighbor that has wrapped to the far side
+    /// of the grid (or lies past the grid edge) is skipped in favor of a one-sided difference.
+    fn compute_normals(&mut self) {
+        let row = self.grid_size + 1;
+        let spacing = self.grid_spacing;
+
+        // Height slope along one axis, using only neighbors actually adjacent in world space
+        let slope = |idx: usize, prev: Option<usize>, next: Option<usize>, axis: usize| -> f32 {
+            let adjacent = |n: usize, sign: f32| {
+                let delta = self.rest_xz[n][axis] - self.rest_xz[idx][axis];
+                (delta - sign * spacing).abs() < spacing * 0.5
+            };
+            let height = |i: usize| self.vertices[i].position[1];
+
+            let prev = prev.filter(|&n| adjacent(n, -1.0));
+            let next = next.filter(|&n| adjacent(n, 1.0));
+            match (prev, next) {
+                (Some(p), Some(n)) => (height(n) - height(p)) / (2.0 * spacing),
+                (Some(p), None) => (height(idx) - height(p)) / spacing,
+                (None, Some(n)) => (height(n) - height(idx)) / spacing,
+                (None, None) => 0.0,
+            }
+        };
+
+        let normals: Vec<[f32; 3]> = (0..self.vertices.len())
+            .map(|idx| {
+                let (x, z) = (idx % row, idx / row);
+                let dh_dx = slope(
+                    idx,
+                    (x > 0).then(|| idx - 1),
+                    (x + 1 < row).then(|| idx + 1),
+                    0,
+                );
+                let dh_dz = slope(
+                    idx,
+                    (z > 0).then(|| idx - row),
+                    (z + 1 < row).then(|| idx + row),
+                    1,
                 );
-                let h = base_noise * physics.base_terrain_amplitude_m;
-                self.base_terrain_heights[idx] = h;
-                self.dirty_base_terrain[idx] = false;
-                h
+
+                Vec3::new(-dh_dx, 1.0, -dh_dz).normalize().to_array()
+            })
+            .collect();
+
+        for (vertex, normal) in self.vertices.iter_mut().zip(normals) {
+            vertex.normal = normal;
+        }
+    }
+
+    /// Compute per-vertex foam intensity from surface steepness
+    ///
+    /// Slope magnitude |∇h| is recovered from the normal (|n.xz| / n.y) and mapped through
+    /// a smoothstep between `foam_slope_start` and `foam_slope_full`. Louder bass raises the
+    /// detail amplitude and therefore the slopes, so foam grows with the music.
+    /// A `foam_slope_full` of 0 disables foam.
+    fn compute_foam(&mut self, physics: &OceanPhysics) {
+        let start = physics.foam_slope_start;
+        let full = physics.foam_slope_full;
+
+        for vertex in &mut self.vertices {
+            let [nx, ny, nz] = vertex.normal;
+            let slope = (nx * nx + nz * nz).sqrt() / ny.max(f32::EPSILON);
+
+            vertex.foam = if full <= 0.0 {
+                0.0
+            } else if full > start {
+                let t = ((slope - start) / (full - start)).clamp(0.0, 1.0);
+                t * t * (3.0 - 2.0 * t)
+            } else if slope >= start {
+                1.0
             } else {
-                // Use cached base height
-                self.base_terrain_heights[idx]
+                0.0
             };
+        }
+    }
+
+    /// Filter indices to remove stretched triangles caused by vertex wrapping
+    ///
+    /// This prevents "phantom lines" from wrapped vertices. A triangle is dropped if any edge spans more than `threshold_factor × grid_spacing`
+    /// along X or Z. Only horizontal extent counts: wrapping stretches triangles across the
+    /// grid, while tall waves only stretch them vertically and must stay visible.
+    /// Undisplaced edges span at most one spacing per axis, so any factor > 1 keeps a flat grid.
+    fn filter_stretched_triangles(&mut self, threshold_factor: f32) {
+        let max_axis_extent = self.grid_spacing * threshold_factor;
+        let vertices = &self.vertices;
+        let keep = |tri: &&[u32]| !is_stretched(vertices, tri, max_axis_extent);
 
-            // Layer 2: Detail (audio-reactive, animated)
-            let detail_noise = self.noise.sample_3d(
-                (x_world * detail_frequency) as f64,
-                (z_world * detail_frequency) as f64,
-                detail_t as f64,
+        self.filtered_indices.clear();
+
+        // Each rayon job collects its kept triangles; `par_extend` merges them in order
+        #[cfg(feature = "parallel")]
+        if self.parallel {
+            self.filtered_indices.par_extend(
+                self.indices
+                    .par_chunks(3)
+                    .filter(keep)
+                    .flat_map_iter(|tri| tri.iter().copied()),
             );
-            let detail_height = detail_noise * detail_amplitude_m;
+            return;
+        }
 
-            // Combine layers for visual rendering
-            vertex.position[1] = base_height + detail_height;
+        for tri in self.indices.chunks(3).filter(keep) {
+            self.filtered_indices.extend_from_slice(tri);
         }
+    }
+}
 
-        // Filter out stretched triangles (from toroidal wrapping)
-        self.filter_stretched_triangles();
+/// Whether a triangle spans more than `max_axis_extent` along X or Z (wrap-stretched)
+fn is_stretched(vertices: &[Vertex], tri: &[u32], max_axis_extent: f32) -> bool {
+    let [x0, _, z0] = vertices[tri[0] as usize].position;
+    let [x1, _, z1] = vertices[tri[1] as usize].position;
+    let [x2, _, z2] = vertices[tri[2] as usize].position;
+
+    // Horizontal extent of the triangle on each axis (covers all three edges)
+    let extent_x = x0.max(x1).max(x2) - x0.min(x1).min(x2);
+    let extent_z = z0.max(z1).max(z2) - z0.min(z1).min(z2);
+    extent_x >= max_axis_extent || extent_z >= max_axis_extent
+}
+
+/// Per-update inputs shared by every vertex of `OceanGrid::update`
+struct VertexStep<'a> {
+    noise: &'a NoiseGenerator,
+    physics: &'a OceanPhysics,
+    terrain: BaseTerrain,
+    lattice: Lattice,
+    /// Background-sampled base heights for cells vertices are about to wrap onto
+    prefetched: Option<&'a HashMap<Cell, f32>>,
+    time_s: f32,
+    detail_amplitude_m: f32,
+    detail_frequency: f32,
+    /// Detail noise time (`time_s` × wave speed)
+    detail_t: f32,
+    /// Distance the swell has carried the detail noise (`swell_direction` × speed × time)
+    swell_offset: glam::Vec2,
+    camera_pos: Vec3,
+    camera_delta: Vec3,
+    grid_world_size: f32,
+    half_size: f32,
+    gerstner_waves: &'a [GerstnerWave],
+    steepness_scale: f32,
+}
+
+/// What `VertexStep::apply` did, summed over the grid
+#[derive(Clone, Copy, Debug, Default)]
+struct VertexStats {
+    /// Non-finite heights reset to 0.0
+    invalid_heights: usize,
+    /// Base terrain noise samples (wrapped or dirty vertices without a prefetched height)
+    base_terrain_samples: usize,
+}
+
+impl std::iter::Sum for VertexStats {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |total, stats| Self {
+            invalid_heights: total.invalid_heights + stats.invalid_heights,
+            base_terrain_samples: total.base_terrain_samples + stats.base_terrain_samples,
+        })
     }
+}
 
-    /// Filter indices to remove stretched triangles caused by vertex wrapping
+impl VertexStep<'_> {
+    /// Flow, wrap and displace one vertex
+    fn apply(
+        &self,
+        vertex: &mut Vertex,
+        rest: &mut [f32; 2],
+        base_terrain_height: &mut f32,
+        dirty_base_terrain: &mut bool,
+    ) -> VertexStats {
+        let physics = self.physics;
+        let mut stats = VertexStats::default();
+
+        // Move vertex opposite to camera motion
+        rest[0] -= self.camera_delta.x;
+        rest[1] -= self.camera_delta.z;
+
+        // Toroidal wrapping using modulo (branchless, better for SIMD/pipelining)
+        // Map to [0, grid_world_size) range, then shift to [-half_size, half_size)
+        let wrapped_x =
+            ((rest[0] + self.half_size).rem_euclid(self.grid_world_size)) - self.half_size;
+        let wrapped_z =
+            ((rest[1] + self.half_size).rem_euclid(self.grid_world_size)) - self.half_size;
+
+        let wrapped = (wrapped_x - rest[0]).abs() > 0.01 || (wrapped_z - rest[1]).abs() > 0.01;
+
+        rest[0] = wrapped_x;
+        rest[1] = wrapped_z;
+
+        // Get absolute world coordinates
+        let x_world = self.camera_pos.x + rest[0];
+        let z_world = self.camera_pos.z + rest[1];
+
+        // Layer 1: Base terrain (stable, time-independent hills)
+        // Only recompute if this vertex was just wrapped (changed position)
+        if wrapped || *dirty_base_terrain {
+            let cell = self.lattice.cell(x_world, z_world);
+            *base_terrain_height = match self.prefetched.and_then(|heights| heights.get(&cell)) {
+                Some(&height) => height,
+                None => {
+                    stats.base_terrain_samples += 1;
+                    self.terrain.height(self.noise, x_world, z_world)
+                }
+            };
+            *dirty_base_terrain = false;
+        }
+
+        // Layer 2: Detail (audio-reactive, animated, advected by the swell)
+        let detail_noise = self.noise.sample(
+            ((x_world - self.swell_offset.x) * self.detail_frequency) as f64,
+            ((z_world - self.swell_offset.y) * self.detail_frequency) as f64,
+            self.detail_t as f64,
+            physics.detail_octaves,
+            physics.noise_lacunarity,
+            physics.noise_gain,
+        );
+        let detail_height = detail_noise * self.detail_amplitude_m;
+
+        // Layer 3: Gerstner displacement (zero in noise mode)
+        let gerstner = gerstner_displacement(
+            self.gerstner_waves,
+            x_world,
+            z_world,
+            self.time_s,
+            self.steepness_scale,
+        );
+
+        // Combine layers for visual rendering
+        let height = *base_terrain_height + detail_height + gerstner.y;
+        vertex.position[0] = rest[0] + gerstner.x;
+        vertex.position[1] = if height.is_finite() { height } else { 0.0 };
+        vertex.base_height = if height.is_finite() {
+            *base_terrain_height
+        } else {
+            0.0
+        };
+        vertex.position[2] = rest[1] + gerstner.z;
+        if !height.is_finite() {
+            stats.invalid_heights += 1;
+        }
+        stats
+    }
+
+    /// `apply` to every vertex in order
+    fn apply_serial(
+        &self,
+        vertices: &mut [Vertex],
+        rest_xz: &mut [[f32; 2]],
+        base_terrain_heights: &mut [f32],
+        dirty_base_terrain: &mut [bool],
+    ) -> VertexStats {
+        vertices
+            .iter_mut()
+            .zip(rest_xz)
+            .zip(base_terrain_heights)
+            .zip(dirty_base_terrain)
+            .map(|(((vertex, rest), base), dirty)| self.apply(vertex, rest, base, dirty))
+            .sum()
+    }
+
+    /// `apply_serial` split into chunks of at least `PARALLEL_MIN_VERTICES` across threads
+    #[cfg(feature = "parallel")]
+    fn apply_parallel(
+        &self,
+        vertices: &mut [Vertex],
+        rest_xz: &mut [[f32; 2]],
+        base_terrain_heights: &mut [f32],
+        dirty_base_terrain: &mut [bool],
+    ) -> VertexStats {
+        vertices
+            .par_iter_mut()
+            .zip(rest_xz)
+            .zip(base_terrain_heights)
+            .zip(dirty_base_terrain)
+            .with_min_len(PARALLEL_MIN_VERTICES)
+            .map(|(((vertex, rest), base), dirty)| self.apply(vertex, rest, base, dirty))
+            .sum()
+    }
+
+    /// Cells the vertices within `band` of a trailing grid edge land on at their next wrap
     ///
-    /// Triangles with any edge longer than threshold are excluded from rendering.
-    /// This prevents "phantom lines" from wrapped vertices.
-    fn filter_stretched_triangles(&mut self) {
-        // Threshold: any edge longer than this is considered stretched
-        // Use 10x grid spacing as reasonable max edge length
-        let max_edge_length = self.grid_spacing * 10.0;
-        let max_edge_sq = max_edge_length * max_edge_length; // Use squared distance (cheaper)
+    /// On each axis the camera moves along, the trailing edge is the one vertices flow
+    /// toward; a vertex there wraps one grid extent ahead.
+    fn lookahead_cells(&self, rest_xz: &[[f32; 2]], band: f32) -> HashSet<Cell> {
+        let shift = |rest: f32, delta: f32| {
+            if delta > 0.0 && rest < band - self.half_size {
+                self.grid_world_size
+            } else if delta < 0.0 && rest >= self.half_size - band {
+                -self.grid_world_size
+            } else {
+                0.0
+            }
+        };
+
+        // Near a corner the vertex may wrap on either axis first, so both orders are covered
+        let mut cells = HashSet::new();
+        for rest in rest_xz {
+            let shift_x = shift(rest[0], self.camera_delta.x);
+            let shift_z = shift(rest[1], self.camera_delta.z);
+            for (dx, dz) in [(shift_x, 0.0), (0.0, shift_z), (shift_x, shift_z)] {
+                if dx != 0.0 || dz != 0.0 {
+                    cells.insert(self.lattice.cell(
+                        self.camera_pos.x + rest[0] + dx,
+                        self.camera_pos.z + rest[1] + dz,
+                    ));
+                }
+            }
+        }
+        cells
+    }
+}
 
-        self.filtered_indices.clear();
+/// Line-list indices for a row-major `grid_size`² vertex lattice (`grid_size` vertices per side)
+///
+/// Horizontal then vertical lines, skipping the edges along row 0 and column 0 like the toys'
+/// boundary-aware grids, so tiled or wrapped grids never draw a shared edge twice.
+pub fn generate_line_indices(grid_size: u32) -> Vec<u32> {
+    let edges_per_line = grid_size.saturating_sub(1);
+    let mut indices = Vec::with_capacity((4 * edges_per_line * edges_per_line) as usize);
+
+    // Horizontal lines (skip the z = 0 row)
+    for z in 1..grid_size {
+        for x in 0..edges_per_line {
+            let current = z * grid_size + x;
+            indices.extend_from_slice(&[current, current + 1]);
+        }
+    }
 
-        // Check each triangle
-        for tri in self.indices.chunks(3) {
-            let i0 = tri[0] as usize;
-            let i1 = tri[1] as usize;
-            let i2 = tri[2] as usize;
+    // Vertical lines (skip the x = 0 column)
+    for x in 1..grid_size {
+        for z in 0..edges_per_line {
+            let current = z * grid_size + x;
+            indices.extend_from_slice(&[current, current + grid_size]);
+        }
+    }
 
-            let v0 = Vec3::from_array(self.vertices[i0].position);
-            let v1 = Vec3::from_array(self.vertices[i1].position);
-            let v2 = Vec3::from_array(self.vertices[i2].position);
+    indices
+}
+
+/// Sum of Gerstner wave displacements at a world XZ position
+///
+/// Each wave moves points in a circle: horizontally along its direction and vertically,
+/// with radius `steepness / k`. Steepness is multiplied by `steepness_scale` and clamped
+/// to 1.0 (beyond which crests loop over themselves).
+fn gerstner_displacement(
+    waves: &[GerstnerWave],
+    x_world: f32,
+    z_world: f32,
+    time_s: f32,
+    steepness_scale: f32,
+) -> Vec3 {
+    let mut displacement = Vec3::ZERO;
+
+    for wave in waves {
+        if wave.wavelength_m <= 0.0 {
+            continue;
+        }
+        let direction = glam::Vec2::from_array(wave.direction).normalize_or_zero();
+        let k = std::f32::consts::TAU / wave.wavelength_m;
+        let steepness = (wave.steepness * steepness_scale).clamp(0.0, 1.0);
+        let amplitude = steepness / k;
+
+        let phase =
+            k * (direction.x * x_world + direction.y * z_world - wave.speed_m_per_s * time_s);
+        let (sin_phase, cos_phase) = phase.sin_cos();
+
+        displacement += Vec3::new(
+            direction.x * amplitude * cos_phase,
+            amplitude * sin_phase,
+            direction.y * amplitude * cos_phase,
+        );
+    }
 
-            // Check all three edges
-            let edge1_sq = v0.distance_squared(v1);
-            let edge2_sq = v1.distance_squared(v2);
-            let edge3_sq = v2.distance_squared(v0);
+    displacement
+}
 
-            // Keep triangle only if all edges are reasonable length
-            if edge1_sq < max_edge_sq && edge2_sq < max_edge_sq && edge3_sq < max_edge_sq {
-                self.filtered_indices.extend_from_slice(tri);
+#[cfg(all(test, feature = "parallel"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parallel_update_matches_serial() {
+        let physics = OceanPhysics {
+            grid_size: 128,
+            noise_seed: 42,
+            wave_model: WaveModel::Gerstner,
+            ..OceanPhysics::default()
+        };
+        let mut serial = OceanGrid::new(&physics);
+        serial.parallel = false;
+        let mut parallel = OceanGrid::new(&physics);
+        assert!(parallel.parallel);
+
+        // Fast diagonal flight: rows and columns wrap (256 m grid) on most frames
+        for frame in 0..20 {
+            let time_s = frame as f32 / 60.0;
+            let camera_pos = Vec3::new(frame as f32 * 37.0, 101.0, frame as f32 * 90.0);
+            for grid in [&mut serial, &mut parallel] {
+                grid.update(time_s, 4.0, 0.3, camera_pos, &physics);
             }
+
+            assert_eq!(
+                bytemuck::cast_slice::<Vertex, u8>(&serial.vertices),
+                bytemuck::cast_slice::<Vertex, u8>(&parallel.vertices),
+                "Frame {}",
+                frame
+            );
+            assert_eq!(serial.filtered_indices, parallel.filtered_indices);
+            assert_eq!(serial.base_terrain_heights, parallel.base_terrain_heights);
+            assert_eq!(serial.dirty_base_terrain, parallel.dirty_base_terrain);
         }
+        assert!(serial.filtered_indices.len() < serial.indices.len());
     }
 }
@@ -1,10 +1,11 @@
 //! Ocean surface simulation with procedural noise and audio-reactive modulation.
 
 mod mesh;
+mod prefetch;
 mod system;
 
 // Re-export public types
-pub use mesh::{OceanGrid, Vertex};
+pub use mesh::{generate_line_indices, OceanGrid, Vertex};
 pub use system::OceanSystem;
 
 /// Audio frequency band energies (shared between audio and rendering threads)
@@ -18,7 +19,7 @@ pub struct AudioBands {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::params::OceanPhysics;
+    use crate::params::{AudioReactiveMapping, OceanPhysics};
 
     #[test]
     fn test_ocean_grid_creation() {
@@ -31,4 +32,593 @@ mod tests {
         // Check triangle count: grid_size^2 * 2 triangles * 3 indices
         assert_eq!(grid.indices.len(), physics.grid_size.pow(2) * 6);
     }
+
+    #[test]
+    fn test_flat_surface_normals_point_up() {
+        use glam::Vec3;
+
+        let physics = OceanPhysics {
+            grid_size: 32,
+            base_terrain_amplitude_m: 0.0,
+            ..OceanPhysics::default()
+        };
+        let mut grid = OceanGrid::new(&physics);
+
+        // Move camera far enough that some vertices wrap
+        grid.update(0.0, 0.0, 0.1, Vec3::ZERO, &physics);
+        grid.update(1.0, 0.0, 0.1, Vec3::new(5.0, 0.0, 37.0), &physics);
+
+        for vertex in &grid.vertices {
+            let normal = Vec3::from_array(vertex.normal);
+            assert!(
+                normal.distance(Vec3::Y) < 1e-5,
+                "Normal {:?} not up",
+                normal
+            );
+        }
+    }
+
+    #[test]
+    fn test_base_height_is_separated_from_detail() {
+        use glam::Vec3;
+
+        let physics = OceanPhysics {
+            grid_size: 32,
+            noise_seed: 7,
+            ..OceanPhysics::default()
+        };
+        let camera_pos = Vec3::new(12.0, 100.0, -40.0);
+        let update = |detail_amplitude_m| {
+            let mut grid = OceanGrid::new(&physics);
+            grid.update(1.5, detail_amplitude_m, 0.1, camera_pos, &physics);
+            grid.vertices
+        };
+
+        // Without detail, each vertex sits exactly on its base terrain height
+        let calm = update(0.0);
+        assert!(calm.iter().all(|v| v.position[1] == v.base_height));
+        assert!(calm.iter().any(|v| v.base_height.abs() > 1.0));
+
+        // Audio detail moves the surface but not the base terrain under it
+        let loud = update(4.0);
+        let mut max_wave = 0.0f32;
+        for (calm, loud) in calm.iter().zip(&loud) {
+            assert_eq!(loud.base_height, calm.base_height);
+            let wave_height = loud.position[1] - loud.base_height;
+            assert!(wave_height.abs() <= 4.0 + 1e-3, "{}", wave_height);
+            max_wave = max_wave.max(wave_height.abs());
+        }
+        assert!(max_wave > 0.5, "Detail height {}", max_wave);
+    }
+
+    #[test]
+    fn test_invalid_inputs_keep_positions_finite() {
+        use glam::Vec3;
+
+        let physics = OceanPhysics {
+            grid_size: 32,
+            ..OceanPhysics::default()
+        };
+        let all_finite = |grid: &OceanGrid| {
+            grid.vertices
+                .iter()
+                .all(|v| v.position.iter().all(|p| p.is_finite()))
+        };
+
+        // NaN amplitude (e.g. from a config typo) is treated as no detail
+        let mut grid = OceanGrid::new(&physics);
+        grid.update(1.0, f32::NAN, 0.1, Vec3::new(3.0, 50.0, 7.0), &physics);
+        assert!(all_finite(&grid));
+
+        // Non-finite heights from the physics itself are reset to 0.0
+        let broken = OceanPhysics {
+            base_terrain_amplitude_m: f32::INFINITY,
+            ..physics.clone()
+        };
+        let mut grid = OceanGrid::new(&broken);
+        grid.update(1.0, 2.0, f32::NAN, Vec3::ZERO, &broken);
+        assert!(all_finite(&grid));
+    }
+
+    #[test]
+    fn test_bilinear_height_matches_mesh() {
+        use glam::Vec3;
+
+        let physics = OceanPhysics {
+            grid_size: 32,
+            ..OceanPhysics::default()
+        };
+        let mut grid = OceanGrid::new(&physics);
+        let camera_pos = Vec3::new(13.3, 50.0, -7.9);
+        grid.update(0.0, 0.0, 0.1, Vec3::ZERO, &physics);
+        grid.update(1.5, 2.0, 0.1, camera_pos, &physics);
+
+        // Exactly at a vertex: that vertex's height
+        let row = physics.grid_size + 1;
+        let vertex = grid.vertices[10 * row + 12];
+        let world_x = camera_pos.x + vertex.position[0];
+        let world_z = camera_pos.z + vertex.position[2];
+        let sampled = grid.sample_height_bilinear(world_x, world_z, &physics);
+        assert!(
+            (sampled - vertex.position[1]).abs() < 1e-3,
+            "Sampled {} vs vertex {}",
+            sampled,
+            vertex.position[1]
+        );
+
+        // Halfway to the +X neighbor: the average of the two heights
+        let neighbor = grid
+            .vertices
+            .iter()
+            .find(|v| {
+                (v.position[0] - vertex.position[0] - physics.grid_spacing_m).abs() < 1e-3
+                    && (v.position[2] - vertex.position[2]).abs() < 1e-3
+            })
+            .expect("vertex has a +X neighbor");
+        let midpoint =
+            grid.sample_height_bilinear(world_x + physics.grid_spacing_m * 0.5, world_z, &physics);
+        let average = (vertex.position[1] + neighbor.position[1]) * 0.5;
+        assert!((midpoint - average).abs() < 1e-3);
+
+        // Every vertex with a cell beyond it, including those that wrapped across the seam
+        let last_line =
+            physics.grid_size as f32 * physics.grid_spacing_m / 2.0 - physics.grid_spacing_m;
+        for vertex in grid
+            .vertices
+            .iter()
+            .filter(|v| v.position[0] < last_line - 1e-3 && v.position[2] < last_line - 1e-3)
+        {
+            let sampled = grid.sample_height_bilinear(
+                camera_pos.x + vertex.position[0],
+                camera_pos.z + vertex.position[2],
+                &physics,
+            );
+            assert!((sampled - vertex.position[1]).abs() < 1e-3);
+        }
+
+        // Outside the grid window: base terrain only
+        let (far_x, far_z) = (camera_pos.x + 10_000.0, camera_pos.z);
+        assert_eq!(
+            grid.sample_height_bilinear(far_x, far_z, &physics),
+            grid.query_base_terrain(far_x, far_z, &physics)
+        );
+    }
+
+    #[test]
+    fn test_gerstner_displacement() {
+        use crate::params::WaveModel;
+        use glam::Vec3;
+
+        let noise_physics = OceanPhysics {
+            grid_size: 32,
+            ..OceanPhysics::default()
+        };
+        let mut flat_physics = OceanPhysics {
+            wave_model: WaveModel::Gerstner,
+            ..noise_physics.clone()
+        };
+        for wave in &mut flat_physics.gerstner_waves {
+            wave.steepness = 0.0;
+        }
+        let steep_physics = OceanPhysics {
+            wave_model: WaveModel::Gerstner,
+            ..noise_physics.clone()
+        };
+
+        let camera_pos = Vec3::new(10.0, 50.0, 25.0);
+        let update = |physics: &OceanPhysics| {
+            let mut grid = OceanGrid::new(physics);
+            grid.update(1.5, 2.0, 0.1, camera_pos, physics);
+            grid.vertices
+        };
+        let noise_vertices = update(&noise_physics);
+        let flat_vertices = update(&flat_physics);
+        let steep_vertices = update(&steep_physics);
+
+        // Zero steepness: identical to the noise path
+        for (a, b) in noise_vertices.iter().zip(&flat_vertices) {
+            assert_eq!(a.position, b.position);
+        }
+
+        // Positive steepness: crests shift horizontally
+        let max_shift = noise_vertices
+            .iter()
+            .zip(&steep_vertices)
+            .map(|(a, b)| {
+                let dx = a.position[0] - b.position[0];
+                let dz = a.position[2] - b.position[2];
+                (dx * dx + dz * dz).sqrt()
+            })
+            .fold(0.0f32, f32::max);
+        assert!(
+            max_shift > 0.5,
+            "Max horizontal shift {} too small",
+            max_shift
+        );
+    }
+
+    #[test]
+    fn test_foam_follows_steepness() {
+        use glam::Vec3;
+
+        let physics = OceanPhysics {
+            grid_size: 32,
+            foam_slope_start: 0.6,
+            foam_slope_full: 1.2,
+            ..OceanPhysics::default()
+        };
+        let flat_physics = OceanPhysics {
+            base_terrain_amplitude_m: 0.0,
+            ..physics.clone()
+        };
+        let camera_pos = Vec3::new(10.0, 50.0, 25.0);
+        let foam_values = |physics: &OceanPhysics, detail_amplitude_m: f32| {
+            let mut grid = OceanGrid::new(physics);
+            grid.update(1.5, detail_amplitude_m, 0.1, camera_pos, physics);
+            grid.vertices.iter().map(|v| v.foam).collect::<Vec<f32>>()
+        };
+
+        // Flat surface: no foam anywhere
+        assert!(foam_values(&flat_physics, 0.0).iter().all(|&f| f == 0.0));
+
+        // Loud bass (high detail amplitude): foam appears, and more of it than at rest
+        let rest: f32 = foam_values(&physics, physics.detail_amplitude_m)
+            .iter()
+            .sum();
+        let loud = foam_values(&physics, physics.detail_amplitude_m * 5.0);
+        assert!(loud.iter().any(|&f| f > 0.0));
+        assert!(loud.iter().all(|&f| (0.0..=1.0).contains(&f)));
+        assert!(
+            loud.iter().sum::<f32>() > rest,
+            "Foam did not grow with amplitude"
+        );
+
+        // Foam is opt-in: the default slopes leave even loud chop clear
+        let default_physics = OceanPhysics {
+            grid_size: 32,
+            ..OceanPhysics::default()
+        };
+        assert!(
+            foam_values(&default_physics, physics.detail_amplitude_m * 5.0)
+                .iter()
+                .all(|&f| f == 0.0)
+        );
+    }
+
+    #[test]
+    fn test_wrap_edge_threshold_factor() {
+        use crate::params::WaveModel;
+        use glam::Vec3;
+
+        let kept_triangles = |physics: &OceanPhysics, camera_pos: Vec3| {
+            let mut grid = OceanGrid::new(physics);
+            grid.update(0.0, 0.0, 0.1, Vec3::ZERO, physics);
+            grid.update(1.5, physics.detail_amplitude_m, 0.1, camera_pos, physics);
+            grid.filtered_indices.len() / 3
+        };
+        let with_factor = |physics: &OceanPhysics, factor: f32| OceanPhysics {
+            wrap_edge_threshold_factor: factor,
+            ..physics.clone()
+        };
+
+        // Flat grid that has flowed and wrapped: every non-seam triangle survives any factor > 1
+        let flat = OceanPhysics {
+            grid_size: 32,
+            base_terrain_amplitude_m: 0.0,
+            detail_amplitude_m: 0.0,
+            ..OceanPhysics::default()
+        };
+        let moved = Vec3::new(5.0, 0.0, 37.0);
+        let unwrapped = kept_triangles(&with_factor(&flat, 10.0), moved);
+        assert!(unwrapped < 32 * 32 * 2, "Seam triangles should be culled");
+        for factor in [1.01, 2.0, 10.0, 20.0] {
+            assert_eq!(
+                kept_triangles(&with_factor(&flat, factor), moved),
+                unwrapped
+            );
+        }
+
+        // Steep Gerstner waves with wrapping: more factor keeps more triangles
+        let waves = OceanPhysics {
+            wave_model: WaveModel::Gerstner,
+            ..flat.clone()
+        };
+        let tight = kept_triangles(&with_factor(&waves, 1.05), moved);
+        let default = kept_triangles(&with_factor(&waves, 10.0), moved);
+        let loose = kept_triangles(&with_factor(&waves, 40.0), moved);
+        assert!(
+            tight < default && default < loose,
+            "Kept triangles {} / {} / {} not increasing with factor",
+            tight,
+            default,
+            loose
+        );
+    }
+
+    #[test]
+    fn test_line_indices_match_analytic_count() {
+        // n² lattice minus row 0 and column 0: 2 (n - 1)² edges, two indices each
+        for n in [2, 3, 17] {
+            let indices = generate_line_indices(n);
+            assert_eq!(
+                indices.len(),
+                4 * ((n - 1) * (n - 1)) as usize,
+                "{} per side",
+                n
+            );
+            assert!(indices.iter().all(|&i| i < n * n), "{} per side", n);
+
+            for edge in indices.chunks(2) {
+                let (a, b) = (edge[0], edge[1]);
+                // Right neighbor off row 0, or the vertex below off column 0
+                let horizontal = b == a + 1 && a % n != n - 1 && a >= n;
+                let vertical = b == a + n && a % n != 0;
+                assert!(horizontal || vertical, "{:?}", edge);
+            }
+        }
+        assert!(generate_line_indices(1).is_empty());
+
+        // The CPU grid has grid_size + 1 vertices per side
+        let physics = OceanPhysics {
+            grid_size: 8,
+            ..OceanPhysics::default()
+        };
+        let grid = OceanGrid::new(&physics);
+        assert_eq!(grid.line_indices, generate_line_indices(9));
+        assert!(grid
+            .line_indices
+            .iter()
+            .all(|&i| (i as usize) < grid.vertices.len()));
+    }
+
+    #[test]
+    fn test_prefetch_takes_base_terrain_out_of_the_update_loop() {
+        use glam::Vec3;
+
+        let run = |prefetch_rows| {
+            let physics = OceanPhysics {
+                grid_size: 64,
+                grid_spacing_m: 2.0,
+                prefetch_rows,
+                ..OceanPhysics::default()
+            };
+            let mut grid = OceanGrid::new(&physics);
+            grid.update(0.0, 0.0, 0.0, Vec3::ZERO, &physics); // Initial fill samples every vertex
+
+            // Steady flight: one row wraps per frame
+            let mut samples = 0;
+            let mut heights = Vec::new();
+            for frame in 1..=60 {
+                let camera_pos = Vec3::new(0.5 * frame as f32, 0.0, 2.0 * frame as f32);
+                grid.update(0.0, 0.0, 0.0, camera_pos, &physics);
+                grid.wait_for_prefetch();
+                samples += grid.base_terrain_samples();
+                heights.extend(grid.vertices.iter().map(|v| v.position[1]));
+            }
+            (samples, heights)
+        };
+
+        let (inline_samples, inline_heights) = run(0);
+        let (prefetched_samples, prefetched_heights) = run(8);
+
+        // Without prefetch every wrapped row is sampled in the loop; with it, only the first
+        // frame's (nothing has been requested yet)
+        assert!(inline_samples >= 60 * 65, "Only {} samples", inline_samples);
+        assert!(
+            prefetched_samples * 10 < inline_samples,
+            "{} prefetched vs {} inline samples",
+            prefetched_samples,
+            inline_samples
+        );
+
+        // Same terrain either way
+        for (a, b) in inline_heights.iter().zip(&prefetched_heights) {
+            assert!((a - b).abs() < 1e-2, "Height {} vs {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_swell_moves_waves_under_a_stationary_camera() {
+        use glam::Vec3;
+
+        // Frozen noise time: only the swell can change the detail layer
+        let heights = |swell_speed_m_per_s| {
+            let physics = OceanPhysics {
+                grid_size: 16,
+                wave_speed: 0.0,
+                swell_direction: [1.0, 1.0],
+                swell_speed_m_per_s,
+                ..OceanPhysics::default()
+            };
+            let mut grid = OceanGrid::new(&physics);
+            (0..5)
+                .map(|frame| {
+                    grid.update(frame as f32 * 0.5, 2.0, 0.1, Vec3::ZERO, &physics);
+                    grid.vertices[100].position[1]
+                })
+                .collect::<Vec<f32>>()
+        };
+
+        let still = heights(0.0);
+        assert!(still.iter().all(|&h| h == still[0]), "Moved: {:?}", still);
+
+        let swell = heights(3.0);
+        assert!(
+            swell
+                .windows(2)
+                .all(|pair| (pair[1] - pair[0]).abs() > 1e-4),
+            "Swell didn't move the vertex: {:?}",
+            swell
+        );
+        assert_eq!(swell[0], still[0]); // No offset yet at t = 0
+    }
+
+    #[test]
+    fn test_reset_reproduces_the_first_update() {
+        use glam::Vec3;
+
+        let physics = OceanPhysics {
+            grid_size: 16,
+            ..OceanPhysics::default()
+        };
+        let bands = AudioBands {
+            low: 0.8,
+            mid: 0.4,
+            high: 0.2,
+        };
+        let mut ocean = OceanSystem::new(physics, AudioReactiveMapping::default()).unwrap();
+        let camera = Vec3::new(30.0, 50.0, -120.0);
+        let positions = |ocean: &OceanSystem| {
+            ocean
+                .grid
+                .vertices
+                .iter()
+                .map(|v| v.position)
+                .collect::<Vec<_>>()
+        };
+
+        let first = ocean.update(1.0, &bands, camera);
+        let first_positions = positions(&ocean);
+
+        // Move on: later time, quiet audio, camera far enough to wrap rows
+        for frame in 1..20 {
+            ocean.update(
+                1.0 + frame as f32 * 0.1,
+                &AudioBands::default(),
+                camera + Vec3::new(0.0, 0.0, -40.0 * frame as f32),
+            );
+        }
+        assert_ne!(positions(&ocean), first_positions);
+
+        ocean.reset();
+        assert!(ocean.grid.vertices.iter().all(|v| v.position[1] == 0.0));
+        assert_eq!(ocean.update(1.0, &bands, camera), first);
+        assert_eq!(positions(&ocean), first_positions);
+    }
+
+    #[test]
+    fn test_high_band_speeds_up_detail_phase() {
+        use crate::params::{AudioBand, Mapping, ParamTarget};
+        use glam::Vec3;
+
+        let physics = OceanPhysics {
+            grid_size: 16,
+            wave_speed: 0.5,
+            ..OceanPhysics::default()
+        };
+        let mapping = AudioReactiveMapping {
+            routes: vec![Mapping::new(AudioBand::High, ParamTarget::WaveSpeed, 0.25)],
+            ..AudioReactiveMapping::default()
+        };
+        let mut quiet = OceanSystem::new(physics.clone(), mapping.clone()).unwrap();
+        let mut loud = OceanSystem::new(physics, mapping).unwrap();
+        let loud_bands = AudioBands {
+            high: 2.0,
+            ..AudioBands::default()
+        };
+
+        // 1 s of silence, then 1 s of high band 2.0 (wave speed 0.5 + 0.5) at 60 Hz
+        let dt = 1.0 / 60.0;
+        for step in 0..=120 {
+            let time_s = step as f32 * dt;
+            let bands = if step > 60 {
+                loud_bands
+            } else {
+                AudioBands::default()
+            };
+            let before = loud.detail_phase();
+            quiet.update(time_s, &AudioBands::default(), Vec3::ZERO);
+            loud.update(time_s, &bands, Vec3::ZERO);
+
+            // The phase never jumps when the speed changes
+            assert!(
+                loud.detail_phase() - before <= 1.0 * dt + 1e-5,
+                "step {}",
+                step
+            );
+        }
+
+        assert!((quiet.detail_phase() - 1.0).abs() < 1e-3);
+        assert!((loud.detail_phase() - 1.5).abs() < 1e-3);
+        let heights = |ocean: &OceanSystem| {
+            ocean
+                .grid
+                .vertices
+                .iter()
+                .map(|v| v.position[1])
+                .collect::<Vec<_>>()
+        };
+        assert_ne!(heights(&quiet), heights(&loud));
+    }
+
+    #[test]
+    fn test_time_offset_separates_instances() {
+        use glam::Vec3;
+
+        let heights = |time_offset_s| {
+            let physics = OceanPhysics {
+                grid_size: 16,
+                time_offset_s,
+                ..OceanPhysics::default()
+            };
+            let mut grid = OceanGrid::new(&physics);
+            grid.update(2.0, 2.0, 0.1, Vec3::ZERO, &physics);
+            grid.vertices
+                .iter()
+                .map(|v| v.position[1])
+                .collect::<Vec<_>>()
+        };
+
+        // Same seed, same clock: only the offset tells them apart
+        assert_ne!(heights(0.0), heights(30.0));
+
+        // An offset instance at t = 2 shows what an unshifted one shows at t = 32
+        let physics = OceanPhysics {
+            grid_size: 16,
+            ..OceanPhysics::default()
+        };
+        let mut grid = OceanGrid::new(&physics);
+        grid.update(32.0, 2.0, 0.1, Vec3::ZERO, &physics);
+        let unshifted: Vec<f32> = grid.vertices.iter().map(|v| v.position[1]).collect();
+        assert_eq!(heights(30.0), unshifted);
+    }
+
+    #[test]
+    fn test_seed_selects_cpu_terrain() {
+        let height_with = |noise_seed| {
+            let physics = OceanPhysics {
+                grid_size: 16,
+                noise_seed,
+                ..OceanPhysics::default()
+            };
+            OceanGrid::new(&physics).query_base_terrain(123.0, -456.0, &physics)
+        };
+
+        assert_eq!(height_with(7), height_with(7));
+        assert_ne!(height_with(7), height_with(8));
+    }
+
+    #[test]
+    fn test_grid_dispatches_on_noise_kind() {
+        use crate::params::NoiseKind;
+
+        let height_with = |noise_kind| {
+            let physics = OceanPhysics {
+                grid_size: 16,
+                base_terrain_octaves: 4,
+                noise_kind,
+                ..OceanPhysics::default()
+            };
+            OceanGrid::new(&physics).query_base_terrain(123.0, -456.0, &physics)
+        };
+
+        let perlin = height_with(NoiseKind::Perlin);
+        let simplex = height_with(NoiseKind::Simplex);
+        let fbm = height_with(NoiseKind::Fbm);
+        for h in [perlin, simplex, fbm] {
+            assert!(h.is_finite(), "Height {} not finite", h);
+        }
+        assert_ne!(perlin, simplex);
+        assert_ne!(simplex, fbm);
+    }
 }
@@ -0,0 +1,232 @@
+//! Background base-terrain prefetch for rows about to wrap (see `OceanPhysics::prefetch_rows`).
+//!
+//! Grid vertices never leave the world lattice laid out by `OceanGrid::build`: flowing keeps
+//! their world position fixed and a wrap moves it by exactly one grid extent. So the cells the
+//! trailing rows will land on are known frames ahead, and a worker thread samples their base
+//! heights while the update loop reads finished ones from a cache.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::JoinHandle;
+
+use crate::noise::NoiseGenerator;
+use crate::params::{NoiseKind, OceanPhysics};
+
+/// World lattice cell: grid steps along X and Z from the lattice origin
+pub type Cell = (i64, i64);
+
+/// Everything a base terrain height depends on besides position
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BaseTerrain {
+    seed: u32,
+    kind: NoiseKind,
+    frequency: f32,
+    amplitude_m: f32,
+    octaves: u32,
+    lacunarity: f32,
+    gain: f32,
+}
+
+impl BaseTerrain {
+    /// Base terrain settings from `physics`
+    pub fn new(physics: &OceanPhysics) -> Self {
+        Self {
+            seed: physics.noise_seed,
+            kind: physics.noise_kind,
+            frequency: physics.base_terrain_frequency,
+            amplitude_m: physics.base_terrain_amplitude_m,
+            octaves: physics.base_terrain_octaves,
+            lacunarity: physics.noise_lacunarity,
+            gain: physics.noise_gain,
+        }
+    }
+
+    /// Base terrain height at world XZ (`noise` must match `seed` and `kind`)
+    pub fn height(&self, noise: &NoiseGenerator, x_world: f32, z_world: f32) -> f32 {
+        let base_noise = noise.sample(
+            (x_world * self.frequency) as f64,
+            (z_world * self.frequency) as f64,
+            0.0, // Time-independent for stable terrain
+            self.octaves,
+            self.lacunarity,
+            self.gain,
+        );
+        base_noise * self.amplitude_m
+    }
+}
+
+/// Grid lattice in world space (fixed between `OceanGrid` rebuilds)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lattice {
+    /// World XZ of cell (0, 0)
+    pub origin: [f32; 2],
+    pub spacing: f32,
+}
+
+impl Lattice {
+    /// Cell nearest world XZ
+    pub fn cell(&self, x_world: f32, z_world: f32) -> Cell {
+        (
+            ((x_world - self.origin[0]) / self.spacing).round() as i64,
+            ((z_world - self.origin[1]) / self.spacing).round() as i64,
+        )
+    }
+
+    /// World XZ of `cell`
+    pub fn position(&self, cell: Cell) -> (f32, f32) {
+        (
+            self.origin[0] + cell.0 as f32 * self.spacing,
+            self.origin[1] + cell.1 as f32 * self.spacing,
+        )
+    }
+}
+
+/// Cells to sample, tagged with the prefetcher generation that asked for them
+struct Request {
+    generation: u64,
+    terrain: BaseTerrain,
+    lattice: Lattice,
+    cells: Vec<Cell>,
+}
+
+/// Sampled heights for one `Request`
+struct Response {
+    generation: u64,
+    heights: Vec<(Cell, f32)>,
+}
+
+/// Worker thread sampling base terrain ahead of wraps, plus the heights it has finished
+///
+/// Results for an outdated lattice or terrain (after `OceanGrid::regenerate` or a config
+/// change) are discarded. The worker stops when the prefetcher is dropped.
+pub struct TerrainPrefetcher {
+    requests: Option<Sender<Request>>,
+    responses: Receiver<Response>,
+    thread: Option<JoinHandle<()>>,
+    /// Bumped whenever the lattice or terrain changes
+    generation: u64,
+    terrain: Option<BaseTerrain>,
+    lattice: Option<Lattice>,
+    cache: HashMap<Cell, f32>,
+    /// Cells requested but not yet received
+    pending: HashSet<Cell>,
+}
+
+impl TerrainPrefetcher {
+    /// Start the worker thread (idle until `request`)
+    pub fn spawn() -> Self {
+        let (request_sender, request_receiver) = mpsc::channel::<Request>();
+        let (response_sender, response_receiver) = mpsc::channel();
+
+        let thread = std::thread::spawn(move || {
+            for request in request_receiver {
+                let terrain = request.terrain;
+                let noise = NoiseGenerator::with_kind(terrain.seed, terrain.kind);
+                let heights = request
+                    .cells
+                    .iter()
+                    .map(|&cell| {
+                        let (x, z) = request.lattice.position(cell);
+                        (cell, terrain.height(&noise, x, z))
+                    })
+                    .collect();
+                let response = Response {
+                    generation: request.generation,
+                    heights,
+                };
+                if response_sender.send(response).is_err() {
+                    break; // Prefetcher gone
+                }
+            }
+        });
+
+        Self {
+            requests: Some(request_sender),
+            responses: response_receiver,
+            thread: Some(thread),
+            generation: 0,
+            terrain: None,
+            lattice: None,
+            cache: HashMap::new(),
+            pending: HashSet::new(),
+        }
+    }
+
+    /// Move finished heights into the cache (non-blocking)
+    pub fn receive(&mut self) {
+        while let Ok(response) = self.responses.try_recv() {
+            self.accept(response);
+        }
+    }
+
+    /// Cache a response's heights unless it was requested for an outdated lattice or terrain
+    fn accept(&mut self, response: Response) {
+        if response.generation != self.generation {
+            return;
+        }
+        for (cell, height) in response.heights {
+            self.pending.remove(&cell);
+            self.cache.insert(cell, height);
+        }
+    }
+
+    /// Prefetched base heights for `terrain` on `lattice` (None if either changed since `request`)
+    pub fn heights(&self, terrain: &BaseTerrain, lattice: &Lattice) -> Option<&HashMap<Cell, f32>> {
+        (self.terrain.as_ref() == Some(terrain) && self.lattice.as_ref() == Some(lattice))
+            .then_some(&self.cache)
+    }
+
+    /// Keep only `lookahead` cells and ask the worker for the ones not cached or in flight
+    pub fn request(&mut self, lookahead: HashSet<Cell>, terrain: BaseTerrain, lattice: Lattice) {
+        if self.terrain != Some(terrain) || self.lattice != Some(lattice) {
+            self.generation += 1;
+            self.terrain = Some(terrain);
+            self.lattice = Some(lattice);
+            self.cache.clear();
+            self.pending.clear();
+        }
+
+        self.cache.retain(|cell, _| lookahead.contains(cell));
+        self.pending.retain(|cell| lookahead.contains(cell));
+        let cells: Vec<Cell> = lookahead
+            .into_iter()
+            .filter(|cell| !self.cache.contains_key(cell) && !self.pending.contains(cell))
+            .collect();
+        if cells.is_empty() {
+            return;
+        }
+
+        self.pending.extend(&cells);
+        if let Some(requests) = &self.requests {
+            let _ = requests.send(Request {
+                generation: self.generation,
+                terrain,
+                lattice,
+                cells,
+            });
+        }
+    }
+
+    /// Block until every requested cell has arrived (deterministic tests)
+    #[cfg(test)]
+    pub fn wait(&mut self) {
+        while !self.pending.is_empty() {
+            match self
+                .responses
+                .recv_timeout(std::time::Duration::from_secs(5))
+            {
+                Ok(response) => self.accept(response),
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+impl Drop for TerrainPrefetcher {
+    fn drop(&mut self) {
+        self.requests.take(); // Ends the worker's request loop
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
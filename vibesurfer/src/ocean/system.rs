@@ -4,30 +4,214 @@ use glam::Vec3;
 
 use super::mesh::OceanGrid;
 use super::AudioBands;
-use crate::params::{AudioReactiveMapping, OceanPhysics};
+use crate::params::{automated_value, AudioReactiveMapping, Automation, OceanPhysics, ParamTarget};
+
+/// Follower that rises instantly and releases exponentially (audio gust/recovery)
+#[derive(Debug, Clone, Copy, Default)]
+struct Envelope {
+    value: f32,
+}
+
+impl Envelope {
+    /// Jump up to `target`, or decay toward it at `decay_per_s` over `dt` seconds
+    fn follow(&mut self, target: f32, decay_per_s: f32, dt: f32) -> f32 {
+        self.value = if self.value > target {
+            target + (self.value - target) * (-decay_per_s.max(0.0) * dt).exp()
+        } else {
+            target
+        };
+        self.value
+    }
+}
 
 /// High-level ocean system with physics and audio-reactive parameters
 pub struct OceanSystem {
     pub grid: OceanGrid,
     pub physics: OceanPhysics,
     pub mapping: AudioReactiveMapping,
+    /// Scripted curves for the detail layer and line width (see `set_automation`)
+    automation: Vec<Automation>,
+    amplitude: Envelope,
+    frequency: Envelope,
+    line_width: Envelope,
+    /// Mid band swaying the key light (see `light_direction`)
+    light_mid: Envelope,
+    /// Time of the last `modulate` call (None before the first)
+    last_modulate_s: Option<f32>,
+    /// Detail layer phase, integrated over the changing wave speed (see `detail_phase`)
+    detail_phase: f32,
 }
 
 impl OceanSystem {
-    /// Create new ocean system with specified parameters
-    pub fn new(physics: OceanPhysics, mapping: AudioReactiveMapping) -> Self {
+    /// Create new ocean system with specified parameters (errors if `physics` is invalid)
+    pub fn new(physics: OceanPhysics, mapping: AudioReactiveMapping) -> Result<Self, String> {
+        physics
+            .validate()
+            .map_err(|e| format!("Invalid ocean physics: {}", e))?;
+
         let grid = OceanGrid::new(&physics);
-        Self {
+        Ok(Self {
             grid,
             physics,
             mapping,
+            automation: Vec::new(),
+            amplitude: Envelope::default(),
+            frequency: Envelope::default(),
+            line_width: Envelope::default(),
+            light_mid: Envelope::default(),
+            last_modulate_s: None,
+            detail_phase: 0.0,
+        })
+    }
+
+    /// Replace the automation curves (errors, keeping the current ones, if any is invalid)
+    pub fn set_automation(&mut self, automation: Vec<Automation>) -> Result<(), String> {
+        for curve in &automation {
+            curve.validate()?;
         }
+        self.automation = automation;
+        Ok(())
+    }
+
+    /// Automation curves in effect
+    pub fn automation(&self) -> &[Automation] {
+        &self.automation
+    }
+
+    /// Configured value of `target` at `time_s`, with automation applied
+    fn automated(&self, target: ParamTarget, time_s: f32, configured: f32) -> f32 {
+        automated_value(&self.automation, target, time_s, configured)
+    }
+
+    /// Return to the state `new` created: reset grid at `physics.grid_size`, envelopes at rest
+    pub fn reset(&mut self) {
+        self.grid.reset();
+        if self.grid.grid_size() != self.physics.grid_size {
+            self.grid.regenerate(self.physics.grid_size);
+        }
+        self.amplitude = Envelope::default();
+        self.frequency = Envelope::default();
+        self.line_width = Envelope::default();
+        self.light_mid = Envelope::default();
+        self.last_modulate_s = None;
+        self.detail_phase = 0.0;
+    }
+
+    /// Audio-modulated (detail_amplitude, detail_frequency, line_width) at `time_s`
+    ///
+    /// Each follows its mapped audio target upward instantly and decays back down at the
+    /// mapping's `*_decay_per_s` rate, with dt taken from the previous call's `time_s`.
+    /// The mid band behind `light_direction` is smoothed the same way, and `detail_phase`
+    /// advances by dt at the current wave speed.
+    pub fn modulate(&mut self, time_s: f32, audio_bands: &AudioBands) -> (f32, f32, f32) {
+        let last_modulate_s = self.last_modulate_s.replace(time_s);
+        let dt = last_modulate_s.map_or(0.0, |last| (time_s - last).max(0.0));
+
+        // Detail phase advances at the automated, audio-routed wave speed (starting where a
+        // fixed speed would have it, so without routes it stays world time × wave_speed)
+        let wave_speed = self.automated(ParamTarget::WaveSpeed, time_s, self.physics.wave_speed)
+            + self.mapping.offset(ParamTarget::WaveSpeed, audio_bands);
+        self.detail_phase = match last_modulate_s {
+            Some(_) => self.detail_phase + wave_speed * dt,
+            None => self.physics.world_time_s(time_s) * wave_speed,
+        };
+
+        // Map audio bands to detail layer parameters (not base terrain), on top of automation
+        let detail_amplitude = self.amplitude.follow(
+            self.automated(
+                ParamTarget::Amplitude,
+                time_s,
+                self.physics.detail_amplitude_m,
+            ) + self.mapping.offset(ParamTarget::Amplitude, audio_bands),
+            self.mapping.amplitude_decay_per_s,
+            dt,
+        );
+        let detail_frequency = self.frequency.follow(
+            self.automated(
+                ParamTarget::Frequency,
+                time_s,
+                self.physics.detail_frequency,
+            ) + self.mapping.offset(ParamTarget::Frequency, audio_bands),
+            self.mapping.frequency_decay_per_s,
+            dt,
+        );
+        let line_width = self.line_width.follow(
+            self.automated(ParamTarget::LineWidth, time_s, self.physics.base_line_width)
+                + self.mapping.offset(ParamTarget::LineWidth, audio_bands),
+            self.mapping.line_width_decay_per_s,
+            dt,
+        );
+        self.light_mid
+            .follow(audio_bands.mid, self.mapping.light_sway_decay_per_s, dt);
+
+        (detail_amplitude, detail_frequency, line_width)
+    }
+
+    /// Detail layer phase as of the last `modulate` (`TerrainParams::time`)
+    ///
+    /// The integral of `OceanPhysics::wave_speed` plus any `ParamTarget::WaveSpeed`
+    /// automation and routes, so the waves speed up and slow down without jumping.
+    pub fn detail_phase(&self) -> f32 {
+        self.detail_phase
+    }
+
+    /// Key light for `Uniforms::with_light`, swayed by the mid band as of the last `modulate`
+    pub fn light_direction(&self) -> [f32; 4] {
+        self.mapping.light_direction(self.light_mid.value)
+    }
+
+    /// Render the base terrain top-down as a grayscale heightmap
+    ///
+    /// Samples `query_base_terrain` at pixel centers of a `resolution`² grid spanning
+    /// `extent_m` around `center_xz` (columns along +X, rows along +Z), normalized so the
+    /// lowest sample is black and the highest white. Flat terrain comes out all black.
+    pub fn export_heightmap(
+        &self,
+        center_xz: [f32; 2],
+        extent_m: f32,
+        resolution: u32,
+    ) -> image::GrayImage {
+        let step_m = extent_m / resolution.max(1) as f32;
+        let sample_at = |center: f32, i: u32| center - extent_m * 0.5 + (i as f32 + 0.5) * step_m;
+
+        let heights: Vec<f32> = (0..resolution)
+            .flat_map(|row| (0..resolution).map(move |col| (col, row)))
+            .map(|(col, row)| {
+                self.grid.query_base_terrain(
+                    sample_at(center_xz[0], col),
+                    sample_at(center_xz[1], row),
+                    &self.physics,
+                )
+            })
+            .collect();
+
+        let (min, max) = heights
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &h| {
+                (lo.min(h), hi.max(h))
+            });
+        let range = max - min;
+        let pixels = heights
+            .iter()
+            .map(|&h| {
+                if range > 0.0 {
+                    ((h - min) / range * 255.0).round() as u8
+                } else {
+                    0
+                }
+            })
+            .collect();
+
+        image::GrayImage::from_raw(resolution, resolution, pixels)
+            .expect("One sample per heightmap pixel")
     }
 
     /// Update ocean simulation with audio-reactive modulation
     ///
     /// Audio modulation only affects detail layer (ripples), not base terrain (hills).
+    /// The grid is regenerated when the camera altitude selects another `grid_lods` level.
     /// This preserves stable skiing physics while adding visual reactivity.
+    /// Modulated values are smoothed by `modulate`'s gust/recovery envelope.
     ///
     /// # Arguments
     /// * `time_s` - Current time in seconds
@@ -42,19 +226,18 @@ impl OceanSystem {
         audio_bands: &AudioBands,
         camera_pos: Vec3,
     ) -> (f32, f32, f32) {
-        // Map audio bands to detail layer parameters (not base terrain)
-        let detail_amplitude = self.physics.detail_amplitude_m
-            + audio_bands.low * self.mapping.bass_to_amplitude_scale;
+        let (detail_amplitude, detail_frequency, line_width) = self.modulate(time_s, audio_bands);
 
-        let detail_frequency =
-            self.physics.detail_frequency + audio_bands.mid * self.mapping.mid_to_frequency_scale;
-
-        let line_width =
-            self.physics.base_line_width + audio_bands.high * self.mapping.high_to_glow_scale;
+        // Level of detail: coarser grid higher up (same world extent)
+        let lod_grid_size = self.physics.grid_size_for_altitude(camera_pos.y);
+        if lod_grid_size != self.grid.grid_size() {
+            self.grid.regenerate(lod_grid_size);
+        }
 
         // Update mesh vertices (base terrain + audio-reactive detail)
-        self.grid.update(
+        self.grid.update_with_detail_phase(
             time_s,
+            self.detail_phase,
             detail_amplitude,
             detail_frequency,
             camera_pos,
@@ -68,12 +251,13 @@ impl OceanSystem {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::params::GridLod;
 
     #[test]
     fn test_audio_reactive_mapping() {
         let physics = OceanPhysics::default();
         let mapping = AudioReactiveMapping::default();
-        let mut ocean = OceanSystem::new(physics, mapping);
+        let mut ocean = OceanSystem::new(physics, mapping).unwrap();
 
         let bands = AudioBands {
             low: 1.0,
@@ -88,4 +272,161 @@ mod tests {
         assert!(frequency > ocean.physics.detail_frequency);
         assert!(line_width > ocean.physics.base_line_width);
     }
+
+    #[test]
+    fn test_default_routing_matches_fixed_band_scales() {
+        let physics = OceanPhysics {
+            grid_size: 2,
+            ..OceanPhysics::default()
+        };
+        let mut ocean = OceanSystem::new(physics.clone(), AudioReactiveMapping::default()).unwrap();
+
+        // Bass → amplitude × 3.0, mid → frequency × 0.15, high → line width × 0.03
+        for (low, mid, high) in [(0.0, 0.0, 0.0), (1.0, 0.5, 0.2), (2.5, 1.5, 4.0)] {
+            let bands = AudioBands { low, mid, high };
+            let (amplitude, frequency, line_width) = ocean.modulate(0.0, &bands);
+            assert_eq!(amplitude, physics.detail_amplitude_m + low * 3.0);
+            assert_eq!(frequency, physics.detail_frequency + mid * 0.15);
+            assert_eq!(line_width, physics.base_line_width + high * 0.03);
+        }
+    }
+
+    #[test]
+    fn test_amplitude_decays_gradually_after_bass_spike() {
+        let physics = OceanPhysics {
+            grid_size: 16,
+            ..OceanPhysics::default()
+        };
+        let base = physics.detail_amplitude_m;
+        let mut ocean = OceanSystem::new(physics, AudioReactiveMapping::default()).unwrap();
+
+        let spike = AudioBands {
+            low: 2.0,
+            ..AudioBands::default()
+        };
+        let silence = AudioBands::default();
+
+        // Bass spike: amplitude jumps up immediately
+        let (peak, _, _) = ocean.modulate(0.0, &spike);
+        assert_eq!(
+            peak,
+            base + ocean.mapping.offset(ParamTarget::Amplitude, &spike)
+        );
+
+        // Silence afterwards: falls a little each frame instead of snapping to base
+        let mut previous = peak;
+        for frame in 1..=6 {
+            let (amplitude, _, _) = ocean.modulate(frame as f32 / 60.0, &silence);
+            assert!(
+                amplitude < previous && amplitude > base,
+                "Frame {}: amplitude {} (previous {})",
+                frame,
+                amplitude,
+                previous
+            );
+            previous = amplitude;
+        }
+
+        // ...and settles back to base after a few seconds
+        let (settled, _, _) = ocean.modulate(5.0, &silence);
+        assert!((settled - base).abs() < 1e-3, "Settled at {}", settled);
+    }
+
+    #[test]
+    fn test_export_heightmap() {
+        let physics = OceanPhysics {
+            grid_size: 2,
+            ..OceanPhysics::default()
+        };
+        let ocean = OceanSystem::new(physics.clone(), AudioReactiveMapping::default()).unwrap();
+
+        let heightmap = ocean.export_heightmap([100.0, -50.0], 2000.0, 48);
+        assert_eq!(heightmap.dimensions(), (48, 48));
+        let (lo, hi) = heightmap
+            .pixels()
+            .fold((255, 0), |(lo, hi), p| (p[0].min(lo), p[0].max(hi)));
+        assert_eq!((lo, hi), (0, 255), "Hills span the full gray range");
+
+        // No base terrain: every pixel the same
+        let flat = OceanSystem::new(
+            OceanPhysics {
+                base_terrain_amplitude_m: 0.0,
+                ..physics
+            },
+            AudioReactiveMapping::default(),
+        )
+        .unwrap();
+        let heightmap = flat.export_heightmap([0.0, 0.0], 500.0, 17);
+        assert_eq!(heightmap.dimensions(), (17, 17));
+        assert!(heightmap
+            .pixels()
+            .all(|p| p[0] == heightmap.get_pixel(0, 0)[0]));
+    }
+
+    #[test]
+    fn test_altitude_selects_grid_lod() {
+        let physics = OceanPhysics {
+            grid_size: 32,
+            grid_spacing_m: 4.0,
+            grid_lods: vec![
+                GridLod {
+                    min_altitude_m: 400.0,
+                    grid_size: 8,
+                },
+                GridLod {
+                    min_altitude_m: 150.0,
+                    grid_size: 16,
+                },
+            ],
+            ..OceanPhysics::default()
+        };
+        assert_eq!(physics.grid_size_for_altitude(20.0), 32);
+        assert_eq!(physics.grid_size_for_altitude(200.0), 16);
+        assert_eq!(physics.grid_size_for_altitude(1000.0), 8);
+
+        let extent = physics.grid_size as f32 * physics.grid_spacing_m;
+        let mut ocean = OceanSystem::new(physics, AudioReactiveMapping::default()).unwrap();
+        let bands = AudioBands::default();
+
+        // Climb (coarser), then dive back down (finer): buffers stay consistent every switch
+        for (frame, (altitude, expected_size)) in [
+            (20.0, 32),
+            (200.0, 16),
+            (1000.0, 8),
+            (200.0, 16),
+            (20.0, 32),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let camera_pos = Vec3::new(frame as f32 * 3.0, altitude, 0.0);
+            ocean.update(frame as f32 / 60.0, &bands, camera_pos);
+
+            let grid = &ocean.grid;
+            let row = expected_size + 1;
+            assert_eq!(grid.grid_size(), expected_size);
+            assert!((grid.grid_spacing() * expected_size as f32 - extent).abs() < 1e-3);
+            // The GPU lattice (main's compute dispatch) uses the same spacing
+            let gpu_spacing = ocean.physics.grid_spacing_for_size(expected_size);
+            assert!((grid.grid_spacing() - gpu_spacing).abs() < 1e-5);
+            assert_eq!(grid.vertices.len(), row * row);
+            assert_eq!(grid.indices.len(), expected_size * expected_size * 6);
+            assert!(grid.filtered_indices.len() <= grid.indices.len());
+            assert!(grid
+                .indices
+                .iter()
+                .all(|&i| (i as usize) < grid.vertices.len()));
+            assert!(grid.vertices.iter().all(|v| v.position[1].is_finite()));
+        }
+    }
+
+    #[test]
+    fn test_invalid_physics_rejected() {
+        let physics = OceanPhysics {
+            grid_size: 0,
+            ..OceanPhysics::default()
+        };
+        let result = OceanSystem::new(physics, AudioReactiveMapping::default());
+        assert!(result.is_err_and(|e| e.contains("Grid size")));
+    }
 }
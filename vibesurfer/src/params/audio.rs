@@ -1,9 +1,11 @@
 //! Audio analysis configuration and constants.
 
+use serde::{Deserialize, Serialize};
 use std::ops::Range;
 
 /// FFT analysis configuration with frequency band mappings
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct FFTConfig {
     /// Audio sample rate (Hz)
     /// toy2 value: 44100
@@ -17,6 +19,10 @@ pub struct FFTConfig {
     /// toy2 value: 50 (= 20 Hz update rate)
     pub update_interval_ms: u64,
 
+    /// Fraction of each FFT window shared with the next (0.0..1.0)
+    /// Higher overlap analyzes more often for smoother bands; toy2 value: 0.5
+    pub overlap: f32,
+
     /// Bass frequency range (Hz)
     /// toy2 bins: 1..10 ≈ 20-200 Hz
     pub bass_range_hz: (f32, f32),
@@ -28,6 +34,30 @@ pub struct FFTConfig {
     /// High frequency range (Hz)
     /// toy2 bins: 50..200 ≈ 1000-4000 Hz
     pub high_range_hz: (f32, f32),
+
+    /// Analyze left and right channels separately (false = mono downmix of both)
+    pub stereo: bool,
+
+    /// Cap on samples waiting for analysis, in FFT windows (≥ 1, see `fft_buffer_cap`)
+    /// If the FFT thread stalls, the oldest samples are dropped instead of piling up
+    pub buffer_cap_windows: usize,
+
+    /// Noise gate: subtracted from every raw band energy, clamping at zero (0 = no gate)
+    /// Applied in `BandAnalyzer::analyze`, before normalization and envelope smoothing, so
+    /// near-silent FFT noise below the floor reads as exactly zero
+    pub gate_floor: f32,
+
+    /// Divide each band by its running peak, so bands stay in 0..1 at any signal level
+    /// Note: the `AudioReactiveMapping` scales then act on 0..1 (not raw FFT magnitudes)
+    pub normalize: bool,
+
+    /// Release rate of the running band peaks (per second; peaks decay as exp(-rate × t))
+    pub normalize_decay_per_s: f32,
+
+    /// Delay the visuals' reaction by this much (milliseconds, 0..=1000, live playback only)
+    /// Bands are sampled from a short history to line up with what is heard; negative
+    /// values are rejected (the visuals can't lead the analysis)
+    pub visual_latency_ms: f32,
 }
 
 impl Default for FFTConfig {
@@ -36,9 +66,16 @@ impl Default for FFTConfig {
             sample_rate_hz: 44100,
             fft_size: 1024,
             update_interval_ms: 50,
+            overlap: 0.5,
             bass_range_hz: (20.0, 200.0),
             mid_range_hz: (200.0, 1000.0),
             high_range_hz: (1000.0, 4000.0),
+            stereo: false,
+            buffer_cap_windows: 4,
+            gate_floor: 0.0,
+            normalize: false,
+            normalize_decay_per_s: 0.5, // Adapts to a quieter passage within a few seconds
+            visual_latency_ms: 0.0,
         }
     }
 }
@@ -64,6 +101,23 @@ impl FFTConfig {
         self.hz_to_bin(self.high_range_hz.0)..self.hz_to_bin(self.high_range_hz.1)
     }
 
+    /// Time one synthesis block of `BLOCK_SIZE` samples covers (ms; 2.9 ms at 44.1 kHz)
+    ///
+    /// The floor on synthesis latency: the engine renders whole blocks before they play.
+    pub fn expected_block_latency_ms(&self) -> f32 {
+        audio_constants::BLOCK_SIZE as f32 * 1000.0 / self.sample_rate_hz as f32
+    }
+
+    /// Samples dropped from the FFT buffer after each analysis (`fft_size × (1 − overlap)`)
+    pub fn hop_size(&self) -> usize {
+        ((self.fft_size as f32 * (1.0 - self.overlap)) as usize).max(1)
+    }
+
+    /// Most samples the FFT buffer holds per channel (`fft_size × buffer_cap_windows`)
+    pub fn fft_buffer_cap(&self) -> usize {
+        self.fft_size * self.buffer_cap_windows.max(1)
+    }
+
     /// Validate configuration (FFT size must be power of 2, etc.)
     pub fn validate(&self) -> Result<(), String> {
         if !self.fft_size.is_power_of_two() {
@@ -72,16 +126,44 @@ impl FFTConfig {
                 self.fft_size
             ));
         }
+        if !(0.0..1.0).contains(&self.overlap) {
+            return Err(format!(
+                "FFT overlap must be in 0.0..1.0, got {}",
+                self.overlap
+            ));
+        }
+        if self.buffer_cap_windows == 0 {
+            return Err("FFT buffer cap must be at least 1 window".to_string());
+        }
         if self.sample_rate_hz == 0 {
             return Err("Sample rate must be > 0".to_string());
         }
+        if !(0.0..=1000.0).contains(&self.visual_latency_ms) {
+            return Err(format!(
+                "Visual latency must be 0 to 1000 ms (visuals can't lead the audio), got {}",
+                self.visual_latency_ms
+            ));
+        }
+        if !(self.gate_floor >= 0.0 && self.gate_floor.is_finite()) {
+            return Err(format!(
+                "Gate floor must be a finite value >= 0, got {}",
+                self.gate_floor
+            ));
+        }
+        if self.normalize_decay_per_s < 0.0 {
+            return Err(format!(
+                "Normalize decay must be >= 0, got {}",
+                self.normalize_decay_per_s
+            ));
+        }
         Ok(())
     }
 }
 
 /// Audio constants (compile-time, match Glicol engine setup)
 pub mod audio_constants {
-    /// Audio block size (samples per buffer)
-    /// toy2 value: 128 (= 2.9ms @ 44.1kHz)
+    /// Audio block size (samples per buffer; the Glicol `Engine` const generic)
+    /// toy2 value: 128 (= 2.9ms @ 44.1kHz, see `FFTConfig::expected_block_latency_ms`)
+    /// Device buffers of any length are filled from whole blocks
     pub const BLOCK_SIZE: usize = 128;
 }
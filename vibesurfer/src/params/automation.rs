@@ -0,0 +1,235 @@
+//! Scripted parameter changes over the timeline (automation curves).
+
+use serde::{Deserialize, Serialize};
+
+use super::{ParamTarget, RenderConfig};
+
+/// Curve between two automation keyframes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Interpolation {
+    /// Straight ramp
+    #[default]
+    Linear,
+
+    /// Eased ramp (smoothstep: zero slope at both keyframes)
+    Smooth,
+
+    /// Hold each keyframe's value until the next one
+    Step,
+}
+
+/// How an automation combines with the configured value
+///
+/// Audio routes (`AudioReactiveMapping::offset`) are added on top either way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutomationMode {
+    /// Curve value is added to the configured value
+    #[default]
+    Additive,
+
+    /// Curve value replaces the configured value
+    Override,
+}
+
+/// Keyframed curve driving one parameter over time
+///
+/// In TOML: `[[automation]]` with `target = "amplitude"` and
+/// `keyframes = [[10.0, 2.0], [20.0, 8.0]]` (seconds, target units).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Automation {
+    /// Parameter being driven
+    pub target: ParamTarget,
+
+    /// (time in seconds, value in target units), in increasing time order
+    /// Before the first and after the last keyframe the curve holds that keyframe's value
+    pub keyframes: Vec<(f32, f32)>,
+
+    /// Curve between keyframes (linear by default)
+    #[serde(default)]
+    pub interp: Interpolation,
+
+    /// Added to or replacing the configured value (additive by default)
+    #[serde(default)]
+    pub mode: AutomationMode,
+}
+
+impl Automation {
+    /// Curve value at `time_s` (None without keyframes)
+    pub fn value_at(&self, time_s: f32) -> Option<f32> {
+        let &(first_s, first) = self.keyframes.first()?;
+        let &(last_s, last) = self.keyframes.last()?;
+        if time_s <= first_s {
+            return Some(first);
+        }
+        if time_s >= last_s {
+            return Some(last);
+        }
+
+        // Segment containing time_s (first keyframe after it, and the one before)
+        let next = self.keyframes.partition_point(|&(t, _)| t <= time_s);
+        let (t0, v0) = self.keyframes[next - 1];
+        let (t1, v1) = self.keyframes[next];
+        let u = (time_s - t0) / (t1 - t0);
+        let u = match self.interp {
+            Interpolation::Linear => u,
+            Interpolation::Smooth => u * u * (3.0 - 2.0 * u),
+            Interpolation::Step => 0.0,
+        };
+        Some(v0 + (v1 - v0) * u)
+    }
+
+    /// Check keyframes: at least one, finite, strictly increasing times
+    pub fn validate(&self) -> Result<(), String> {
+        if self.keyframes.is_empty() {
+            return Err(format!("{:?} automation has no keyframes", self.target));
+        }
+        if let Some(&(t, v)) = self
+            .keyframes
+            .iter()
+            .find(|(t, v)| !t.is_finite() || !v.is_finite())
+        {
+            return Err(format!(
+                "{:?} automation keyframe ({}, {}) must be finite",
+                self.target, t, v
+            ));
+        }
+        if let Some(pair) = self
+            .keyframes
+            .windows(2)
+            .find(|pair| pair[1].0 <= pair[0].0)
+        {
+            return Err(format!(
+                "{:?} automation keyframe times must increase, got {} after {}",
+                self.target, pair[1].0, pair[0].0
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// `configured` value of `target` at `time_s` with every matching automation applied
+///
+/// Automations apply in list order: overrides replace the running value, additive
+/// curves add to it.
+pub fn automated_value(
+    automation: &[Automation],
+    target: ParamTarget,
+    time_s: f32,
+    configured: f32,
+) -> f32 {
+    automation
+        .iter()
+        .filter(|curve| curve.target == target)
+        .fold(configured, |value, curve| {
+            match (curve.mode, curve.value_at(time_s)) {
+                (_, None) => value,
+                (AutomationMode::Additive, Some(v)) => value + v,
+                (AutomationMode::Override, Some(v)) => v,
+            }
+        })
+}
+
+/// `render_config` with the fog density and FOV automation applied at `time_s`
+///
+/// FOV moves both ends of the speed-based range, as the FOV audio route does.
+pub fn automate_render_config(
+    automation: &[Automation],
+    time_s: f32,
+    render_config: &RenderConfig,
+) -> RenderConfig {
+    let fov_offset = automated_value(
+        automation,
+        ParamTarget::Fov,
+        time_s,
+        render_config.fov_degrees,
+    ) - render_config.fov_degrees;
+    RenderConfig {
+        fog_density: automated_value(
+            automation,
+            ParamTarget::FogDensity,
+            time_s,
+            render_config.fog_density,
+        ),
+        fov_degrees: render_config.fov_degrees + fov_offset,
+        fov_max_degrees: render_config.fov_max_degrees + fov_offset,
+        ..render_config.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ramp(interp: Interpolation) -> Automation {
+        Automation {
+            target: ParamTarget::Amplitude,
+            keyframes: vec![(10.0, 2.0), (20.0, 8.0)],
+            interp,
+            mode: AutomationMode::Override,
+        }
+    }
+
+    #[test]
+    fn test_linear_automation_interpolates_and_clamps() {
+        let curve = ramp(Interpolation::Linear);
+        curve.validate().unwrap();
+
+        assert_eq!(curve.value_at(15.0), Some(5.0));
+        assert_eq!(curve.value_at(12.5), Some(3.5));
+
+        // Holds the end values outside the keyframed range
+        assert_eq!(curve.value_at(0.0), Some(2.0));
+        assert_eq!(curve.value_at(-5.0), Some(2.0));
+        assert_eq!(curve.value_at(20.0), Some(8.0));
+        assert_eq!(curve.value_at(300.0), Some(8.0));
+
+        assert_eq!(ramp(Interpolation::Step).value_at(19.9), Some(2.0));
+        assert_eq!(ramp(Interpolation::Smooth).value_at(15.0), Some(5.0));
+    }
+
+    #[test]
+    fn test_automated_value_combines_modes() {
+        let boost = Automation {
+            target: ParamTarget::Amplitude,
+            keyframes: vec![(0.0, 1.0)],
+            interp: Interpolation::Linear,
+            mode: AutomationMode::Additive,
+        };
+        let fog = Automation {
+            target: ParamTarget::FogDensity,
+            ..boost.clone()
+        };
+        let curves = [ramp(Interpolation::Linear), boost, fog];
+
+        // Override replaces the configured 2.5, the additive curve adds on top
+        assert_eq!(
+            automated_value(&curves, ParamTarget::Amplitude, 15.0, 2.5),
+            6.0
+        );
+        assert_eq!(
+            automated_value(&curves, ParamTarget::Frequency, 15.0, 0.1),
+            0.1
+        );
+
+        let render_config = automate_render_config(&curves, 15.0, &RenderConfig::default());
+        assert_eq!(
+            render_config.fog_density,
+            RenderConfig::default().fog_density + 1.0
+        );
+    }
+
+    #[test]
+    fn test_automation_validation() {
+        let mut curve = ramp(Interpolation::Linear);
+        curve.keyframes = vec![(10.0, 2.0), (10.0, 3.0)];
+        assert!(curve.validate().is_err());
+        curve.keyframes = vec![(10.0, f32::NAN)];
+        assert!(curve.validate().is_err());
+        curve.keyframes.clear();
+        assert!(curve.validate().is_err());
+        assert_eq!(curve.value_at(1.0), None);
+    }
+}
@@ -1,7 +1,10 @@
 //! Camera path configuration and presets.
 
+use serde::{Deserialize, Serialize};
+
 /// Basic camera path parameters (simple straight-line flight)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct BasicCameraPath {
     /// Constant altitude (meters)
     pub altitude_m: f32,
@@ -11,6 +14,15 @@ pub struct BasicCameraPath {
 
     /// Look-ahead distance (meters)
     pub look_ahead_m: f32,
+
+    /// How far below the camera the look-at target sits, as a fraction of the altitude
+    pub look_down_fraction: f32,
+
+    /// Side-to-side sway of the flight path (meters, 0 = straight line)
+    pub sway_amplitude_m: f32,
+
+    /// Sway frequency (cycles per second)
+    pub sway_freq_hz: f32,
 }
 
 impl Default for BasicCameraPath {
@@ -19,12 +31,16 @@ impl Default for BasicCameraPath {
             altitude_m: 30.0,             // Moderate altitude
             forward_speed_m_per_s: 150.0, // Fast speed
             look_ahead_m: 150.0,
+            look_down_fraction: 0.4, // ~15-20° downward angle at the default look-ahead
+            sway_amplitude_m: 0.0,   // Dead straight, as before sway existed
+            sway_freq_hz: 0.05,
         }
     }
 }
 
 /// Fixed camera position (for debugging)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct FixedCamera {
     /// Camera position (meters)
     pub position: [f32; 3],
@@ -33,6 +49,10 @@ pub struct FixedCamera {
     pub target: [f32; 3],
 
     /// Simulated forward velocity (m/s) to flow the grid
+    ///
+    /// The eye advances along +Z at this speed (target and height kept). `OceanGrid::update`
+    /// and the terrain compute pass see that moving position, so the surface flows past the
+    /// view while the framing stays fixed. 0 = truly stationary.
     pub simulated_velocity: f32,
 }
 
@@ -47,7 +67,8 @@ impl Default for FixedCamera {
 }
 
 /// Floating camera (follows terrain contour at fixed height above surface)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct FloatingCamera {
     /// XZ position (meters)
     pub position_xz: [f32; 2],
@@ -77,8 +98,172 @@ impl Default for FloatingCamera {
     }
 }
 
+/// Orbit camera (circles a fixed point, always looking inward)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OrbitCamera {
+    /// Point the camera circles and looks at (meters)
+    pub center: [f32; 3],
+
+    /// Horizontal distance from center (meters)
+    pub radius_m: f32,
+
+    /// Height above center (meters)
+    pub altitude_m: f32,
+
+    /// Angular speed around center (radians per second, positive = counter-clockwise from above)
+    pub angular_speed_rad_per_s: f32,
+}
+
+impl Default for OrbitCamera {
+    fn default() -> Self {
+        Self {
+            center: [0.0, 0.0, 0.0],
+            radius_m: 300.0,              // Wide enough to frame the hills
+            altitude_m: 80.0,             // Same as cinematic base altitude
+            angular_speed_rad_per_s: 0.1, // One revolution every ~63 seconds
+        }
+    }
+}
+
+impl OrbitCamera {
+    /// Time for one full revolution (seconds)
+    pub fn period_s(&self) -> f32 {
+        std::f32::consts::TAU / self.angular_speed_rad_per_s.abs()
+    }
+}
+
+/// Manual camera state (user-driven fly camera)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ManualCameraState {
+    /// Camera position (meters)
+    pub position: [f32; 3],
+
+    /// Heading (radians, 0 = looking along +Z, positive turns toward +X)
+    pub yaw_rad: f32,
+
+    /// Elevation angle (radians, positive = looking up, clamped to ±89°)
+    pub pitch_rad: f32,
+
+    /// Movement speed at full input (meters per second)
+    pub speed_m_per_s: f32,
+}
+
+impl Default for ManualCameraState {
+    fn default() -> Self {
+        Self {
+            position: [0.0, 101.0, 0.0], // Same starting point as fixed preset
+            yaw_rad: 0.0,
+            pitch_rad: -0.4, // Slightly down toward the ocean
+            speed_m_per_s: 100.0,
+        }
+    }
+}
+
+/// Single keyframe of an authored camera path
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CameraKeyframe {
+    /// Time at which the camera reaches this keyframe (seconds)
+    pub time_s: f32,
+
+    /// Camera position (meters)
+    pub position: [f32; 3],
+
+    /// Look-at target (meters)
+    pub target: [f32; 3],
+}
+
+impl CameraKeyframe {
+    /// Parse keyframes from a JSON array, sorted by time
+    ///
+    /// Expected format: `[{"time_s": 0.0, "position": [x, y, z], "target": [x, y, z]}, ...]`
+    pub fn parse_json(json: &str) -> Result<Vec<Self>, String> {
+        let mut keyframes: Vec<Self> =
+            serde_json::from_str(json).map_err(|e| format!("Invalid keyframe JSON: {}", e))?;
+
+        if keyframes.is_empty() {
+            return Err("Keyframe list is empty".to_string());
+        }
+        if let Some(bad) = keyframes.iter().find(|k| !k.time_s.is_finite()) {
+            return Err(format!("Keyframe time must be finite, got {}", bad.time_s));
+        }
+
+        // Interpolation requires keyframes in time order
+        keyframes.sort_by(|a, b| a.time_s.total_cmp(&b.time_s));
+
+        Ok(keyframes)
+    }
+}
+
+/// Camera shake parameters (transient jitter, e.g. on beats)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CameraShakeConfig {
+    /// Exponential decay time constant (seconds, amplitude falls to ~37% after this long)
+    pub decay_time_constant_s: f32,
+
+    /// Eye position jitter at intensity 1.0 (meters)
+    pub position_amplitude_m: f32,
+
+    /// Look-at target jitter at intensity 1.0 (meters, reads as rotational shake)
+    pub target_amplitude_m: f32,
+
+    /// Jitter oscillation frequency (Hz)
+    pub frequency_hz: f32,
+
+    /// Jump in bass energy over its running average that shakes the camera
+    /// (0 = no audio-triggered shake)
+    pub bass_onset_threshold: f32,
+}
+
+impl Default for CameraShakeConfig {
+    fn default() -> Self {
+        Self {
+            decay_time_constant_s: 0.15, // Short punch, gone well before the next beat
+            position_amplitude_m: 1.5,
+            target_amplitude_m: 4.0,
+            frequency_hz: 18.0,
+            bass_onset_threshold: 0.0, // Off: shake only when triggered explicitly
+        }
+    }
+}
+
+impl CameraShakeConfig {
+    /// Ranges: `decay_time_constant_s` > 0, amplitudes and frequency finite,
+    /// `bass_onset_threshold` ≥ 0.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.decay_time_constant_s > 0.0 && self.decay_time_constant_s.is_finite()) {
+            return Err(format!(
+                "Camera shake decay time constant must be > 0 s, got {}",
+                self.decay_time_constant_s
+            ));
+        }
+        for (name, value) in [
+            ("position_amplitude_m", self.position_amplitude_m),
+            ("target_amplitude_m", self.target_amplitude_m),
+            ("frequency_hz", self.frequency_hz),
+        ] {
+            if !value.is_finite() {
+                return Err(format!(
+                    "Camera shake {} must be finite, got {}",
+                    name, value
+                ));
+            }
+        }
+        if !(self.bass_onset_threshold >= 0.0 && self.bass_onset_threshold.is_finite()) {
+            return Err(format!(
+                "Camera shake bass onset threshold must be >= 0, got {}",
+                self.bass_onset_threshold
+            ));
+        }
+        Ok(())
+    }
+}
+
 /// Camera preset selection
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum CameraPreset {
     /// Cinematic preset: complex procedural path with sweeping arcs and altitude changes
     Cinematic(CameraJourney),
@@ -91,6 +276,15 @@ pub enum CameraPreset {
 
     /// Floating preset: follows terrain contour at fixed height above surface
     Floating(FloatingCamera),
+
+    /// Orbit preset: circles a fixed point at constant radius and altitude, looking inward
+    Orbit(OrbitCamera),
+
+    /// Keyframed preset: Catmull-Rom spline through authored keyframes (sorted by time)
+    Keyframed(Vec<CameraKeyframe>),
+
+    /// Manual preset: user-driven fly camera (WASD + mouse)
+    Manual(ManualCameraState),
 }
 
 impl Default for CameraPreset {
@@ -99,8 +293,24 @@ impl Default for CameraPreset {
     }
 }
 
+impl CameraPreset {
+    /// Short display name (matches the `--camera-preset` value where one exists)
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cinematic(_) => "cinematic",
+            Self::Basic(_) => "basic",
+            Self::Fixed(_) => "fixed",
+            Self::Floating(_) => "floating",
+            Self::Orbit(_) => "orbit",
+            Self::Keyframed(_) => "keyframed",
+            Self::Manual(_) => "manual",
+        }
+    }
+}
+
 /// Camera journey path parameters (procedural cinematic path)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct CameraJourney {
     // X axis: Wide sweeping arcs
     /// Primary X oscillation frequency (Hz)
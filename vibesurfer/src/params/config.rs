@@ -0,0 +1,330 @@
+//! Bundled application configuration with TOML load/save.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+
+use super::{
+    AudioBand, AudioReactiveMapping, Automation, CameraJourney, CameraShakeConfig, FFTConfig,
+    OceanPhysics, ParamTarget, RecordingConfig, RenderConfig,
+};
+
+/// `[audio_mapping]` scale fields from before the routing table, and the route each set
+const LEGACY_MAPPING_SCALES: [(&str, AudioBand, ParamTarget); 3] = [
+    (
+        "bass_to_amplitude_scale",
+        AudioBand::Low,
+        ParamTarget::Amplitude,
+    ),
+    (
+        "mid_to_frequency_scale",
+        AudioBand::Mid,
+        ParamTarget::Frequency,
+    ),
+    (
+        "high_to_glow_scale",
+        AudioBand::High,
+        ParamTarget::LineWidth,
+    ),
+];
+
+/// All tunable parameters, as stored in a TOML config file
+///
+/// Every section and field is optional in the file (missing values keep their defaults),
+/// but unknown fields are rejected so typos don't silently fall back to defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppConfig {
+    /// Ocean grid, terrain layers, waves
+    pub ocean: OceanPhysics,
+
+    /// Audio band → visual parameter mapping
+    pub audio_mapping: AudioReactiveMapping,
+
+    /// FFT analysis settings
+    pub fft: FFTConfig,
+
+    /// Cinematic camera path
+    pub camera_journey: CameraJourney,
+
+    /// Camera jitter on bass onsets
+    pub camera_shake: CameraShakeConfig,
+
+    /// Window and projection settings
+    pub render: RenderConfig,
+
+    /// Recording settings (absent = not recording unless `--record` is given)
+    pub recording: Option<RecordingConfig>,
+
+    /// Scripted parameter curves over the timeline (`[[automation]]` entries)
+    pub automation: Vec<Automation>,
+}
+
+impl AppConfig {
+    /// Parse configuration from TOML text
+    ///
+    /// Old `[audio_mapping]` scale fields are migrated into `routes` (with a warning).
+    pub fn from_toml_str(toml: &str) -> Result<Self, String> {
+        let invalid = |e: toml::de::Error| format!("Invalid config TOML: {}", e);
+        let mut table: toml::Table = toml::from_str(toml).map_err(invalid)?;
+        if !migrate_legacy_mapping(&mut table)? {
+            // Parse the text itself so errors point at the offending line
+            return toml::from_str(toml).map_err(invalid);
+        }
+        table.try_into().map_err(invalid)
+    }
+
+    /// Serialize configuration to TOML text
+    pub fn to_toml_string(&self) -> Result<String, String> {
+        toml::to_string_pretty(self).map_err(|e| format!("Failed to serialize config: {}", e))
+    }
+}
+
+/// Rewrite old `[audio_mapping]` scale fields as `routes` (true if there were any)
+///
+/// Each field sets the scale of its default route; the other default routes stay, as they
+/// did when the fields existed. Combining the old fields with `routes` is an error.
+fn migrate_legacy_mapping(config: &mut toml::Table) -> Result<bool, String> {
+    let Some(toml::Value::Table(mapping)) = config.get_mut("audio_mapping") else {
+        return Ok(false);
+    };
+    let legacy: Vec<_> = LEGACY_MAPPING_SCALES
+        .iter()
+        .filter_map(|&(key, band, target)| Some((key, band, target, mapping.remove(key)?)))
+        .collect();
+    if legacy.is_empty() {
+        return Ok(false);
+    }
+    if mapping.contains_key("routes") {
+        return Err(format!(
+            "audio_mapping.{} was replaced by routes; move it into the routes table",
+            legacy[0].0
+        ));
+    }
+
+    let mut routes = AudioReactiveMapping::default().routes;
+    for (key, band, target, value) in legacy {
+        let scale = value
+            .as_float()
+            .or_else(|| value.as_integer().map(|i| i as f64))
+            .ok_or_else(|| format!("audio_mapping.{} must be a number", key))?;
+        eprintln!(
+            "Warning: audio_mapping.{} is deprecated, use routes = [{{ source_band = \"{}\", target = \"{}\", scale = {} }}]",
+            key,
+            toml_name(band)?,
+            toml_name(target)?,
+            scale
+        );
+        routes
+            .iter_mut()
+            .filter(|route| route.source_band == band && route.target == target)
+            .for_each(|route| route.scale = scale as f32);
+    }
+    let routes = toml::Value::try_from(routes)
+        .map_err(|e| format!("Failed to migrate audio_mapping: {}", e))?;
+    mapping.insert("routes".to_string(), routes);
+    Ok(true)
+}
+
+/// Name of an enum value as written in TOML (e.g. `"low"`)
+fn toml_name(value: impl Serialize) -> Result<String, String> {
+    match toml::Value::try_from(value) {
+        Ok(toml::Value::String(name)) => Ok(name),
+        _ => Err("Failed to migrate audio_mapping".to_string()),
+    }
+}
+
+/// Load configuration from a TOML file
+pub fn load_from_toml(path: &str) -> Result<AppConfig, String> {
+    let toml = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read config '{}': {}", path, e))?;
+    AppConfig::from_toml_str(&toml)
+}
+
+/// Save configuration to a TOML file
+pub fn save_to_toml(config: &AppConfig, path: &str) -> Result<(), String> {
+    let toml = config.to_toml_string()?;
+    std::fs::write(path, toml).map_err(|e| format!("Failed to write config '{}': {}", path, e))
+}
+
+/// Background watcher that reloads a config file when it changes on disk
+///
+/// Polls the file's modification time and length; on change, parses it and sends the new
+/// `AppConfig` through a channel. Parse errors are reported and skipped (the previous
+/// config stays in effect). The polling thread stops when the watcher is dropped.
+pub struct ConfigWatcher {
+    receiver: Receiver<AppConfig>,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ConfigWatcher {
+    /// Start watching `path`, checking every `poll_interval`
+    pub fn spawn(path: impl Into<PathBuf>, poll_interval: Duration) -> Self {
+        let path = path.into();
+        let (sender, receiver) = mpsc::channel();
+        let running = Arc::new(AtomicBool::new(true));
+
+        let thread = {
+            let running = Arc::clone(&running);
+            std::thread::spawn(move || {
+                let stamp = |path: &PathBuf| -> Option<(SystemTime, u64)> {
+                    let metadata = std::fs::metadata(path).ok()?;
+                    Some((metadata.modified().ok()?, metadata.len()))
+                };
+                let mut last_stamp = stamp(&path);
+
+                while running.load(Ordering::Relaxed) {
+                    std::thread::sleep(poll_interval);
+
+                    let current = stamp(&path);
+                    if current.is_none() || current == last_stamp {
+                        continue;
+                    }
+                    last_stamp = current;
+
+                    match load_from_toml(&path.to_string_lossy()) {
+                        Ok(config) => {
+                            if sender.send(config).is_err() {
+                                break; // Receiver gone
+                            }
+                        }
+                        Err(e) => eprintln!("Warning: config reload skipped: {}", e),
+                    }
+                }
+            })
+        };
+
+        Self {
+            receiver,
+            running,
+            thread: Some(thread),
+        }
+    }
+
+    /// Most recent reloaded config since the last call, if any (older ones are discarded)
+    pub fn try_recv(&self) -> Option<AppConfig> {
+        self.receiver.try_iter().last()
+    }
+}
+
+impl Drop for ConfigWatcher {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::Mapping;
+
+    #[test]
+    fn test_default_config_round_trip() {
+        let config = AppConfig {
+            recording: Some(RecordingConfig::new(12.5)),
+            ..AppConfig::default()
+        };
+
+        let toml = config.to_toml_string().unwrap();
+        let parsed = AppConfig::from_toml_str(&toml).unwrap();
+        assert_eq!(parsed, config);
+
+        // Through the filesystem too
+        let path =
+            std::env::temp_dir().join(format!("vibesurfer_config_{}.toml", std::process::id()));
+        let path = path.to_str().unwrap();
+        save_to_toml(&config, path).unwrap();
+        let loaded = load_from_toml(path);
+        let _ = std::fs::remove_file(path);
+        assert_eq!(loaded.unwrap(), config);
+    }
+
+    #[test]
+    fn test_legacy_mapping_scales_migrate_to_routes() {
+        let config = AppConfig::from_toml_str(
+            "[audio_mapping]\nbass_to_amplitude_scale = 5.0\nhigh_to_glow_scale = 0\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.audio_mapping.routes,
+            vec![
+                Mapping::new(AudioBand::Low, ParamTarget::Amplitude, 5.0),
+                Mapping::new(AudioBand::Mid, ParamTarget::Frequency, 0.15),
+                Mapping::new(AudioBand::High, ParamTarget::LineWidth, 0.0),
+            ]
+        );
+
+        // Ambiguous next to an explicit routing table
+        let mixed = "[audio_mapping]\nmid_to_frequency_scale = 1.0\nroutes = []\n";
+        assert!(AppConfig::from_toml_str(mixed)
+            .unwrap_err()
+            .contains("mid_to_frequency_scale"));
+        assert!(AppConfig::from_toml_str("[audio_mapping]\nhigh_to_glow_scale = \"x\"\n").is_err());
+    }
+
+    #[test]
+    fn test_partial_config_keeps_defaults() {
+        let config = AppConfig::from_toml_str("[ocean]\ngrid_size = 256\n").unwrap();
+        assert_eq!(config.ocean.grid_size, 256);
+        assert_eq!(
+            config.ocean.grid_spacing_m,
+            OceanPhysics::default().grid_spacing_m
+        );
+        assert_eq!(config.render, RenderConfig::default());
+    }
+
+    #[test]
+    fn test_automation_loads_from_toml() {
+        let toml =
+            "[[automation]]\ntarget = \"amplitude\"\nkeyframes = [[10.0, 2.0], [20.0, 8.0]]\n\
+                    mode = \"override\"\n";
+        let config = AppConfig::from_toml_str(toml).unwrap();
+        assert_eq!(config.automation.len(), 1);
+        assert_eq!(config.automation[0].value_at(15.0), Some(5.0));
+
+        // Survives a save alongside the table sections
+        let parsed = AppConfig::from_toml_str(&config.to_toml_string().unwrap()).unwrap();
+        assert_eq!(parsed, config);
+    }
+
+    #[test]
+    fn test_unknown_field_errors() {
+        assert!(AppConfig::from_toml_str("[ocean]\ngrid_sise = 256\n").is_err());
+        assert!(AppConfig::from_toml_str("[oceans]\ngrid_size = 256\n").is_err());
+    }
+
+    #[test]
+    fn test_watcher_delivers_changed_config() {
+        let path =
+            std::env::temp_dir().join(format!("vibesurfer_watch_{}.toml", std::process::id()));
+        let path_str = path.to_str().unwrap().to_string();
+        save_to_toml(&AppConfig::default(), &path_str).unwrap();
+
+        let watcher = ConfigWatcher::spawn(&path, Duration::from_millis(10));
+
+        // Let the watcher record the initial state, then edit one value
+        std::thread::sleep(Duration::from_millis(50));
+        let edited = AppConfig {
+            ocean: OceanPhysics {
+                detail_amplitude_m: 7.5,
+                ..OceanPhysics::default()
+            },
+            ..AppConfig::default()
+        };
+        save_to_toml(&edited, &path_str).unwrap();
+
+        let delivered = watcher.receiver.recv_timeout(Duration::from_secs(5));
+        drop(watcher);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(delivered.unwrap().ocean.detail_amplitude_m, 7.5);
+    }
+}
@@ -6,12 +6,27 @@
 //! - Type safety where possible
 
 mod audio;
+mod automation;
 mod camera;
+mod config;
 mod ocean;
 mod render;
 
 // Re-export all types
 pub use audio::{audio_constants, FFTConfig};
-pub use camera::{BasicCameraPath, CameraJourney, CameraPreset, FixedCamera, FloatingCamera};
-pub use ocean::{AudioReactiveMapping, OceanPhysics, TerrainParams};
-pub use render::{RecordingConfig, RenderConfig};
+pub use automation::{
+    automate_render_config, automated_value, Automation, AutomationMode, Interpolation,
+};
+pub use camera::{
+    BasicCameraPath, CameraJourney, CameraKeyframe, CameraPreset, CameraShakeConfig, FixedCamera,
+    FloatingCamera, ManualCameraState, OrbitCamera,
+};
+pub use config::{load_from_toml, save_to_toml, AppConfig, ConfigWatcher};
+pub use ocean::{
+    AudioBand, AudioReactiveMapping, GerstnerWave, GridLod, Mapping, NoiseKind, OceanPhysics,
+    ParamTarget, TerrainParams, WaveModel,
+};
+pub use render::{
+    BackgroundMode, BloomConfig, CullMode, OutputFormat, RecordingConfig, RenderConfig, SkyConfig,
+    ToneMapping, WavSampleFormat,
+};
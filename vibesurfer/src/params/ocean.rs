@@ -1,6 +1,10 @@
 //! Ocean simulation physics parameters and audio-reactive mapping.
 
 use bytemuck::{Pod, Zeroable};
+use serde::{Deserialize, Serialize};
+
+use super::RenderConfig;
+use crate::ocean::AudioBands;
 
 /// GPU uniform buffer for terrain compute shader
 /// Must match WGSL TerrainParams struct exactly (including padding)
@@ -16,11 +20,83 @@ pub struct TerrainParams {
     pub grid_size: u32,
     pub grid_spacing: f32,
     pub time: f32,
-    pub _padding2: f32,
+    pub noise_seed: u32,        // Same seed as OceanPhysics::noise_seed
+    pub noise_kind: u32,        // NoiseKind::shader_id
+    pub base_octaves: u32,      // OceanPhysics::base_terrain_octaves (fBm only)
+    pub detail_octaves: u32,    // OceanPhysics::detail_octaves (fBm only)
+    pub noise_lacunarity: f32,  // OceanPhysics::noise_lacunarity (fBm only)
+    pub noise_gain: f32,        // OceanPhysics::noise_gain (fBm only)
+    pub _padding2: u32,         // Align swell_offset to 8 bytes
+    pub swell_offset: [f32; 2], // OceanPhysics::swell_offset_m (meters, XZ)
+}
+
+/// Surface wave model used by the CPU ocean update
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WaveModel {
+    /// Height-field only: noise offsets vertex height, XZ stays on the grid
+    Noise,
+
+    /// Noise height-field plus directional Gerstner waves (sharpened crests, horizontal motion)
+    Gerstner,
+}
+
+/// Noise function for the ocean terrain (`NoiseGenerator::sample`, and the GPU compute pass)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoiseKind {
+    /// Single octave of classic gradient (Perlin) noise
+    Perlin,
+
+    /// Single octave of simplex noise
+    Simplex,
+
+    /// Multi-octave simplex noise using the layer's octave count, lacunarity and gain
+    Fbm,
+}
+
+impl NoiseKind {
+    /// Value of `TerrainParams::noise_kind` (must match `terrain_noise` in terrain_compute.wgsl)
+    pub fn shader_id(self) -> u32 {
+        match self {
+            Self::Perlin => 0,
+            Self::Simplex => 1,
+            Self::Fbm => 2,
+        }
+    }
+}
+
+/// Single directional Gerstner wave component
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GerstnerWave {
+    /// Crest-to-crest distance (meters)
+    pub wavelength_m: f32,
+
+    /// Crest sharpness (0 = flat, 1 = cusped crests; scaled by audio amplitude)
+    pub steepness: f32,
+
+    /// Travel direction in XZ plane (normalized on use)
+    pub direction: [f32; 2],
+
+    /// Phase speed (meters per second)
+    pub speed_m_per_s: f32,
+}
+
+/// Grid resolution used from a camera altitude upward (level of detail)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GridLod {
+    /// Lowest camera altitude (meters) at which this level applies
+    pub min_altitude_m: f32,
+
+    /// Grid resolution (vertices per side) at this level
+    pub grid_size: usize,
 }
 
 /// Ocean simulation physics parameters
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct OceanPhysics {
     /// Grid resolution (vertices per side, e.g., 128 = 16,641 vertices)
     pub grid_size: usize,
@@ -28,9 +104,19 @@ pub struct OceanPhysics {
     /// Spacing between grid vertices in world units (meters)
     pub grid_spacing_m: f32,
 
+    /// Altitude-based grid resolutions (empty = always `grid_size`)
+    ///
+    /// The level with the highest `min_altitude_m` at or below the camera is used; below all
+    /// levels the grid is `grid_size`. Levels keep the grid's world extent (spacing adapts).
+    pub grid_lods: Vec<GridLod>,
+
     /// Wave animation speed multiplier (dimensionless, affects time scaling)
     pub wave_speed: f32,
 
+    /// Added to the clock wherever the ocean and camera read it (seconds)
+    /// Instances with different offsets (or seeds) show different oceans side by side
+    pub time_offset_s: f32,
+
     // === Base terrain (stable physics surface for skiing) ===
     /// Base terrain amplitude in meters (large stable hills)
     pub base_terrain_amplitude_m: f32,
@@ -38,6 +124,9 @@ pub struct OceanPhysics {
     /// Base terrain frequency (cycles per meter, low = long slopes)
     pub base_terrain_frequency: f32,
 
+    /// Base terrain noise octaves (1 = single smooth layer)
+    pub base_terrain_octaves: u32,
+
     // === Detail layer (audio-reactive visual ripples) ===
     /// Detail wave height in meters (before audio modulation)
     pub detail_amplitude_m: f32,
@@ -45,11 +134,81 @@ pub struct OceanPhysics {
     /// Detail spatial frequency (cycles per meter, controls wave chop)
     pub detail_frequency: f32,
 
+    /// Detail noise octaves (1 = single smooth layer)
+    pub detail_octaves: u32,
+
+    /// Direction the detail chop travels in the XZ plane (normalized on use)
+    pub swell_direction: [f32; 2],
+
+    /// Speed the detail chop travels along `swell_direction` (meters per second, 0 = off)
+    ///
+    /// Advects the detail noise independent of the camera, so the chop keeps moving while
+    /// hovering. Base terrain stays put (it is the skiing surface).
+    pub swell_speed_m_per_s: f32,
+
+    // === Fractal noise shape (shared by base and detail layers) ===
+    /// Frequency multiplier per octave (dimensionless, typically 2.0)
+    pub noise_lacunarity: f32,
+
+    /// Amplitude multiplier per octave (dimensionless, typically 0.5)
+    pub noise_gain: f32,
+
     /// Base wireframe line width (screen-space or shader units)
     pub base_line_width: f32,
 
-    /// Perlin noise seed
+    /// Noise seed
     pub noise_seed: u32,
+
+    /// Noise function for base and detail layers (CPU ocean and GPU compute)
+    pub noise_kind: NoiseKind,
+
+    // === Wave model ===
+    /// Surface wave model (noise height-field or noise + Gerstner)
+    pub wave_model: WaveModel,
+
+    /// Gerstner wave components (used when `wave_model` is `Gerstner`)
+    pub gerstner_waves: Vec<GerstnerWave>,
+
+    // === Foam (whitecaps on steep slopes) ===
+    /// Surface slope (rise over run) where foam starts to appear
+    pub foam_slope_start: f32,
+
+    /// Surface slope (rise over run) at which foam reaches full intensity (0 = no foam)
+    pub foam_slope_full: f32,
+
+    // === Wrap artifact filtering ===
+    /// Max horizontal triangle extent, in grid spacings, before it counts as wrap-stretched
+    ///
+    /// Triangles spanning more than this many spacings along X or Z (i.e. across the
+    /// toroidal seam) are not drawn. Vertical extent is ignored. Must be > 1.
+    pub wrap_edge_threshold_factor: f32,
+
+    // === Horizon fade (hides the edge of the finite grid) ===
+    /// Distance from the camera where the ocean starts fading out, as a fraction of half the grid
+    pub edge_fade_start: f32,
+
+    /// Distance where the ocean is fully transparent, as a fraction of half the grid
+    ///
+    /// Fade distances scale with the grid's world extent (`grid_size` × `grid_spacing_m`), so
+    /// the horizon dissolves before the wrap boundary at any grid size. Must exceed the start.
+    pub edge_fade_end: f32,
+
+    // === Base terrain prefetch ===
+    /// Grid rows ahead of the wrap edge whose base terrain a worker thread precomputes
+    ///
+    /// Rows about to wrap (judged from camera motion) have their post-wrap base heights
+    /// sampled in the background, so `OceanGrid::update` doesn't sample noise for a whole row
+    /// at once. 0 = off (no thread). At most half the grid size.
+    /// CPU ocean only: the GPU compute pass recomputes every vertex each frame anyway.
+    pub prefetch_rows: usize,
+
+    // === Camera clearance ===
+    /// Headroom kept between the detail wave crests and the camera's near plane (meters)
+    ///
+    /// Loud bass can raise the chop into the camera; the render loop caps the detail
+    /// amplitude per frame with `clamp_detail_amplitude` so crests stay this far below the
+    /// near plane. Must be ≥ 0.
+    pub camera_clearance_margin_m: f32,
 }
 
 impl Default for OceanPhysics {
@@ -57,47 +216,535 @@ impl Default for OceanPhysics {
         Self {
             grid_size: 1024,     // Extra large grid pushes wrap boundary far beyond visibility
             grid_spacing_m: 2.0, // Fine spacing for many lines
+            grid_lods: Vec::new(),
             wave_speed: 0.5,
+            time_offset_s: 0.0,
 
             // Base terrain: EXTREME Tribes-style hills for skiing (100m tall, long slopes)
             base_terrain_amplitude_m: 100.0,
             base_terrain_frequency: 0.003, // Even longer wavelengths for massive hills
+            base_terrain_octaves: 1,       // Single smooth layer
 
             // Detail layer: audio-reactive chop (2m tall, fine detail)
             detail_amplitude_m: 2.0,
             detail_frequency: 0.1,
+            detail_octaves: 1,
+
+            // No swell: the chop only scrolls against camera motion
+            swell_direction: [0.0, 1.0],
+            swell_speed_m_per_s: 0.0,
+
+            noise_lacunarity: 2.0,
+            noise_gain: 0.5,
 
             base_line_width: 0.02,
             noise_seed: 42,
+            noise_kind: NoiseKind::Fbm, // Octave counts above decide how rough it is
+
+            // Wave model: noise only by default; Gerstner set is a swell plus two cross-chops
+            wave_model: WaveModel::Noise,
+            gerstner_waves: vec![
+                GerstnerWave {
+                    wavelength_m: 60.0,
+                    steepness: 0.25,
+                    direction: [0.0, 1.0],
+                    speed_m_per_s: 9.7, // Deep-water speed sqrt(g * L / 2π)
+                },
+                GerstnerWave {
+                    wavelength_m: 31.0,
+                    steepness: 0.2,
+                    direction: [0.6, 0.8],
+                    speed_m_per_s: 7.0,
+                },
+                GerstnerWave {
+                    wavelength_m: 18.0,
+                    steepness: 0.15,
+                    direction: [-0.7, 0.7],
+                    speed_m_per_s: 5.3,
+                },
+            ],
+
+            // Foam off (0.6 → 1.2 keeps resting chop mostly clear and foams bass-driven chop)
+            foam_slope_start: 0.0,
+            foam_slope_full: 0.0,
+
+            // Same cutoff as the original hardcoded 10× grid spacing
+            wrap_edge_threshold_factor: 10.0,
+
+            // The original 800 m → 1000 m fade on the default 2048 m grid
+            edge_fade_start: 0.78125,
+            edge_fade_end: 0.9765625,
+
+            // Off: wrapped rows sample their base terrain inline
+            prefetch_rows: 0,
+
+            camera_clearance_margin_m: 1.0,
+        }
+    }
+}
+
+impl OceanPhysics {
+    /// Validate parameters that would otherwise silently produce a broken mesh
+    ///
+    /// Ranges: `grid_size` ≥ 2, `grid_spacing_m` > 0, amplitudes, frequencies and swell finite,
+    /// `wrap_edge_threshold_factor` > 1, 0 ≤ `edge_fade_start` < `edge_fade_end`,
+    /// `prefetch_rows` ≤ `grid_size` / 2, `camera_clearance_margin_m` ≥ 0, `noise_gain` ≥ 0.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.grid_size < 2 {
+            return Err(format!("Grid size must be >= 2, got {}", self.grid_size));
+        }
+        if !(self.grid_spacing_m > 0.0 && self.grid_spacing_m.is_finite()) {
+            return Err(format!(
+                "Grid spacing must be > 0 m, got {}",
+                self.grid_spacing_m
+            ));
+        }
+        for (name, value) in [
+            ("base_terrain_amplitude_m", self.base_terrain_amplitude_m),
+            ("base_terrain_frequency", self.base_terrain_frequency),
+            ("detail_amplitude_m", self.detail_amplitude_m),
+            ("detail_frequency", self.detail_frequency),
+            ("swell_direction[0]", self.swell_direction[0]),
+            ("swell_direction[1]", self.swell_direction[1]),
+            ("swell_speed_m_per_s", self.swell_speed_m_per_s),
+            ("time_offset_s", self.time_offset_s),
+        ] {
+            if !value.is_finite() {
+                return Err(format!("{} must be finite, got {}", name, value));
+            }
+        }
+        if !(self.noise_gain >= 0.0 && self.noise_gain.is_finite()) {
+            return Err(format!("Noise gain must be >= 0, got {}", self.noise_gain));
+        }
+        if self.wrap_edge_threshold_factor.is_nan() || self.wrap_edge_threshold_factor <= 1.0 {
+            return Err(format!(
+                "Wrap edge threshold factor must be > 1, got {}",
+                self.wrap_edge_threshold_factor
+            ));
+        }
+        if !(self.edge_fade_start >= 0.0
+            && self.edge_fade_start < self.edge_fade_end
+            && self.edge_fade_end.is_finite())
+        {
+            return Err(format!(
+                "Edge fade needs 0 <= start < end, got {} to {}",
+                self.edge_fade_start, self.edge_fade_end
+            ));
+        }
+        if self.prefetch_rows > self.grid_size / 2 {
+            return Err(format!(
+                "Prefetch rows must be at most half the grid size ({}), got {}",
+                self.grid_size / 2,
+                self.prefetch_rows
+            ));
+        }
+        if !(self.camera_clearance_margin_m >= 0.0 && self.camera_clearance_margin_m.is_finite()) {
+            return Err(format!(
+                "Camera clearance margin must be >= 0 m, got {}",
+                self.camera_clearance_margin_m
+            ));
+        }
+        for lod in &self.grid_lods {
+            if lod.grid_size < 2 || !lod.min_altitude_m.is_finite() {
+                return Err(format!(
+                    "Grid LOD needs grid_size >= 2 and a finite altitude, got {} at {} m",
+                    lod.grid_size, lod.min_altitude_m
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// `detail_amplitude_m` capped so detail crests over `surface_y` stay below the camera
+    ///
+    /// The detail noise peaks at ±amplitude, so the cap is the camera's height above the
+    /// base terrain under it, minus the near plane and `camera_clearance_margin_m` (never
+    /// below 0: a camera at or under the terrain flattens the chop).
+    pub fn clamp_detail_amplitude(
+        &self,
+        detail_amplitude_m: f32,
+        camera_y: f32,
+        surface_y: f32,
+        near_plane_m: f32,
+    ) -> f32 {
+        let clearance = camera_y - surface_y - near_plane_m - self.camera_clearance_margin_m;
+        detail_amplitude_m.min(clearance.max(0.0))
+    }
+
+    /// Ocean and camera time for app clock `time_s` (shifted by `time_offset_s`)
+    pub fn world_time_s(&self, time_s: f32) -> f32 {
+        time_s + self.time_offset_s
+    }
+
+    /// Side length of the grid window around the camera (meters, the same at every LOD)
+    pub fn grid_extent_m(&self) -> f32 {
+        self.grid_size as f32 * self.grid_spacing_m
+    }
+
+    /// Grid resolution for a camera at `altitude_m` (see `grid_lods`)
+    pub fn grid_size_for_altitude(&self, altitude_m: f32) -> usize {
+        self.grid_lods
+            .iter()
+            .filter(|lod| lod.min_altitude_m <= altitude_m)
+            .max_by(|a, b| a.min_altitude_m.total_cmp(&b.min_altitude_m))
+            .map_or(self.grid_size, |lod| lod.grid_size)
+    }
+
+    /// Vertex spacing of a `grid_size` grid covering `grid_extent_m` (meters)
+    pub fn grid_spacing_for_size(&self, grid_size: usize) -> f32 {
+        self.grid_extent_m() / grid_size.max(1) as f32
+    }
+
+    /// Distance the swell has carried the detail noise by world time `time_s` (meters, XZ)
+    pub fn swell_offset_m(&self, time_s: f32) -> glam::Vec2 {
+        glam::Vec2::from_array(self.swell_direction).normalize_or_zero()
+            * self.swell_speed_m_per_s
+            * time_s
+    }
+}
+
+/// Audio frequency band a route reads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioBand {
+    /// Bass (`AudioBands::low`)
+    Low,
+
+    /// Mids (`AudioBands::mid`)
+    Mid,
+
+    /// Highs (`AudioBands::high`)
+    High,
+}
+
+impl AudioBand {
+    /// This band's energy in `audio_bands`
+    pub fn energy(self, audio_bands: &AudioBands) -> f32 {
+        match self {
+            AudioBand::Low => audio_bands.low,
+            AudioBand::Mid => audio_bands.mid,
+            AudioBand::High => audio_bands.high,
+        }
+    }
+}
+
+/// Visual parameter an audio band can drive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParamTarget {
+    /// Detail layer wave amplitude (meters, added to `OceanPhysics::detail_amplitude_m`)
+    Amplitude,
+
+    /// Detail layer frequency (added to `OceanPhysics::detail_frequency`)
+    Frequency,
+
+    /// Line glow width (added to `OceanPhysics::base_line_width`)
+    LineWidth,
+
+    /// Fog density (per meter, added to `RenderConfig::fog_density`)
+    FogDensity,
+
+    /// Field of view (degrees, added to both ends of the speed-based FOV range)
+    Fov,
+
+    /// Detail layer wave speed (added to `OceanPhysics::wave_speed`; the phase accumulates,
+    /// so speed changes never jump the waves)
+    WaveSpeed,
+}
+
+/// One routing entry: target += base + band energy × scale
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Mapping {
+    /// Band whose energy drives the target
+    pub source_band: AudioBand,
+
+    /// Parameter being driven
+    pub target: ParamTarget,
+
+    /// Target units per unit of band energy
+    pub scale: f32,
+
+    /// Constant offset in target units (applied even in silence)
+    #[serde(default)]
+    pub base: f32,
+}
+
+impl Mapping {
+    /// Route `source_band` into `target` at `scale`, with no constant offset
+    pub fn new(source_band: AudioBand, target: ParamTarget, scale: f32) -> Self {
+        Self {
+            source_band,
+            target,
+            scale,
+            base: 0.0,
         }
     }
 }
 
 /// Mapping from audio frequency bands to visual parameters
-#[derive(Debug, Clone)]
+///
+/// Scales are per unit of band energy: raw FFT magnitude by default, or 0..1 when
+/// `FFTConfig::normalize` is set (the toy2 values assume raw magnitudes).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct AudioReactiveMapping {
-    /// Scale factor: bass energy → wave amplitude (meters per unit energy)
-    /// toy2 value: 3.0
-    /// Formula: amplitude = base_amplitude + bass * this_scale
-    pub bass_to_amplitude_scale: f32,
+    /// Band → parameter routing table (routes into the same target add up)
+    /// Formula: target = configured value + Σ (route.base + band * route.scale)
+    /// toy2 routing: bass → amplitude × 3.0, mid → frequency × 0.15, high → line width × 0.03
+    pub routes: Vec<Mapping>,
+
+    /// Release rate of the audio-driven wave amplitude (per second)
+    /// Rises instantly with the bass; the excess over the new target then decays as
+    /// exp(-rate × t), so waves settle between beats instead of collapsing
+    pub amplitude_decay_per_s: f32,
+
+    /// Release rate of the audio-driven wave frequency (per second, as above)
+    pub frequency_decay_per_s: f32,
+
+    /// Release rate of the audio-driven line glow width (per second, as above)
+    pub line_width_decay_per_s: f32,
 
-    /// Scale factor: mid energy → wave frequency (dimensionless)
-    /// toy2 value: 0.15
-    /// Formula: frequency = base_frequency + mid * this_scale
-    pub mid_to_frequency_scale: f32,
+    /// Ocean tint at wave troughs (linear RGBA, multiplies the grid color)
+    pub trough_color: [f32; 4],
 
-    /// Scale factor: high energy → line glow width
-    /// toy2 value: 0.03
-    /// Formula: line_width = base_line_width + high * this_scale
-    pub high_to_glow_scale: f32,
+    /// Ocean tint at wave peaks (linear RGBA, multiplies the grid color)
+    pub peak_color: [f32; 4],
+
+    /// Height at which the tint is fully the peak color (meters; -this is fully trough)
+    pub color_height_range_m: f32,
+
+    /// Scale factor: mid energy → palette hue rotation (degrees per unit energy)
+    /// Formula: hue_shift = mid * this_scale (0 = static palette)
+    pub mid_to_hue_shift_deg: f32,
+
+    /// Direction toward the key light at rest (world space, normalized on use)
+    pub light_direction: [f32; 3],
+
+    /// Strength of the Lambert key light on the ocean (0 = flat, unlit as before; 1 = full)
+    pub light_intensity: f32,
+
+    /// Scale factor: smoothed mid energy → light swing about the vertical (degrees per unit)
+    pub mid_to_light_sway_deg: f32,
+
+    /// Release rate of the smoothed mid band swaying the light (per second, as above)
+    pub light_sway_decay_per_s: f32,
 }
 
 impl Default for AudioReactiveMapping {
     fn default() -> Self {
         Self {
-            bass_to_amplitude_scale: 3.0,
-            mid_to_frequency_scale: 0.15,
-            high_to_glow_scale: 0.03,
+            routes: vec![
+                Mapping::new(AudioBand::Low, ParamTarget::Amplitude, 3.0),
+                Mapping::new(AudioBand::Mid, ParamTarget::Frequency, 0.15),
+                Mapping::new(AudioBand::High, ParamTarget::LineWidth, 0.03),
+            ],
+            amplitude_decay_per_s: 3.0, // ~1/3 s to shed 63% of a bass swell
+            frequency_decay_per_s: 3.0,
+            line_width_decay_per_s: 6.0, // Glow flickers faster than the swell
+            trough_color: [1.0, 1.0, 1.0, 1.0], // Troughs keep the grid colors (white = no tint)
+            peak_color: [1.0, 1.0, 1.0, 1.0], // Crests keep the grid colors
+            color_height_range_m: 100.0, // Matches base terrain amplitude
+            mid_to_hue_shift_deg: 30.0,
+            light_direction: [0.5, 0.8, 0.3], // High, off to one side
+            light_intensity: 0.0,             // Off: keeps the flat neon look
+            mid_to_light_sway_deg: 40.0,
+            light_sway_decay_per_s: 1.5, // Slow swing back between phrases
         }
     }
 }
+
+impl AudioReactiveMapping {
+    /// Summed audio offset of every route into `target` (0 when nothing routes there)
+    pub fn offset(&self, target: ParamTarget, audio_bands: &AudioBands) -> f32 {
+        self.routes
+            .iter()
+            .filter(|route| route.target == target)
+            .map(|route| route.base + route.source_band.energy(audio_bands) * route.scale)
+            .sum()
+    }
+
+    /// `render_config` with the fog density and FOV routes applied
+    pub fn modulate_render_config(
+        &self,
+        render_config: &RenderConfig,
+        audio_bands: &AudioBands,
+    ) -> RenderConfig {
+        let fog = self.offset(ParamTarget::FogDensity, audio_bands);
+        let fov = self.offset(ParamTarget::Fov, audio_bands);
+        RenderConfig {
+            fog_density: (render_config.fog_density + fog).max(0.0),
+            fov_degrees: (render_config.fov_degrees + fov).clamp(1.0, 179.0),
+            fov_max_degrees: (render_config.fov_max_degrees + fov).clamp(1.0, 179.0),
+            ..render_config.clone()
+        }
+    }
+
+    /// (trough, peak) tint colors, hue-rotated by the audio mid band
+    pub fn color_ramp(&self, mid: f32) -> ([f32; 4], [f32; 4]) {
+        let degrees = mid * self.mid_to_hue_shift_deg;
+        (
+            rotate_hue(self.trough_color, degrees),
+            rotate_hue(self.peak_color, degrees),
+        )
+    }
+
+    /// Key light for the ocean shader: xyz toward the light, w = `light_intensity`
+    ///
+    /// `light_direction` is rotated about the vertical by `mid` × `mid_to_light_sway_deg`
+    /// (`mid` should be smoothed, see `OceanSystem::light_direction`). A zero base direction
+    /// lights from straight above.
+    pub fn light_direction(&self, mid: f32) -> [f32; 4] {
+        let base = glam::Vec3::from(self.light_direction)
+            .try_normalize()
+            .unwrap_or(glam::Vec3::Y);
+        let sway = glam::Quat::from_rotation_y((mid * self.mid_to_light_sway_deg).to_radians());
+        (sway * base).extend(self.light_intensity).to_array()
+    }
+}
+
+/// Rotate an RGBA color's hue about the gray axis (alpha unchanged, channels kept ≥ 0)
+fn rotate_hue([r, g, b, a]: [f32; 4], degrees: f32) -> [f32; 4] {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let diagonal = cos + (1.0 - cos) / 3.0;
+    let plus = (1.0 - cos) / 3.0 + sin / 3.0f32.sqrt();
+    let minus = (1.0 - cos) / 3.0 - sin / 3.0f32.sqrt();
+    [
+        (r * diagonal + g * minus + b * plus).max(0.0),
+        (r * plus + g * diagonal + b * minus).max(0.0),
+        (r * minus + g * plus + b * diagonal).max(0.0),
+        a,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_physics_is_valid() {
+        assert_eq!(OceanPhysics::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_fog_and_fov_routes_modulate_render_config() {
+        let mapping = AudioReactiveMapping {
+            routes: vec![
+                Mapping::new(AudioBand::High, ParamTarget::FogDensity, 0.001),
+                Mapping {
+                    base: 5.0,
+                    ..Mapping::new(AudioBand::Low, ParamTarget::Fov, 10.0)
+                },
+            ],
+            ..AudioReactiveMapping::default()
+        };
+        let render_config = RenderConfig::default();
+        let bands = AudioBands {
+            low: 0.5,
+            mid: 1.0,
+            high: 2.0,
+        };
+
+        let modulated = mapping.modulate_render_config(&render_config, &bands);
+        assert_eq!(modulated.fog_density, render_config.fog_density + 0.002);
+        assert_eq!(modulated.fov_degrees, render_config.fov_degrees + 10.0);
+        assert_eq!(
+            modulated.fov_max_degrees,
+            render_config.fov_max_degrees + 10.0
+        );
+
+        // The default routing leaves the render config alone
+        let unrouted =
+            AudioReactiveMapping::default().modulate_render_config(&render_config, &bands);
+        assert_eq!(unrouted, render_config);
+    }
+
+    #[test]
+    fn test_validate_rejects_each_invalid_field() {
+        let invalid = [
+            OceanPhysics {
+                grid_size: 1,
+                ..OceanPhysics::default()
+            },
+            OceanPhysics {
+                grid_spacing_m: 0.0,
+                ..OceanPhysics::default()
+            },
+            OceanPhysics {
+                grid_spacing_m: -2.0,
+                ..OceanPhysics::default()
+            },
+            OceanPhysics {
+                base_terrain_amplitude_m: f32::NAN,
+                ..OceanPhysics::default()
+            },
+            OceanPhysics {
+                base_terrain_frequency: f32::INFINITY,
+                ..OceanPhysics::default()
+            },
+            OceanPhysics {
+                detail_amplitude_m: f32::NAN,
+                ..OceanPhysics::default()
+            },
+            OceanPhysics {
+                detail_frequency: f32::NEG_INFINITY,
+                ..OceanPhysics::default()
+            },
+            OceanPhysics {
+                swell_speed_m_per_s: f32::NAN,
+                ..OceanPhysics::default()
+            },
+            OceanPhysics {
+                wrap_edge_threshold_factor: 1.0,
+                ..OceanPhysics::default()
+            },
+            OceanPhysics {
+                edge_fade_start: 0.9,
+                edge_fade_end: 0.8,
+                ..OceanPhysics::default()
+            },
+            OceanPhysics {
+                grid_size: 64,
+                prefetch_rows: 33,
+                ..OceanPhysics::default()
+            },
+            OceanPhysics {
+                camera_clearance_margin_m: -1.0,
+                ..OceanPhysics::default()
+            },
+            OceanPhysics {
+                noise_gain: -1.0,
+                ..OceanPhysics::default()
+            },
+        ];
+        for physics in invalid {
+            assert!(physics.validate().is_err(), "Accepted {:?}", physics);
+        }
+    }
+
+    #[test]
+    fn test_detail_amplitude_clamped_below_low_camera() {
+        let physics = OceanPhysics::default();
+        let near_plane_m = RenderConfig::default().near_plane_m;
+
+        // Camera 5 m over a 40 m hill, bass asking for 30 m chop
+        let amplitude = physics.clamp_detail_amplitude(30.0, 45.0, 40.0, near_plane_m);
+        let crest = 40.0 + amplitude;
+        assert!(
+            crest <= 45.0 - near_plane_m - physics.camera_clearance_margin_m + 1e-4,
+            "Crest at {} m",
+            crest
+        );
+        assert!(amplitude > 0.0);
+
+        // Plenty of clearance: untouched; camera below the hill: flat
+        assert_eq!(
+            physics.clamp_detail_amplitude(2.0, 100.0, 40.0, near_plane_m),
+            2.0
+        );
+        assert_eq!(
+            physics.clamp_detail_amplitude(30.0, 35.0, 40.0, near_plane_m),
+            0.0
+        );
+    }
+}
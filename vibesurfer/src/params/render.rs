@@ -1,7 +1,167 @@
 //! Rendering and recording configuration.
 
+use serde::{Deserialize, Serialize};
+
+/// Background drawn behind the ocean (by the skybox pass, under the stars)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackgroundMode {
+    /// Single linear RGBA color
+    Solid([f32; 4]),
+
+    /// Screen-space gradient from the top color to the bottom color (linear RGBA)
+    VerticalGradient([f32; 4], [f32; 4]),
+}
+
+impl Default for BackgroundMode {
+    fn default() -> Self {
+        Self::Solid([0.0, 0.0, 0.0, 1.0])
+    }
+}
+
+impl BackgroundMode {
+    /// (top, bottom) colors; a solid background uses the same color for both
+    pub fn colors(&self) -> ([f32; 4], [f32; 4]) {
+        match *self {
+            Self::Solid(color) => (color, color),
+            Self::VerticalGradient(top, bottom) => (top, bottom),
+        }
+    }
+}
+
+/// Curve mapping HDR scene colors to the display (applied by the bloom composite)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToneMapping {
+    /// Exposure only: output == input at exposure 0 (values above 1 clip)
+    #[default]
+    Passthrough,
+
+    /// Reinhard: c / (1 + c), rolls highlights off smoothly
+    Reinhard,
+
+    /// ACES filmic fit (Narkowicz): more contrast, saturated highlights desaturate to white
+    Aces,
+}
+
+impl ToneMapping {
+    /// Curve selector for `bloom.wgsl` (`tone_mapping` uniform)
+    pub fn shader_index(self) -> u32 {
+        match self {
+            Self::Passthrough => 0,
+            Self::Reinhard => 1,
+            Self::Aces => 2,
+        }
+    }
+}
+
+/// Which ocean triangles the rasterizer discards (baked into the pipeline at build time)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CullMode {
+    /// Draw both sides (triangles flipped by displacement stay visible)
+    None,
+
+    /// Discard back faces (counter-clockwise is front)
+    #[default]
+    Back,
+
+    /// Discard front faces (e.g. to check for flipped triangles)
+    Front,
+}
+
+/// Bloom (glow) post-process settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BloomConfig {
+    /// Enable the bloom pass (read at startup; the other fields can change while running)
+    pub enabled: bool,
+
+    /// Luminance above which pixels start to glow
+    pub threshold: f32,
+
+    /// Glow strength added back onto the scene
+    pub intensity: f32,
+
+    /// Blur radius (Gaussian sigma in pixels)
+    pub radius: f32,
+
+    /// Extra intensity per unit of audio high-band energy
+    pub high_to_intensity_scale: f32,
+}
+
+impl Default for BloomConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold: 0.6,
+            intensity: 1.2,
+            radius: 4.0,
+            high_to_intensity_scale: 0.0,
+        }
+    }
+}
+
+impl BloomConfig {
+    /// Glow intensity for the current audio high-band energy
+    pub fn intensity_for(&self, high: f32) -> f32 {
+        (self.intensity + high * self.high_to_intensity_scale).max(0.0)
+    }
+}
+
+/// Day-night sky gradient with a sun (drawn by the skybox pass over the background)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SkyConfig {
+    /// Draw the sky gradient instead of the plain background (off = background only)
+    pub enabled: bool,
+
+    /// Sky color at the horizon in full daylight (linear RGBA)
+    pub horizon_color: [f32; 4],
+
+    /// Sky color straight up in full daylight (linear RGBA)
+    pub zenith_color: [f32; 4],
+
+    /// Sun elevation above the horizon at time 0 (degrees; negative = below)
+    pub sun_elevation_deg: f32,
+
+    /// Compass direction of the sun (degrees; 0 = +Z, 90 = +X)
+    pub sun_azimuth_deg: f32,
+
+    /// Sun elevation sweep rate (degrees per second; 0 = fixed time of day)
+    /// The sun rises, crosses overhead and sets on the opposite side, then continues below
+    pub sun_sweep_deg_per_s: f32,
+}
+
+impl Default for SkyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            horizon_color: [0.85, 0.55, 0.45, 1.0], // Warm haze
+            zenith_color: [0.1, 0.25, 0.6, 1.0],
+            sun_elevation_deg: 10.0, // Low sun: long sunset light
+            sun_azimuth_deg: 0.0,    // Ahead of the default camera (+Z)
+            sun_sweep_deg_per_s: 0.0,
+        }
+    }
+}
+
+impl SkyConfig {
+    /// Unit direction toward the sun at `time_s` (world space, Y up)
+    pub fn sun_direction(&self, time_s: f32) -> [f32; 3] {
+        let elevation = (self.sun_elevation_deg + self.sun_sweep_deg_per_s * time_s).to_radians();
+        let azimuth = self.sun_azimuth_deg.to_radians();
+        [
+            elevation.cos() * azimuth.sin(),
+            elevation.sin(),
+            elevation.cos() * azimuth.cos(),
+        ]
+    }
+}
+
 /// Rendering configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct RenderConfig {
     /// Window width (pixels)
     pub window_width: u32,
@@ -9,10 +169,24 @@ pub struct RenderConfig {
     /// Window height (pixels)
     pub window_height: u32,
 
+    /// Open the window borderless fullscreen on the current monitor
+    pub fullscreen: bool,
+
     /// Field of view (degrees)
     /// 75° = wide perspective for sense of speed and vastness
     pub fov_degrees: f32,
 
+    /// Maximum field of view at high speed (degrees)
+    /// FOV widens from `fov_degrees` toward this as camera speed increases (equal = fixed FOV)
+    pub fov_max_degrees: f32,
+
+    /// Camera speed at which FOV reaches `fov_max_degrees` (meters per second)
+    pub speed_for_max_fov_m_per_s: f32,
+
+    /// Horizontal projection scale (1.0 = none; 2.0 halves the horizontal extent, stretching
+    /// the image sideways while the vertical FOV stays `fov_degrees`)
+    pub anamorphic_squeeze: f32,
+
     /// Near clipping plane (meters)
     /// toy2 value: 0.1
     pub near_plane_m: f32,
@@ -20,6 +194,84 @@ pub struct RenderConfig {
     /// Far clipping plane (meters)
     /// Extended to 2000m for more visible ocean horizon
     pub far_plane_m: f32,
+
+    /// Fit the far plane to the grid window around the camera each frame instead of
+    /// `far_plane_m` (tighter depth range; see `CameraSystem::far_plane_m`)
+    pub far_plane_auto_fit: bool,
+
+    /// MSAA samples per pixel (1 = off, 4 = typical anti-aliasing)
+    /// Falls back to 1 if the surface format doesn't support the requested count
+    pub sample_count: u32,
+
+    /// Background behind the ocean (solid color or vertical gradient)
+    pub background: BackgroundMode,
+
+    /// Background brightness gain per unit of audio high-band energy (0 = static background)
+    pub background_high_band_gain: f32,
+
+    /// Day-night sky gradient and sun (off by default)
+    pub sky: SkyConfig,
+
+    /// Color of the glow band the skybox draws along the world horizon
+    pub horizon_glow_color: [f32; 4],
+
+    /// Half-width of the horizon glow in view-ray elevation (sine; 0 = no glow)
+    /// The glow falls to 1/e this far above or below the horizon
+    pub horizon_glow_width: f32,
+
+    /// Horizon glow brightness gain per unit of audio high-band energy (0 = steady glow)
+    pub horizon_glow_high_band_gain: f32,
+
+    /// Ocean tint where the surface dips below the base terrain (deep troughs)
+    pub depth_color_trough: [f32; 4],
+
+    /// Ocean tint where waves crest above the base terrain
+    pub depth_color_crest: [f32; 4],
+
+    /// Wave height above/below the base terrain (m) over which the depth tint blends
+    /// Both tints default to white (no change)
+    pub depth_color_range_m: f32,
+
+    /// Distance fog density (per meter; 0 = no fog)
+    /// Fog amount is 1 - exp(-(density × view depth)²), so ~63% at 1/density meters
+    pub fog_density: f32,
+
+    /// Color the ocean fades toward with distance (linear RGBA)
+    pub fog_color: [f32; 4],
+
+    /// Bloom post-process (off by default)
+    pub bloom: BloomConfig,
+
+    /// Exposure of the HDR image in stops (0 = unchanged; +1 doubles the brightness)
+    /// Applied with `tone_mapping` in the bloom composite, so only while bloom is enabled
+    pub exposure: f32,
+
+    /// Extra exposure per unit of audio bass energy (stops; 0 = steady exposure)
+    pub low_to_exposure: f32,
+
+    /// Tone-mapping curve after exposure (passthrough by default)
+    pub tone_mapping: ToneMapping,
+
+    /// Simulation steps per second (envelopes and manual camera advance in fixed steps)
+    pub sim_hz: f32,
+
+    /// Frame rate cap (None = as fast as the present mode allows, e.g. vsync)
+    ///
+    /// Frames are paced by sleeping; simulated time is unaffected (real time live, exactly one
+    /// frame period per frame while recording).
+    pub max_fps: Option<u32>,
+
+    /// Draw the ocean as thick screen-space lines (width from the audio-reactive line width)
+    pub thick_lines: bool,
+
+    /// Ocean face culling (read at startup; back faces by default)
+    pub cull_mode: CullMode,
+
+    /// Debug HUD with FPS, band levels and camera state (toggle with F1)
+    pub show_hud: bool,
+
+    /// Debug FFT spectrum bar graph along the bottom of the frame (toggle with F2)
+    pub show_spectrum: bool,
 }
 
 impl Default for RenderConfig {
@@ -27,9 +279,36 @@ impl Default for RenderConfig {
         Self {
             window_width: 1280,
             window_height: 720,
-            fov_degrees: 100.0, // Very wide FOV for extreme perspective
+            fullscreen: false,
+            fov_degrees: 100.0,     // Very wide FOV for extreme perspective
+            fov_max_degrees: 100.0, // Same as fov_degrees: no speed widening until configured
+            speed_for_max_fov_m_per_s: 300.0, // Twice the basic preset speed
+            anamorphic_squeeze: 1.0,
             near_plane_m: 0.1,
             far_plane_m: 3000.0, // Enough for grid extent (2048m)
+            far_plane_auto_fit: false,
+            sample_count: 1,
+            background: BackgroundMode::default(),
+            background_high_band_gain: 0.0,
+            sky: SkyConfig::default(),
+            horizon_glow_color: [0.3, 0.6, 1.0, 1.0],
+            horizon_glow_width: 0.0,
+            horizon_glow_high_band_gain: 0.0,
+            depth_color_trough: [1.0; 4],
+            depth_color_crest: [1.0; 4],
+            depth_color_range_m: 2.0,
+            fog_density: 0.0, // No fog (0.0006 softens the horizon: ~63% fog at 1667m)
+            fog_color: [0.0, 0.0, 0.0, 1.0], // Matches the default black background
+            bloom: BloomConfig::default(),
+            exposure: 0.0,
+            low_to_exposure: 0.0,
+            tone_mapping: ToneMapping::default(),
+            sim_hz: 120.0, // At least one step per frame at common refresh rates
+            max_fps: None,
+            thick_lines: false,
+            cull_mode: CullMode::default(),
+            show_hud: false,
+            show_spectrum: false,
         }
     }
 }
@@ -38,10 +317,90 @@ impl RenderConfig {
     pub fn aspect_ratio(&self) -> f32 {
         self.window_width as f32 / self.window_height as f32
     }
+
+    /// Field of view for given camera speed (degrees)
+    ///
+    /// Linear from `fov_degrees` at rest to `fov_max_degrees` at `speed_for_max_fov_m_per_s`,
+    /// clamped to that range.
+    pub fn fov_for_speed(&self, speed_m_per_s: f32) -> f32 {
+        if self.speed_for_max_fov_m_per_s <= 0.0 {
+            return self.fov_degrees;
+        }
+        let t = (speed_m_per_s / self.speed_for_max_fov_m_per_s).clamp(0.0, 1.0);
+        let fov = self.fov_degrees + (self.fov_max_degrees - self.fov_degrees) * t;
+
+        fov.clamp(
+            self.fov_degrees.min(self.fov_max_degrees),
+            self.fov_degrees.max(self.fov_max_degrees),
+        )
+    }
+
+    /// Linear color multiplier for the current audio bass energy (1 at 0 stops)
+    pub fn exposure_scale(&self, low: f32) -> f32 {
+        (self.exposure + low * self.low_to_exposure).exp2()
+    }
+
+    /// Horizon glow color for the current audio high-band energy
+    ///
+    /// RGB is scaled by `1 + horizon_glow_high_band_gain * high`; alpha is unchanged.
+    pub fn horizon_glow_color_for(&self, high: f32) -> [f32; 4] {
+        let brightness = (1.0 + self.horizon_glow_high_band_gain * high).max(0.0);
+        let [r, g, b, a] = self.horizon_glow_color;
+        [r * brightness, g * brightness, b * brightness, a]
+    }
+
+    /// Background (top, bottom) colors for the current audio high-band energy
+    ///
+    /// RGB is scaled by `1 + background_high_band_gain * high`; alpha is unchanged.
+    pub fn background_colors(&self, high: f32) -> ([f32; 4], [f32; 4]) {
+        let brightness = (1.0 + self.background_high_band_gain * high).max(0.0);
+        let scale = |[r, g, b, a]: [f32; 4]| [r * brightness, g * brightness, b * brightness, a];
+        let (top, bottom) = self.background.colors();
+        (scale(top), scale(bottom))
+    }
+}
+
+/// Recording output format
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    /// Numbered PNG frames in `frames_dir()` (combine with `scripts/combine-recording.sh`)
+    #[default]
+    PngSequence,
+
+    /// H.264 MP4 encoded by an `ffmpeg` child process, with the recorded audio muxed in
+    Mp4 {
+        /// x264 constant rate factor (lower is higher quality, 18-28 is typical)
+        crf: u32,
+        /// x264 speed preset (e.g. "veryfast", "medium", "slow")
+        preset: String,
+    },
+}
+
+impl OutputFormat {
+    /// MP4 with the settings used by `scripts/combine-recording.sh`
+    pub fn mp4() -> Self {
+        Self::Mp4 {
+            crf: 23,
+            preset: "medium".to_string(),
+        }
+    }
+}
+
+/// Sample encoding of the recorded WAV track
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WavSampleFormat {
+    /// IEEE float (32-bit only)
+    #[default]
+    Float,
+    /// Signed PCM (8, 16, 24 or 32-bit)
+    Int,
 }
 
 /// Recording mode configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct RecordingConfig {
     /// Duration to record (seconds)
     pub duration_secs: f32,
@@ -51,6 +410,53 @@ pub struct RecordingConfig {
 
     /// Frame rate (FPS)
     pub fps: u32,
+
+    /// Output format (PNG sequence or MP4)
+    #[serde(default)]
+    pub output_format: OutputFormat,
+
+    /// Audio track channels (1 = mono downmix of the stereo synthesis, 2 = stereo)
+    #[serde(default = "default_audio_channels")]
+    pub audio_channels: u16,
+
+    /// Audio track bits per sample (32 for float; 8, 16, 24 or 32 for int)
+    #[serde(default = "default_audio_bits_per_sample")]
+    pub audio_bits_per_sample: u16,
+
+    /// Audio track sample encoding
+    #[serde(default)]
+    pub audio_sample_format: WavSampleFormat,
+
+    /// Capture resolution (pixels); both unset records at the window size
+    #[serde(default)]
+    pub width: Option<u32>,
+    #[serde(default)]
+    pub height: Option<u32>,
+
+    /// Renders averaged per captured frame (motion blur; 1 = off, 0 counts as 1)
+    #[serde(default = "default_subframes")]
+    pub subframes: u32,
+
+    /// Capture only every Nth rendered frame (time-lapse; 1 = every frame, 0 counts as 1)
+    /// Captured frames are numbered contiguously
+    #[serde(default = "default_capture_every_n")]
+    pub capture_every_n: u32,
+}
+
+fn default_capture_every_n() -> u32 {
+    1
+}
+
+fn default_subframes() -> u32 {
+    1
+}
+
+fn default_audio_channels() -> u16 {
+    2
+}
+
+fn default_audio_bits_per_sample() -> u16 {
+    32
 }
 
 impl RecordingConfig {
@@ -59,14 +465,100 @@ impl RecordingConfig {
             duration_secs,
             output_dir: "recording".to_string(),
             fps: 60,
+            output_format: OutputFormat::PngSequence,
+            audio_channels: default_audio_channels(),
+            audio_bits_per_sample: default_audio_bits_per_sample(),
+            audio_sample_format: WavSampleFormat::Float,
+            width: None,
+            height: None,
+            subframes: default_subframes(),
+            capture_every_n: default_capture_every_n(),
         }
     }
 
-    /// Total number of frames to capture
+    /// Capture resolution override (None records at the window size)
+    pub fn resolution(&self) -> Option<(u32, u32)> {
+        self.width.zip(self.height)
+    }
+
+    /// Check `width` and `height` are set together and non-zero
+    pub fn validate_resolution(&self) -> Result<(), String> {
+        match (self.width, self.height) {
+            (None, None) => Ok(()),
+            (Some(width), Some(height)) if width > 0 && height > 0 => Ok(()),
+            (width, height) => Err(format!(
+                "Recording resolution needs both width and height, got {:?}x{:?}",
+                width, height
+            )),
+        }
+    }
+
+    /// Check the audio track format is one the WAV writer supports
+    pub fn validate_audio_format(&self) -> Result<(), String> {
+        if !matches!(self.audio_channels, 1 | 2) {
+            return Err(format!(
+                "Audio channels must be 1 or 2, got {}",
+                self.audio_channels
+            ));
+        }
+        let bits_supported = match self.audio_sample_format {
+            WavSampleFormat::Float => self.audio_bits_per_sample == 32,
+            WavSampleFormat::Int => matches!(self.audio_bits_per_sample, 8 | 16 | 24 | 32),
+        };
+        if !bits_supported {
+            return Err(format!(
+                "Unsupported audio format: {}-bit {:?}",
+                self.audio_bits_per_sample, self.audio_sample_format
+            ));
+        }
+        Ok(())
+    }
+
+    /// Total number of frames to render (every `capture_every_n`th of them is captured)
     pub fn total_frames(&self) -> usize {
         (self.duration_secs * self.fps as f32).ceil() as usize
     }
 
+    /// Number of frames written: rendered frames 0, N, 2N, ... with N = `capture_every_n`
+    pub fn captured_frames(&self) -> usize {
+        self.total_frames()
+            .div_ceil(self.capture_every_n.max(1) as usize)
+    }
+
+    /// Estimated real time left (seconds) after `frames_done` frames took `elapsed_s`
+    ///
+    /// Extrapolates the average frame time so far; None before the first frame.
+    pub fn eta_s(&self, frames_done: usize, elapsed_s: f32) -> Option<f32> {
+        if frames_done == 0 {
+            return None;
+        }
+        let frames_left = self.total_frames().saturating_sub(frames_done);
+        Some(elapsed_s / frames_done as f32 * frames_left as f32)
+    }
+
+    /// Simulated time of frame `frame_num` (seconds): exactly one frame period per frame
+    pub fn frame_time_s(&self, frame_num: usize) -> f32 {
+        (frame_num as f64 / self.fps.max(1) as f64) as f32
+    }
+
+    /// Simulation times of the sub-frames averaged into the frame shown at `frame_time_s`
+    ///
+    /// Evenly spread over the frame period ending at `frame_time_s`, so motion trails behind.
+    pub fn subframe_times(&self, frame_time_s: f32) -> Vec<f32> {
+        let subframes = self.subframes.max(1);
+        let period_s = 1.0 / self.fps.max(1) as f32;
+        (0..subframes)
+            .map(|i| frame_time_s - period_s * (subframes - 1 - i) as f32 / subframes as f32)
+            .collect()
+    }
+
+    /// Audio samples (per channel) elapsed before frame `frame_num` starts
+    ///
+    /// Integer arithmetic, so frames of a recording never drift from the audio track.
+    pub fn samples_until_frame(&self, frame_num: usize, sample_rate_hz: usize) -> u64 {
+        frame_num as u64 * sample_rate_hz as u64 / self.fps.max(1) as u64
+    }
+
     /// Frame directory path
     pub fn frames_dir(&self) -> String {
         format!("{}/frames", self.output_dir)
@@ -76,4 +568,151 @@ impl RecordingConfig {
     pub fn audio_path(&self) -> String {
         format!("{}/audio.wav", self.output_dir)
     }
+
+    /// Path of the config the session was recorded with (read back by `replay`)
+    pub fn config_path(&self) -> String {
+        format!("{}/config.toml", self.output_dir)
+    }
+
+    /// Final video path (MP4 output format)
+    pub fn video_path(&self) -> String {
+        format!("{}/output.mp4", self.output_dir)
+    }
+
+    /// Encoded video before the audio is muxed in (MP4 output format)
+    pub fn silent_video_path(&self) -> String {
+        format!("{}/video_only.mp4", self.output_dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recording_frame_time_steps_one_period() {
+        let config = RecordingConfig::new(10.0);
+        assert_eq!(config.fps, 60);
+        for frame in [0, 1, 59, 60, 61, 599, 600] {
+            assert_eq!(config.frame_time_s(frame), (frame as f64 / 60.0) as f32);
+        }
+    }
+
+    #[test]
+    fn test_recording_samples_track_frames_without_drift() {
+        let config = RecordingConfig::new(10.0);
+
+        // 44.1 kHz at 60 fps is 735 samples per frame; 48 kHz at 60 fps is 800
+        assert_eq!(config.samples_until_frame(1, 44100), 735);
+        assert_eq!(config.samples_until_frame(600, 48000), 480_000);
+
+        // Uneven rates still land exactly on each whole second
+        let odd = RecordingConfig {
+            fps: 24,
+            ..RecordingConfig::new(10.0)
+        };
+        assert_eq!(odd.samples_until_frame(24 * 7, 44100), 7 * 44100);
+    }
+
+    #[test]
+    fn test_recording_audio_format_validation() {
+        let with_format = |channels, bits, format| RecordingConfig {
+            audio_channels: channels,
+            audio_bits_per_sample: bits,
+            audio_sample_format: format,
+            ..RecordingConfig::new(1.0)
+        };
+
+        assert!(RecordingConfig::new(1.0).validate_audio_format().is_ok());
+        assert!(with_format(1, 16, WavSampleFormat::Int)
+            .validate_audio_format()
+            .is_ok());
+        assert!(with_format(3, 16, WavSampleFormat::Int)
+            .validate_audio_format()
+            .is_err());
+        assert!(with_format(2, 16, WavSampleFormat::Float)
+            .validate_audio_format()
+            .is_err());
+        assert!(with_format(2, 12, WavSampleFormat::Int)
+            .validate_audio_format()
+            .is_err());
+    }
+
+    #[test]
+    fn test_recording_resolution_validation() {
+        let with_size = |width, height| RecordingConfig {
+            width,
+            height,
+            ..RecordingConfig::new(1.0)
+        };
+
+        assert_eq!(RecordingConfig::new(1.0).resolution(), None);
+        assert!(RecordingConfig::new(1.0).validate_resolution().is_ok());
+        let full_hd = with_size(Some(1920), Some(1080));
+        assert_eq!(full_hd.resolution(), Some((1920, 1080)));
+        assert!(full_hd.validate_resolution().is_ok());
+        assert!(with_size(Some(1920), None).validate_resolution().is_err());
+        assert!(with_size(Some(0), Some(1080))
+            .validate_resolution()
+            .is_err());
+    }
+
+    #[test]
+    fn test_subframes_spread_over_the_frame_period() {
+        let config = RecordingConfig {
+            fps: 25,
+            subframes: 4,
+            ..RecordingConfig::new(1.0)
+        };
+        let times = config.subframe_times(1.0);
+        let expected = [0.97, 0.98, 0.99, 1.0];
+        assert_eq!(times.len(), expected.len());
+        for (time, expected) in times.iter().zip(expected) {
+            assert!((time - expected).abs() < 1e-6, "{:?}", times);
+        }
+
+        // Off (and 0) render just the frame time
+        for subframes in [0, 1] {
+            let config = RecordingConfig {
+                subframes,
+                ..RecordingConfig::new(1.0)
+            };
+            assert_eq!(config.subframe_times(2.5), [2.5]);
+        }
+    }
+
+    #[test]
+    fn test_capture_every_n_decimates_captured_frames() {
+        let recording = RecordingConfig {
+            fps: 10,
+            capture_every_n: 3,
+            ..RecordingConfig::new(0.9)
+        };
+        assert_eq!(recording.total_frames(), 9);
+        assert_eq!(recording.captured_frames(), 3);
+
+        // Partial strides still capture their first frame; 0 behaves like 1
+        let ten = RecordingConfig {
+            duration_secs: 1.0,
+            ..recording.clone()
+        };
+        assert_eq!(ten.captured_frames(), 4);
+        let every = RecordingConfig {
+            capture_every_n: 0,
+            ..recording
+        };
+        assert_eq!(every.captured_frames(), 9);
+    }
+
+    #[test]
+    fn test_recording_eta_extrapolates_average_frame_time() {
+        // 10 s at 60 fps = 600 frames
+        let config = RecordingConfig::new(10.0);
+
+        assert_eq!(config.eta_s(0, 0.0), None);
+        // 150 frames in 30 s is 0.2 s per frame, so 450 frames left take 90 s
+        assert_eq!(config.eta_s(150, 30.0), Some(90.0));
+        assert_eq!(config.eta_s(600, 120.0), Some(0.0));
+        assert_eq!(config.eta_s(700, 120.0), Some(0.0));
+    }
 }
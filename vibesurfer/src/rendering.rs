@@ -1,11 +1,45 @@
 //! Rendering system with wgpu pipeline and shader management.
 
+use std::path::{Path, PathBuf};
+
 use bytemuck::{Pod, Zeroable};
 use glam::Mat4;
 use wgpu::util::DeviceExt;
 
-use crate::ocean::{OceanGrid, Vertex};
-use crate::params::RecordingConfig;
+mod accumulate;
+mod bloom;
+mod capture;
+mod headless;
+mod hud;
+mod lines;
+mod shader_export;
+mod shader_reload;
+mod spectrum;
+mod timing;
+mod video;
+pub use accumulate::Accumulator;
+pub use bloom::{Bloom, HDR_FORMAT};
+pub use capture::{capture_texture_to_image, FrameCapture, FrameSink};
+pub use headless::HeadlessContext;
+pub use hud::{rasterize_text, Hud, HudState};
+pub use lines::{LineRenderer, PIXELS_PER_LINE_WIDTH};
+pub use shader_export::{export_shaders, translate_wgsl, ShaderBackend, CRATE_SHADERS};
+pub use shader_reload::{validate_wgsl, ReloadableShader, ShaderWatcher, SHADER_SOURCE_DIR};
+pub use spectrum::{bar_heights, SpectrumGraph, SPECTRUM_BARS};
+pub use timing::FrameTimings;
+pub use video::{ffmpeg_available, mux_audio, VideoEncoder};
+
+use capture::save_texture_png;
+use timing::GpuTimer;
+
+use crate::ocean::{AudioBands, OceanGrid, Vertex};
+use crate::params::{
+    AudioReactiveMapping, CullMode, NoiseKind, OceanPhysics, OutputFormat, RecordingConfig,
+    RenderConfig, TerrainParams, WaveModel,
+};
+
+/// Depth buffer format shared by the ocean and skybox pipelines
+pub const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
 
 /// Uniform buffer for ocean shader (view-projection matrix + parameters)
 #[repr(C)]
@@ -16,35 +50,289 @@ pub struct Uniforms {
     pub amplitude: f32,
     pub frequency: f32,
     pub time: f32,
+    pub fog_color: [f32; 4],
+    pub fog_density: f32,
+    pub color_height_range_m: f32,
+    pub _padding: [f32; 2], // Align color_low to 16 bytes
+    pub color_low: [f32; 4],
+    pub color_high: [f32; 4],
+    /// xyz: unit direction toward the key light; w: Lambert intensity (0 = unlit)
+    pub light_direction: [f32; 4],
+    /// Horizontal distance from the camera (m) where the ocean starts fading out
+    pub edge_fade_start: f32,
+    /// Horizontal distance from the camera (m) where the ocean is fully transparent
+    pub edge_fade_end: f32,
+    /// Wave height above the base terrain (m) spanning the depth tint below
+    pub depth_color_range_m: f32,
+    pub _padding2: f32, // Align depth_color_trough to 16 bytes
+    /// Tint where the surface dips below the base terrain (deep water)
+    pub depth_color_trough: [f32; 4],
+    /// Tint where waves crest above the base terrain
+    pub depth_color_crest: [f32; 4],
+    /// Camera world XZ (m): the center of the edge fade
+    pub camera_xz: [f32; 2],
+    pub _padding3: [f32; 2], // Round the struct up to 16 bytes
+}
+
+impl Uniforms {
+    /// Ocean uniforms for this frame, with fog and depth tint settings from `render_config`
+    ///
+    /// The peak/trough tint starts out neutral (white), the key light off and the edge fade
+    /// at 800 m → 1000 m (the default grid's) around the world origin; see `with_color_ramp`,
+    /// `with_light` and `with_edge_fade`.
+    pub fn new(
+        view_proj: Mat4,
+        line_width: f32,
+        amplitude: f32,
+        frequency: f32,
+        time: f32,
+        render_config: &RenderConfig,
+    ) -> Self {
+        Self {
+            view_proj: view_proj.to_cols_array_2d(),
+            line_width,
+            amplitude,
+            frequency,
+            time,
+            fog_color: render_config.fog_color,
+            fog_density: render_config.fog_density,
+            color_height_range_m: 1.0,
+            _padding: [0.0; 2],
+            color_low: [1.0; 4],
+            color_high: [1.0; 4],
+            light_direction: [0.0, 1.0, 0.0, 0.0],
+            edge_fade_start: 800.0,
+            edge_fade_end: 1000.0,
+            depth_color_range_m: render_config.depth_color_range_m,
+            _padding2: 0.0,
+            depth_color_trough: render_config.depth_color_trough,
+            depth_color_crest: render_config.depth_color_crest,
+            camera_xz: [0.0; 2],
+            _padding3: [0.0; 2],
+        }
+    }
+
+    /// Tint troughs and peaks with the mapping's palette, hue-shifted by the audio mid band
+    pub fn with_color_ramp(mut self, mapping: &AudioReactiveMapping, mid: f32) -> Self {
+        (self.color_low, self.color_high) = mapping.color_ramp(mid);
+        self.color_height_range_m = mapping.color_height_range_m;
+        self
+    }
+
+    /// Light the ocean from `light_direction` (see `AudioReactiveMapping::light_direction`)
+    pub fn with_light(mut self, light_direction: [f32; 4]) -> Self {
+        self.light_direction = light_direction;
+        self
+    }
+
+    /// Fade the ocean out toward the edge of the grid centered on `camera_xz`
+    /// (`OceanPhysics::edge_fade_start`/`_end`)
+    pub fn with_edge_fade(mut self, physics: &OceanPhysics, camera_xz: [f32; 2]) -> Self {
+        let half_size = physics.grid_size as f32 * physics.grid_spacing_m / 2.0;
+        self.edge_fade_start = physics.edge_fade_start * half_size;
+        self.edge_fade_end = physics.edge_fade_end * half_size;
+        self.camera_xz = camera_xz;
+        self
+    }
 }
 
-/// Uniform buffer for skybox shader (inverse view-projection + time)
+/// Uniform buffer for skybox shader (inverse view-projection + time + background + sky)
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Pod, Zeroable)]
 pub struct SkyboxUniforms {
     pub inv_view_proj: [[f32; 4]; 4],
     pub time: f32,
-    pub _padding: [f32; 3], // Padding for alignment
+    /// 1.0 draws the sky gradient and sun, 0.0 the background only (`SkyConfig::enabled`)
+    pub sky_enabled: f32,
+    pub _padding: [f32; 2], // Padding for alignment
+    pub background_top: [f32; 4],
+    pub background_bottom: [f32; 4],
+    pub sun_direction: [f32; 3],
+    pub _padding2: f32, // Align vec3 sun_direction to 16 bytes
+    pub horizon_color: [f32; 4],
+    pub zenith_color: [f32; 4],
+    /// Horizon glow band, pulsed by the audio high band (`RenderConfig::horizon_glow_color_for`)
+    pub horizon_glow_color: [f32; 4],
+    /// Glow half-width in view-ray elevation (0 = no glow)
+    pub horizon_glow_width: f32,
+    pub _padding3: [f32; 3], // Round the struct up to 16 bytes
+}
+
+impl SkyboxUniforms {
+    /// Skybox uniforms with the configured background and horizon glow (brightened by the
+    /// audio high band) and the sky gradient with the sun at its position for `time`
+    pub fn new(inv_view_proj: Mat4, time: f32, render_config: &RenderConfig, high: f32) -> Self {
+        let (background_top, background_bottom) = render_config.background_colors(high);
+        let sky = &render_config.sky;
+        Self {
+            inv_view_proj: inv_view_proj.to_cols_array_2d(),
+            time,
+            sky_enabled: if sky.enabled { 1.0 } else { 0.0 },
+            _padding: [0.0; 2],
+            background_top,
+            background_bottom,
+            sun_direction: sky.sun_direction(time),
+            _padding2: 0.0,
+            horizon_color: sky.horizon_color,
+            zenith_color: sky.zenith_color,
+            horizon_glow_color: render_config.horizon_glow_color_for(high),
+            horizon_glow_width: render_config.horizon_glow_width,
+            _padding3: [0.0; 3],
+        }
+    }
+}
+
+/// Filled and wireframe ocean pipelines (wgpu bakes polygon mode into the pipeline)
+struct OceanPipelines {
+    fill: wgpu::RenderPipeline,
+    /// None when the adapter lacks `Features::POLYGON_MODE_LINE`
+    line: Option<wgpu::RenderPipeline>,
+    wireframe: bool,
+    /// Faces both pipelines discard
+    cull_mode: CullMode,
+}
+
+impl OceanPipelines {
+    /// Build the fill pipeline, plus the line pipeline if the device supports it, from
+    /// `shader.wgsl` source
+    fn new(
+        device: &wgpu::Device,
+        color_format: wgpu::TextureFormat,
+        uniform_bind_group_layout: &wgpu::BindGroupLayout,
+        sample_count: u32,
+        cull_mode: CullMode,
+        source: &str,
+    ) -> Self {
+        let create = |polygon_mode| {
+            create_ocean_pipeline(
+                device,
+                color_format,
+                uniform_bind_group_layout,
+                sample_count,
+                polygon_mode,
+                cull_face(cull_mode),
+                source,
+            )
+        };
+        let line = device
+            .features()
+            .contains(wgpu::Features::POLYGON_MODE_LINE)
+            .then(|| create(wgpu::PolygonMode::Line));
+        Self {
+            fill: create(wgpu::PolygonMode::Fill),
+            line,
+            wireframe: false,
+            cull_mode,
+        }
+    }
+
+    /// Switch to wireframe (ignored with a warning if line mode is unsupported)
+    fn set_wireframe(&mut self, wireframe: bool) {
+        if wireframe && self.line.is_none() {
+            eprintln!("Warning: wireframe not supported by this GPU (POLYGON_MODE_LINE)");
+            return;
+        }
+        self.wireframe = wireframe;
+    }
+
+    /// Polygon mode of the pipeline `current` returns
+    fn polygon_mode(&self) -> wgpu::PolygonMode {
+        if self.wireframe {
+            wgpu::PolygonMode::Line
+        } else {
+            wgpu::PolygonMode::Fill
+        }
+    }
+
+    /// Faces the pipelines were built to cull (None = both sides drawn)
+    fn cull_mode(&self) -> Option<wgpu::Face> {
+        cull_face(self.cull_mode)
+    }
+
+    /// Pipeline for the current mode
+    fn current(&self) -> &wgpu::RenderPipeline {
+        match self.line {
+            Some(ref line) if self.wireframe => line,
+            _ => &self.fill,
+        }
+    }
+}
+
+/// Format of the headless render target (`RenderSystem::new_headless`)
+pub const HEADLESS_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// Where frames are drawn: the window surface, or an offscreen texture when headless
+enum RenderTarget {
+    Surface(wgpu::Surface<'static>),
+    Offscreen(wgpu::Texture),
+}
+
+/// Per-frame inputs for a one-off headless render
+#[derive(Copy, Clone, Debug)]
+pub struct FrameParams {
+    pub uniforms: Uniforms,
+    pub skybox_uniforms: SkyboxUniforms,
+    /// Ocean indices to draw (from the start of the index buffer)
+    pub index_count: u32,
 }
 
 /// Rendering system managing wgpu device, pipelines, and buffers
 pub struct RenderSystem {
-    pub surface: wgpu::Surface<'static>,
+    target: RenderTarget,
     pub device: wgpu::Device,
     pub queue: wgpu::Queue,
-    render_pipeline: wgpu::RenderPipeline,
+    ocean_pipelines: OceanPipelines,
+    /// Thick screen-space line pipeline (None without vertex-stage storage buffers)
+    lines: Option<LineRenderer>,
+    /// Draw the ocean with `lines` instead of the triangle pipelines
+    thick_lines: bool,
+    /// Wireframe is on via `thick_lines` (no `POLYGON_MODE_LINE`, see `wireframe_path`),
+    /// holding the `thick_lines` setting to restore when it is turned off
+    wireframe_fallback: Option<bool>,
+    /// Debug overlay drawn over the finished frame while `show_hud` is set
+    hud: Hud,
+    show_hud: bool,
+    /// FFT bar graph drawn along the bottom of the frame while `show_spectrum` is set
+    spectrum: SpectrumGraph,
+    show_spectrum: bool,
     skybox_pipeline: wgpu::RenderPipeline,
+    /// Terrain vertices: written by the compute shader, drawn by the ocean pipeline
     vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
+    /// Indices in `index_buffer` (full terrain lattice)
+    index_count: u32,
+    /// Vertices per side of the terrain lattice in `vertex_buffer`
+    grid_size: u32,
     uniform_buffer: wgpu::Buffer,
+    uniform_bind_group_layout: wgpu::BindGroupLayout,
     uniform_bind_group: wgpu::BindGroup,
     skybox_uniform_buffer: wgpu::Buffer,
+    skybox_bind_group_layout: wgpu::BindGroupLayout,
     skybox_bind_group: wgpu::BindGroup,
-    recording_config: Option<RecordingConfig>,
-    window_size: (u32, u32),
+    /// Async PNG capture of presented frames (recording mode only)
+    frame_capture: Option<FrameCapture>,
+    /// Offscreen target at the recording resolution override (the window only previews it)
+    recording_texture: Option<wgpu::Texture>,
+    /// Motion-blur sub-frame averaging (created by the first `accumulate_subframe`)
+    accumulator: Option<Accumulator>,
+    /// Where to save the next presented frame (see `capture_current_frame`)
+    screenshot_path: Option<PathBuf>,
+    /// Target size and format (the offscreen texture's, when headless)
+    surface_config: wgpu::SurfaceConfiguration,
+    sample_count: u32,
+    depth_view: wgpu::TextureView,
+    /// Multisampled color target resolved into the scene target (None when sample_count = 1)
+    msaa_view: Option<wgpu::TextureView>,
+    /// Format the scene is rendered in (HDR when bloom is on, else the surface format)
+    scene_format: wgpu::TextureFormat,
+    /// Bloom post-process; the scene renders into its HDR target (None when disabled)
+    bloom: Option<Bloom>,
+    /// Compute/render pass timestamps (None without `TIMESTAMP_QUERY`)
+    gpu_timer: Option<GpuTimer>,
 
     // GPU compute terrain generation
-    compute_pipeline: wgpu::ComputePipeline,
+    compute_pipelines: TerrainComputePipelines,
+    compute_bind_group_layout: wgpu::BindGroupLayout,
     compute_bind_group: wgpu::BindGroup,
     terrain_params_buffer: wgpu::Buffer,
 }
@@ -53,11 +341,11 @@ impl RenderSystem {
     /// Create new rendering system
     pub async fn new(
         window: std::sync::Arc<winit::window::Window>,
-        ocean_grid: &OceanGrid,
+        grid_size: u32,
+        render_config: &RenderConfig,
         recording_config: Option<RecordingConfig>,
     ) -> Result<Self, String> {
         let size = window.inner_size();
-        let window_size = (size.width, size.height);
 
         // Create wgpu instance
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
@@ -70,29 +358,7 @@ impl RenderSystem {
             .create_surface(window)
             .map_err(|e| format!("Failed to create surface: {}", e))?;
 
-        // Request adapter
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::HighPerformance,
-                compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
-            })
-            .await
-            .ok_or("Failed to find suitable GPU adapter")?;
-
-        // Request device
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    label: Some("Main Device"),
-                    required_features: wgpu::Features::empty(),
-                    required_limits: wgpu::Limits::default(),
-                    memory_hints: Default::default(),
-                },
-                None,
-            )
-            .await
-            .map_err(|e| format!("Failed to request device: {}", e))?;
+        let (adapter, device, queue) = request_device(&instance, Some(&surface)).await?;
 
         // Configure surface
         let surface_caps = surface.get_capabilities(&adapter);
@@ -103,12 +369,11 @@ impl RenderSystem {
             .copied()
             .unwrap_or(surface_caps.formats[0]);
 
-        let mut usage = wgpu::TextureUsages::RENDER_ATTACHMENT;
-
-        // Add COPY_SRC if recording (needed for frame capture)
-        if recording_config.is_some() {
-            usage |= wgpu::TextureUsages::COPY_SRC;
-        }
+        // COPY_SRC (when supported) lets frames be read back for recording and screenshots;
+        // COPY_DST lets a recording at its own resolution be previewed in the window
+        let usage = wgpu::TextureUsages::RENDER_ATTACHMENT
+            | (surface_caps.usages
+                & (wgpu::TextureUsages::COPY_SRC | wgpu::TextureUsages::COPY_DST));
 
         let config = wgpu::SurfaceConfiguration {
             usage,
@@ -122,41 +387,102 @@ impl RenderSystem {
         };
         surface.configure(&device, &config);
 
-        // Load shaders
-        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Ocean Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("shader.wgsl").into()),
-        });
+        let mut render_system = Self::with_target(
+            &adapter,
+            device,
+            queue,
+            RenderTarget::Surface(surface),
+            config,
+            grid_size,
+            render_config,
+        );
 
-        let skybox_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Skybox Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("skybox.wgsl").into()),
-        });
+        if let Some(recording) = recording_config {
+            render_system.start_recording(&recording)?;
+        }
 
-        // Create buffers
-        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Vertex Buffer"),
-            contents: bytemuck::cast_slice(&ocean_grid.vertices),
-            usage: wgpu::BufferUsages::VERTEX
-                | wgpu::BufferUsages::STORAGE  // GPU compute writes to this
-                | wgpu::BufferUsages::COPY_DST
-                | wgpu::BufferUsages::COPY_SRC, // For physics readback (future)
-        });
+        Ok(render_system)
+    }
 
-        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Index Buffer"),
-            contents: bytemuck::cast_slice(&ocean_grid.indices),
-            usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
-        });
+    /// Create a windowless rendering system drawing into a `width`×`height` texture
+    ///
+    /// Uses the default `RenderConfig`; read frames back with `render_to_image`.
+    pub async fn new_headless(width: u32, height: u32, grid_size: u32) -> Result<Self, String> {
+        if width == 0 || height == 0 {
+            return Err(format!("Invalid headless size {}x{}", width, height));
+        }
 
-        let uniforms = Uniforms {
-            view_proj: Mat4::IDENTITY.to_cols_array_2d(),
-            line_width: 0.02,
-            amplitude: 2.0,
-            frequency: 0.1,
-            time: 0.0,
+        let HeadlessContext {
+            adapter,
+            device,
+            queue,
+        } = HeadlessContext::request().await?;
+
+        // Describes the offscreen texture (never used to configure a surface)
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            format: HEADLESS_FORMAT,
+            width,
+            height,
+            present_mode: wgpu::PresentMode::Fifo,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            view_formats: vec![],
+            desired_maximum_frame_latency: 2,
+        };
+        let texture = create_offscreen_texture(&device, &config);
+
+        Ok(Self::with_target(
+            &adapter,
+            device,
+            queue,
+            RenderTarget::Offscreen(texture),
+            config,
+            grid_size,
+            &RenderConfig::default(),
+        ))
+    }
+
+    /// Pipelines, buffers and size-dependent targets for a configured render target
+    fn with_target(
+        adapter: &wgpu::Adapter,
+        device: wgpu::Device,
+        queue: wgpu::Queue,
+        target: RenderTarget,
+        config: wgpu::SurfaceConfiguration,
+        grid_size: u32,
+        render_config: &RenderConfig,
+    ) -> Self {
+        // With bloom the scene renders into an HDR target composited onto the surface
+        let bloom = render_config
+            .bloom
+            .enabled
+            .then(|| Bloom::new(&device, config.format, config.width, config.height));
+        let scene_format = if bloom.is_some() {
+            HDR_FORMAT
+        } else {
+            config.format
+        };
+
+        // MSAA sample count (fall back to 1 if the scene format can't multisample)
+        let format_features = adapter.get_texture_format_features(scene_format);
+        let sample_count = if format_features
+            .flags
+            .sample_count_supported(render_config.sample_count)
+        {
+            render_config.sample_count
+        } else {
+            eprintln!(
+                "Warning: {}x MSAA not supported for {:?}, disabling",
+                render_config.sample_count, scene_format
+            );
+            1
         };
 
+        // Create buffers
+        let (vertex_buffer, index_buffer, index_count) = create_ocean_buffers(&device, grid_size);
+
+        let uniforms = Uniforms::new(Mat4::IDENTITY, 0.02, 2.0, 0.1, 0.0, render_config);
+
         let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Uniform Buffer"),
             contents: bytemuck::cast_slice(&[uniforms]),
@@ -165,19 +491,7 @@ impl RenderSystem {
 
         // Create ocean bind group
         let uniform_bind_group_layout =
-            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                label: Some("Uniform Bind Group Layout"),
-                entries: &[wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
-                }],
-            });
+            create_uniform_bind_group_layout(&device, "Uniform Bind Group Layout");
 
         let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Uniform Bind Group"),
@@ -188,69 +502,42 @@ impl RenderSystem {
             }],
         });
 
-        // Create ocean render pipeline
-        let render_pipeline_layout =
-            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("Render Pipeline Layout"),
-                bind_group_layouts: &[&uniform_bind_group_layout],
-                push_constant_ranges: &[],
+        // Create ocean render pipelines (filled + wireframe)
+        let ocean_pipelines = OceanPipelines::new(
+            &device,
+            scene_format,
+            &uniform_bind_group_layout,
+            sample_count,
+            render_config.cull_mode,
+            ReloadableShader::Ocean.builtin_source(),
+        );
+
+        // Thick line pipeline reads the terrain vertices as storage in the vertex stage
+        let lines = adapter
+            .get_downlevel_capabilities()
+            .flags
+            .contains(wgpu::DownlevelFlags::VERTEX_STORAGE)
+            .then(|| {
+                LineRenderer::new(
+                    &device,
+                    scene_format,
+                    &uniform_bind_group_layout,
+                    sample_count,
+                    &vertex_buffer,
+                    grid_size,
+                    (config.width, config.height),
+                )
             });
+        if render_config.thick_lines && lines.is_none() {
+            eprintln!("Warning: thick lines not supported by this GPU (vertex storage buffers)");
+        }
+        let thick_lines = render_config.thick_lines && lines.is_some();
 
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Ocean Render Pipeline"),
-            layout: Some(&render_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: Some("vs_main"),
-                buffers: &[wgpu::VertexBufferLayout {
-                    array_stride: std::mem::size_of::<Vertex>() as wgpu::BufferAddress,
-                    step_mode: wgpu::VertexStepMode::Vertex,
-                    attributes: &[
-                        wgpu::VertexAttribute {
-                            offset: 0,
-                            shader_location: 0,
-                            format: wgpu::VertexFormat::Float32x3,
-                        },
-                        wgpu::VertexAttribute {
-                            offset: 16, // After position (12 bytes) + padding (4 bytes)
-                            shader_location: 1,
-                            format: wgpu::VertexFormat::Float32x2,
-                        },
-                    ],
-                }],
-                compilation_options: Default::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: config.format,
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: Default::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: Some(wgpu::Face::Back),
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            multiview: None,
-            cache: None,
-        });
+        let hud = Hud::new(&device, config.format, config.width, config.height);
+        let spectrum = SpectrumGraph::new(&device, config.format);
 
         // Create skybox uniforms and bind group
-        let skybox_uniforms = SkyboxUniforms {
-            inv_view_proj: Mat4::IDENTITY.to_cols_array_2d(),
-            time: 0.0,
-            _padding: [0.0; 3],
-        };
+        let skybox_uniforms = SkyboxUniforms::new(Mat4::IDENTITY, 0.0, render_config, 0.0);
 
         let skybox_uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Skybox Uniform Buffer"),
@@ -259,19 +546,7 @@ impl RenderSystem {
         });
 
         let skybox_bind_group_layout =
-            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                label: Some("Skybox Bind Group Layout"),
-                entries: &[wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
-                }],
-            });
+            create_uniform_bind_group_layout(&device, "Skybox Bind Group Layout");
 
         let skybox_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Skybox Bind Group"),
@@ -283,164 +558,399 @@ impl RenderSystem {
         });
 
         // Create skybox pipeline
-        let skybox_pipeline_layout =
-            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("Skybox Pipeline Layout"),
-                bind_group_layouts: &[&skybox_bind_group_layout],
-                push_constant_ranges: &[],
-            });
+        let skybox_pipeline = create_skybox_pipeline(
+            &device,
+            scene_format,
+            &skybox_bind_group_layout,
+            sample_count,
+            ReloadableShader::Skybox.builtin_source(),
+        );
 
-        let skybox_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Skybox Pipeline"),
-            layout: Some(&skybox_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &skybox_shader,
-                entry_point: Some("vs_main"),
-                buffers: &[],
-                compilation_options: Default::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &skybox_shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: config.format,
-                    blend: None,
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: Default::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: None,
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            multiview: None,
-            cache: None,
-        });
+        // Depth buffer and MSAA color target (sized with the surface)
+        let depth_view = create_depth_view(&device, config.width, config.height, sample_count);
+        let msaa_view = create_msaa_view(
+            &device,
+            scene_format,
+            config.width,
+            config.height,
+            sample_count,
+        );
 
-        // === GPU Compute Pipeline ===
+        // GPU pass timing (only when the adapter supports timestamp queries)
+        let gpu_timer = GpuTimer::new(&device, &queue);
 
-        let (compute_pipeline, compute_bind_group, terrain_params_buffer) = {
-            use crate::params::TerrainParams;
+        // === GPU Compute Pipeline ===
 
-            // Load compute shader
-            let compute_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-                label: Some("Terrain Compute Shader"),
-                source: wgpu::ShaderSource::Wgsl(include_str!("terrain_compute.wgsl").into()),
-            });
+        let (compute_pipelines, compute_bind_group_layout) =
+            create_terrain_compute_pipelines(&device);
 
-            // Create terrain params uniform buffer
-            let terrain_params_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-                label: Some("Terrain Params Buffer"),
-                size: std::mem::size_of::<TerrainParams>() as u64,
-                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-                mapped_at_creation: false,
-            });
+        // Create terrain params uniform buffer
+        let terrain_params_buffer = create_terrain_params_buffer(&device);
 
-            // Create compute bind group layout
-            let compute_bind_group_layout =
-                device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                    label: Some("Compute Bind Group Layout"),
-                    entries: &[
-                        // Vertex buffer (storage, read-write)
-                        wgpu::BindGroupLayoutEntry {
-                            binding: 0,
-                            visibility: wgpu::ShaderStages::COMPUTE,
-                            ty: wgpu::BindingType::Buffer {
-                                ty: wgpu::BufferBindingType::Storage { read_only: false },
-                                has_dynamic_offset: false,
-                                min_binding_size: None,
-                            },
-                            count: None,
-                        },
-                        // Terrain params (uniform)
-                        wgpu::BindGroupLayoutEntry {
-                            binding: 1,
-                            visibility: wgpu::ShaderStages::COMPUTE,
-                            ty: wgpu::BindingType::Buffer {
-                                ty: wgpu::BufferBindingType::Uniform,
-                                has_dynamic_offset: false,
-                                min_binding_size: None,
-                            },
-                            count: None,
-                        },
-                    ],
-                });
-
-            // Create compute bind group
-            let compute_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some("Compute Bind Group"),
-                layout: &compute_bind_group_layout,
-                entries: &[
-                    wgpu::BindGroupEntry {
-                        binding: 0,
-                        resource: vertex_buffer.as_entire_binding(),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 1,
-                        resource: terrain_params_buffer.as_entire_binding(),
-                    },
-                ],
-            });
+        // Create compute bind group
+        let compute_bind_group = create_terrain_compute_bind_group(
+            &device,
+            &compute_bind_group_layout,
+            &vertex_buffer,
+            &terrain_params_buffer,
+        );
 
-            // Create compute pipeline
-            let compute_pipeline_layout =
-                device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                    label: Some("Compute Pipeline Layout"),
-                    bind_group_layouts: &[&compute_bind_group_layout],
-                    push_constant_ranges: &[],
-                });
-
-            let compute_pipeline =
-                device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
-                    label: Some("Terrain Compute Pipeline"),
-                    layout: Some(&compute_pipeline_layout),
-                    module: &compute_shader,
-                    entry_point: Some("main"),
-                    compilation_options: Default::default(),
-                    cache: None,
-                });
-
-            (compute_pipeline, compute_bind_group, terrain_params_buffer)
-        };
-
-        Ok(Self {
-            surface,
+        Self {
+            target,
             device,
             queue,
-            render_pipeline,
+            ocean_pipelines,
+            lines,
+            thick_lines,
+            wireframe_fallback: None,
+            hud,
+            show_hud: render_config.show_hud,
+            spectrum,
+            show_spectrum: render_config.show_spectrum,
             skybox_pipeline,
             vertex_buffer,
             index_buffer,
+            index_count,
+            grid_size,
             uniform_buffer,
+            uniform_bind_group_layout,
             uniform_bind_group,
             skybox_uniform_buffer,
+            skybox_bind_group_layout,
             skybox_bind_group,
-            recording_config,
-            window_size,
-
-            compute_pipeline,
+            frame_capture: None,
+            recording_texture: None,
+            accumulator: None,
+            screenshot_path: None,
+            surface_config: config,
+            sample_count,
+            depth_view,
+            msaa_view,
+            scene_format,
+            bloom,
+            gpu_timer,
+
+            compute_pipelines,
+            compute_bind_group_layout,
             compute_bind_group,
             terrain_params_buffer,
-        })
+        }
     }
 
-    /// Update ocean vertex buffer with new mesh data
-    pub fn update_vertices(&self, vertices: &[Vertex]) {
-        self.queue
-            .write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(vertices));
+    /// GPU time of the last measured compute and render passes
+    ///
+    /// Results arrive a frame or two late; all zeros when timestamp queries are unsupported.
+    pub fn frame_timings(&self) -> FrameTimings {
+        self.gpu_timer
+            .as_ref()
+            .map_or(FrameTimings::default(), |timer| timer.timings(&self.device))
     }
 
-    /// Update ocean index buffer with new index data
-    pub fn update_indices(&self, indices: &[u32]) {
-        self.queue
-            .write_buffer(&self.index_buffer, 0, bytemuck::cast_slice(indices));
+    /// Current surface size (pixels)
+    pub fn window_size(&self) -> (u32, u32) {
+        (self.surface_config.width, self.surface_config.height)
+    }
+
+    /// Size frames are rendered and captured at: the recording resolution override if set,
+    /// else the window size (use for the camera aspect ratio)
+    pub fn render_size(&self) -> (u32, u32) {
+        match self.recording_texture {
+            Some(ref texture) => (texture.width(), texture.height()),
+            None => self.window_size(),
+        }
+    }
+
+    /// Capture every rendered frame to the recording's PNG sequence or video
+    ///
+    /// With a resolution override, frames are drawn into an offscreen target of that size
+    /// (whatever the window size) and the window shows its top-left corner.
+    pub fn start_recording(&mut self, recording: &RecordingConfig) -> Result<(), String> {
+        recording.validate_resolution()?;
+        if let Some((width, height)) = recording.resolution() {
+            let config = wgpu::SurfaceConfiguration {
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+                width,
+                height,
+                ..self.surface_config.clone()
+            };
+            self.recording_texture = Some(create_offscreen_texture(&self.device, &config));
+            self.resize_targets(width, height);
+        }
+
+        let (width, height) = self.render_size();
+        let sink = match recording.output_format {
+            OutputFormat::PngSequence => FrameSink::PngSequence(recording.frames_dir()),
+            OutputFormat::Mp4 { crf, ref preset } => FrameSink::Video(VideoEncoder::spawn(
+                &recording.silent_video_path(),
+                width,
+                height,
+                recording.fps,
+                crf,
+                preset,
+            )?),
+        };
+        self.frame_capture = Some(
+            FrameCapture::new(sink, self.surface_config.format)
+                .with_every_n(recording.capture_every_n),
+        );
+        Ok(())
+    }
+
+    /// Resize the surface, depth, MSAA and bloom targets (e.g. when the window size changes)
+    ///
+    /// Frame capture follows automatically: staging buffers are sized per captured frame.
+    /// With a recording resolution override only the surface is resized.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            return; // Minimized
+        }
+        self.surface_config.width = width;
+        self.surface_config.height = height;
+        match self.target {
+            RenderTarget::Surface(ref surface) => {
+                surface.configure(&self.device, &self.surface_config)
+            }
+            RenderTarget::Offscreen(ref mut texture) => {
+                *texture = create_offscreen_texture(&self.device, &self.surface_config)
+            }
+        }
+        if self.recording_texture.is_none() {
+            self.resize_targets(width, height);
+        }
+    }
+
+    /// Resize the depth, MSAA, bloom, line and HUD targets to the render size
+    fn resize_targets(&mut self, width: u32, height: u32) {
+        self.depth_view = create_depth_view(&self.device, width, height, self.sample_count);
+        if let Some(ref mut bloom) = self.bloom {
+            bloom.resize(&self.device, width, height);
+        }
+        self.msaa_view = create_msaa_view(
+            &self.device,
+            self.scene_format,
+            width,
+            height,
+            self.sample_count,
+        );
+        if let Some(ref lines) = self.lines {
+            lines.resize(&self.queue, width, height);
+        }
+        self.hud.resize(&self.queue, width, height);
+        if let Some(ref mut accumulator) = self.accumulator {
+            accumulator.resize(&self.device, width, height);
+        }
+    }
+
+    /// Draw the ocean as a wireframe (true) or filled triangles (false)
+    ///
+    /// Without `POLYGON_MODE_LINE` the wireframe is drawn with thick lines where supported
+    /// (with a warning), else the toggle is refused.
+    pub fn set_wireframe(&mut self, wireframe: bool) {
+        let path = wireframe_path(self.device.features(), self.lines.is_some());
+        self.set_wireframe_via(path, wireframe);
+    }
+
+    /// `set_wireframe` on the given path (the device's own outside tests)
+    fn set_wireframe_via(&mut self, path: WireframePath, wireframe: bool) {
+        match path {
+            WireframePath::ThickLines => {
+                if wireframe {
+                    eprintln!(
+                        "Warning: wireframe not supported by this GPU (POLYGON_MODE_LINE), \
+                         drawing thick lines instead"
+                    );
+                    self.wireframe_fallback.get_or_insert(self.thick_lines);
+                    self.thick_lines = true;
+                } else if let Some(thick_lines) = self.wireframe_fallback.take() {
+                    self.thick_lines = thick_lines;
+                }
+            }
+            WireframePath::PolygonLine | WireframePath::Unsupported => {
+                self.ocean_pipelines.set_wireframe(wireframe);
+            }
+        }
+    }
+
+    /// Whether the ocean is currently drawn as a wireframe
+    pub fn wireframe(&self) -> bool {
+        self.ocean_pipelines.wireframe || self.wireframe_fallback.is_some()
+    }
+
+    /// Draw the ocean as thick screen-space lines (ignored with a warning if unsupported)
+    ///
+    /// Turning thick lines off also ends a thick-line wireframe; turning them on during one
+    /// keeps them on after it.
+    pub fn set_thick_lines(&mut self, thick_lines: bool) {
+        if thick_lines && self.lines.is_none() {
+            eprintln!("Warning: thick lines not supported by this GPU (vertex storage buffers)");
+            return;
+        }
+        self.thick_lines = thick_lines;
+        if !thick_lines {
+            self.wireframe_fallback = None;
+        } else if let Some(restore) = self.wireframe_fallback.as_mut() {
+            *restore = true;
+        }
+    }
+
+    /// Whether the ocean is currently drawn as thick lines
+    pub fn thick_lines(&self) -> bool {
+        self.thick_lines
+    }
+
+    /// Show or hide the debug HUD
+    pub fn set_show_hud(&mut self, show_hud: bool) {
+        self.show_hud = show_hud;
+    }
+
+    /// Whether the debug HUD is drawn
+    pub fn show_hud(&self) -> bool {
+        self.show_hud
+    }
+
+    /// Set the values the HUD shows from the next frame on
+    pub fn update_hud(&self, state: &HudState) {
+        self.hud.update(&self.queue, state);
+    }
+
+    /// Show or hide the spectrum bar graph
+    pub fn set_show_spectrum(&mut self, show_spectrum: bool) {
+        self.show_spectrum = show_spectrum;
+    }
+
+    /// Whether the spectrum bar graph is drawn
+    pub fn show_spectrum(&self) -> bool {
+        self.show_spectrum
+    }
+
+    /// Set the FFT bin magnitudes the bar graph shows from the next frame on
+    pub fn update_spectrum(&self, spectrum: &[f32]) {
+        self.spectrum.update(&self.queue, spectrum);
+    }
+
+    /// Rebuild `shader`'s pipelines from `source` (e.g. an edited file, see `ShaderWatcher`)
+    ///
+    /// The source is parsed and validated with naga first, and pipeline creation runs in a
+    /// wgpu error scope; on any error the current pipelines stay in use and the diagnostic
+    /// is returned.
+    pub fn reload_shader(&mut self, shader: ReloadableShader, source: &str) -> Result<(), String> {
+        validate_wgsl(source)?;
+
+        // A valid module can still fail pipeline creation (e.g. a renamed entry point)
+        self.device.push_error_scope(wgpu::ErrorFilter::Validation);
+        match shader {
+            ReloadableShader::Ocean => {
+                let mut pipelines = OceanPipelines::new(
+                    &self.device,
+                    self.scene_format,
+                    &self.uniform_bind_group_layout,
+                    self.sample_count,
+                    self.ocean_pipelines.cull_mode,
+                    source,
+                );
+                pipelines.wireframe = self.ocean_pipelines.wireframe;
+                pop_error_scope(&self.device)?;
+                self.ocean_pipelines = pipelines;
+            }
+            ReloadableShader::Skybox => {
+                let pipeline = create_skybox_pipeline(
+                    &self.device,
+                    self.scene_format,
+                    &self.skybox_bind_group_layout,
+                    self.sample_count,
+                    source,
+                );
+                pop_error_scope(&self.device)?;
+                self.skybox_pipeline = pipeline;
+            }
+        }
+        Ok(())
+    }
+
+    /// Polygon mode the ocean is currently drawn with
+    pub fn polygon_mode(&self) -> wgpu::PolygonMode {
+        self.ocean_pipelines.polygon_mode()
+    }
+
+    /// Faces the ocean pipelines cull (`RenderConfig::cull_mode` at startup)
+    pub fn cull_mode(&self) -> Option<wgpu::Face> {
+        self.ocean_pipelines.cull_mode()
+    }
+
+    /// Update bloom threshold/intensity/radius and exposure (no-op when bloom is disabled)
+    pub fn update_bloom(&self, render_config: &RenderConfig, audio_bands: &AudioBands) {
+        if let Some(ref bloom) = self.bloom {
+            bloom.update_uniforms(&self.queue, render_config, audio_bands);
+        }
+    }
+
+    /// Save a frame to `path` as PNG
+    ///
+    /// Headless systems save the last rendered frame immediately; with a window the next
+    /// presented frame is saved (errors are then reported on stderr).
+    pub fn capture_current_frame(&mut self, path: &Path) -> Result<(), String> {
+        if let Some(ref texture) = self.recording_texture {
+            return save_texture_png(&self.device, &self.queue, texture, path);
+        }
+        match self.target {
+            RenderTarget::Offscreen(ref texture) => {
+                save_texture_png(&self.device, &self.queue, texture, path)
+            }
+            RenderTarget::Surface(_) => {
+                if !self
+                    .surface_config
+                    .usage
+                    .contains(wgpu::TextureUsages::COPY_SRC)
+                {
+                    return Err("Surface does not support frame readback".to_string());
+                }
+                self.screenshot_path = Some(path.to_path_buf());
+                Ok(())
+            }
+        }
+    }
+
+    /// Wait for outstanding captured frames and write them out (call before exiting)
+    pub fn flush_capture(&mut self) -> Result<(), String> {
+        match self.frame_capture {
+            Some(ref mut frame_capture) => frame_capture.flush(&self.device),
+            None => Ok(()),
+        }
+    }
+
+    /// Recreate ocean vertex/index buffers for a new grid (e.g. after a grid size change)
+    pub fn recreate_ocean_buffers(&mut self, grid_size: u32) {
+        let (vertex_buffer, index_buffer, index_count) =
+            create_ocean_buffers(&self.device, grid_size);
+        self.compute_bind_group = create_terrain_compute_bind_group(
+            &self.device,
+            &self.compute_bind_group_layout,
+            &vertex_buffer,
+            &self.terrain_params_buffer,
+        );
+        if let Some(ref mut lines) = self.lines {
+            lines.set_vertex_buffer(&self.device, &vertex_buffer, grid_size);
+        }
+        self.vertex_buffer = vertex_buffer;
+        self.index_buffer = index_buffer;
+        self.index_count = index_count;
+        self.grid_size = grid_size;
+    }
+
+    /// Vertices per side of the current ocean buffers (see `recreate_ocean_buffers`)
+    pub fn grid_size(&self) -> u32 {
+        self.grid_size
+    }
+
+    /// Indices covering the whole terrain lattice (pass to `render`)
+    pub fn index_count(&self) -> u32 {
+        self.index_count
+    }
+
+    /// Read the terrain vertices back from the GPU (blocking; for tests and debugging)
+    pub fn read_ocean_vertices(&self) -> Result<Vec<Vertex>, String> {
+        let bytes = read_buffer(&self.device, &self.queue, &self.vertex_buffer)?;
+        Ok(bytemuck::cast_slice::<u8, Vertex>(&bytes).to_vec())
     }
 
     /// Update ocean uniforms
@@ -459,7 +969,7 @@ impl RenderSystem {
     }
 
     /// Dispatch GPU compute shader to generate terrain
-    pub fn dispatch_terrain_compute(&self, params: &crate::params::TerrainParams, grid_size: u32) {
+    pub fn dispatch_terrain_compute(&self, params: &TerrainParams, grid_size: u32) {
         // Update terrain params uniform
         self.queue.write_buffer(
             &self.terrain_params_buffer,
@@ -477,158 +987,2813 @@ impl RenderSystem {
         {
             let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                 label: Some("Terrain Compute Pass"),
-                timestamp_writes: None,
+                timestamp_writes: self.gpu_timer.as_ref().map(GpuTimer::compute_writes),
             });
 
-            compute_pass.set_pipeline(&self.compute_pipeline);
             compute_pass.set_bind_group(0, &self.compute_bind_group, &[]);
+            self.compute_pipelines
+                .dispatch(&mut compute_pass, grid_size);
+        }
 
-            // Dispatch compute shader (workgroup_size = 256)
-            let vertex_count = grid_size * grid_size;
-            let workgroup_count = (vertex_count + 255) / 256;
-            compute_pass.dispatch_workgroups(workgroup_count, 1, 1);
+        if let Some(ref timer) = self.gpu_timer {
+            timer.resolve_compute(&mut encoder);
         }
 
         self.queue.submit(std::iter::once(encoder.finish()));
     }
 
     /// Render a frame (and optionally capture if recording)
-    pub fn render(&self, frame_num: usize, index_count: u32) -> Result<(), wgpu::SurfaceError> {
-        let output = self.surface.get_current_texture()?;
-        let view = output
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
+    ///
+    /// When headless, or recording at a resolution override, the frame is drawn into an
+    /// offscreen texture instead.
+    pub fn render(&mut self, frame_num: usize, index_count: u32) -> Result<(), wgpu::SurfaceError> {
+        self.render_with(frame_num, |render_system, texture| {
+            render_system.draw_frame(texture, index_count)
+        })
+    }
+
+    /// Draw the current uniforms and terrain as sub-frame `subframe` of `subframes`
+    ///
+    /// Sub-frames are averaged into a motion-blurred frame; present it with
+    /// `render_accumulated` after the last one.
+    pub fn accumulate_subframe(&mut self, index_count: u32, subframe: u32, subframes: u32) {
+        if self.accumulator.is_none() {
+            let (width, height) = self.render_size();
+            self.accumulator = Some(Accumulator::new(
+                &self.device,
+                self.surface_config.format,
+                width,
+                height,
+            ));
+        }
+        let Some(ref accumulator) = self.accumulator else {
+            return;
+        };
+
+        self.draw_frame(accumulator.frame_texture(), index_count);
 
         let mut encoder = self
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                label: Some("Render Encoder"),
+                label: Some("Accumulate Encoder"),
             });
+        accumulator.accumulate(&mut encoder, 1.0 / subframes.max(1) as f64, subframe == 0);
+        self.queue.submit(std::iter::once(encoder.finish()));
+    }
 
-        {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Render Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: None,
-                timestamp_writes: None,
-                occlusion_query_set: None,
+    /// Present (and capture if recording) the average of the accumulated sub-frames
+    pub fn render_accumulated(&mut self, frame_num: usize) -> Result<(), wgpu::SurfaceError> {
+        self.render_with(frame_num, Self::resolve_accumulated)
+    }
+
+    /// Write the accumulated average into `texture` and submit
+    fn resolve_accumulated(&self, texture: &wgpu::Texture) {
+        let Some(ref accumulator) = self.accumulator else {
+            return;
+        };
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Accumulate Resolve Encoder"),
             });
+        accumulator.resolve(&mut encoder, &view);
+        self.queue.submit(std::iter::once(encoder.finish()));
+    }
 
-            // Render skybox first
-            render_pass.set_pipeline(&self.skybox_pipeline);
-            render_pass.set_bind_group(0, &self.skybox_bind_group, &[]);
-            render_pass.draw(0..3, 0..1); // Fullscreen triangle
+    /// Fill the frame's target texture with `draw`, then screenshot, capture and present it
+    fn render_with(
+        &mut self,
+        frame_num: usize,
+        draw: impl FnOnce(&Self, &wgpu::Texture),
+    ) -> Result<(), wgpu::SurfaceError> {
+        let output = match (&self.target, &self.recording_texture) {
+            (RenderTarget::Surface(ref surface), None) => {
+                Some(self.acquire_surface_texture(surface)?)
+            }
+            _ => None,
+        };
+        let texture = match (&output, &self.recording_texture, &self.target) {
+            (Some(output), _, _) => &output.texture,
+            (None, Some(texture), _) | (None, None, RenderTarget::Offscreen(texture)) => texture,
+            (None, None, RenderTarget::Surface(_)) => unreachable!("surface texture acquired"),
+        };
 
-            // Render ocean
-            render_pass.set_pipeline(&self.render_pipeline);
-            render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
-            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
-            render_pass.draw_indexed(0..index_count, 0, 0..1);
-        }
+        draw(self, texture);
 
-        self.queue.submit(std::iter::once(encoder.finish()));
+        if let Some(path) = self.screenshot_path.take() {
+            match save_texture_png(&self.device, &self.queue, texture, &path) {
+                Ok(()) => println!("Saved screenshot {}", path.display()),
+                Err(e) => eprintln!("Screenshot failed: {}", e),
+            }
+        }
 
         // Capture frame if recording
-        if let Some(ref config) = self.recording_config {
-            self.capture_frame(frame_num, config, &output);
+        if let Some(ref mut frame_capture) = self.frame_capture {
+            frame_capture.capture(&self.device, &self.queue, texture, frame_num);
         }
 
-        output.present();
+        match output {
+            Some(output) => output.present(),
+            None => self.present_preview()?,
+        }
 
         Ok(())
     }
 
-    /// Capture a frame to disk (recording mode only)
-    fn capture_frame(
+    /// Next texture of `surface`, reconfigured at the current size and retried once if lost
+    fn acquire_surface_texture(
         &self,
-        frame_num: usize,
-        config: &RecordingConfig,
-        texture: &wgpu::SurfaceTexture,
-    ) {
-        let (width, height) = self.window_size;
-        let bytes_per_pixel = 4; // RGBA8
-        let unpadded_bytes_per_row = width * bytes_per_pixel;
-        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
-        let padded_bytes_per_row = (unpadded_bytes_per_row + align - 1) / align * align;
-
-        // Create buffer to read texture data
-        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Frame Capture Buffer"),
-            size: (padded_bytes_per_row * height) as u64,
-            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
-            mapped_at_creation: false,
-        });
+        surface: &wgpu::Surface,
+    ) -> Result<wgpu::SurfaceTexture, wgpu::SurfaceError> {
+        retry_on_surface_loss(
+            || surface.get_current_texture(),
+            &self.surface_config,
+            |config| surface.configure(&self.device, config),
+        )
+    }
+
+    /// Show the top-left corner of the recording texture in the window
+    ///
+    /// No-op when headless, without a recording override, or when the surface can't be
+    /// copied into.
+    fn present_preview(&self) -> Result<(), wgpu::SurfaceError> {
+        let (RenderTarget::Surface(ref surface), Some(ref texture)) =
+            (&self.target, &self.recording_texture)
+        else {
+            return Ok(());
+        };
+        if !self
+            .surface_config
+            .usage
+            .contains(wgpu::TextureUsages::COPY_DST)
+        {
+            return Ok(());
+        }
+
+        let output = self.acquire_surface_texture(surface)?;
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Recording Preview Encoder"),
+            });
+        encoder.copy_texture_to_texture(
+            texture.as_image_copy(),
+            output.texture.as_image_copy(),
+            wgpu::Extent3d {
+                width: texture.width().min(output.texture.width()),
+                height: texture.height().min(output.texture.height()),
+                depth_or_array_layers: 1,
+            },
+        );
+        self.queue.submit(std::iter::once(encoder.finish()));
+        output.present();
+        Ok(())
+    }
+
+    /// Render one frame offscreen and read it back (headless systems only)
+    pub fn render_to_image(&self, frame_params: &FrameParams) -> Result<image::RgbaImage, String> {
+        let RenderTarget::Offscreen(ref offscreen) = self.target else {
+            return Err("render_to_image requires a headless RenderSystem".to_string());
+        };
+        let texture = self.recording_texture.as_ref().unwrap_or(offscreen);
+
+        self.update_uniforms(&frame_params.uniforms);
+        self.update_skybox_uniforms(&frame_params.skybox_uniforms);
+        self.draw_frame(texture, frame_params.index_count);
+
+        let (width, height) = self.render_size();
+        capture_texture_to_image(&self.device, &self.queue, texture, width, height)
+    }
+
+    /// Render `subframes` offscreen, averaged like a motion-blurred recording frame, and read
+    /// the result back (headless systems only)
+    pub fn render_subframes_to_image(
+        &mut self,
+        subframes: &[FrameParams],
+    ) -> Result<image::RgbaImage, String> {
+        if !matches!(self.target, RenderTarget::Offscreen(_)) {
+            return Err("render_subframes_to_image requires a headless RenderSystem".to_string());
+        }
+        if subframes.is_empty() {
+            return Err("render_subframes_to_image needs at least one sub-frame".to_string());
+        }
+
+        let count = subframes.len() as u32;
+        for (i, frame_params) in subframes.iter().enumerate() {
+            self.update_uniforms(&frame_params.uniforms);
+            self.update_skybox_uniforms(&frame_params.skybox_uniforms);
+            self.accumulate_subframe(frame_params.index_count, i as u32, count);
+        }
+
+        let RenderTarget::Offscreen(ref offscreen) = self.target else {
+            unreachable!("checked above");
+        };
+        let texture = self.recording_texture.as_ref().unwrap_or(offscreen);
+        self.resolve_accumulated(texture);
+
+        let (width, height) = self.render_size();
+        capture_texture_to_image(&self.device, &self.queue, texture, width, height)
+    }
+
+    /// Draw the skybox and ocean (plus bloom and HUD) into `texture` and submit
+    fn draw_frame(&self, texture: &wgpu::Texture, index_count: u32) {
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
 
-        // Copy texture to buffer
         let mut encoder = self
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                label: Some("Frame Capture Encoder"),
+                label: Some("Render Encoder"),
+            });
+
+        // Scene goes straight to the target, or into the bloom HDR target
+        let scene_view = self.bloom.as_ref().map_or(&view, Bloom::scene_view);
+
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    // With MSAA, render multisampled and resolve into the scene target
+                    view: self.msaa_view.as_ref().unwrap_or(scene_view),
+                    resolve_target: self.msaa_view.as_ref().map(|_| scene_view),
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &self.depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Discard,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: self.gpu_timer.as_ref().map(GpuTimer::render_writes),
+                occlusion_query_set: None,
             });
 
-        encoder.copy_texture_to_buffer(
-            wgpu::ImageCopyTexture {
-                texture: &texture.texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
+            // Render skybox first
+            render_pass.set_pipeline(&self.skybox_pipeline);
+            render_pass.set_bind_group(0, &self.skybox_bind_group, &[]);
+            render_pass.draw(0..3, 0..1); // Fullscreen triangle
+
+            // Render ocean
+            match self.lines {
+                Some(ref lines) if self.thick_lines => {
+                    lines.draw(&mut render_pass, &self.uniform_bind_group)
+                }
+                _ => {
+                    render_pass.set_pipeline(self.ocean_pipelines.current());
+                    render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+                    render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+                    render_pass
+                        .set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                    render_pass.draw_indexed(0..index_count, 0, 0..1);
+                }
+            }
+        }
+
+        if let Some(ref timer) = self.gpu_timer {
+            timer.resolve_render(&self.device, &mut encoder);
+        }
+
+        if let Some(ref bloom) = self.bloom {
+            bloom.apply(&mut encoder, &view);
+        }
+
+        if self.show_spectrum {
+            self.spectrum.draw(&mut encoder, &view);
+        }
+        if self.show_hud {
+            self.hud.draw(&mut encoder, &view);
+        }
+
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        if let Some(ref timer) = self.gpu_timer {
+            timer.map_readback();
+        }
+    }
+}
+
+/// Request a high-performance adapter and device (with line polygon mode when available)
+///
+/// `compatible_surface` is None for headless rendering.
+async fn request_device(
+    instance: &wgpu::Instance,
+    compatible_surface: Option<&wgpu::Surface<'_>>,
+) -> Result<(wgpu::Adapter, wgpu::Device, wgpu::Queue), String> {
+    let adapter = instance
+        .request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            compatible_surface,
+            force_fallback_adapter: false,
+        })
+        .await
+        .ok_or("Failed to find suitable GPU adapter")?;
+
+    // Line polygon mode enables the wireframe toggle, timestamp queries the pass timings
+    let required_features =
+        adapter.features() & (wgpu::Features::POLYGON_MODE_LINE | wgpu::Features::TIMESTAMP_QUERY);
+    let (device, queue) = adapter
+        .request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some("Main Device"),
+                required_features,
+                required_limits: wgpu::Limits::default(),
+                memory_hints: Default::default(),
+            },
+            None,
+        )
+        .await
+        .map_err(|e| format!("Failed to request device: {}", e))?;
+
+    Ok((adapter, device, queue))
+}
+
+/// Run `acquire`; on a lost or outdated surface, `reconfigure` with `config` (the current
+/// surface configuration) and try once more
+///
+/// Other errors (timeout, out of memory) are returned as-is for the caller to handle.
+fn retry_on_surface_loss<T>(
+    mut acquire: impl FnMut() -> Result<T, wgpu::SurfaceError>,
+    config: &wgpu::SurfaceConfiguration,
+    reconfigure: impl FnOnce(&wgpu::SurfaceConfiguration),
+) -> Result<T, wgpu::SurfaceError> {
+    match acquire() {
+        Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
+            reconfigure(config);
+            acquire()
+        }
+        result => result,
+    }
+}
+
+/// Create the headless color target described by `config` (size, format, usage)
+fn create_offscreen_texture(
+    device: &wgpu::Device,
+    config: &wgpu::SurfaceConfiguration,
+) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Offscreen Color Target"),
+        size: wgpu::Extent3d {
+            width: config.width,
+            height: config.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: config.format,
+        usage: config.usage,
+        view_formats: &[],
+    })
+}
+
+/// Bind group layout with a single uniform buffer at binding 0 (vertex + fragment)
+fn create_uniform_bind_group_layout(device: &wgpu::Device, label: &str) -> wgpu::BindGroupLayout {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some(label),
+        entries: &[wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
             },
-            wgpu::ImageCopyBuffer {
-                buffer: &buffer,
-                layout: wgpu::ImageDataLayout {
-                    offset: 0,
-                    bytes_per_row: Some(padded_bytes_per_row),
-                    rows_per_image: Some(height),
+            count: None,
+        }],
+    })
+}
+
+/// Errors raised since the matching `push_error_scope`, as a message
+fn pop_error_scope(device: &wgpu::Device) -> Result<(), String> {
+    match pollster::block_on(device.pop_error_scope()) {
+        Some(error) => Err(error.to_string()),
+        None => Ok(()),
+    }
+}
+
+/// How the wireframe toggle draws the ocean on a device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WireframePath {
+    /// Rasterizer line mode (`OceanPipelines::line`)
+    PolygonLine,
+    /// Quad-expanded screen-space lines (`LineRenderer`)
+    ThickLines,
+    /// Neither available: the ocean stays filled
+    Unsupported,
+}
+
+/// Wireframe path for a device with `features`, falling back to thick lines if supported
+fn wireframe_path(features: wgpu::Features, thick_lines_supported: bool) -> WireframePath {
+    if features.contains(wgpu::Features::POLYGON_MODE_LINE) {
+        WireframePath::PolygonLine
+    } else if thick_lines_supported {
+        WireframePath::ThickLines
+    } else {
+        WireframePath::Unsupported
+    }
+}
+
+/// wgpu face culling for a configured `CullMode`
+fn cull_face(mode: CullMode) -> Option<wgpu::Face> {
+    match mode {
+        CullMode::None => None,
+        CullMode::Back => Some(wgpu::Face::Back),
+        CullMode::Front => Some(wgpu::Face::Front),
+    }
+}
+
+/// Create the ocean render pipeline (depth-tested, alpha-blended) from `shader.wgsl` source
+///
+/// `PolygonMode::Line` requires `Features::POLYGON_MODE_LINE` on the device.
+fn create_ocean_pipeline(
+    device: &wgpu::Device,
+    color_format: wgpu::TextureFormat,
+    uniform_bind_group_layout: &wgpu::BindGroupLayout,
+    sample_count: u32,
+    polygon_mode: wgpu::PolygonMode,
+    cull_mode: Option<wgpu::Face>,
+    source: &str,
+) -> wgpu::RenderPipeline {
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Ocean Shader"),
+        source: wgpu::ShaderSource::Wgsl(source.into()),
+    });
+
+    let render_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Render Pipeline Layout"),
+        bind_group_layouts: &[uniform_bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Ocean Render Pipeline"),
+        layout: Some(&render_pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: Some("vs_main"),
+            buffers: &[wgpu::VertexBufferLayout {
+                array_stride: std::mem::size_of::<Vertex>() as wgpu::BufferAddress,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &[
+                    wgpu::VertexAttribute {
+                        offset: 0,
+                        shader_location: 0,
+                        format: wgpu::VertexFormat::Float32x3,
+                    },
+                    wgpu::VertexAttribute {
+                        offset: 12, // base_height, in position's 16-byte slot
+                        shader_location: 4,
+                        format: wgpu::VertexFormat::Float32,
+                    },
+                    wgpu::VertexAttribute {
+                        offset: 16, // After position (12 bytes) + base_height (4 bytes)
+                        shader_location: 1,
+                        format: wgpu::VertexFormat::Float32x2,
+                    },
+                    wgpu::VertexAttribute {
+                        offset: 32, // After uv (8 bytes) + padding (8 bytes)
+                        shader_location: 2,
+                        format: wgpu::VertexFormat::Float32x3,
+                    },
+                    wgpu::VertexAttribute {
+                        offset: 44, // After normal (12 bytes)
+                        shader_location: 3,
+                        format: wgpu::VertexFormat::Float32,
+                    },
+                ],
+            }],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: Some("fs_main"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format: color_format,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode,
+            polygon_mode,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: DEPTH_FORMAT,
+            depth_write_enabled: true,
+            depth_compare: wgpu::CompareFunction::LessEqual,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache: None,
+    })
+}
+
+/// Create the skybox pipeline (fullscreen triangle at the far plane, no depth writes)
+/// from `skybox.wgsl` source
+fn create_skybox_pipeline(
+    device: &wgpu::Device,
+    color_format: wgpu::TextureFormat,
+    skybox_bind_group_layout: &wgpu::BindGroupLayout,
+    sample_count: u32,
+    source: &str,
+) -> wgpu::RenderPipeline {
+    let skybox_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Skybox Shader"),
+        source: wgpu::ShaderSource::Wgsl(source.into()),
+    });
+
+    let skybox_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Skybox Pipeline Layout"),
+        bind_group_layouts: &[skybox_bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Skybox Pipeline"),
+        layout: Some(&skybox_pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &skybox_shader,
+            entry_point: Some("vs_main"),
+            buffers: &[],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &skybox_shader,
+            entry_point: Some("fs_main"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format: color_format,
+                blend: None,
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: DEPTH_FORMAT,
+            depth_write_enabled: false, // Background: never occludes the ocean
+            depth_compare: wgpu::CompareFunction::LessEqual,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache: None,
+    })
+}
+
+/// Create a depth texture view matching the render target size and sample count
+fn create_depth_view(
+    device: &wgpu::Device,
+    width: u32,
+    height: u32,
+    sample_count: u32,
+) -> wgpu::TextureView {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Depth Texture"),
+        size: wgpu::Extent3d {
+            width: width.max(1),
+            height: height.max(1),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count,
+        dimension: wgpu::TextureDimension::D2,
+        format: DEPTH_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+
+    texture.create_view(&wgpu::TextureViewDescriptor::default())
+}
+
+/// Create the multisampled color target (None when `sample_count` is 1)
+fn create_msaa_view(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    width: u32,
+    height: u32,
+    sample_count: u32,
+) -> Option<wgpu::TextureView> {
+    if sample_count <= 1 {
+        return None;
+    }
+
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("MSAA Color Texture"),
+        size: wgpu::Extent3d {
+            width: width.max(1),
+            height: height.max(1),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+
+    Some(texture.create_view(&wgpu::TextureViewDescriptor::default()))
+}
+
+/// Create the terrain vertex buffer (shared by compute and render) and its index buffer
+///
+/// Returns (vertex buffer, index buffer, index count) for a `grid_size`² vertex lattice.
+fn create_ocean_buffers(
+    device: &wgpu::Device,
+    grid_size: u32,
+) -> (wgpu::Buffer, wgpu::Buffer, u32) {
+    // Flat lattice with up normals; the compute shader only rewrites position and UV
+    let vertex_count = (grid_size * grid_size) as usize;
+    let flat = Vertex {
+        position: [0.0; 3],
+        base_height: 0.0,
+        uv: [0.0; 2],
+        _padding2: [0.0; 2],
+        normal: [0.0, 1.0, 0.0],
+        foam: 0.0,
+    };
+    let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Vertex Buffer"),
+        contents: bytemuck::cast_slice(&vec![flat; vertex_count]),
+        usage: wgpu::BufferUsages::STORAGE  // Terrain compute writes vertices here
+            | wgpu::BufferUsages::VERTEX    // ...and the ocean pass draws them directly
+            | wgpu::BufferUsages::COPY_SRC, // Readback (tests, physics)
+    });
+
+    let indices = terrain_lattice_indices(grid_size);
+    let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Index Buffer"),
+        contents: bytemuck::cast_slice(&indices),
+        usage: wgpu::BufferUsages::INDEX,
+    });
+
+    (vertex_buffer, index_buffer, indices.len() as u32)
+}
+
+/// Triangle indices for the compute shader's `grid_size`×`grid_size` vertex lattice
+///
+/// Row-major with stride `grid_size`, counter-clockwise winding (as `OceanGrid`).
+fn terrain_lattice_indices(grid_size: u32) -> Vec<u32> {
+    let quads = grid_size.saturating_sub(1);
+    let mut indices = Vec::with_capacity((quads * quads * 6) as usize);
+    for z in 0..quads {
+        for x in 0..quads {
+            let top_left = z * grid_size + x;
+            let top_right = top_left + 1;
+            let bottom_left = top_left + grid_size;
+            let bottom_right = bottom_left + 1;
+
+            indices.extend_from_slice(&[
+                top_left,
+                bottom_left,
+                top_right,
+                top_right,
+                bottom_left,
+                bottom_right,
+            ]);
+        }
+    }
+    indices
+}
+
+/// Terrain compute kernels: heights and UVs, then normals from the written heights
+struct TerrainComputePipelines {
+    heights: wgpu::ComputePipeline,
+    normals: wgpu::ComputePipeline,
+}
+
+impl TerrainComputePipelines {
+    /// Fill a `grid_size`² vertex buffer (bound at group 0) in two dispatches
+    ///
+    /// wgpu orders the dispatches, so every normal sees its neighbors' finished heights.
+    fn dispatch(&self, compute_pass: &mut wgpu::ComputePass, grid_size: u32) {
+        // workgroup_size = 256
+        let workgroup_count = (grid_size * grid_size).div_ceil(256);
+        for pipeline in [&self.heights, &self.normals] {
+            compute_pass.set_pipeline(pipeline);
+            compute_pass.dispatch_workgroups(workgroup_count, 1, 1);
+        }
+    }
+}
+
+/// Create the terrain compute pipelines and their bind group layout
+///
+/// Binding 0: vertex storage buffer (read-write), binding 1: `TerrainParams` uniform.
+fn create_terrain_compute_pipelines(
+    device: &wgpu::Device,
+) -> (TerrainComputePipelines, wgpu::BindGroupLayout) {
+    // Load compute shader
+    let compute_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Terrain Compute Shader"),
+        source: wgpu::ShaderSource::Wgsl(include_str!("terrain_compute.wgsl").into()),
+    });
+
+    // Create compute bind group layout
+    let compute_bind_group_layout =
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Compute Bind Group Layout"),
+            entries: &[
+                // Vertex buffer (storage, read-write)
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // Terrain params (uniform)
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
                 },
+            ],
+        });
+
+    // Create compute pipeline
+    let compute_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Compute Pipeline Layout"),
+        bind_group_layouts: &[&compute_bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    let create = |label, entry_point| {
+        device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some(label),
+            layout: Some(&compute_pipeline_layout),
+            module: &compute_shader,
+            entry_point: Some(entry_point),
+            compilation_options: Default::default(),
+            cache: None,
+        })
+    };
+    let pipelines = TerrainComputePipelines {
+        heights: create("Terrain Compute Pipeline", "main"),
+        normals: create("Terrain Normals Compute Pipeline", "compute_normals"),
+    };
+
+    (pipelines, compute_bind_group_layout)
+}
+
+/// Bind a vertex storage buffer and params uniform to the terrain compute layout
+fn create_terrain_compute_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    vertex_buffer: &wgpu::Buffer,
+    terrain_params_buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Compute Bind Group"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: vertex_buffer.as_entire_binding(),
             },
-            wgpu::Extent3d {
-                width,
-                height,
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: terrain_params_buffer.as_entire_binding(),
+            },
+        ],
+    })
+}
+
+/// Create the uniform buffer holding `TerrainParams`
+fn create_terrain_params_buffer(device: &wgpu::Device) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Terrain Params Buffer"),
+        size: std::mem::size_of::<TerrainParams>() as u64,
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+/// Run the terrain compute shader once and read the generated vertices back
+///
+/// Same dispatch as `RenderSystem::dispatch_terrain_compute`, but into a standalone storage
+/// buffer of `grid_size²` vertices that is copied to a mappable buffer. Blocks until done.
+pub fn read_back_terrain_compute(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    params: &TerrainParams,
+) -> Result<Vec<Vertex>, String> {
+    let vertex_count = params.grid_size * params.grid_size;
+    let buffer_size = (vertex_count as usize * std::mem::size_of::<Vertex>()) as u64;
+    if buffer_size == 0 {
+        return Err("Terrain grid is empty".to_string());
+    }
+
+    let (compute_pipelines, compute_bind_group_layout) = create_terrain_compute_pipelines(device);
+    let terrain_params_buffer = create_terrain_params_buffer(device);
+    queue.write_buffer(&terrain_params_buffer, 0, bytemuck::cast_slice(&[*params]));
+
+    let storage_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Terrain Readback Storage Buffer"),
+        size: buffer_size,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    });
+    let compute_bind_group = create_terrain_compute_bind_group(
+        device,
+        &compute_bind_group_layout,
+        &storage_buffer,
+        &terrain_params_buffer,
+    );
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Terrain Readback Encoder"),
+    });
+
+    {
+        let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Terrain Readback Compute Pass"),
+            timestamp_writes: None,
+        });
+
+        compute_pass.set_bind_group(0, &compute_bind_group, &[]);
+        compute_pipelines.dispatch(&mut compute_pass, params.grid_size);
+    }
+
+    queue.submit(std::iter::once(encoder.finish()));
+
+    let bytes = read_buffer(device, queue, &storage_buffer)?;
+    Ok(bytemuck::cast_slice::<u8, Vertex>(&bytes).to_vec())
+}
+
+/// Copy a `COPY_SRC` buffer into a staging buffer and read it back (blocking)
+fn read_buffer(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    buffer: &wgpu::Buffer,
+) -> Result<Vec<u8>, String> {
+    let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Readback Buffer"),
+        size: buffer.size(),
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Readback Encoder"),
+    });
+    encoder.copy_buffer_to_buffer(buffer, 0, &readback_buffer, 0, buffer.size());
+    queue.submit(std::iter::once(encoder.finish()));
+
+    // Map buffer and copy bytes out
+    let buffer_slice = readback_buffer.slice(..);
+    let (sender, receiver) = std::sync::mpsc::channel();
+    buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+    device.poll(wgpu::Maintain::Wait);
+
+    receiver
+        .recv()
+        .map_err(|e| format!("Readback callback dropped: {}", e))?
+        .map_err(|e| format!("Failed to map readback buffer: {}", e))?;
+
+    let bytes = buffer_slice.get_mapped_range().to_vec();
+    readback_buffer.unmap();
+
+    Ok(bytes)
+}
+
+/// Max absolute height difference between GPU and CPU terrain (meters)
+///
+/// Generates terrain with the compute shader, then builds an `OceanGrid` from the same
+/// parameters (same noise kind and octaves, no Gerstner waves, `params.time` as detail time,
+/// swell reaching `params.swell_offset` at `params.time`) and compares every GPU vertex that shares its world XZ with a CPU vertex. Both grids cover the
+/// world lattice points within half an extent of the camera, so this errors only if none match.
+pub fn terrain_parity_max_height_diff(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    params: &TerrainParams,
+) -> Result<f32, String> {
+    let gpu_vertices = read_back_terrain_compute(device, queue, params)?;
+    let noise_kind = [NoiseKind::Perlin, NoiseKind::Simplex, NoiseKind::Fbm]
+        .into_iter()
+        .find(|kind| kind.shader_id() == params.noise_kind)
+        .unwrap_or(NoiseKind::Fbm); // The shader's default branch
+    let swell = glam::Vec2::from_array(params.swell_offset);
+
+    let physics = OceanPhysics {
+        grid_size: params.grid_size as usize,
+        grid_spacing_m: params.grid_spacing,
+        wave_speed: 1.0, // params.time is already scaled by wave speed
+        base_terrain_amplitude_m: params.base_amplitude,
+        base_terrain_frequency: params.base_frequency,
+        base_terrain_octaves: params.base_octaves,
+        detail_amplitude_m: params.detail_amplitude,
+        detail_frequency: params.detail_frequency,
+        detail_octaves: params.detail_octaves,
+        noise_lacunarity: params.noise_lacunarity,
+        noise_gain: params.noise_gain,
+        noise_seed: params.noise_seed,
+        noise_kind,
+        swell_direction: params.swell_offset,
+        swell_speed_m_per_s: if params.time != 0.0 {
+            swell.length() / params.time
+        } else {
+            0.0
+        },
+        wave_model: WaveModel::Noise,
+        ..OceanPhysics::default()
+    };
+    let mut grid = OceanGrid::new(&physics);
+    grid.update(
+        params.time,
+        params.detail_amplitude,
+        params.detail_frequency,
+        glam::Vec3::from_array(params.camera_pos),
+        &physics,
+    );
+
+    // CPU positions are camera-relative; GPU grid starts at its first (lowest XZ) vertex
+    let grid_size = params.grid_size as i64;
+    let gpu_origin = gpu_vertices[0].position;
+    let gpu_index = |world: f32, origin: f32| -> Option<i64> {
+        let cell = (world - origin) / params.grid_spacing;
+        let index = cell.round();
+        ((cell - index).abs() < 1e-2 && (0..grid_size).contains(&(index as i64)))
+            .then_some(index as i64)
+    };
+
+    let mut max_diff: Option<f32> = None;
+    for vertex in &grid.vertices {
+        let (Some(x), Some(z)) = (
+            gpu_index(params.camera_pos[0] + vertex.position[0], gpu_origin[0]),
+            gpu_index(params.camera_pos[2] + vertex.position[2], gpu_origin[2]),
+        ) else {
+            continue;
+        };
+        let gpu_height = gpu_vertices[(z * grid_size + x) as usize].position[1];
+        let diff = (gpu_height - vertex.position[1]).abs();
+        max_diff = Some(max_diff.map_or(diff, |m| m.max(diff)));
+    }
+
+    max_diff.ok_or_else(|| "No shared vertices between GPU and CPU terrain".to_string())
+}
+
+/// Shader interface checks that don't need a GPU adapter
+#[cfg(test)]
+mod shader_tests {
+    use super::*;
+    use crate::params::SkyConfig;
+
+    /// Parse and validate WGSL the same way wgpu does at pipeline creation
+    fn validate_wgsl(name: &str, source: &str) {
+        super::validate_wgsl(source)
+            .unwrap_or_else(|e| panic!("{} failed to validate:\n{}", name, e));
+    }
+
+    #[test]
+    fn test_shaders_validate() {
+        validate_wgsl("shader.wgsl", include_str!("shader.wgsl"));
+        validate_wgsl("skybox.wgsl", include_str!("skybox.wgsl"));
+        validate_wgsl("bloom.wgsl", include_str!("bloom.wgsl"));
+        validate_wgsl("terrain_compute.wgsl", include_str!("terrain_compute.wgsl"));
+        validate_wgsl("lines.wgsl", include_str!("lines.wgsl"));
+        validate_wgsl("hud.wgsl", include_str!("hud.wgsl"));
+        validate_wgsl("accumulate.wgsl", include_str!("accumulate.wgsl"));
+        validate_wgsl("spectrum.wgsl", include_str!("spectrum.wgsl"));
+    }
+
+    #[test]
+    fn test_line_shader_matches_rust_layouts() {
+        // Group 0 is the ocean uniform buffer, so lines.wgsl must declare the same Uniforms
+        let (ocean_size, ocean_offsets) =
+            wgsl_struct_layout("shader.wgsl", include_str!("shader.wgsl"), "Uniforms");
+        let (size, offsets) =
+            wgsl_struct_layout("lines.wgsl", include_str!("lines.wgsl"), "Uniforms");
+        assert_eq!((size, offsets), (ocean_size, ocean_offsets));
+
+        let (size, offsets) =
+            wgsl_struct_layout("lines.wgsl", include_str!("lines.wgsl"), "LineUniforms");
+        assert_eq!(size, std::mem::size_of::<lines::LineUniforms>());
+        assert!(offsets.contains(&(
+            "pixels_per_width".to_string(),
+            std::mem::offset_of!(lines::LineUniforms, pixels_per_width)
+        )));
+
+        let (size, _) = wgsl_struct_layout("lines.wgsl", include_str!("lines.wgsl"), "Vertex");
+        assert_eq!(size, std::mem::size_of::<Vertex>());
+    }
+
+    #[test]
+    fn test_bloom_uniforms_match_wgsl_layout() {
+        let (size, offsets) =
+            wgsl_struct_layout("bloom.wgsl", include_str!("bloom.wgsl"), "BloomUniforms");
+        assert_eq!(size, std::mem::size_of::<bloom::BloomUniforms>());
+        for (field, offset) in [
+            (
+                "exposure",
+                std::mem::offset_of!(bloom::BloomUniforms, exposure),
+            ),
+            (
+                "direction",
+                std::mem::offset_of!(bloom::BloomUniforms, direction),
+            ),
+            (
+                "tone_mapping",
+                std::mem::offset_of!(bloom::BloomUniforms, tone_mapping),
+            ),
+        ] {
+            assert!(offsets.contains(&(field.to_string(), offset)), "{}", field);
+        }
+    }
+
+    #[test]
+    fn test_uniforms_carry_fog_from_render_config() {
+        let config = RenderConfig {
+            fog_density: 0.002,
+            fog_color: [0.1, 0.05, 0.2, 1.0],
+            ..RenderConfig::default()
+        };
+        let uniforms = Uniforms::new(Mat4::IDENTITY, 0.02, 2.0, 0.1, 3.0, &config);
+        assert_eq!(uniforms.fog_density, 0.002);
+        assert_eq!(uniforms.fog_color, [0.1, 0.05, 0.2, 1.0]);
+        assert_eq!(uniforms.time, 3.0);
+
+        // WGSL layout: vec4 fog_color at byte 80, fog_density at 96
+        let bytes = bytemuck::bytes_of(&uniforms);
+        assert_eq!(
+            &bytes[80..96],
+            bytemuck::cast_slice::<f32, u8>(&config.fog_color)
+        );
+        assert_eq!(&bytes[96..100], &0.002f32.to_ne_bytes());
+    }
+
+    #[test]
+    fn test_uniforms_carry_color_ramp_from_mapping() {
+        let mapping = AudioReactiveMapping {
+            trough_color: [1.0, 0.0, 0.0, 1.0],
+            peak_color: [0.2, 0.4, 0.6, 0.5],
+            color_height_range_m: 40.0,
+            mid_to_hue_shift_deg: 120.0,
+            ..AudioReactiveMapping::default()
+        };
+        let base = Uniforms::new(
+            Mat4::IDENTITY,
+            0.02,
+            2.0,
+            0.1,
+            0.0,
+            &RenderConfig::default(),
+        );
+        assert_eq!((base.color_low, base.color_high), ([1.0; 4], [1.0; 4]));
+
+        // Silent mids: exactly the configured palette
+        let uniforms = base.with_color_ramp(&mapping, 0.0);
+        assert_eq!(uniforms.color_low, mapping.trough_color);
+        assert_eq!(uniforms.color_high, mapping.peak_color);
+        assert_eq!(uniforms.color_height_range_m, 40.0);
+
+        // One unit of mid energy rotates the hue 120°: red → green, alpha kept
+        let shifted = base.with_color_ramp(&mapping, 1.0);
+        let green = [0.0, 1.0, 0.0, 1.0];
+        for (actual, expected) in shifted.color_low.iter().zip(green) {
+            assert!((actual - expected).abs() < 1e-5, "{:?}", shifted.color_low);
+        }
+        assert_eq!(shifted.color_high[3], 0.5);
+
+        // WGSL layout: vec4 color_low at byte 112, color_high at 128
+        let bytes = bytemuck::bytes_of(&uniforms);
+        assert_eq!(
+            &bytes[112..128],
+            bytemuck::cast_slice::<f32, u8>(&mapping.trough_color)
+        );
+        assert_eq!(
+            &bytes[128..144],
+            bytemuck::cast_slice::<f32, u8>(&mapping.peak_color)
+        );
+    }
+
+    /// Byte size and member offsets of struct `name` in WGSL `source` (validated first)
+    fn wgsl_struct_layout(file: &str, source: &str, name: &str) -> (usize, Vec<(String, usize)>) {
+        validate_wgsl(file, source);
+
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let mut layouter = naga::proc::Layouter::default();
+        layouter.update(module.to_ctx()).unwrap();
+        let (handle, ty) = module
+            .types
+            .iter()
+            .find(|(_, ty)| ty.name.as_deref() == Some(name))
+            .unwrap_or_else(|| panic!("{} declares no {}", file, name));
+
+        let naga::TypeInner::Struct { ref members, .. } = ty.inner else {
+            panic!("{} is not a struct", name);
+        };
+        let offsets = members
+            .iter()
+            .map(|m| (m.name.clone().unwrap_or_default(), m.offset as usize))
+            .collect();
+        (layouter[handle].size as usize, offsets)
+    }
+
+    #[test]
+    fn test_ocean_uniforms_match_wgsl_layout() {
+        let (size, offsets) =
+            wgsl_struct_layout("shader.wgsl", include_str!("shader.wgsl"), "Uniforms");
+        assert_eq!(size, std::mem::size_of::<Uniforms>());
+
+        let offset = |name: &str| {
+            offsets
+                .iter()
+                .find(|(member, _)| member == name)
+                .unwrap_or_else(|| panic!("Uniforms has no {}", name))
+                .1
+        };
+        assert_eq!(
+            offset("color_height_range_m"),
+            std::mem::offset_of!(Uniforms, color_height_range_m)
+        );
+        assert_eq!(
+            offset("color_low"),
+            std::mem::offset_of!(Uniforms, color_low)
+        );
+        assert_eq!(
+            offset("color_high"),
+            std::mem::offset_of!(Uniforms, color_high)
+        );
+        assert_eq!(
+            offset("light_direction"),
+            std::mem::offset_of!(Uniforms, light_direction)
+        );
+        assert_eq!(
+            offset("edge_fade_start"),
+            std::mem::offset_of!(Uniforms, edge_fade_start)
+        );
+        assert_eq!(
+            offset("edge_fade_end"),
+            std::mem::offset_of!(Uniforms, edge_fade_end)
+        );
+        assert_eq!(
+            offset("depth_color_range_m"),
+            std::mem::offset_of!(Uniforms, depth_color_range_m)
+        );
+        assert_eq!(
+            offset("depth_color_trough"),
+            std::mem::offset_of!(Uniforms, depth_color_trough)
+        );
+        assert_eq!(
+            offset("depth_color_crest"),
+            std::mem::offset_of!(Uniforms, depth_color_crest)
+        );
+        assert_eq!(
+            offset("camera_xz"),
+            std::mem::offset_of!(Uniforms, camera_xz)
+        );
+    }
+
+    #[test]
+    fn test_edge_fade_scales_with_grid_extent() {
+        let base = Uniforms::new(
+            Mat4::IDENTITY,
+            0.02,
+            2.0,
+            0.1,
+            0.0,
+            &RenderConfig::default(),
+        );
+
+        // Default physics reproduce the original 800 m → 1000 m fade
+        let default = base.with_edge_fade(&OceanPhysics::default(), [0.0; 2]);
+        assert_eq!(
+            (default.edge_fade_start, default.edge_fade_end),
+            (800.0, 1000.0)
+        );
+        assert_eq!(
+            (base.edge_fade_start, base.edge_fade_end),
+            (default.edge_fade_start, default.edge_fade_end)
+        );
+
+        // 256 vertices × 4 m: half the grid is 512 m
+        let physics = OceanPhysics {
+            grid_size: 256,
+            grid_spacing_m: 4.0,
+            edge_fade_start: 0.5,
+            edge_fade_end: 0.75,
+            ..OceanPhysics::default()
+        };
+        let uniforms = base.with_edge_fade(&physics, [5000.0, -300.0]);
+        assert_eq!(uniforms.edge_fade_start, 256.0);
+        assert_eq!(uniforms.edge_fade_end, 384.0);
+        assert_eq!(uniforms.camera_xz, [5000.0, -300.0]);
+
+        // WGSL layout: edge_fade_start at byte 160, edge_fade_end at 164
+        let bytes = bytemuck::bytes_of(&uniforms);
+        assert_eq!(&bytes[160..164], &256.0f32.to_ne_bytes());
+        assert_eq!(&bytes[164..168], &384.0f32.to_ne_bytes());
+    }
+
+    #[test]
+    fn test_light_direction_is_normalized_and_sways_with_mid() {
+        let mapping = AudioReactiveMapping {
+            light_direction: [2.0, 2.0, 0.0],
+            light_intensity: 0.75,
+            mid_to_light_sway_deg: 90.0,
+            ..AudioReactiveMapping::default()
+        };
+        let uniforms = |mid| {
+            Uniforms::new(
+                Mat4::IDENTITY,
+                0.02,
+                2.0,
+                0.1,
+                0.0,
+                &RenderConfig::default(),
+            )
+            .with_light(mapping.light_direction(mid))
+        };
+
+        let rest = uniforms(0.0).light_direction;
+        let swayed = uniforms(1.0).light_direction;
+        for [x, y, z, w] in [rest, swayed] {
+            assert!(((x * x + y * y + z * z).sqrt() - 1.0).abs() < 1e-5);
+            assert_eq!(w, 0.75);
+        }
+
+        // A unit of mid swings the light 90° about the vertical: +X → -Z, same elevation
+        let h = 0.5f32.sqrt();
+        for (actual, expected) in rest.iter().zip([h, h, 0.0]) {
+            assert!((actual - expected).abs() < 1e-5, "{:?}", rest);
+        }
+        for (actual, expected) in swayed.iter().zip([0.0, h, -h]) {
+            assert!((actual - expected).abs() < 1e-5, "{:?}", swayed);
+        }
+
+        // Off by default: unlit, as before the light existed
+        let default = Uniforms::new(
+            Mat4::IDENTITY,
+            0.02,
+            2.0,
+            0.1,
+            0.0,
+            &RenderConfig::default(),
+        );
+        assert_eq!(default.light_direction[3], 0.0);
+        assert_eq!(AudioReactiveMapping::default().light_direction(1.0)[3], 0.0);
+
+        // WGSL layout: vec4 light_direction at byte 144
+        let swayed_uniforms = uniforms(1.0);
+        let bytes = bytemuck::bytes_of(&swayed_uniforms);
+        assert_eq!(&bytes[144..160], bytemuck::cast_slice::<f32, u8>(&swayed));
+    }
+
+    #[test]
+    fn test_skybox_uniforms_match_wgsl_layout() {
+        let (size, offsets) =
+            wgsl_struct_layout("skybox.wgsl", include_str!("skybox.wgsl"), "SkyboxUniforms");
+        assert_eq!(size, std::mem::size_of::<SkyboxUniforms>());
+
+        let expected = [
+            (
+                "inv_view_proj",
+                std::mem::offset_of!(SkyboxUniforms, inv_view_proj),
+            ),
+            ("time", std::mem::offset_of!(SkyboxUniforms, time)),
+            (
+                "sky_enabled",
+                std::mem::offset_of!(SkyboxUniforms, sky_enabled),
+            ),
+            (
+                "background_top",
+                std::mem::offset_of!(SkyboxUniforms, background_top),
+            ),
+            (
+                "background_bottom",
+                std::mem::offset_of!(SkyboxUniforms, background_bottom),
+            ),
+            (
+                "sun_direction",
+                std::mem::offset_of!(SkyboxUniforms, sun_direction),
+            ),
+            (
+                "horizon_color",
+                std::mem::offset_of!(SkyboxUniforms, horizon_color),
+            ),
+            (
+                "zenith_color",
+                std::mem::offset_of!(SkyboxUniforms, zenith_color),
+            ),
+            (
+                "horizon_glow_color",
+                std::mem::offset_of!(SkyboxUniforms, horizon_glow_color),
+            ),
+            (
+                "horizon_glow_width",
+                std::mem::offset_of!(SkyboxUniforms, horizon_glow_width),
+            ),
+        ];
+        for (name, rust_offset) in expected {
+            let wgsl_offset = offsets
+                .iter()
+                .find(|(member, _)| member == name)
+                .unwrap_or_else(|| panic!("SkyboxUniforms has no {}", name))
+                .1;
+            assert_eq!(wgsl_offset, rust_offset, "Offset of {}", name);
+        }
+    }
+
+    #[test]
+    fn test_skybox_uniforms_carry_sky_config() {
+        let sky = SkyConfig {
+            enabled: true,
+            horizon_color: [0.9, 0.6, 0.3, 1.0],
+            zenith_color: [0.05, 0.1, 0.4, 1.0],
+            sun_elevation_deg: 0.0,
+            sun_azimuth_deg: 90.0,
+            sun_sweep_deg_per_s: 9.0,
+        };
+        let config = RenderConfig {
+            sky,
+            ..RenderConfig::default()
+        };
+
+        // Sun on the horizon toward +X at time 0
+        let uniforms = SkyboxUniforms::new(Mat4::IDENTITY, 0.0, &config, 0.0);
+        assert_eq!(uniforms.sky_enabled, 1.0);
+        assert_eq!(uniforms.horizon_color, sky.horizon_color);
+        assert_eq!(uniforms.zenith_color, sky.zenith_color);
+        let close = |a: [f32; 3], b: [f32; 3]| a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-5);
+        assert!(
+            close(uniforms.sun_direction, [1.0, 0.0, 0.0]),
+            "{:?}",
+            uniforms.sun_direction
+        );
+
+        // 10 s at 9°/s: sun straight overhead
+        let noon = SkyboxUniforms::new(Mat4::IDENTITY, 10.0, &config, 0.0);
+        assert!(
+            close(noon.sun_direction, [0.0, 1.0, 0.0]),
+            "{:?}",
+            noon.sun_direction
+        );
+
+        // Disabled by default: background only
+        let plain = SkyboxUniforms::new(Mat4::IDENTITY, 0.0, &RenderConfig::default(), 0.0);
+        assert_eq!(plain.sky_enabled, 0.0);
+    }
+
+    #[test]
+    fn test_skybox_uniforms_carry_horizon_glow() {
+        let config = RenderConfig {
+            horizon_glow_color: [0.2, 0.4, 0.8, 1.0],
+            horizon_glow_width: 0.05,
+            horizon_glow_high_band_gain: 1.5,
+            ..RenderConfig::default()
+        };
+
+        let quiet = SkyboxUniforms::new(Mat4::IDENTITY, 0.0, &config, 0.0);
+        assert_eq!(quiet.horizon_glow_color, config.horizon_glow_color);
+        assert_eq!(quiet.horizon_glow_width, 0.05);
+
+        // High band 2.0 → RGB × (1 + 1.5 × 2.0), alpha unchanged
+        let pulsed = SkyboxUniforms::new(Mat4::IDENTITY, 0.0, &config, 2.0);
+        let expected = [0.8, 1.6, 3.2, 1.0];
+        assert!(
+            pulsed
+                .horizon_glow_color
+                .iter()
+                .zip(expected)
+                .all(|(a, b)| (a - b).abs() < 1e-5),
+            "{:?}",
+            pulsed.horizon_glow_color
+        );
+
+        // Off by default
+        let plain = SkyboxUniforms::new(Mat4::IDENTITY, 0.0, &RenderConfig::default(), 1.0);
+        assert_eq!(plain.horizon_glow_width, 0.0);
+    }
+}
+
+#[cfg(all(test, feature = "gpu-tests"))]
+mod tests {
+    use super::*;
+    use crate::params::BackgroundMode;
+
+    /// Flat terrain (no base hills) centered on the origin with the given detail amplitude
+    fn flat_terrain_params(grid_size: u32, detail_amplitude: f32) -> TerrainParams {
+        let physics = OceanPhysics::default();
+        TerrainParams {
+            base_amplitude: 0.0,
+            base_frequency: physics.base_terrain_frequency,
+            detail_amplitude,
+            detail_frequency: physics.detail_frequency,
+            camera_pos: [0.0, 101.0, 0.0],
+            _padding1: 0.0,
+            grid_size,
+            grid_spacing: physics.grid_spacing_m,
+            time: 0.0,
+            noise_seed: physics.noise_seed,
+            noise_kind: physics.noise_kind.shader_id(),
+            base_octaves: physics.base_terrain_octaves,
+            detail_octaves: physics.detail_octaves,
+            noise_lacunarity: physics.noise_lacunarity,
+            noise_gain: physics.noise_gain,
+            _padding2: 0,
+            swell_offset: [0.0; 2],
+        }
+    }
+
+    /// Headless device (no surface) for compute-only tests
+    pub(super) fn headless_device() -> (wgpu::Device, wgpu::Queue) {
+        let context = HeadlessContext::new().expect("No GPU adapter available");
+        (context.device, context.queue)
+    }
+
+    /// Offscreen target size for render tests (pixels)
+    const TEST_TARGET_SIZE: u32 = 64;
+
+    /// Flat quad covering NDC [-0.5, 0.5]² at `depth`, counter-clockwise
+    fn test_quad(depth: f32, uv: [f32; 2]) -> Vec<Vertex> {
+        [
+            [-0.5, -0.5],
+            [0.5, -0.5],
+            [0.5, 0.5],
+            [-0.5, -0.5],
+            [0.5, 0.5],
+            [-0.5, 0.5],
+        ]
+        .iter()
+        .map(|&[x, y]| Vertex {
+            position: [x, y, depth],
+            base_height: 0.0,
+            uv,
+            _padding2: [0.0; 2],
+            normal: [0.0, 1.0, 0.0],
+            foam: 0.0,
+        })
+        .collect()
+    }
+
+    /// Format of the offscreen test target
+    const TEST_TARGET_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+    /// Uniform buffer and bind group for a pipeline created with `create_uniform_bind_group_layout`
+    fn test_uniform_bind_group<T: Pod>(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        uniforms: &T,
+    ) -> wgpu::BindGroup {
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Test Uniform Buffer"),
+            contents: bytemuck::bytes_of(uniforms),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Test Bind Group"),
+            layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        })
+    }
+
+    /// Draw vertices with the ocean pipeline (identity view-projection) and read back RGBA8
+    fn render_ocean_offscreen(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        vertices: &[Vertex],
+        sample_count: u32,
+    ) -> Vec<u8> {
+        let layout = create_uniform_bind_group_layout(device, "Test Uniform Layout");
+        let pipeline = create_ocean_pipeline(
+            device,
+            TEST_TARGET_FORMAT,
+            &layout,
+            sample_count,
+            wgpu::PolygonMode::Fill,
+            Some(wgpu::Face::Back),
+            include_str!("shader.wgsl"),
+        );
+        draw_ocean_offscreen(device, queue, &layout, &pipeline, vertices, sample_count)
+    }
+
+    /// `render_ocean_offscreen` with a given pipeline built from `layout`
+    fn draw_ocean_offscreen(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        layout: &wgpu::BindGroupLayout,
+        pipeline: &wgpu::RenderPipeline,
+        vertices: &[Vertex],
+        sample_count: u32,
+    ) -> Vec<u8> {
+        let uniforms = Uniforms::new(
+            Mat4::IDENTITY,
+            0.02,
+            2.0,
+            0.1,
+            0.0,
+            &RenderConfig {
+                fog_density: 0.0,
+                ..RenderConfig::default()
+            },
+        );
+        let bind_group = test_uniform_bind_group(device, layout, &uniforms);
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Test Vertex Buffer"),
+            contents: bytemuck::cast_slice(vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        render_offscreen(device, queue, sample_count, |render_pass| {
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_bind_group(0, &bind_group, &[]);
+            render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+            render_pass.draw(0..vertices.len() as u32, 0..1);
+        })
+    }
+
+    /// Draw the skybox pipeline with `uniforms` and read back RGBA8
+    fn render_skybox_offscreen(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        uniforms: &SkyboxUniforms,
+    ) -> Vec<u8> {
+        let layout = create_uniform_bind_group_layout(device, "Test Skybox Layout");
+        let pipeline = create_skybox_pipeline(
+            device,
+            TEST_TARGET_FORMAT,
+            &layout,
+            1,
+            include_str!("skybox.wgsl"),
+        );
+        let bind_group = test_uniform_bind_group(device, &layout, uniforms);
+
+        render_offscreen(device, queue, 1, |render_pass| {
+            render_pass.set_pipeline(&pipeline);
+            render_pass.set_bind_group(0, &bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        })
+    }
+
+    /// Run `draw` in a render pass over a cleared offscreen target and read back RGBA8
+    fn render_offscreen(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        sample_count: u32,
+        draw: impl FnOnce(&mut wgpu::RenderPass),
+    ) -> Vec<u8> {
+        let size = TEST_TARGET_SIZE;
+        let format = TEST_TARGET_FORMAT;
+
+        let target = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Test Color Target"),
+            size: wgpu::Extent3d {
+                width: size,
+                height: size,
                 depth_or_array_layers: 1,
             },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let target_view = target.create_view(&wgpu::TextureViewDescriptor::default());
+        let depth_view = create_depth_view(device, size, size, sample_count);
+        let msaa_view = create_msaa_view(device, format, size, size, sample_count);
+
+        let mut encoder = device.create_command_encoder(&Default::default());
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Test Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: msaa_view.as_ref().unwrap_or(&target_view),
+                    resolve_target: msaa_view.as_ref().map(|_| &target_view),
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Discard,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            draw(&mut render_pass);
+        }
+        queue.submit(std::iter::once(encoder.finish()));
+
+        capture_texture_to_image(device, queue, &target, size, size)
+            .expect("Test readback failed")
+            .into_raw()
+    }
+
+    /// RGBA of the center pixel
+    fn center_pixel(pixels: &[u8]) -> [u8; 4] {
+        let center = TEST_TARGET_SIZE / 2;
+        let offset = ((center * TEST_TARGET_SIZE + center) * 4) as usize;
+        pixels[offset..offset + 4].try_into().unwrap()
+    }
+
+    /// Median RGBA over one row of the target (robust to the odd star)
+    fn row_median(pixels: &[u8], row: u32) -> [u8; 4] {
+        let start = (row * TEST_TARGET_SIZE * 4) as usize;
+        let row_pixels = &pixels[start..start + (TEST_TARGET_SIZE * 4) as usize];
+        std::array::from_fn(|channel| {
+            let mut values: Vec<u8> = row_pixels
+                .iter()
+                .skip(channel)
+                .step_by(4)
+                .copied()
+                .collect();
+            values.sort_unstable();
+            values[values.len() / 2]
+        })
+    }
+
+    #[test]
+    fn test_skybox_draws_configured_background() {
+        let (device, queue) = headless_device();
+        let to_rgba8 = |c: [f32; 4]| c.map(|v| (v * 255.0).round() as u8);
+        let assert_close = |actual: [u8; 4], expected: [u8; 4]| {
+            assert!(
+                actual
+                    .iter()
+                    .zip(&expected)
+                    .all(|(a, e)| a.abs_diff(*e) <= 8),
+                "pixel {:?}, expected {:?}",
+                actual,
+                expected
+            );
+        };
+
+        let top = [0.1, 0.2, 0.6, 1.0];
+        let bottom = [0.5, 0.1, 0.3, 1.0];
+        let mut config = RenderConfig {
+            background: BackgroundMode::VerticalGradient(top, bottom),
+            ..RenderConfig::default()
+        };
+        let uniforms = SkyboxUniforms::new(Mat4::IDENTITY, 0.0, &config, 0.0);
+        assert_eq!(uniforms.background_top, top);
+        assert_eq!(uniforms.background_bottom, bottom);
+
+        let pixels = render_skybox_offscreen(&device, &queue, &uniforms);
+        assert_close(row_median(&pixels, 0), to_rgba8(top));
+        assert_close(row_median(&pixels, TEST_TARGET_SIZE - 1), to_rgba8(bottom));
+
+        // Solid color, brightened by the high band
+        let solid = [0.2, 0.3, 0.1, 1.0];
+        config.background = BackgroundMode::Solid(solid);
+        config.background_high_band_gain = 1.0;
+        let uniforms = SkyboxUniforms::new(Mat4::IDENTITY, 0.0, &config, 0.5);
+        let brightened = [0.3, 0.45, 0.15, 1.0];
+        assert_eq!(uniforms.background_top, uniforms.background_bottom);
+        for (actual, expected) in uniforms.background_top.iter().zip(brightened) {
+            assert!((actual - expected).abs() < 1e-6);
+        }
+
+        let pixels = render_skybox_offscreen(&device, &queue, &uniforms);
+        let middle = TEST_TARGET_SIZE / 2;
+        assert_close(row_median(&pixels, middle), to_rgba8(brightened));
+    }
+
+    #[test]
+    fn test_wireframe_toggle_switches_pipeline() {
+        let (device, _queue) = headless_device();
+        let layout = create_uniform_bind_group_layout(&device, "Test Uniform Layout");
+        let mut pipelines = OceanPipelines::new(
+            &device,
+            TEST_TARGET_FORMAT,
+            &layout,
+            1,
+            CullMode::Back,
+            include_str!("shader.wgsl"),
         );
+        assert_eq!(pipelines.polygon_mode(), wgpu::PolygonMode::Fill);
+        assert!(std::ptr::eq(pipelines.current(), &pipelines.fill));
+
+        let Some(ref line) = pipelines.line else {
+            // Without line mode the toggle is refused and the fill pipeline stays in use
+            pipelines.set_wireframe(true);
+            assert!(!pipelines.wireframe);
+            assert!(std::ptr::eq(pipelines.current(), &pipelines.fill));
+            return;
+        };
+        let line: *const wgpu::RenderPipeline = line;
 
-        self.queue.submit(std::iter::once(encoder.finish()));
+        pipelines.set_wireframe(true);
+        assert_eq!(pipelines.polygon_mode(), wgpu::PolygonMode::Line);
+        assert!(std::ptr::eq(pipelines.current(), line));
+
+        pipelines.set_wireframe(false);
+        assert_eq!(pipelines.polygon_mode(), wgpu::PolygonMode::Fill);
+        assert!(std::ptr::eq(pipelines.current(), &pipelines.fill));
+    }
+
+    #[test]
+    fn test_wireframe_falls_back_without_polygon_mode_line() {
+        let line = wgpu::Features::POLYGON_MODE_LINE | wgpu::Features::TIMESTAMP_QUERY;
+        assert_eq!(wireframe_path(line, true), WireframePath::PolygonLine);
+        assert_eq!(wireframe_path(line, false), WireframePath::PolygonLine);
+
+        let no_line = wgpu::Features::TIMESTAMP_QUERY;
+        assert_eq!(wireframe_path(no_line, true), WireframePath::ThickLines);
+        assert_eq!(
+            wireframe_path(wgpu::Features::empty(), false),
+            WireframePath::Unsupported
+        );
+    }
+
+    #[test]
+    fn test_wireframe_fallback_restores_thick_lines_setting() {
+        let mut render =
+            pollster::block_on(RenderSystem::new_headless(64, 64, 16)).expect("Headless render");
+        if render.lines.is_none() {
+            return; // No thick lines on this device: nothing to fall back to
+        }
+
+        // Filled ocean: the wireframe toggle turns thick lines on and back off
+        render.set_wireframe_via(WireframePath::ThickLines, true);
+        assert!(render.wireframe() && render.thick_lines());
+        render.set_wireframe_via(WireframePath::ThickLines, false);
+        assert!(!render.wireframe() && !render.thick_lines());
+
+        // Thick lines chosen by the user survive a wireframe toggle
+        render.set_thick_lines(true);
+        render.set_wireframe_via(WireframePath::ThickLines, true);
+        render.set_wireframe_via(WireframePath::ThickLines, true);
+        render.set_wireframe_via(WireframePath::ThickLines, false);
+        assert!(!render.wireframe() && render.thick_lines());
+
+        // Thick lines turned on during the wireframe stay on after it
+        render.set_thick_lines(false);
+        render.set_wireframe_via(WireframePath::ThickLines, true);
+        render.set_thick_lines(true);
+        render.set_wireframe_via(WireframePath::ThickLines, false);
+        assert!(render.thick_lines());
+
+        // Turning thick lines off ends the wireframe
+        render.set_wireframe_via(WireframePath::ThickLines, true);
+        render.set_thick_lines(false);
+        assert!(!render.wireframe());
+    }
+
+    #[test]
+    fn test_cull_mode_reaches_ocean_pipelines() {
+        let (device, queue) = headless_device();
+        let layout = create_uniform_bind_group_layout(&device, "Test Uniform Layout");
+
+        // Counter-clockwise half of the test quad, and the same triangle wound clockwise
+        let front_facing = test_quad(0.5, [0.0, 0.0])[..3].to_vec();
+        let back_facing: Vec<Vertex> = front_facing.iter().rev().copied().collect();
+
+        for (mode, expected, draws_front, draws_back) in [
+            (CullMode::None, None, true, true),
+            (CullMode::Back, Some(wgpu::Face::Back), true, false),
+            (CullMode::Front, Some(wgpu::Face::Front), false, true),
+        ] {
+            let pipelines = OceanPipelines::new(
+                &device,
+                TEST_TARGET_FORMAT,
+                &layout,
+                1,
+                mode,
+                include_str!("shader.wgsl"),
+            );
+            assert_eq!(pipelines.cull_mode(), expected, "{:?}", mode);
+
+            let drawn = |vertices: &[Vertex]| {
+                draw_ocean_offscreen(&device, &queue, &layout, pipelines.current(), vertices, 1)
+                    .chunks(4)
+                    .any(|p| p[..3] != [0, 0, 0])
+            };
+            assert_eq!(drawn(&front_facing), draws_front, "{:?} front face", mode);
+            assert_eq!(drawn(&back_facing), draws_back, "{:?} back face", mode);
+        }
+        assert_eq!(RenderConfig::default().cull_mode, CullMode::Back);
+    }
+
+    #[test]
+    fn test_lost_surface_is_reconfigured_at_current_size_and_retried() {
+        // Reconfigure sees the surface configuration as of the latest resize
+        let mut render_system =
+            pollster::block_on(RenderSystem::new_headless(320, 240, 16)).unwrap();
+        render_system.resize(640, 360);
+
+        // Simulated surface: lost on first acquire, configured sizes recorded
+        let mut results = vec![Ok(7), Err(wgpu::SurfaceError::Lost)];
+        let mut configured = Vec::new();
+        let texture = retry_on_surface_loss(
+            || results.pop().unwrap(),
+            &render_system.surface_config,
+            |config| configured.push((config.width, config.height)),
+        );
+        assert_eq!(texture, Ok(7));
+        assert_eq!(configured, vec![(640, 360)]);
+
+        // Outdated retries too, but only once
+        let mut acquires = 0;
+        let result = retry_on_surface_loss(
+            || {
+                acquires += 1;
+                Err::<(), _>(wgpu::SurfaceError::Outdated)
+            },
+            &render_system.surface_config,
+            |_| {},
+        );
+        assert_eq!(result, Err(wgpu::SurfaceError::Outdated));
+        assert_eq!(acquires, 2);
+
+        // Out of memory is passed straight to the caller
+        let result = retry_on_surface_loss(
+            || Err::<(), _>(wgpu::SurfaceError::OutOfMemory),
+            &render_system.surface_config,
+            |_| panic!("reconfigured on out of memory"),
+        );
+        assert_eq!(result, Err(wgpu::SurfaceError::OutOfMemory));
+    }
+
+    #[test]
+    fn test_headless_render_to_image() {
+        let (width, height) = (320, 240);
+        let render_system =
+            pollster::block_on(RenderSystem::new_headless(width, height, 32)).unwrap();
+        assert_eq!(render_system.window_size(), (width, height));
+        render_system.dispatch_terrain_compute(&flat_terrain_params(32, 2.0), 32);
+
+        // Gently rippled grid seen from above and behind, against the black background
+        let config = RenderConfig {
+            window_width: width,
+            window_height: height,
+            ..RenderConfig::default()
+        };
+        let view = Mat4::look_at_rh(
+            glam::Vec3::new(0.0, 40.0, -80.0),
+            glam::Vec3::ZERO,
+            glam::Vec3::Y,
+        );
+        let proj = Mat4::perspective_rh(
+            config.fov_degrees.to_radians(),
+            config.aspect_ratio(),
+            config.near_plane_m,
+            config.far_plane_m,
+        );
+        let view_proj = proj * view;
+        let frame_params = FrameParams {
+            uniforms: Uniforms::new(view_proj, 0.02, 2.0, 0.1, 0.0, &config),
+            skybox_uniforms: SkyboxUniforms::new(view_proj.inverse(), 0.0, &config, 0.0),
+            index_count: render_system.index_count(),
+        };
+
+        let image = render_system.render_to_image(&frame_params).unwrap();
+        assert_eq!(image.dimensions(), (width, height));
+        let corner = *image.get_pixel(0, 0);
+        assert!(
+            image.pixels().any(|&p| p != corner),
+            "Rendered image is a single color"
+        );
+    }
+
+    #[test]
+    fn test_edge_fade_follows_camera_far_from_origin() {
+        let (width, height) = (160, 120);
+        let render_system =
+            pollster::block_on(RenderSystem::new_headless(width, height, 16)).unwrap();
+        let camera_xz = [5000.0, -3000.0];
+        let terrain_params = TerrainParams {
+            camera_pos: [camera_xz[0], 101.0, camera_xz[1]],
+            ..flat_terrain_params(16, 0.0)
+        };
+        render_system.dispatch_terrain_compute(&terrain_params, 16);
+
+        // Flat grid seen from above the camera, thousands of meters from the world origin
+        let config = RenderConfig {
+            window_width: width,
+            window_height: height,
+            ..RenderConfig::default()
+        };
+        let eye = glam::Vec3::new(camera_xz[0], 40.0, camera_xz[1] - 1.0);
+        let view = Mat4::look_at_rh(
+            eye,
+            glam::Vec3::new(camera_xz[0], 0.0, camera_xz[1]),
+            glam::Vec3::Y,
+        );
+        let proj = Mat4::perspective_rh(
+            config.fov_degrees.to_radians(),
+            config.aspect_ratio(),
+            config.near_plane_m,
+            config.far_plane_m,
+        );
+        let view_proj = proj * view;
+        let lit_pixels = |fade_center| {
+            let frame_params = FrameParams {
+                uniforms: Uniforms::new(view_proj, 0.02, 0.0, 0.1, 0.0, &config)
+                    .with_edge_fade(&OceanPhysics::default(), fade_center),
+                skybox_uniforms: SkyboxUniforms::new(view_proj.inverse(), 0.0, &config, 0.0),
+                index_count: render_system.index_count(),
+            };
+            let image = render_system.render_to_image(&frame_params).unwrap();
+            image.pixels().filter(|p| p.0[..3] != [0, 0, 0]).count()
+        };
+
+        // Fading around the camera keeps the grid; around the origin it fades it all out
+        let around_camera = lit_pixels(camera_xz);
+        assert!(around_camera > 0, "Grid faded out around the camera");
+        assert!(
+            lit_pixels([0.0; 2]) * 100 < around_camera,
+            "Grid visible thousands of meters past the fade"
+        );
+    }
+
+    #[test]
+    fn test_thick_lines_widen_with_line_width() {
+        let (width, height) = (160, 120);
+        let mut render_system =
+            pollster::block_on(RenderSystem::new_headless(width, height, 16)).unwrap();
+        render_system.set_thick_lines(true);
+        if !render_system.thick_lines() {
+            eprintln!("Skipping: adapter does not support vertex storage buffers");
+            return;
+        }
+        // 8 m cells, several pixels apart on screen
+        let terrain_params = TerrainParams {
+            grid_spacing: 8.0,
+            ..flat_terrain_params(16, 0.0)
+        };
+        render_system.dispatch_terrain_compute(&terrain_params, 16);
+
+        // Flat grid from above, so every edge is a straight line on screen
+        let config = RenderConfig {
+            window_width: width,
+            window_height: height,
+            ..RenderConfig::default()
+        };
+        let view = Mat4::look_at_rh(
+            glam::Vec3::new(0.0, 60.0, -1.0),
+            glam::Vec3::ZERO,
+            glam::Vec3::Y,
+        );
+        let proj = Mat4::perspective_rh(
+            config.fov_degrees.to_radians(),
+            config.aspect_ratio(),
+            config.near_plane_m,
+            config.far_plane_m,
+        );
+        let view_proj = proj * view;
+        let lit_pixels = |line_width| {
+            let frame_params = FrameParams {
+                uniforms: Uniforms::new(view_proj, line_width, 0.0, 0.1, 0.0, &config),
+                skybox_uniforms: SkyboxUniforms::new(view_proj.inverse(), 0.0, &config, 0.0),
+                index_count: render_system.index_count(),
+            };
+            let image = render_system.render_to_image(&frame_params).unwrap();
+            image.pixels().filter(|p| p.0[..3] != [0, 0, 0]).count()
+        };
+
+        // 1 px vs 3 px lines
+        let thin = lit_pixels(0.01);
+        let thick = lit_pixels(0.03);
+        assert!(thin > 0, "Thin lines drew nothing");
+        assert!(thick > thin * 2, "thin {} px, thick {} px", thin, thick);
+    }
+
+    #[test]
+    fn test_thick_lines_follow_key_light() {
+        let (width, height) = (160, 120);
+        let mut render_system =
+            pollster::block_on(RenderSystem::new_headless(width, height, 16)).unwrap();
+        render_system.set_thick_lines(true);
+        if !render_system.thick_lines() {
+            eprintln!("Skipping: adapter does not support vertex storage buffers");
+            return;
+        }
+        let terrain_params = TerrainParams {
+            grid_spacing: 8.0,
+            ..flat_terrain_params(16, 0.0)
+        };
+        render_system.dispatch_terrain_compute(&terrain_params, 16);
+
+        // Flat grid (normals straight up) seen from above
+        let config = RenderConfig {
+            window_width: width,
+            window_height: height,
+            ..RenderConfig::default()
+        };
+        let view = Mat4::look_at_rh(
+            glam::Vec3::new(0.0, 60.0, -1.0),
+            glam::Vec3::ZERO,
+            glam::Vec3::Y,
+        );
+        let proj = Mat4::perspective_rh(
+            config.fov_degrees.to_radians(),
+            config.aspect_ratio(),
+            config.near_plane_m,
+            config.far_plane_m,
+        );
+        let view_proj = proj * view;
+        let lit_pixels = |light_direction| {
+            let frame_params = FrameParams {
+                uniforms: Uniforms::new(view_proj, 0.03, 0.0, 0.1, 0.0, &config)
+                    .with_light(light_direction),
+                skybox_uniforms: SkyboxUniforms::new(view_proj.inverse(), 0.0, &config, 0.0),
+                index_count: render_system.index_count(),
+            };
+            let image = render_system.render_to_image(&frame_params).unwrap();
+            image.pixels().filter(|p| p.0[..3] != [0, 0, 0]).count()
+        };
+
+        // Lit from above: as bright as unlit; lit from below: Lambert darkens every line
+        // (stars in the sky at the frame edge stay lit)
+        let unlit = lit_pixels([0.0, 1.0, 0.0, 0.0]);
+        assert!(unlit > 0, "Lines drew nothing");
+        assert_eq!(lit_pixels([0.0, 1.0, 0.0, 1.0]), unlit);
+        let from_below = lit_pixels([0.0, -1.0, 0.0, 1.0]);
+        assert!(from_below * 100 < unlit, "{} px lit from below", from_below);
+    }
+
+    #[test]
+    fn test_recording_resolution_override_sets_capture_size() {
+        let mut render_system =
+            pollster::block_on(RenderSystem::new_headless(1280, 720, 16)).unwrap();
+        let output_dir = std::env::temp_dir().join(format!(
+            "vibesurfer_recording_override_{}",
+            std::process::id()
+        ));
+        let recording = RecordingConfig {
+            output_dir: output_dir.to_string_lossy().into_owned(),
+            width: Some(1920),
+            height: Some(1080),
+            ..RecordingConfig::new(1.0)
+        };
+        std::fs::create_dir_all(recording.frames_dir()).unwrap();
+        render_system.start_recording(&recording).unwrap();
+
+        // Window resizes don't change the recording size
+        render_system.resize(640, 360);
+        assert_eq!(render_system.window_size(), (640, 360));
+        assert_eq!(render_system.render_size(), (1920, 1080));
+
+        render_system.dispatch_terrain_compute(&flat_terrain_params(16, 0.0), 16);
+        render_system
+            .render(0, render_system.index_count())
+            .unwrap();
+        render_system.flush_capture().unwrap();
+
+        let frame = format!("{}/frame_00000.png", recording.frames_dir());
+        assert_eq!(image::image_dimensions(&frame).unwrap(), (1920, 1080));
+        let _ = std::fs::remove_dir_all(&output_dir);
+    }
+
+    #[test]
+    fn test_recording_follows_window_resize() {
+        let mut render_system =
+            pollster::block_on(RenderSystem::new_headless(320, 240, 16)).unwrap();
+        let output_dir = std::env::temp_dir().join(format!(
+            "vibesurfer_recording_resize_{}",
+            std::process::id()
+        ));
+        let recording = RecordingConfig {
+            output_dir: output_dir.to_string_lossy().into_owned(),
+            ..RecordingConfig::new(1.0)
+        };
+        std::fs::create_dir_all(recording.frames_dir()).unwrap();
+        render_system.start_recording(&recording).unwrap();
+        render_system.dispatch_terrain_compute(&flat_terrain_params(16, 0.0), 16);
+
+        // No size override: frames after a resize are captured at the new window size
+        render_system
+            .render(0, render_system.index_count())
+            .unwrap();
+        render_system.resize(200, 100);
+        assert_eq!(render_system.render_size(), (200, 100));
+        render_system
+            .render(1, render_system.index_count())
+            .unwrap();
+        render_system.flush_capture().unwrap();
+
+        let dimensions = |frame_num: usize| {
+            let frame = format!("{}/frame_{:05}.png", recording.frames_dir(), frame_num);
+            image::image_dimensions(&frame).unwrap()
+        };
+        assert_eq!(dimensions(0), (320, 240));
+        assert_eq!(dimensions(1), (200, 100));
+        let _ = std::fs::remove_dir_all(&output_dir);
+    }
+
+    #[test]
+    fn test_recording_run_writes_frames_and_audio() {
+        use crate::audio::{AudioBackend, AudioSystem};
+        use crate::params::FFTConfig;
+
+        let output_dir =
+            std::env::temp_dir().join(format!("vibesurfer_recording_run_{}", std::process::id()));
+        let recording = RecordingConfig {
+            output_dir: output_dir.to_string_lossy().into_owned(),
+            fps: 10,
+            width: Some(96),
+            height: Some(64),
+            ..RecordingConfig::new(0.5)
+        };
+        std::fs::create_dir_all(recording.frames_dir()).unwrap();
+
+        let mut render_system = pollster::block_on(RenderSystem::new_headless(64, 64, 16)).unwrap();
+        render_system.start_recording(&recording).unwrap();
+        let fft_config = FFTConfig::default();
+        let sample_rate_hz = fft_config.sample_rate_hz;
+        let audio =
+            AudioSystem::new(fft_config, Some(recording.clone()), AudioBackend::Null).unwrap();
+
+        // The app's recording loop: synthesize up to each frame, render and capture it
+        for frame in 0..recording.total_frames() {
+            let bands = audio.bands_for_frame(frame);
+            let params = TerrainParams {
+                time: recording.frame_time_s(frame),
+                ..flat_terrain_params(16, bands.low)
+            };
+            render_system.dispatch_terrain_compute(&params, 16);
+            render_system
+                .render(frame, render_system.index_count())
+                .unwrap();
+        }
+        render_system.flush_capture().unwrap();
+        audio.finish_recording().unwrap();
+
+        // Exactly 0.5 s × 10 fps frames, at the configured size
+        let mut frames: Vec<String> = std::fs::read_dir(recording.frames_dir())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        frames.sort();
+        let expected: Vec<String> = (0..5).map(|i| format!("frame_{:05}.png", i)).collect();
+        assert_eq!(frames, expected);
+        for frame in &frames {
+            let path = format!("{}/{}", recording.frames_dir(), frame);
+            assert_eq!(image::image_dimensions(&path).unwrap(), (96, 64));
+        }
+
+        // Half a second of readable audio
+        let mut reader = hound::WavReader::open(recording.audio_path()).unwrap();
+        let duration_s = reader.duration() as f32 / reader.spec().sample_rate as f32;
+        assert_eq!(reader.spec().sample_rate as usize, sample_rate_hz);
+        assert!((duration_s - 0.5).abs() < 0.01, "{} s of audio", duration_s);
+        assert_eq!(
+            reader.samples::<f32>().filter_map(Result::ok).count(),
+            reader.len() as usize
+        );
+
+        drop(audio);
+        let _ = std::fs::remove_dir_all(&output_dir);
+    }
+
+    #[test]
+    fn test_capture_every_n_writes_contiguous_frames() {
+        let output_dir =
+            std::env::temp_dir().join(format!("vibesurfer_time_lapse_{}", std::process::id()));
+        let recording = RecordingConfig {
+            output_dir: output_dir.to_string_lossy().into_owned(),
+            fps: 10,
+            capture_every_n: 3,
+            ..RecordingConfig::new(0.9)
+        };
+        std::fs::create_dir_all(recording.frames_dir()).unwrap();
+
+        let mut render_system = pollster::block_on(RenderSystem::new_headless(32, 32, 16)).unwrap();
+        render_system.start_recording(&recording).unwrap();
+        render_system.dispatch_terrain_compute(&flat_terrain_params(16, 0.0), 16);
+        assert_eq!(recording.total_frames(), 9);
+        for frame in 0..recording.total_frames() {
+            render_system
+                .render(frame, render_system.index_count())
+                .unwrap();
+        }
+        render_system.flush_capture().unwrap();
+
+        // Frames 0, 3 and 6 written as 0, 1 and 2
+        let mut frames: Vec<String> = std::fs::read_dir(recording.frames_dir())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        frames.sort();
+        let expected: Vec<String> = (0..3).map(|i| format!("frame_{:05}.png", i)).collect();
+        assert_eq!(frames, expected);
+        assert_eq!(frames.len(), recording.captured_frames());
+        let _ = std::fs::remove_dir_all(&output_dir);
+    }
+
+    #[test]
+    fn test_subframes_average_moving_camera() {
+        let (width, height) = (160, 120);
+        let mut render_system =
+            pollster::block_on(RenderSystem::new_headless(width, height, 16)).unwrap();
+        let terrain_params = TerrainParams {
+            grid_spacing: 8.0,
+            ..flat_terrain_params(16, 0.0)
+        };
+        render_system.dispatch_terrain_compute(&terrain_params, 16);
+
+        // Camera above the grid sliding sideways between sub-frames
+        let config = RenderConfig {
+            window_width: width,
+            window_height: height,
+            ..RenderConfig::default()
+        };
+        let proj = Mat4::perspective_rh(
+            config.fov_degrees.to_radians(),
+            config.aspect_ratio(),
+            config.near_plane_m,
+            config.far_plane_m,
+        );
+        let index_count = render_system.index_count();
+        let frame_params = |x: f32| {
+            let eye = glam::Vec3::new(x, 60.0, -1.0);
+            let target = glam::Vec3::new(x, 0.0, 0.0);
+            let view_proj = proj * Mat4::look_at_rh(eye, target, glam::Vec3::Y);
+            FrameParams {
+                uniforms: Uniforms::new(view_proj, 0.03, 0.0, 0.1, 0.0, &config),
+                skybox_uniforms: SkyboxUniforms::new(view_proj.inverse(), 0.0, &config, 0.0),
+                index_count,
+            }
+        };
+
+        // One sub-frame is the plain render (up to HDR rounding)
+        let sharp = render_system.render_to_image(&frame_params(0.0)).unwrap();
+        let single = render_system
+            .render_subframes_to_image(&[frame_params(0.0)])
+            .unwrap();
+        let max_diff = |a: &image::RgbaImage, b: &image::RgbaImage| {
+            a.pixels()
+                .zip(b.pixels())
+                .flat_map(|(p, q)| (0..3).map(move |c| p.0[c].abs_diff(q.0[c])))
+                .max()
+                .unwrap()
+        };
+        assert!(max_diff(&sharp, &single) <= 1);
 
-        // Map buffer and save to PNG
-        let buffer_slice = buffer.slice(..);
-        buffer_slice.map_async(wgpu::MapMode::Read, |_| {});
-        self.device.poll(wgpu::Maintain::Wait);
-
-        let data = buffer_slice.get_mapped_range();
-        let mut image_data = vec![0u8; (width * height * bytes_per_pixel) as usize];
-
-        // Remove padding
-        for y in 0..height {
-            let padded_offset = (y * padded_bytes_per_row) as usize;
-            let unpadded_offset = (y * unpadded_bytes_per_row) as usize;
-            image_data[unpadded_offset..unpadded_offset + unpadded_bytes_per_row as usize]
-                .copy_from_slice(
-                    &data[padded_offset..padded_offset + unpadded_bytes_per_row as usize],
+        // Several sub-frames: the (linear) average of the individual renders
+        let offsets = [0.0, 1.5, 3.0, 4.5];
+        let renders: Vec<_> = offsets
+            .iter()
+            .map(|&x| render_system.render_to_image(&frame_params(x)).unwrap())
+            .collect();
+        let subframes: Vec<_> = offsets.iter().map(|&x| frame_params(x)).collect();
+        let blurred = render_system.render_subframes_to_image(&subframes).unwrap();
+
+        let to_linear = |v: u8| {
+            let v = v as f32 / 255.0;
+            if v <= 0.04045 {
+                v / 12.92
+            } else {
+                ((v + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        let to_srgb = |v: f32| {
+            let v = if v <= 0.0031308 {
+                v * 12.92
+            } else {
+                1.055 * v.powf(1.0 / 2.4) - 0.055
+            };
+            (v * 255.0).round() as u8
+        };
+        for (x, y, pixel) in blurred.enumerate_pixels() {
+            for c in 0..3 {
+                let mean = renders
+                    .iter()
+                    .map(|r| to_linear(r.get_pixel(x, y).0[c]))
+                    .sum::<f32>()
+                    / renders.len() as f32;
+                let expected = to_srgb(mean);
+                assert!(
+                    pixel.0[c].abs_diff(expected) <= 2,
+                    "({}, {}) channel {}: {} vs average {}",
+                    x,
+                    y,
+                    c,
+                    pixel.0[c],
+                    expected
                 );
+            }
         }
+        // Edges smear: blurred pixels between the sub-frames' values
+        assert!(max_diff(&blurred, &renders[0]) > 8);
+        assert!(max_diff(&blurred, &renders[3]) > 8);
+    }
 
-        drop(data);
-        buffer.unmap();
+    #[test]
+    fn test_hud_draws_text_in_top_left_corner() {
+        let (width, height) = (640, 240);
+        let mut render_system =
+            pollster::block_on(RenderSystem::new_headless(width, height, 16)).unwrap();
+        let config = RenderConfig::default();
+        let state = HudState {
+            fps: 60.0,
+            ..HudState::default()
+        };
+        render_system.update_hud(&state);
 
-        // Save as PNG
-        let frame_path = format!("{}/frame_{:05}.png", config.frames_dir(), frame_num);
-        if let Err(e) = image::save_buffer(
-            &frame_path,
-            &image_data,
-            width,
-            height,
-            image::ColorType::Rgba8,
-        ) {
-            eprintln!("Failed to save frame {}: {}", frame_num, e);
+        let frame_params = FrameParams {
+            uniforms: Uniforms::new(Mat4::IDENTITY, 0.02, 0.0, 0.1, 0.0, &config),
+            skybox_uniforms: SkyboxUniforms::new(Mat4::IDENTITY, 0.0, &config, 0.0),
+            index_count: 0,
+        };
+        let mut render = |show_hud| {
+            render_system.set_show_hud(show_hud);
+            render_system.render_to_image(&frame_params).unwrap()
+        };
+        let without_hud = render(false);
+        let with_hud = render(true);
+
+        // The HUD changes pixels, and only inside its rect
+        let changed: Vec<_> = with_hud
+            .enumerate_pixels()
+            .filter(|&(x, y, p)| p != without_hud.get_pixel(x, y))
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        assert!(!changed.is_empty(), "HUD drew nothing");
+        let (hud_width, hud_height) = hud::HUD_SIZE;
+        assert!(changed
+            .iter()
+            .all(|&(x, y)| x < 8 + hud_width && y < 8 + hud_height));
+        assert!(with_hud.pixels().any(|p| p.0[..3] == [255, 255, 255]));
+    }
+
+    #[test]
+    fn test_spectrum_draws_bars_along_bottom() {
+        let (width, height) = (640, 240);
+        let mut render_system =
+            pollster::block_on(RenderSystem::new_headless(width, height, 16)).unwrap();
+        let config = RenderConfig::default();
+        render_system.update_spectrum(&[1e6; 512]);
+
+        let frame_params = FrameParams {
+            uniforms: Uniforms::new(Mat4::IDENTITY, 0.02, 0.0, 0.1, 0.0, &config),
+            skybox_uniforms: SkyboxUniforms::new(Mat4::IDENTITY, 0.0, &config, 0.0),
+            index_count: 0,
+        };
+        let mut render = |show_spectrum| {
+            render_system.set_show_spectrum(show_spectrum);
+            render_system.render_to_image(&frame_params).unwrap()
+        };
+        let without_bars = render(false);
+        let with_bars = render(true);
+
+        // Full-scale bars change pixels, and only in the bottom quarter
+        let changed: Vec<_> = with_bars
+            .enumerate_pixels()
+            .filter(|&(x, y, p)| p != without_bars.get_pixel(x, y))
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        assert!(!changed.is_empty(), "Spectrum drew nothing");
+        assert!(changed.iter().all(|&(_, y)| y >= height * 3 / 4));
+        assert!(changed.iter().any(|&(x, _)| x < width / 8));
+        assert!(changed.iter().any(|&(x, _)| x > width * 7 / 8));
+    }
+
+    #[test]
+    fn test_shader_reload_swaps_only_valid_sources() {
+        let (width, height) = (64, 64);
+        let mut render_system =
+            pollster::block_on(RenderSystem::new_headless(width, height, 16)).unwrap();
+        let config = RenderConfig::default();
+        let frame_params = FrameParams {
+            uniforms: Uniforms::new(Mat4::IDENTITY, 0.02, 0.0, 0.1, 0.0, &config),
+            skybox_uniforms: SkyboxUniforms::new(Mat4::IDENTITY, 0.0, &config, 0.0),
+            index_count: 0,
+        };
+        let before = render_system.render_to_image(&frame_params).unwrap();
+        let skybox = ReloadableShader::Skybox.builtin_source();
+
+        // A typo, and a module naga accepts but the pipeline can't use: sky unchanged
+        let typo = skybox.replace("return output;", "return output");
+        let renamed = skybox.replace("fn vs_main(", "fn vs_renamed(");
+        for source in [typo, renamed] {
+            assert!(render_system
+                .reload_shader(ReloadableShader::Skybox, &source)
+                .is_err());
+            assert_eq!(
+                render_system.render_to_image(&frame_params).unwrap(),
+                before
+            );
+        }
+
+        // A valid edit takes effect: solid red sky
+        let red = skybox.replace(
+            "return vec4<f32>(final_color, 1.0);",
+            "return vec4<f32>(1.0, 0.0, 0.0, 1.0);",
+        );
+        render_system
+            .reload_shader(ReloadableShader::Skybox, &red)
+            .unwrap();
+        let after = render_system.render_to_image(&frame_params).unwrap();
+        let center = after.get_pixel(width / 2, height / 2).0;
+        assert!(
+            center[0] > 200 && center[1] < 50 && center[2] < 50,
+            "{:?}",
+            center
+        );
+    }
+
+    #[test]
+    fn test_frame_timings_nonnegative_and_finite() {
+        let render_system = pollster::block_on(RenderSystem::new_headless(64, 64, 32)).unwrap();
+        assert_eq!(render_system.frame_timings(), FrameTimings::default());
+        if render_system.gpu_timer.is_none() {
+            eprintln!("Skipping: adapter does not support timestamp queries");
+            return;
+        }
+
+        let config = RenderConfig::default();
+        let frame_params = FrameParams {
+            uniforms: Uniforms::new(Mat4::IDENTITY, 0.02, 2.0, 0.1, 0.0, &config),
+            skybox_uniforms: SkyboxUniforms::new(Mat4::IDENTITY, 0.0, &config, 0.0),
+            index_count: render_system.index_count(),
+        };
+
+        // render_to_image waits for the GPU, so the frame's readback has mapped by now
+        render_system.dispatch_terrain_compute(&flat_terrain_params(32, 2.0), 32);
+        render_system.render_to_image(&frame_params).unwrap();
+        render_system.device.poll(wgpu::Maintain::Wait);
+
+        let timings = render_system.frame_timings();
+        for (pass, ms) in [
+            ("compute", timings.compute_ms),
+            ("render", timings.render_ms),
+        ] {
+            assert!(ms.is_finite() && ms >= 0.0, "{} pass time {} ms", pass, ms);
+        }
+    }
+
+    #[test]
+    fn test_compute_output_is_the_drawn_vertex_buffer() {
+        let grid_size = 32;
+        let render_system =
+            pollster::block_on(RenderSystem::new_headless(64, 64, grid_size)).unwrap();
+        assert_eq!(render_system.index_count(), (grid_size - 1).pow(2) * 6);
+
+        // Heights in the buffer the ocean pass draws from, after a dispatch
+        let heights = |detail_amplitude: f32| -> Vec<f32> {
+            render_system.dispatch_terrain_compute(
+                &flat_terrain_params(grid_size, detail_amplitude),
+                grid_size,
+            );
+            let vertices = render_system.read_ocean_vertices().unwrap();
+            assert_eq!(vertices.len(), (grid_size * grid_size) as usize);
+            vertices.iter().map(|v| v.position[1]).collect()
+        };
+
+        let calm = heights(0.0);
+        assert!(calm.iter().all(|&h| h == 0.0), "Calm terrain not flat");
+
+        // Same noise field, so heights scale with the detail amplitude
+        let low = heights(1.0);
+        let high = heights(5.0);
+        let max_low = low.iter().fold(0.0f32, |m, h| m.max(h.abs()));
+        assert!(max_low > 0.1, "Detail amplitude had no visible effect");
+        for (l, h) in low.iter().zip(&high) {
+            assert!((h - 5.0 * l).abs() < 1e-3, "Height {} not 5x {}", h, l);
+        }
+    }
+
+    #[test]
+    fn test_depth_buffer_nearer_quad_wins() {
+        let (device, queue) = headless_device();
+
+        // Distinct colors: uv.y picks the pink → purple gradient end
+        let near = test_quad(0.2, [0.0, 0.0]);
+        let far = test_quad(0.8, [0.0, 1.0]);
+        let near_color = center_pixel(&render_ocean_offscreen(&device, &queue, &near, 1));
+        let far_color = center_pixel(&render_ocean_offscreen(&device, &queue, &far, 1));
+        assert_ne!(near_color, far_color);
+
+        // Near drawn first: without depth testing the far quad would overwrite it
+        let both: Vec<Vertex> = near.iter().chain(&far).copied().collect();
+        let overlap = center_pixel(&render_ocean_offscreen(&device, &queue, &both, 1));
+        assert_eq!(overlap, near_color);
+    }
+
+    #[test]
+    fn test_msaa_antialiases_diagonal_edge() {
+        let (device, queue) = headless_device();
+
+        // Half of the test quad: one triangle whose hypotenuse is a diagonal edge
+        let triangle = &test_quad(0.5, [0.0, 0.0])[..3];
+        let green_levels = |sample_count: u32| {
+            let pixels = render_ocean_offscreen(&device, &queue, triangle, sample_count);
+            let mut levels: Vec<u8> = pixels.chunks(4).map(|p| p[1]).collect();
+            levels.sort_unstable();
+            levels.dedup();
+            levels
+        };
+
+        // Without MSAA: only background and fully covered values
+        let aliased = green_levels(1);
+        assert_eq!(aliased.len(), 2, "Unexpected levels {:?}", aliased);
+
+        // With MSAA: partially covered edge pixels blend to intermediate values
+        let (background, covered) = (aliased[0], aliased[1]);
+        let antialiased = green_levels(4);
+        assert!(
+            antialiased.iter().any(|&g| g > background && g < covered),
+            "No intermediate levels in {:?}",
+            antialiased
+        );
+    }
+
+    #[test]
+    fn test_gpu_terrain_matches_cpu() {
+        let (device, queue) = headless_device();
+        let physics = OceanPhysics::default();
+        let spacing = physics.grid_spacing_m;
+
+        let params = TerrainParams {
+            base_amplitude: physics.base_terrain_amplitude_m,
+            base_frequency: physics.base_terrain_frequency,
+            detail_amplitude: physics.detail_amplitude_m,
+            detail_frequency: physics.detail_frequency,
+            camera_pos: [spacing * 37.0, 101.0, spacing * 250.0], // On the grid lattice
+            _padding1: 0.0,
+            grid_size: 128,
+            grid_spacing: spacing,
+            time: 3.5,
+            noise_seed: physics.noise_seed,
+            noise_kind: physics.noise_kind.shader_id(),
+            base_octaves: physics.base_terrain_octaves,
+            detail_octaves: physics.detail_octaves,
+            noise_lacunarity: physics.noise_lacunarity,
+            noise_gain: physics.noise_gain,
+            _padding2: 0,
+            swell_offset: [0.0; 2],
+        };
+
+        let max_diff = terrain_parity_max_height_diff(&device, &queue, &params).unwrap();
+        assert!(max_diff < 0.01, "GPU/CPU max height diff {} m", max_diff);
+
+        // Every noise kind, with several fBm octaves so lacunarity and gain count too
+        for (kind, octaves) in [
+            (NoiseKind::Perlin, 1),
+            (NoiseKind::Simplex, 1),
+            (NoiseKind::Fbm, 4),
+        ] {
+            let params = TerrainParams {
+                noise_kind: kind.shader_id(),
+                base_octaves: octaves,
+                detail_octaves: octaves,
+                ..params
+            };
+            let max_diff = terrain_parity_max_height_diff(&device, &queue, &params).unwrap();
+            assert!(
+                max_diff < 0.01,
+                "{:?}: GPU/CPU max height diff {} m",
+                kind,
+                max_diff
+            );
+        }
+
+        // Swell advects the detail layer the same way (off the lattice, diagonally)
+        let params = TerrainParams {
+            swell_offset: [7.3, -11.9],
+            ..params
+        };
+        let max_diff = terrain_parity_max_height_diff(&device, &queue, &params).unwrap();
+        assert!(
+            max_diff < 0.01,
+            "Swell: GPU/CPU max height diff {} m",
+            max_diff
+        );
+    }
+
+    #[test]
+    fn test_ocean_buffers_follow_grid_lod() {
+        let mut render_system = pollster::block_on(RenderSystem::new_headless(64, 64, 32)).unwrap();
+        assert_eq!(render_system.grid_size(), 32);
+
+        // Coarser level of detail: same world extent, wider spacing
+        let extent = 32.0 * 2.0;
+        render_system.recreate_ocean_buffers(8);
+        assert_eq!(render_system.grid_size(), 8);
+        assert_eq!(render_system.index_count(), 7 * 7 * 6);
+
+        let params = TerrainParams {
+            grid_spacing: extent / 8.0,
+            ..flat_terrain_params(8, 1.0)
+        };
+        render_system.dispatch_terrain_compute(&params, 8);
+        let vertices = render_system.read_ocean_vertices().unwrap();
+        assert_eq!(vertices.len(), 64);
+        assert!(vertices.iter().all(|v| v.position[1].is_finite()));
+        let [x0, _, z0] = vertices[0].position;
+        let [x_last, _, z_last] = vertices[63].position;
+        assert!((x_last - x0 - extent * 7.0 / 8.0).abs() < 1e-3);
+        assert!((z_last - z0 - extent * 7.0 / 8.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_gpu_simplex_matches_cpu_noise() {
+        let (device, queue) = headless_device();
+        let frequency = 0.37; // Off the simplex lattice
+
+        // 1031 also checks the full seed reaches the shader's hash (not just seed % 1024)
+        for seed in [42, 1031] {
+            // Unit base amplitude and no detail: each height is one raw simplex3d sample
+            let params = TerrainParams {
+                base_amplitude: 1.0,
+                base_frequency: frequency,
+                detail_amplitude: 0.0,
+                detail_frequency: 0.0,
+                camera_pos: [3.25, 0.0, -7.5],
+                _padding1: 0.0,
+                grid_size: 64,
+                grid_spacing: 1.3,
+                time: 0.0,
+                noise_seed: seed,
+                noise_kind: NoiseKind::Simplex.shader_id(),
+                base_octaves: 1,
+                detail_octaves: 1,
+                noise_lacunarity: 2.0,
+                noise_gain: 0.5,
+                _padding2: 0,
+                swell_offset: [0.0; 2],
+            };
+            let vertices = read_back_terrain_compute(&device, &queue, &params).unwrap();
+
+            let noise = crate::noise::NoiseGenerator::new(seed);
+            let mut max_diff = 0.0f32;
+            for vertex in &vertices {
+                // Sampled at the vertex's world XZ, as written by terrain_compute.wgsl
+                let [world_x, _, world_z] = vertex.position;
+                let expected = noise.sample_3d(
+                    (world_x * frequency) as f64,
+                    (world_z * frequency) as f64,
+                    0.0,
+                );
+                max_diff = max_diff.max((vertex.position[1] - expected).abs());
+                // No detail layer: the whole height is base terrain
+                assert_eq!(vertex.base_height, vertex.position[1]);
+            }
+            // Only float rounding differs (e.g. fused multiply-add in the shader compiler)
+            assert!(
+                max_diff < 1e-4,
+                "GPU/CPU max simplex diff {} (seed {})",
+                max_diff,
+                seed
+            );
+        }
+    }
+
+    #[test]
+    fn test_gpu_normals_follow_terrain_slope() {
+        let (device, queue) = headless_device();
+        let grid_size = 32;
+        let params = |base_amplitude| TerrainParams {
+            base_amplitude,
+            base_frequency: 0.02,
+            detail_amplitude: 0.0,
+            detail_frequency: 0.0,
+            camera_pos: [10.0, 0.0, -4.0],
+            _padding1: 0.0,
+            grid_size,
+            grid_spacing: 2.0,
+            time: 0.0,
+            noise_seed: 7,
+            noise_kind: NoiseKind::Simplex.shader_id(),
+            base_octaves: 1,
+            detail_octaves: 1,
+            noise_lacunarity: 2.0,
+            noise_gain: 0.5,
+            _padding2: 0,
+            swell_offset: [0.0; 2],
+        };
+
+        // Flat surface: every normal points straight up
+        let flat = read_back_terrain_compute(&device, &queue, &params(0.0)).unwrap();
+        assert!(flat.iter().all(|v| v.normal == [0.0, 1.0, 0.0]));
+
+        // Hills: interior normals match central differences of the read-back heights
+        let hills = read_back_terrain_compute(&device, &queue, &params(40.0)).unwrap();
+        let size = grid_size as usize;
+        let height = |x: usize, z: usize| hills[z * size + x].position[1];
+        let mut max_tilt = 0.0f32;
+        for z in 1..size - 1 {
+            for x in 1..size - 1 {
+                let slope_x = (height(x + 1, z) - height(x - 1, z)) / 4.0;
+                let slope_z = (height(x, z + 1) - height(x, z - 1)) / 4.0;
+                let expected = glam::Vec3::new(-slope_x, 1.0, -slope_z).normalize();
+                let normal = glam::Vec3::from_array(hills[z * size + x].normal);
+                assert!(
+                    normal.abs_diff_eq(expected, 1e-4),
+                    "Normal {} at ({}, {}), expected {}",
+                    normal,
+                    x,
+                    z,
+                    expected
+                );
+                max_tilt = max_tilt.max(normal.angle_between(glam::Vec3::Y));
+            }
+        }
+        assert!(max_tilt > 0.3, "Hills barely tilted: {} rad", max_tilt);
+    }
+
+    #[test]
+    fn test_gpu_terrain_continuous_across_wrap() {
+        let (device, queue) = headless_device();
+        let physics = OceanPhysics::default();
+        let spacing = physics.grid_spacing_m;
+        let grid_size = 64;
+        let params_at = |camera_x: f32| TerrainParams {
+            base_amplitude: physics.base_terrain_amplitude_m,
+            base_frequency: physics.base_terrain_frequency,
+            detail_amplitude: physics.detail_amplitude_m,
+            detail_frequency: physics.detail_frequency,
+            camera_pos: [camera_x, 101.0, 1000.3],
+            _padding1: 0.0,
+            grid_size,
+            grid_spacing: spacing,
+            time: 3.5,
+            noise_seed: physics.noise_seed,
+            noise_kind: physics.noise_kind.shader_id(),
+            base_octaves: physics.base_terrain_octaves,
+            detail_octaves: physics.detail_octaves,
+            noise_lacunarity: physics.noise_lacunarity,
+            noise_gain: physics.noise_gain,
+            _padding2: 0,
+            swell_offset: [0.0; 2],
+        };
+
+        // Camera just before and just after the grid wraps by one cell (off the lattice)
+        let before = params_at(500.0 - 0.01);
+        let after = params_at(500.0 + 0.01);
+        let before_vertices = read_back_terrain_compute(&device, &queue, &before).unwrap();
+        let after_vertices = read_back_terrain_compute(&device, &queue, &after).unwrap();
+
+        // The grid moved one whole cell: the rows it still shares keep their heights
+        let size = grid_size as usize;
+        let mut max_seam_diff = 0.0f32;
+        for z in 0..size {
+            for x in 0..size - 1 {
+                let old = before_vertices[z * size + x + 1].position;
+                let new = after_vertices[z * size + x].position;
+                assert!((old[0] - new[0]).abs() < 1e-3 && (old[2] - new[2]).abs() < 1e-3);
+                max_seam_diff = max_seam_diff.max((old[1] - new[1]).abs());
+            }
+        }
+        assert!(
+            max_seam_diff < 1e-3,
+            "Heights jumped {} m across the wrap",
+            max_seam_diff
+        );
+
+        // Neighbors in the index order are one lattice cell apart (no seam-spanning edges)
+        for vertices in [&before_vertices, &after_vertices] {
+            for z in 0..size {
+                for x in 0..size - 1 {
+                    let dx =
+                        vertices[z * size + x + 1].position[0] - vertices[z * size + x].position[0];
+                    assert!((dx - spacing).abs() < 1e-3, "Column step {} m", dx);
+                }
+            }
+        }
+
+        // Both sides of the wrap match the CPU grid's world-space noise
+        for params in [before, after] {
+            let max_diff = terrain_parity_max_height_diff(&device, &queue, &params).unwrap();
+            assert!(max_diff < 0.01, "GPU/CPU max height diff {} m", max_diff);
         }
     }
 }
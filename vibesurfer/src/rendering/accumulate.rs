@@ -0,0 +1,230 @@
+//! Temporal accumulation (motion blur) for recordings.
+//!
+//! Each sub-frame is rendered into `frame_texture`, then added to an HDR sum weighted by
+//! 1 / sub-frame count. Resolving writes the average to the output like a normal frame.
+
+use super::HDR_FORMAT;
+
+/// Size-dependent textures and the bind groups that read them
+struct AccumulatorTargets {
+    frame: wgpu::Texture,
+    sum_view: wgpu::TextureView,
+    frame_bind_group: wgpu::BindGroup,
+    sum_bind_group: wgpu::BindGroup,
+}
+
+/// Sub-frame target, HDR sum and the passes between them
+pub struct Accumulator {
+    bind_group_layout: wgpu::BindGroupLayout,
+    accumulate_pipeline: wgpu::RenderPipeline,
+    resolve_pipeline: wgpu::RenderPipeline,
+    output_format: wgpu::TextureFormat,
+    targets: AccumulatorTargets,
+}
+
+impl Accumulator {
+    /// Create the accumulator for a `width`×`height` output in `output_format`
+    pub fn new(
+        device: &wgpu::Device,
+        output_format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Accumulate Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../accumulate.wgsl").into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Accumulate Bind Group Layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            }],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Accumulate Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let create_pipeline = |label, format, blend| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some("vs_main"),
+                    buffers: &[],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some("fs_main"),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+                cache: None,
+            })
+        };
+
+        // sum += sub-frame × blend constant (the sub-frame weight)
+        let weighted_add = wgpu::BlendComponent {
+            src_factor: wgpu::BlendFactor::Constant,
+            dst_factor: wgpu::BlendFactor::One,
+            operation: wgpu::BlendOperation::Add,
+        };
+        let accumulate_pipeline = create_pipeline(
+            "Accumulate Pipeline",
+            HDR_FORMAT,
+            Some(wgpu::BlendState {
+                color: weighted_add,
+                alpha: weighted_add,
+            }),
+        );
+        let resolve_pipeline = create_pipeline("Accumulate Resolve Pipeline", output_format, None);
+
+        let targets = create_targets(device, &bind_group_layout, output_format, width, height);
+
+        Self {
+            bind_group_layout,
+            accumulate_pipeline,
+            resolve_pipeline,
+            output_format,
+            targets,
+        }
+    }
+
+    /// Texture each sub-frame should be rendered into
+    pub fn frame_texture(&self) -> &wgpu::Texture {
+        &self.targets.frame
+    }
+
+    /// Recreate the targets for a new output size (discards the running sum)
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        self.targets = create_targets(
+            device,
+            &self.bind_group_layout,
+            self.output_format,
+            width,
+            height,
+        );
+    }
+
+    /// Encode adding `frame_texture` × `weight` to the sum (`first` starts a new sum)
+    pub fn accumulate(&self, encoder: &mut wgpu::CommandEncoder, weight: f64, first: bool) {
+        let load = if first {
+            wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT)
+        } else {
+            wgpu::LoadOp::Load
+        };
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Accumulate Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &self.targets.sum_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(&self.accumulate_pipeline);
+        render_pass.set_bind_group(0, &self.targets.frame_bind_group, &[]);
+        render_pass.set_blend_constant(wgpu::Color {
+            r: weight,
+            g: weight,
+            b: weight,
+            a: weight,
+        });
+        render_pass.draw(0..3, 0..1); // Fullscreen triangle
+    }
+
+    /// Encode writing the accumulated average into `output_view`
+    pub fn resolve(&self, encoder: &mut wgpu::CommandEncoder, output_view: &wgpu::TextureView) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Accumulate Resolve Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: output_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(&self.resolve_pipeline);
+        render_pass.set_bind_group(0, &self.targets.sum_bind_group, &[]);
+        render_pass.draw(0..3, 0..1); // Fullscreen triangle
+    }
+}
+
+/// Create the sub-frame and sum targets and the bind group reading each
+fn create_targets(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    output_format: wgpu::TextureFormat,
+    width: u32,
+    height: u32,
+) -> AccumulatorTargets {
+    let create_texture = |label, format| {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        })
+    };
+    let frame = create_texture("Accumulate Sub-frame Target", output_format);
+    let sum = create_texture("Accumulate Sum Target", HDR_FORMAT);
+    let frame_view = frame.create_view(&wgpu::TextureViewDescriptor::default());
+    let sum_view = sum.create_view(&wgpu::TextureViewDescriptor::default());
+
+    let create_bind_group = |label, view| {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some(label),
+            layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(view),
+            }],
+        })
+    };
+    let frame_bind_group = create_bind_group("Accumulate Sub-frame Bind Group", &frame_view);
+    let sum_bind_group = create_bind_group("Accumulate Sum Bind Group", &sum_view);
+
+    AccumulatorTargets {
+        frame,
+        sum_view,
+        frame_bind_group,
+        sum_bind_group,
+    }
+}
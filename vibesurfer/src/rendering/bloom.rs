@@ -0,0 +1,548 @@
+//! Bloom post-process for the neon wireframe.
+//!
+//! The scene is rendered into an HDR target; a bright-pass keeps what exceeds the threshold,
+//! a separable Gaussian blur spreads it, and the composite adds it back onto the scene while
+//! writing the final image to the output (surface) view. The composite is the one HDR to
+//! display step, so exposure and tone mapping (`RenderConfig::tone_mapping`) happen there.
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::ocean::AudioBands;
+use crate::params::RenderConfig;
+
+/// HDR format of the scene and blur targets
+pub const HDR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// Uniform buffer for bloom passes (must match `bloom.wgsl`)
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub(crate) struct BloomUniforms {
+    pub threshold: f32,
+    pub intensity: f32,
+    pub radius: f32,
+    pub exposure: f32, // Linear scale (2^stops)
+    pub direction: [f32; 2],
+    pub tone_mapping: u32, // ToneMapping::shader_index
+    pub _padding: f32,
+}
+
+/// Size-dependent textures and the bind groups that read them
+struct BloomTargets {
+    scene: wgpu::Texture,
+    scene_view: wgpu::TextureView,
+    ping_view: wgpu::TextureView,
+    pong_view: wgpu::TextureView,
+    bright_bind_group: wgpu::BindGroup,
+    blur_horizontal_bind_group: wgpu::BindGroup,
+    blur_vertical_bind_group: wgpu::BindGroup,
+    composite_bind_group: wgpu::BindGroup,
+}
+
+/// Bloom post-process pipelines and targets
+pub struct Bloom {
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    bright_pipeline: wgpu::RenderPipeline,
+    blur_pipeline: wgpu::RenderPipeline,
+    composite_pipeline: wgpu::RenderPipeline,
+    horizontal_uniforms: wgpu::Buffer,
+    vertical_uniforms: wgpu::Buffer,
+    size: (u32, u32),
+    targets: BloomTargets,
+}
+
+impl Bloom {
+    /// Create bloom for a `width`×`height` output in `output_format`
+    pub fn new(
+        device: &wgpu::Device,
+        output_format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Bloom Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../bloom.wgsl").into()),
+        });
+
+        let texture_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Bloom Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                texture_entry(1),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                texture_entry(3),
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Bloom Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let create_pipeline = |label, entry_point, format| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some("vs_main"),
+                    buffers: &[],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some(entry_point),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+                cache: None,
+            })
+        };
+        let bright_pipeline = create_pipeline("Bloom Bright Pipeline", "fs_bright", HDR_FORMAT);
+        let blur_pipeline = create_pipeline("Bloom Blur Pipeline", "fs_blur", HDR_FORMAT);
+        let composite_pipeline =
+            create_pipeline("Bloom Composite Pipeline", "fs_composite", output_format);
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Bloom Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let create_uniforms = |label| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(label),
+                size: std::mem::size_of::<BloomUniforms>() as u64,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        };
+        let horizontal_uniforms = create_uniforms("Bloom Horizontal Uniforms");
+        let vertical_uniforms = create_uniforms("Bloom Vertical Uniforms");
+
+        let targets = create_bloom_targets(
+            device,
+            &bind_group_layout,
+            &sampler,
+            &horizontal_uniforms,
+            &vertical_uniforms,
+            width,
+            height,
+        );
+
+        Self {
+            bind_group_layout,
+            sampler,
+            bright_pipeline,
+            blur_pipeline,
+            composite_pipeline,
+            horizontal_uniforms,
+            vertical_uniforms,
+            size: (width, height),
+            targets,
+        }
+    }
+
+    /// HDR view the scene should be rendered into
+    pub fn scene_view(&self) -> &wgpu::TextureView {
+        &self.targets.scene_view
+    }
+
+    /// HDR scene texture (accepts copies, e.g. to upload a prepared scene)
+    pub fn scene_texture(&self) -> &wgpu::Texture {
+        &self.targets.scene
+    }
+
+    /// Recreate the targets for a new output size
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        self.size = (width, height);
+        self.targets = create_bloom_targets(
+            device,
+            &self.bind_group_layout,
+            &self.sampler,
+            &self.horizontal_uniforms,
+            &self.vertical_uniforms,
+            width,
+            height,
+        );
+    }
+
+    /// Upload threshold, intensity (boosted by the high band), radius, exposure and tone curve
+    pub fn update_uniforms(
+        &self,
+        queue: &wgpu::Queue,
+        render_config: &RenderConfig,
+        audio_bands: &AudioBands,
+    ) {
+        let (width, height) = self.size;
+        let config = &render_config.bloom;
+        let uniforms = |direction| BloomUniforms {
+            threshold: config.threshold,
+            intensity: config.intensity_for(audio_bands.high),
+            radius: config.radius,
+            exposure: render_config.exposure_scale(audio_bands.low),
+            direction,
+            tone_mapping: render_config.tone_mapping.shader_index(),
+            _padding: 0.0,
+        };
+        queue.write_buffer(
+            &self.horizontal_uniforms,
+            0,
+            bytemuck::bytes_of(&uniforms([1.0 / width as f32, 0.0])),
+        );
+        queue.write_buffer(
+            &self.vertical_uniforms,
+            0,
+            bytemuck::bytes_of(&uniforms([0.0, 1.0 / height as f32])),
+        );
+    }
+
+    /// Encode bright-pass, blur and composite from the scene into `output_view`
+    pub fn apply(&self, encoder: &mut wgpu::CommandEncoder, output_view: &wgpu::TextureView) {
+        let targets = &self.targets;
+        let passes = [
+            (
+                &self.bright_pipeline,
+                &targets.bright_bind_group,
+                &targets.ping_view,
+            ),
+            (
+                &self.blur_pipeline,
+                &targets.blur_horizontal_bind_group,
+                &targets.pong_view,
+            ),
+            (
+                &self.blur_pipeline,
+                &targets.blur_vertical_bind_group,
+                &targets.ping_view,
+            ),
+            (
+                &self.composite_pipeline,
+                &targets.composite_bind_group,
+                output_view,
+            ),
+        ];
+
+        for (pipeline, bind_group, view) in passes {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Bloom Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_bind_group(0, bind_group, &[]);
+            render_pass.draw(0..3, 0..1); // Fullscreen triangle
+        }
+    }
+}
+
+/// Create scene/ping/pong HDR targets and the bind group for each pass
+///
+/// Pass chain: scene → ping (bright) → pong (horizontal) → ping (vertical) → output.
+fn create_bloom_targets(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    sampler: &wgpu::Sampler,
+    horizontal_uniforms: &wgpu::Buffer,
+    vertical_uniforms: &wgpu::Buffer,
+    width: u32,
+    height: u32,
+) -> BloomTargets {
+    let create_texture = |label, usage| {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: HDR_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | usage,
+            view_formats: &[],
+        })
+    };
+    let scene = create_texture("Bloom Scene Texture", wgpu::TextureUsages::COPY_DST);
+    let ping = create_texture("Bloom Ping Texture", wgpu::TextureUsages::empty());
+    let pong = create_texture("Bloom Pong Texture", wgpu::TextureUsages::empty());
+    let scene_view = scene.create_view(&wgpu::TextureViewDescriptor::default());
+    let ping_view = ping.create_view(&wgpu::TextureViewDescriptor::default());
+    let pong_view = pong.create_view(&wgpu::TextureViewDescriptor::default());
+
+    // Binding 3 is only read by the composite; other passes bind the scene there
+    let create_bind_group = |uniforms: &wgpu::Buffer, source, glow| {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Bloom Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniforms.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(source),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::TextureView(glow),
+                },
+            ],
+        })
+    };
+    let bright_bind_group = create_bind_group(horizontal_uniforms, &scene_view, &scene_view);
+    let blur_horizontal_bind_group =
+        create_bind_group(horizontal_uniforms, &ping_view, &scene_view);
+    let blur_vertical_bind_group = create_bind_group(vertical_uniforms, &pong_view, &scene_view);
+    let composite_bind_group = create_bind_group(horizontal_uniforms, &scene_view, &ping_view);
+
+    BloomTargets {
+        scene,
+        scene_view,
+        ping_view,
+        pong_view,
+        bright_bind_group,
+        blur_horizontal_bind_group,
+        blur_vertical_bind_group,
+        composite_bind_group,
+    }
+}
+
+#[cfg(all(test, feature = "gpu-tests"))]
+mod tests {
+    use super::*;
+    use crate::params::{BloomConfig, ToneMapping};
+    use crate::rendering::capture_texture_to_image;
+    use crate::rendering::tests::headless_device;
+
+    const SIZE: u32 = 64;
+    const LINE_COLUMN: u32 = 32;
+
+    /// Fill the scene with black except one bright vertical line (RGBA16F = 4.0)
+    fn write_bright_line(queue: &wgpu::Queue, bloom: &Bloom) {
+        let bright = 0x4400u16; // 4.0 as f16
+        let mut texels = vec![0u16; (SIZE * SIZE * 4) as usize];
+        for row in 0..SIZE {
+            let offset = ((row * SIZE + LINE_COLUMN) * 4) as usize;
+            texels[offset..offset + 4].copy_from_slice(&[bright, bright, bright, 0x3C00]);
+        }
+        queue.write_texture(
+            bloom.scene_texture().as_image_copy(),
+            bytemuck::cast_slice(&texels),
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(SIZE * 8),
+                rows_per_image: Some(SIZE),
+            },
+            bloom.scene_texture().size(),
+        );
+    }
+
+    /// Fill the whole scene with one gray level (RGBA16F bits)
+    fn write_gray(queue: &wgpu::Queue, bloom: &Bloom, gray: u16) {
+        let texels: Vec<u16> = (0..SIZE * SIZE)
+            .flat_map(|_| [gray, gray, gray, 0x3C00])
+            .collect();
+        queue.write_texture(
+            bloom.scene_texture().as_image_copy(),
+            bytemuck::cast_slice(&texels),
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(SIZE * 8),
+                rows_per_image: Some(SIZE),
+            },
+            bloom.scene_texture().size(),
+        );
+    }
+
+    /// Run bloom over the bright-line scene and return the middle row (RGBA8)
+    fn bloom_middle_row(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        config: &BloomConfig,
+    ) -> Vec<u8> {
+        let render_config = RenderConfig {
+            bloom: *config,
+            ..RenderConfig::default()
+        };
+        composite_middle_row(device, queue, &render_config, |bloom| {
+            write_bright_line(queue, bloom)
+        })
+    }
+
+    /// Composite the scene `fill` writes with `render_config` and return the middle row (RGBA8)
+    fn composite_middle_row(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        render_config: &RenderConfig,
+        fill: impl FnOnce(&Bloom),
+    ) -> Vec<u8> {
+        let format = wgpu::TextureFormat::Rgba8Unorm;
+        let bloom = Bloom::new(device, format, SIZE, SIZE);
+        fill(&bloom);
+        bloom.update_uniforms(queue, render_config, &AudioBands::default());
+
+        let output = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Test Bloom Output"),
+            size: wgpu::Extent3d {
+                width: SIZE,
+                height: SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let output_view = output.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut encoder = device.create_command_encoder(&Default::default());
+        bloom.apply(&mut encoder, &output_view);
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let pixels = capture_texture_to_image(device, queue, &output, SIZE, SIZE)
+            .expect("Test readback failed")
+            .into_raw();
+
+        let row_bytes = (SIZE * 4) as usize;
+        let middle = (SIZE / 2) as usize;
+        pixels[middle * row_bytes..(middle + 1) * row_bytes].to_vec()
+    }
+
+    #[test]
+    fn test_bloom_spreads_bright_line_to_neighbors() {
+        let (device, queue) = headless_device();
+        let red = |row: &[u8], column: u32| row[(column * 4) as usize];
+
+        let config = BloomConfig {
+            enabled: true,
+            threshold: 1.0,
+            intensity: 1.0,
+            radius: 2.0,
+            ..BloomConfig::default()
+        };
+        let no_glow = BloomConfig {
+            intensity: 0.0,
+            ..config
+        };
+
+        let plain = bloom_middle_row(&device, &queue, &no_glow);
+        let bloomed = bloom_middle_row(&device, &queue, &config);
+
+        // The line itself survives either way
+        assert_eq!(red(&plain, LINE_COLUMN), 255);
+        assert_eq!(red(&bloomed, LINE_COLUMN), 255);
+
+        // Neighbors are dark without bloom and lit with it
+        for column in [
+            LINE_COLUMN - 3,
+            LINE_COLUMN - 1,
+            LINE_COLUMN + 1,
+            LINE_COLUMN + 3,
+        ] {
+            assert_eq!(red(&plain, column), 0, "column {} without bloom", column);
+            assert!(red(&bloomed, column) > 0, "column {} with bloom", column);
+        }
+
+        // Glow falls off with distance
+        assert!(red(&bloomed, LINE_COLUMN + 1) > red(&bloomed, LINE_COLUMN + 3));
+        assert_eq!(red(&bloomed, LINE_COLUMN + 20), 0);
+    }
+
+    #[test]
+    fn test_exposure_and_tone_mapping_in_composite() {
+        let (device, queue) = headless_device();
+        let mid_gray = 0x3800u16; // 0.5 as f16
+        let no_glow = RenderConfig {
+            bloom: BloomConfig {
+                enabled: true,
+                intensity: 0.0,
+                ..BloomConfig::default()
+            },
+            ..RenderConfig::default()
+        };
+        let gray_out = |render_config: &RenderConfig| {
+            composite_middle_row(&device, &queue, render_config, |bloom| {
+                write_gray(&queue, bloom, mid_gray)
+            })[0]
+        };
+
+        // Exposure 0 with passthrough leaves mid-gray unchanged
+        assert_eq!(no_glow.exposure_scale(0.0), 1.0);
+        let passthrough = gray_out(&no_glow);
+        assert!((127..=128).contains(&passthrough), "{}", passthrough);
+
+        // +1 stop doubles it to white
+        let brighter = gray_out(&RenderConfig {
+            exposure: 1.0,
+            ..no_glow.clone()
+        });
+        assert_eq!(brighter, 255);
+
+        // Reinhard: 0.5 / 1.5 ≈ 85; ACES lifts mid-gray to ≈ 0.62 (158)
+        let reinhard = gray_out(&RenderConfig {
+            tone_mapping: ToneMapping::Reinhard,
+            ..no_glow.clone()
+        });
+        assert!((84..=86).contains(&reinhard), "{}", reinhard);
+        let aces = gray_out(&RenderConfig {
+            tone_mapping: ToneMapping::Aces,
+            ..no_glow.clone()
+        });
+        assert!((156..=160).contains(&aces), "{}", aces);
+    }
+}
@@ -0,0 +1,580 @@
+//! Asynchronous frame capture for recording mode.
+//!
+//! Each frame is copied into a staging buffer and mapped with `map_async`; the render loop
+//! only polls the device, so it never waits for the GPU. Mapped frames are handed to a worker
+//! thread that strips row padding and encodes them (PNG or video) while the next frames render.
+
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+
+use super::video::VideoEncoder;
+
+/// Frames that may be in flight (copied but not yet read back) before capture waits
+const MAX_FRAMES_IN_FLIGHT: usize = 3;
+
+/// Readback buffer sized for one frame
+struct StagingBuffer {
+    buffer: wgpu::Buffer,
+    width: u32,
+    height: u32,
+    padded_bytes_per_row: u32,
+}
+
+impl StagingBuffer {
+    fn new(device: &wgpu::Device, width: u32, height: u32) -> Self {
+        let padded_bytes_per_row = (width * 4).div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+            * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Frame Capture Buffer"),
+            size: (padded_bytes_per_row * height) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        Self {
+            buffer,
+            width,
+            height,
+            padded_bytes_per_row,
+        }
+    }
+
+    /// Record a copy of `texture` (same size as this buffer) into the buffer
+    fn copy_from(&self, encoder: &mut wgpu::CommandEncoder, texture: &wgpu::Texture) {
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &self.buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(self.padded_bytes_per_row),
+                    rows_per_image: Some(self.height),
+                },
+            },
+            wgpu::Extent3d {
+                width: self.width,
+                height: self.height,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+}
+
+/// Frame whose copy has been submitted, waiting for its buffer to map
+struct PendingFrame {
+    frame_num: usize,
+    staging: StagingBuffer,
+    mapped: mpsc::Receiver<Result<(), wgpu::BufferAsyncError>>,
+}
+
+/// Destination for captured frames
+pub enum FrameSink {
+    /// `frame_XXXXX.png` files in a directory
+    PngSequence(String),
+
+    /// Raw RGBA frames piped to an ffmpeg encoder
+    Video(VideoEncoder),
+}
+
+impl FrameSink {
+    /// Write one tightly packed RGBA frame
+    fn write(
+        &mut self,
+        frame_num: usize,
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+    ) -> Result<(), String> {
+        match self {
+            Self::PngSequence(frames_dir) => {
+                let path = format!("{}/frame_{:05}.png", frames_dir, frame_num);
+                image::save_buffer(&path, rgba, width, height, image::ColorType::Rgba8)
+                    .map_err(|e| format!("Failed to save {}: {}", path, e))
+            }
+            Self::Video(encoder) => encoder.write_frame(rgba, width, height),
+        }
+    }
+
+    /// Finish the output once every frame has been written
+    fn finish(self) -> Result<(), String> {
+        match self {
+            Self::PngSequence(_) => Ok(()),
+            Self::Video(encoder) => encoder.finish(),
+        }
+    }
+}
+
+/// Padded frame data handed to the writer thread
+struct FrameData {
+    frame_num: usize,
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+    padded_bytes_per_row: u32,
+}
+
+/// Background frame writer owning the sink
+struct FrameWriter {
+    sender: mpsc::Sender<FrameData>,
+    thread: JoinHandle<Result<(), String>>,
+}
+
+impl FrameWriter {
+    fn spawn(mut sink: FrameSink, swap_red_blue: bool) -> Self {
+        let (sender, receiver) = mpsc::channel::<FrameData>();
+        let thread = std::thread::spawn(move || {
+            for frame in receiver {
+                let image_data = unpad_rows(&frame, swap_red_blue);
+                if let Err(e) = sink.write(frame.frame_num, &image_data, frame.width, frame.height)
+                {
+                    eprintln!("Failed to write frame {}: {}", frame.frame_num, e);
+                }
+            }
+            sink.finish()
+        });
+        Self { sender, thread }
+    }
+
+    /// Write every queued frame, then stop the thread and finish the sink
+    fn finish(self) -> Result<(), String> {
+        drop(self.sender);
+        self.thread
+            .join()
+            .map_err(|_| "Frame writer thread panicked".to_string())?
+    }
+}
+
+/// Remove row padding (and convert BGRA to RGBA when needed)
+fn unpad_rows(frame: &FrameData, swap_red_blue: bool) -> Vec<u8> {
+    let row_bytes = (frame.width * 4) as usize;
+    let mut image_data = Vec::with_capacity(row_bytes * frame.height as usize);
+    for row in frame
+        .data
+        .chunks(frame.padded_bytes_per_row as usize)
+        .take(frame.height as usize)
+    {
+        image_data.extend_from_slice(&row[..row_bytes]);
+    }
+    if swap_red_blue {
+        for pixel in image_data.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+    }
+    image_data
+}
+
+/// Whether `format` stores pixels as BGRA (captured data then needs a red/blue swap)
+fn is_bgra(format: wgpu::TextureFormat) -> bool {
+    matches!(
+        format,
+        wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+    )
+}
+
+/// Copy the top-left `width` × `height` of `texture` to an RGBA image, blocking on the GPU
+///
+/// Handles the `COPY_BYTES_PER_ROW_ALIGNMENT` row padding of the readback and converts BGRA
+/// textures to RGBA. One-off counterpart to `FrameCapture::capture`.
+pub fn capture_texture_to_image(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+    width: u32,
+    height: u32,
+) -> Result<image::RgbaImage, String> {
+    if width > texture.width() || height > texture.height() {
+        return Err(format!(
+            "Capture size {}x{} exceeds texture size {}x{}",
+            width,
+            height,
+            texture.width(),
+            texture.height()
+        ));
+    }
+
+    let staging = StagingBuffer::new(device, width, height);
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Texture Readback Encoder"),
+    });
+    staging.copy_from(&mut encoder, texture);
+    queue.submit(std::iter::once(encoder.finish()));
+
+    let buffer_slice = staging.buffer.slice(..);
+    let (sender, receiver) = mpsc::channel();
+    buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+    device.poll(wgpu::Maintain::Wait);
+
+    receiver
+        .recv()
+        .map_err(|e| format!("Readback callback dropped: {}", e))?
+        .map_err(|e| format!("Failed to map readback buffer: {}", e))?;
+
+    let frame = FrameData {
+        frame_num: 0,
+        data: buffer_slice.get_mapped_range().to_vec(),
+        width: staging.width,
+        height: staging.height,
+        padded_bytes_per_row: staging.padded_bytes_per_row,
+    };
+    staging.buffer.unmap();
+
+    image::RgbaImage::from_raw(width, height, unpad_rows(&frame, is_bgra(texture.format())))
+        .ok_or_else(|| "Readback size does not match the capture size".to_string())
+}
+
+/// Read `texture` back (blocking) and save it as a PNG at `path`
+pub fn save_texture_png(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+    path: &Path,
+) -> Result<(), String> {
+    capture_texture_to_image(device, queue, texture, texture.width(), texture.height())?
+        .save(path)
+        .map_err(|e| format!("Failed to save {}: {}", path.display(), e))
+}
+
+/// Double-buffered frame capture to a `FrameSink`
+///
+/// Staging buffers are recycled through a small pool; at most `MAX_FRAMES_IN_FLIGHT` frames
+/// wait on the GPU at once. Call `flush` before exiting so every submitted frame is written.
+pub struct FrameCapture {
+    free_buffers: Vec<StagingBuffer>,
+    pending: VecDeque<PendingFrame>,
+    writer: Option<FrameWriter>,
+    /// Only frames that are multiples of this are captured (see `with_every_n`)
+    every_n: usize,
+}
+
+impl FrameCapture {
+    /// Create a capture writing frames to `sink`
+    ///
+    /// `format` is the format of the captured textures (BGRA is converted to RGBA).
+    pub fn new(sink: FrameSink, format: wgpu::TextureFormat) -> Self {
+        Self {
+            free_buffers: Vec::new(),
+            pending: VecDeque::new(),
+            writer: Some(FrameWriter::spawn(sink, is_bgra(format))),
+            every_n: 1,
+        }
+    }
+
+    /// Capture only every `every_n`th frame, numbering them 0, 1, 2... (0 counts as 1)
+    pub fn with_every_n(mut self, every_n: u32) -> Self {
+        self.every_n = every_n.max(1) as usize;
+        self
+    }
+
+    /// Number of frames submitted but not yet handed to the writer
+    pub fn frames_in_flight(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Copy `texture` into a staging buffer and start mapping it (does not wait for the GPU)
+    ///
+    /// Frames off the `with_every_n` stride are skipped; the rest are written as
+    /// `frame_num / every_n`.
+    pub fn capture(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        texture: &wgpu::Texture,
+        frame_num: usize,
+    ) {
+        if !frame_num.is_multiple_of(self.every_n) {
+            return;
+        }
+        let frame_num = frame_num / self.every_n;
+
+        // Hand off whatever finished since the last frame; wait only if the pool is exhausted
+        self.poll(device);
+        while self.pending.len() >= MAX_FRAMES_IN_FLIGHT {
+            device.poll(wgpu::Maintain::Wait);
+            self.collect_mapped();
+        }
+
+        let (width, height) = (texture.width(), texture.height());
+        // Buffers from before a resize are never reused
+        self.free_buffers
+            .retain(|b| b.width == width && b.height == height);
+        let staging = self
+            .free_buffers
+            .pop()
+            .unwrap_or_else(|| StagingBuffer::new(device, width, height));
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Frame Capture Encoder"),
+        });
+        staging.copy_from(&mut encoder, texture);
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let (sender, mapped) = mpsc::channel();
+        staging
+            .buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let _ = sender.send(result);
+            });
+
+        self.pending.push_back(PendingFrame {
+            frame_num,
+            staging,
+            mapped,
+        });
+    }
+
+    /// Hand every frame whose buffer has mapped to the writer (non-blocking)
+    pub fn poll(&mut self, device: &wgpu::Device) {
+        device.poll(wgpu::Maintain::Poll);
+        self.collect_mapped();
+    }
+
+    /// Wait for all submitted frames, write them and finish the sink
+    ///
+    /// Frames captured after a flush are dropped.
+    pub fn flush(&mut self, device: &wgpu::Device) -> Result<(), String> {
+        while !self.pending.is_empty() {
+            device.poll(wgpu::Maintain::Wait);
+            self.collect_mapped();
+        }
+        match self.writer.take() {
+            Some(writer) => writer.finish(),
+            None => Ok(()),
+        }
+    }
+
+    /// Move mapped frames (in submission order) from the pending queue to the writer
+    fn collect_mapped(&mut self) {
+        while let Some(frame) = self.pending.front() {
+            let result = match frame.mapped.try_recv() {
+                Ok(result) => result,
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => Err(wgpu::BufferAsyncError),
+            };
+            let frame = self.pending.pop_front().expect("front frame exists");
+
+            match result {
+                Ok(()) => {
+                    let data = frame.staging.buffer.slice(..).get_mapped_range().to_vec();
+                    frame.staging.buffer.unmap();
+                    if let Some(ref writer) = self.writer {
+                        let _ = writer.sender.send(FrameData {
+                            frame_num: frame.frame_num,
+                            data,
+                            width: frame.staging.width,
+                            height: frame.staging.height,
+                            padded_bytes_per_row: frame.staging.padded_bytes_per_row,
+                        });
+                    }
+                    self.free_buffers.push(frame.staging);
+                }
+                Err(e) => {
+                    eprintln!("Failed to map frame {}: {}", frame.frame_num, e);
+                }
+            }
+        }
+    }
+}
+
+impl Drop for FrameCapture {
+    fn drop(&mut self) {
+        // Frames already read back are still written; unmapped ones need `flush`
+        if let Some(writer) = self.writer.take() {
+            if let Err(e) = writer.finish() {
+                eprintln!("Frame capture: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "gpu-tests"))]
+mod tests {
+    use super::*;
+    use crate::rendering::tests::headless_device;
+
+    /// Fresh temporary frames directory for one test
+    fn test_frames_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("vibesurfer_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Capturable RGBA8 render target
+    fn capture_test_texture(device: &wgpu::Device, width: u32, height: u32) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Capture Test Target"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        })
+    }
+
+    #[test]
+    fn test_async_capture_writes_every_frame() {
+        let (device, queue) = headless_device();
+        let (width, height) = (70, 40); // Row size not a multiple of 256 (exercises padding)
+        let frames = 7; // More than MAX_FRAMES_IN_FLIGHT (exercises buffer reuse)
+
+        let frames_dir = test_frames_dir("capture_test");
+        let texture = capture_test_texture(&device, width, height);
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut capture = FrameCapture::new(
+            FrameSink::PngSequence(frames_dir.to_string_lossy().into_owned()),
+            wgpu::TextureFormat::Rgba8Unorm,
+        );
+        for frame_num in 0..frames {
+            // Clear to a per-frame red level so frames are distinguishable
+            let red = frame_num as f64 / frames as f64;
+            let mut encoder =
+                device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color {
+                            r: red,
+                            g: 0.0,
+                            b: 0.0,
+                            a: 1.0,
+                        }),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            queue.submit(std::iter::once(encoder.finish()));
+
+            capture.capture(&device, &queue, &texture, frame_num);
+            assert!(capture.frames_in_flight() <= MAX_FRAMES_IN_FLIGHT);
+        }
+        capture.flush(&device).unwrap();
+        assert_eq!(capture.frames_in_flight(), 0);
+
+        for frame_num in 0..frames {
+            let path = frames_dir.join(format!("frame_{:05}.png", frame_num));
+            let image = image::open(&path)
+                .unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+                .to_rgba8();
+            assert_eq!(image.dimensions(), (width, height));
+            let expected_red = (frame_num as f32 / frames as f32 * 255.0).round() as u8;
+            let red = image.get_pixel(width - 1, height - 1)[0];
+            assert!(
+                red.abs_diff(expected_red) <= 1,
+                "frame {}: red {} vs {}",
+                frame_num,
+                red,
+                expected_red
+            );
+        }
+
+        std::fs::remove_dir_all(&frames_dir).unwrap();
+    }
+
+    #[test]
+    fn test_capture_unaligned_texture_round_trips() {
+        let (device, queue) = headless_device();
+        // 17 px × 4 bytes = 68 bytes per row, padded to 256 in the readback
+        let (width, height) = (17, 3);
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Unaligned Capture Test"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+
+        // Every pixel distinct, so a wrong row stride shows up as shifted pixels
+        let expected = image::RgbaImage::from_fn(width, height, |x, y| {
+            image::Rgba([x as u8 * 13, y as u8 * 80, (x + y * width) as u8, 255])
+        });
+        queue.write_texture(
+            texture.as_image_copy(),
+            expected.as_raw(),
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(width * 4),
+                rows_per_image: Some(height),
+            },
+            texture.size(),
+        );
+
+        let image = capture_texture_to_image(&device, &queue, &texture, width, height).unwrap();
+        assert_eq!(image.dimensions(), (width, height));
+        assert_eq!(image, expected);
+    }
+
+    #[test]
+    fn test_save_texture_png_writes_texture_size() {
+        let (device, queue) = headless_device();
+        let (width, height) = (70, 40);
+        let texture = capture_test_texture(&device, width, height);
+        let path = test_frames_dir("screenshot_test").join("screenshot.png");
+
+        save_texture_png(&device, &queue, &texture, &path).unwrap();
+
+        assert_eq!(image::image_dimensions(&path).unwrap(), (width, height));
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_capture_after_resize_uses_new_size() {
+        let (device, queue) = headless_device();
+        let frames_dir = test_frames_dir("capture_resize_test");
+        let mut capture = FrameCapture::new(
+            FrameSink::PngSequence(frames_dir.to_string_lossy().into_owned()),
+            wgpu::TextureFormat::Rgba8Unorm,
+        );
+
+        // Window resized between frames 1 and 2 (surface texture changes size)
+        let before = capture_test_texture(&device, 64, 48);
+        let after = capture_test_texture(&device, 100, 30);
+        capture.capture(&device, &queue, &before, 0);
+        capture.capture(&device, &queue, &before, 1);
+        capture.capture(&device, &queue, &after, 2);
+
+        let newest = &capture.pending.back().unwrap().staging;
+        assert_eq!((newest.width, newest.height), (100, 30));
+        assert_eq!(newest.padded_bytes_per_row, 512); // 400 bytes rounded up to 256
+        assert_eq!(newest.buffer.size(), 512 * 30);
+
+        capture.flush(&device).unwrap();
+
+        // Each frame is written at the size it was captured at
+        let dimensions = |frame_num: usize| {
+            image::image_dimensions(frames_dir.join(format!("frame_{:05}.png", frame_num))).unwrap()
+        };
+        assert_eq!(dimensions(0), (64, 48));
+        assert_eq!(dimensions(1), (64, 48));
+        assert_eq!(dimensions(2), (100, 30));
+
+        std::fs::remove_dir_all(&frames_dir).unwrap();
+    }
+}
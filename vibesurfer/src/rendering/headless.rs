@@ -0,0 +1,113 @@
+//! Headless GPU context: adapter, device and queue without a window.
+//!
+//! One call replaces the instance/adapter/device setup that offscreen rendering, GPU tests
+//! and tools would otherwise repeat, and requests the same device as `RenderSystem`.
+
+use super::{capture_texture_to_image, request_device};
+
+/// GPU adapter, device and queue with no surface attached
+pub struct HeadlessContext {
+    pub adapter: wgpu::Adapter,
+    pub device: wgpu::Device,
+    pub queue: wgpu::Queue,
+}
+
+impl HeadlessContext {
+    /// Request a headless adapter and device, blocking (Err: no adapter or device available)
+    pub fn new() -> Result<Self, String> {
+        pollster::block_on(Self::request())
+    }
+
+    /// Async form of `new` (for callers already inside an executor)
+    pub async fn request() -> Result<Self, String> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends: wgpu::Backends::all(),
+            ..Default::default()
+        });
+        let (adapter, device, queue) = request_device(&instance, None).await?;
+        Ok(Self {
+            adapter,
+            device,
+            queue,
+        })
+    }
+
+    /// `width`×`height` color texture to render into and read back with `read_texture`
+    pub fn create_render_target(
+        &self,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+    ) -> wgpu::Texture {
+        self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Headless Render Target"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        })
+    }
+
+    /// Read an 8-bit RGBA or BGRA `texture` back as RGBA (blocking)
+    pub fn read_texture(&self, texture: &wgpu::Texture) -> Result<image::RgbaImage, String> {
+        capture_texture_to_image(
+            &self.device,
+            &self.queue,
+            texture,
+            texture.width(),
+            texture.height(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_headless_context_reads_back_clear_color() {
+        let context = match HeadlessContext::new() {
+            Ok(context) => context,
+            Err(e) => {
+                eprintln!("Skipping headless context test: {}", e);
+                return;
+            }
+        };
+
+        // 70 pixels: 280-byte rows exercise the readback's row padding
+        let target = context.create_render_target(70, 3, wgpu::TextureFormat::Rgba8Unorm);
+        let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = context.device.create_command_encoder(&Default::default());
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Test Clear Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color {
+                        r: 1.0,
+                        g: 0.0,
+                        b: 1.0,
+                        a: 1.0,
+                    }),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        context.queue.submit(std::iter::once(encoder.finish()));
+
+        let image = context.read_texture(&target).unwrap();
+        assert_eq!(image.dimensions(), (70, 3));
+        assert!(image.pixels().all(|pixel| pixel.0 == [255, 0, 255, 255]));
+    }
+}
@@ -0,0 +1,435 @@
+//! Debug HUD: FPS, audio bands and camera state drawn over the finished frame.
+//!
+//! Text is rasterized on the CPU with a built-in 5×7 bitmap font into a small RGBA texture,
+//! which a single alpha-blended quad draws in the top-left corner after bloom.
+
+use bytemuck::{Pod, Zeroable};
+use glam::Vec3;
+use wgpu::util::DeviceExt;
+
+use crate::camera::CameraSystem;
+use crate::ocean::AudioBands;
+
+/// Glyph cell including one column and two rows of spacing (font pixels)
+const CELL_WIDTH: u32 = 6;
+const CELL_HEIGHT: u32 = 9;
+
+/// Screen pixels per font pixel
+const HUD_SCALE: u32 = 2;
+
+/// Text area (characters per line, lines); longer text is cut off
+const HUD_COLUMNS: u32 = 40;
+const HUD_LINES: u32 = 5;
+
+/// Distance from the top-left corner of the frame (pixels)
+const HUD_MARGIN_PX: f32 = 8.0;
+
+/// HUD texture size (pixels)
+pub const HUD_SIZE: (u32, u32) = (
+    (HUD_COLUMNS * CELL_WIDTH + 2) * HUD_SCALE,
+    (HUD_LINES * CELL_HEIGHT + 2) * HUD_SCALE,
+);
+
+/// Values shown on the HUD for one frame
+#[derive(Clone, Debug, Default)]
+pub struct HudState {
+    pub fps: f32,
+    pub bands: AudioBands,
+    pub eye: Vec3,
+    pub target: Vec3,
+    pub preset: &'static str,
+}
+
+impl HudState {
+    /// HUD values for a frame rendered at `time_s` with `camera`
+    pub fn new<F>(
+        fps: f32,
+        bands: AudioBands,
+        camera: &CameraSystem,
+        time_s: f32,
+        terrain_height_fn: Option<F>,
+    ) -> Self
+    where
+        F: Fn(f32, f32) -> f32,
+    {
+        let (eye, target) = camera.compute_position_and_target(time_s, terrain_height_fn);
+        Self {
+            fps,
+            bands,
+            eye,
+            target,
+            preset: camera.preset().name(),
+        }
+    }
+
+    /// Text lines, top to bottom
+    pub fn lines(&self) -> Vec<String> {
+        let vec3 = |v: Vec3| format!("({:.1}, {:.1}, {:.1})", v.x, v.y, v.z);
+        vec![
+            format!("FPS {:.0}", self.fps),
+            format!(
+                "BANDS L {:.2} M {:.2} H {:.2}",
+                self.bands.low, self.bands.mid, self.bands.high
+            ),
+            format!("EYE {}", vec3(self.eye)),
+            format!("TARGET {}", vec3(self.target)),
+            format!("PRESET {}", self.preset),
+        ]
+    }
+}
+
+/// Rows of a 5×7 glyph, top first (bit 4 = leftmost pixel); lowercase draws as uppercase
+fn glyph(c: char) -> [u8; 7] {
+    match c.to_ascii_uppercase() {
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        'A' => [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'G' => [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+        'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'Q' => [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+        'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+        'X' => [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+        'Z' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+        ' ' => [0x00; 7],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        ',' => [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '(' => [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+        ')' => [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+        '/' => [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00],
+        '%' => [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03],
+        '=' => [0x00, 0x00, 0x1F, 0x00, 0x1F, 0x00, 0x00],
+        _ => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04], // '?'
+    }
+}
+
+/// Rasterize `lines` into a `HUD_SIZE` image: white text on a translucent dark panel
+pub fn rasterize_text(lines: &[String]) -> image::RgbaImage {
+    let (width, height) = HUD_SIZE;
+    let mut image = image::RgbaImage::from_pixel(width, height, image::Rgba([0, 0, 0, 140]));
+
+    for (row, line) in lines.iter().take(HUD_LINES as usize).enumerate() {
+        for (column, c) in line.chars().take(HUD_COLUMNS as usize).enumerate() {
+            // One font pixel of padding around the text
+            let cell_x = 1 + column as u32 * CELL_WIDTH;
+            let cell_y = 1 + row as u32 * CELL_HEIGHT;
+            for (gy, bits) in glyph(c).iter().enumerate() {
+                for gx in 0..5 {
+                    if bits & (0x10 >> gx) == 0 {
+                        continue;
+                    }
+                    let x = (cell_x + gx) * HUD_SCALE;
+                    let y = (cell_y + gy as u32) * HUD_SCALE;
+                    for (dx, dy) in
+                        (0..HUD_SCALE).flat_map(|dx| (0..HUD_SCALE).map(move |dy| (dx, dy)))
+                    {
+                        image.put_pixel(x + dx, y + dy, image::Rgba([255, 255, 255, 255]));
+                    }
+                }
+            }
+        }
+    }
+    image
+}
+
+/// Uniform buffer for the HUD quad (must match `hud.wgsl`)
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct HudUniforms {
+    /// Quad corners in NDC: (left, top, right, bottom)
+    rect: [f32; 4],
+}
+
+impl HudUniforms {
+    /// Pixel-exact quad `HUD_MARGIN_PX` from the top-left of a `width`×`height` target
+    fn new(width: u32, height: u32) -> Self {
+        let to_ndc_x = |px: f32| px / width.max(1) as f32 * 2.0 - 1.0;
+        let to_ndc_y = |px: f32| 1.0 - px / height.max(1) as f32 * 2.0;
+        Self {
+            rect: [
+                to_ndc_x(HUD_MARGIN_PX),
+                to_ndc_y(HUD_MARGIN_PX),
+                to_ndc_x(HUD_MARGIN_PX + HUD_SIZE.0 as f32),
+                to_ndc_y(HUD_MARGIN_PX + HUD_SIZE.1 as f32),
+            ],
+        }
+    }
+}
+
+/// HUD text texture and the pipeline drawing it over the frame
+pub struct Hud {
+    pipeline: wgpu::RenderPipeline,
+    texture: wgpu::Texture,
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+impl Hud {
+    /// Create the HUD for a `width`×`height` output in `output_format` (blank until `update`)
+    pub fn new(
+        device: &wgpu::Device,
+        output_format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("HUD Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../hud.wgsl").into()),
+        });
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("HUD Texture"),
+            size: wgpu::Extent3d {
+                width: HUD_SIZE.0,
+                height: HUD_SIZE.1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("HUD Sampler"),
+            ..Default::default() // Nearest filtering keeps the pixel font crisp
+        });
+
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("HUD Uniform Buffer"),
+            contents: bytemuck::bytes_of(&HudUniforms::new(width, height)),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("HUD Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("HUD Bind Group"),
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("HUD Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("HUD Pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: output_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            pipeline,
+            texture,
+            uniform_buffer,
+            bind_group,
+        }
+    }
+
+    /// Keep the HUD at its pixel size and position after the output is resized
+    pub fn resize(&self, queue: &wgpu::Queue, width: u32, height: u32) {
+        queue.write_buffer(
+            &self.uniform_buffer,
+            0,
+            bytemuck::bytes_of(&HudUniforms::new(width, height)),
+        );
+    }
+
+    /// Redraw the HUD text for this frame
+    pub fn update(&self, queue: &wgpu::Queue, state: &HudState) {
+        let image = rasterize_text(&state.lines());
+        queue.write_texture(
+            self.texture.as_image_copy(),
+            image.as_raw(),
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(HUD_SIZE.0 * 4),
+                rows_per_image: Some(HUD_SIZE.1),
+            },
+            self.texture.size(),
+        );
+    }
+
+    /// Draw the HUD over `output_view` (after the scene and bloom)
+    pub fn draw(&self, encoder: &mut wgpu::CommandEncoder, output_view: &wgpu::TextureView) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("HUD Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: output_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.draw(0..6, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::{CameraPreset, FixedCamera};
+
+    #[test]
+    fn test_hud_state_reports_frame_values() {
+        let camera = CameraSystem::new(CameraPreset::Fixed(FixedCamera {
+            position: [1.0, 20.0, 0.0],
+            target: [0.0, 5.0, 40.0],
+            simulated_velocity: 10.0,
+        }));
+        let bands = AudioBands {
+            low: 0.25,
+            mid: 0.5,
+            high: 0.75,
+        };
+
+        // Fixed camera 2 s in at 10 m/s
+        let state = HudState::new(59.6, bands, &camera, 2.0, None::<fn(f32, f32) -> f32>);
+        assert_eq!(state.fps, 59.6);
+        assert_eq!(
+            (state.bands.low, state.bands.mid, state.bands.high),
+            (0.25, 0.5, 0.75)
+        );
+        assert_eq!(state.eye, Vec3::new(1.0, 20.0, 20.0));
+        assert_eq!(state.target, Vec3::new(0.0, 5.0, 60.0));
+        assert_eq!(state.preset, "fixed");
+
+        assert_eq!(
+            state.lines(),
+            [
+                "FPS 60",
+                "BANDS L 0.25 M 0.50 H 0.75",
+                "EYE (1.0, 20.0, 20.0)",
+                "TARGET (0.0, 5.0, 60.0)",
+                "PRESET fixed",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rasterized_text_lights_glyph_pixels() {
+        let image = rasterize_text(&["FPS 60".to_string()]);
+        assert_eq!(image.dimensions(), HUD_SIZE);
+
+        let is_lit = |x: u32, y: u32| image.get_pixel(x, y).0 == [255; 4];
+        // Top-left pixel of the 'F' (first glyph row is 0x1F: all five columns lit)
+        assert!(is_lit(HUD_SCALE, HUD_SCALE));
+        assert!(is_lit(5 * HUD_SCALE, HUD_SCALE));
+        // Spacing column between glyphs stays the panel color
+        assert!(!is_lit(6 * HUD_SCALE, HUD_SCALE));
+        // Second line is empty
+        assert!(!(0..HUD_SIZE.0).any(|x| is_lit(x, (1 + CELL_HEIGHT) * HUD_SCALE)));
+
+        // Unknown characters and overlong lines don't panic
+        let long = "é".repeat(2 * HUD_COLUMNS as usize);
+        rasterize_text(&[
+            long.clone(),
+            long.clone(),
+            long.clone(),
+            long.clone(),
+            long.clone(),
+            long,
+        ]);
+    }
+}
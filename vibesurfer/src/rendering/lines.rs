@@ -0,0 +1,256 @@
+//! Thick neon lines: terrain lattice edges expanded into screen-space quads.
+//!
+//! Each edge is one instance of six vertices. The vertex shader reads both endpoints from the
+//! terrain vertex buffer (bound as storage) and offsets them perpendicular to the projected
+//! edge, so `line_width` gives the same pixel width at any distance.
+
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
+
+use super::DEPTH_FORMAT;
+use crate::ocean::generate_line_indices;
+
+/// Screen pixels per unit of `Uniforms::line_width` (the default 0.02 is 2 px)
+pub const PIXELS_PER_LINE_WIDTH: f32 = 100.0;
+
+/// Uniform buffer for the line pass (must match `lines.wgsl`)
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub(crate) struct LineUniforms {
+    pub viewport_size: [f32; 2],
+    pub pixels_per_width: f32,
+    pub _padding: f32,
+}
+
+impl LineUniforms {
+    fn new(width: u32, height: u32) -> Self {
+        Self {
+            viewport_size: [width as f32, height as f32],
+            pixels_per_width: PIXELS_PER_LINE_WIDTH,
+            _padding: 0.0,
+        }
+    }
+}
+
+/// Line pipeline with the edge list of the terrain lattice it draws
+pub struct LineRenderer {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    uniform_buffer: wgpu::Buffer,
+    edge_buffer: wgpu::Buffer,
+    edge_count: u32,
+    bind_group: wgpu::BindGroup,
+}
+
+impl LineRenderer {
+    /// Create the line pipeline for the `grid_size`² lattice in `vertex_buffer`
+    ///
+    /// Group 0 is the ocean uniform bind group (shared with the fill pipeline); `size` is the
+    /// render target size in pixels.
+    pub fn new(
+        device: &wgpu::Device,
+        color_format: wgpu::TextureFormat,
+        uniform_bind_group_layout: &wgpu::BindGroupLayout,
+        sample_count: u32,
+        vertex_buffer: &wgpu::Buffer,
+        grid_size: u32,
+        (width, height): (u32, u32),
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Line Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../lines.wgsl").into()),
+        });
+
+        let storage_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::VERTEX,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: true },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Line Bind Group Layout"),
+            entries: &[
+                // Terrain vertices and edge index pairs
+                storage_entry(0),
+                storage_entry(1),
+                // Line uniforms
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Line Pipeline Layout"),
+            bind_group_layouts: &[uniform_bind_group_layout, &bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Line Render Pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[], // Vertices are fetched from storage
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: color_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None, // Quads face either way depending on the edge direction
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: DEPTH_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::LessEqual,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Line Uniform Buffer"),
+            contents: bytemuck::bytes_of(&LineUniforms::new(width, height)),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let (edge_buffer, edge_count) = create_edge_buffer(device, grid_size);
+        let bind_group = create_bind_group(
+            device,
+            &bind_group_layout,
+            vertex_buffer,
+            &edge_buffer,
+            &uniform_buffer,
+        );
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            uniform_buffer,
+            edge_buffer,
+            edge_count,
+            bind_group,
+        }
+    }
+
+    /// Draw a new terrain vertex buffer (after `RenderSystem::recreate_ocean_buffers`)
+    pub fn set_vertex_buffer(
+        &mut self,
+        device: &wgpu::Device,
+        vertex_buffer: &wgpu::Buffer,
+        grid_size: u32,
+    ) {
+        (self.edge_buffer, self.edge_count) = create_edge_buffer(device, grid_size);
+        self.bind_group = create_bind_group(
+            device,
+            &self.bind_group_layout,
+            vertex_buffer,
+            &self.edge_buffer,
+            &self.uniform_buffer,
+        );
+    }
+
+    /// Update the viewport size line widths are measured in
+    pub fn resize(&self, queue: &wgpu::Queue, width: u32, height: u32) {
+        queue.write_buffer(
+            &self.uniform_buffer,
+            0,
+            bytemuck::bytes_of(&LineUniforms::new(width, height)),
+        );
+    }
+
+    /// Edges drawn per frame
+    pub fn edge_count(&self) -> u32 {
+        self.edge_count
+    }
+
+    /// Draw every lattice edge (ocean uniforms at group 0)
+    pub fn draw(
+        &self,
+        render_pass: &mut wgpu::RenderPass<'_>,
+        uniform_bind_group: &wgpu::BindGroup,
+    ) {
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, uniform_bind_group, &[]);
+        render_pass.set_bind_group(1, &self.bind_group, &[]);
+        render_pass.draw(0..6, 0..self.edge_count);
+    }
+}
+
+/// Edge index pairs for the compute shader's `grid_size`² lattice, and the edge count
+fn create_edge_buffer(device: &wgpu::Device, grid_size: u32) -> (wgpu::Buffer, u32) {
+    let indices = generate_line_indices(grid_size);
+    let edge_count = (indices.len() / 2) as u32;
+
+    // Storage bindings can't be empty; a lone placeholder edge is never drawn
+    let contents = if indices.is_empty() {
+        vec![0; 2]
+    } else {
+        indices
+    };
+    let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Line Edge Buffer"),
+        contents: bytemuck::cast_slice(&contents),
+        usage: wgpu::BufferUsages::STORAGE,
+    });
+    (buffer, edge_count)
+}
+
+fn create_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    vertex_buffer: &wgpu::Buffer,
+    edge_buffer: &wgpu::Buffer,
+    uniform_buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Line Bind Group"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: vertex_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: edge_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: uniform_buffer.as_entire_binding(),
+            },
+        ],
+    })
+}
@@ -0,0 +1,182 @@
+//! Shader export: translate the crate's WGSL to a native backend with naga (toy5's pipeline).
+//!
+//! Each shader is parsed, validated and written by naga's MSL, SPIR-V or HLSL backend, giving
+//! a close look at what the driver receives (wgpu does the same translation at runtime).
+
+use std::path::{Path, PathBuf};
+
+use super::shader_reload::parse_and_validate;
+
+/// WGSL shaders compiled into the crate (name, source)
+pub const CRATE_SHADERS: [(&str, &str); 9] = [
+    ("shader.wgsl", include_str!("../shader.wgsl")),
+    ("skybox.wgsl", include_str!("../skybox.wgsl")),
+    ("bloom.wgsl", include_str!("../bloom.wgsl")),
+    (
+        "terrain_compute.wgsl",
+        include_str!("../terrain_compute.wgsl"),
+    ),
+    ("lines.wgsl", include_str!("../lines.wgsl")),
+    ("hud.wgsl", include_str!("../hud.wgsl")),
+    ("accumulate.wgsl", include_str!("../accumulate.wgsl")),
+    ("spectrum.wgsl", include_str!("../spectrum.wgsl")),
+    ("ocean_compute.wgsl", include_str!("../ocean_compute.wgsl")),
+];
+
+/// Target language for `translate_wgsl`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShaderBackend {
+    /// Metal Shading Language 2.4 source
+    Msl,
+    /// SPIR-V binary (little-endian words)
+    Spirv,
+    /// HLSL source (shader model 5.1)
+    Hlsl,
+}
+
+impl ShaderBackend {
+    /// Parse a backend name: msl, spirv, hlsl
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "msl" => Ok(Self::Msl),
+            "spirv" | "spv" => Ok(Self::Spirv),
+            "hlsl" => Ok(Self::Hlsl),
+            other => Err(format!(
+                "Unknown shader backend '{}' (expected msl, spirv or hlsl)",
+                other
+            )),
+        }
+    }
+
+    /// File extension of translated output
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Msl => "metal",
+            Self::Spirv => "spv",
+            Self::Hlsl => "hlsl",
+        }
+    }
+}
+
+/// Parse, validate and translate WGSL for `backend` (Err: the failing step's diagnostic)
+pub fn translate_wgsl(source: &str, backend: ShaderBackend) -> Result<Vec<u8>, String> {
+    let (module, info) = parse_and_validate(source)?;
+
+    match backend {
+        ShaderBackend::Msl => {
+            let options = naga::back::msl::Options {
+                lang_version: (2, 4),
+                ..Default::default()
+            };
+            let (msl, _) = naga::back::msl::write_string(
+                &module,
+                &info,
+                &options,
+                &naga::back::msl::PipelineOptions::default(),
+            )
+            .map_err(|e| format!("MSL translation failed: {}", e))?;
+            Ok(msl.into_bytes())
+        }
+        ShaderBackend::Spirv => {
+            let words = naga::back::spv::write_vec(
+                &module,
+                &info,
+                &naga::back::spv::Options::default(),
+                None,
+            )
+            .map_err(|e| format!("SPIR-V translation failed: {}", e))?;
+            Ok(words.iter().flat_map(|word| word.to_le_bytes()).collect())
+        }
+        ShaderBackend::Hlsl => {
+            let mut hlsl = String::new();
+            naga::back::hlsl::Writer::new(&mut hlsl, &naga::back::hlsl::Options::default())
+                .write(&module, &info, None)
+                .map_err(|e| format!("HLSL translation failed: {}", e))?;
+            Ok(hlsl.into_bytes())
+        }
+    }
+}
+
+/// Translate every `CRATE_SHADERS` entry into `out_dir` (created if missing)
+///
+/// `shader.wgsl` becomes e.g. `shader.metal`. Returns each shader's output path or error;
+/// one failure doesn't stop the others.
+pub fn export_shaders(
+    backend: ShaderBackend,
+    out_dir: &Path,
+) -> Vec<(&'static str, Result<PathBuf, String>)> {
+    if let Err(e) = std::fs::create_dir_all(out_dir) {
+        let error = format!("Failed to create {}: {}", out_dir.display(), e);
+        return CRATE_SHADERS
+            .iter()
+            .map(|&(name, _)| (name, Err(error.clone())))
+            .collect();
+    }
+
+    CRATE_SHADERS
+        .iter()
+        .map(|&(name, source)| {
+            let path = out_dir.join(name).with_extension(backend.extension());
+            let result = translate_wgsl(source, backend).and_then(|output| {
+                std::fs::write(&path, output)
+                    .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+                Ok(path)
+            });
+            (name, result)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shader_exports_to_msl_with_fragment_entry_point() {
+        let msl = translate_wgsl(include_str!("../shader.wgsl"), ShaderBackend::Msl).unwrap();
+        let msl = String::from_utf8(msl).unwrap();
+        assert!(
+            msl.contains("fragment"),
+            "No fragment entry point:\n{}",
+            msl
+        );
+        assert!(msl.contains("vertex"));
+
+        // Every crate shader translates with every backend; SPIR-V starts with its magic
+        for backend in [
+            ShaderBackend::Msl,
+            ShaderBackend::Spirv,
+            ShaderBackend::Hlsl,
+        ] {
+            for (name, source) in CRATE_SHADERS {
+                let output = translate_wgsl(source, backend)
+                    .unwrap_or_else(|e| panic!("{} to {:?}: {}", name, backend, e));
+                assert!(!output.is_empty());
+            }
+        }
+        let spirv = translate_wgsl(include_str!("../skybox.wgsl"), ShaderBackend::Spirv).unwrap();
+        assert_eq!(spirv[..4], 0x0723_0203u32.to_le_bytes());
+
+        assert_eq!(ShaderBackend::parse("MSL"), Ok(ShaderBackend::Msl));
+        assert!(ShaderBackend::parse("glsl").is_err());
+        assert!(translate_wgsl("fn broken(", ShaderBackend::Msl).is_err());
+    }
+
+    #[test]
+    fn test_crate_shaders_lists_every_wgsl_file() {
+        let src_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut on_disk: Vec<String> = std::fs::read_dir(&src_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .filter(|name| name.ends_with(".wgsl"))
+            .collect();
+        on_disk.sort();
+
+        let mut listed: Vec<String> = CRATE_SHADERS
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect();
+        listed.sort();
+        assert_eq!(listed, on_disk);
+    }
+}
@@ -0,0 +1,180 @@
+//! Shader hot-reload: watch WGSL files on disk and swap pipelines only when naga accepts them.
+//!
+//! Edited sources go through the same parse and validate steps as toy5 before any pipeline
+//! is rebuilt, so a typo saved mid-edit logs naga's diagnostic instead of crashing wgpu.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+
+/// Directory holding the WGSL sources of this checkout (watched by `--watch-shaders`)
+pub const SHADER_SOURCE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src");
+
+/// Shaders `RenderSystem::reload_shader` can swap while running
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReloadableShader {
+    /// `shader.wgsl` (filled and wireframe ocean pipelines)
+    Ocean,
+    /// `skybox.wgsl`
+    Skybox,
+}
+
+impl ReloadableShader {
+    pub const ALL: [Self; 2] = [Self::Ocean, Self::Skybox];
+
+    /// File name in `SHADER_SOURCE_DIR`
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::Ocean => "shader.wgsl",
+            Self::Skybox => "skybox.wgsl",
+        }
+    }
+
+    /// Source compiled into the binary (used until a reload succeeds)
+    pub fn builtin_source(self) -> &'static str {
+        match self {
+            Self::Ocean => include_str!("../shader.wgsl"),
+            Self::Skybox => include_str!("../skybox.wgsl"),
+        }
+    }
+}
+
+/// Parse and validate WGSL the way wgpu does at pipeline creation (Err: naga's diagnostic)
+pub fn validate_wgsl(source: &str) -> Result<(), String> {
+    parse_and_validate(source).map(|_| ())
+}
+
+/// Parsed module and its validation info (for naga's backends, see `translate_wgsl`)
+///
+/// Validates against `Capabilities::default()`, the conservative set toy5 showed every
+/// crate shader passes, so nothing here depends on optional GPU features.
+pub fn parse_and_validate(source: &str) -> Result<(naga::Module, naga::valid::ModuleInfo), String> {
+    let module = naga::front::wgsl::parse_str(source).map_err(|e| e.emit_to_string(source))?;
+    let info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::default(),
+    )
+    .validate(&module)
+    .map_err(|e| e.emit_to_string(source))?;
+    Ok((module, info))
+}
+
+/// Background watcher sending the new source of each reloadable shader edited on disk
+///
+/// Polls modification time and length like `ConfigWatcher`. Sources are sent unvalidated;
+/// `RenderSystem::reload_shader` decides whether to use them. The polling thread stops when
+/// the watcher is dropped.
+pub struct ShaderWatcher {
+    receiver: Receiver<(ReloadableShader, String)>,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ShaderWatcher {
+    /// Start watching every `ReloadableShader` file in `dir`, checking every `poll_interval`
+    pub fn spawn(dir: impl Into<PathBuf>, poll_interval: Duration) -> Self {
+        let dir = dir.into();
+        let (sender, receiver) = mpsc::channel();
+        let running = Arc::new(AtomicBool::new(true));
+
+        let thread = {
+            let running = Arc::clone(&running);
+            std::thread::spawn(move || {
+                let stamp = |path: &PathBuf| -> Option<(SystemTime, u64)> {
+                    let metadata = std::fs::metadata(path).ok()?;
+                    Some((metadata.modified().ok()?, metadata.len()))
+                };
+                let mut watched: Vec<_> = ReloadableShader::ALL
+                    .into_iter()
+                    .map(|shader| {
+                        let path = dir.join(shader.file_name());
+                        let last_stamp = stamp(&path);
+                        (shader, path, last_stamp)
+                    })
+                    .collect();
+
+                while running.load(Ordering::Relaxed) {
+                    std::thread::sleep(poll_interval);
+
+                    for (shader, path, last_stamp) in &mut watched {
+                        let current = stamp(path);
+                        if current.is_none() || current == *last_stamp {
+                            continue;
+                        }
+                        *last_stamp = current;
+
+                        match std::fs::read_to_string(&*path) {
+                            Ok(source) => {
+                                if sender.send((*shader, source)).is_err() {
+                                    return; // Receiver gone
+                                }
+                            }
+                            Err(e) => eprintln!(
+                                "Warning: shader reload skipped, can't read {}: {}",
+                                path.display(),
+                                e
+                            ),
+                        }
+                    }
+                }
+            })
+        };
+
+        Self {
+            receiver,
+            running,
+            thread: Some(thread),
+        }
+    }
+
+    /// Shaders edited since the last call, with their newest source
+    pub fn try_recv(&self) -> Vec<(ReloadableShader, String)> {
+        let mut edited: Vec<(ReloadableShader, String)> = Vec::new();
+        for (shader, source) in self.receiver.try_iter() {
+            edited.retain(|(s, _)| *s != shader);
+            edited.push((shader, source));
+        }
+        edited
+    }
+}
+
+impl Drop for ShaderWatcher {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_wgsl_rejects_invalid_source() {
+        for shader in ReloadableShader::ALL {
+            assert_eq!(validate_wgsl(shader.builtin_source()), Ok(()));
+        }
+
+        // Syntax error: the diagnostic points at the source
+        let typo = ReloadableShader::Skybox
+            .builtin_source()
+            .replace("return output;", "return output");
+        let error = validate_wgsl(&typo).unwrap_err();
+        assert!(
+            error.contains("expected"),
+            "Unhelpful diagnostic: {}",
+            error
+        );
+
+        // Parses, but fails validation (wrong return type)
+        let mistyped = ReloadableShader::Skybox
+            .builtin_source()
+            .replace("return vec4<f32>(final_color, 1.0);", "return final_color;");
+        assert!(validate_wgsl(&mistyped).is_err());
+    }
+}
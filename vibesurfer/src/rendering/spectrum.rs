@@ -0,0 +1,206 @@
+//! Debug spectrum analyzer: FFT bin magnitudes drawn as bars along the bottom of the frame.
+//!
+//! The spectrum is averaged down to `SPECTRUM_BARS` bars on a decibel scale. Each bar is one
+//! quad in a small vertex buffer rewritten every frame, drawn over the finished frame.
+
+use bytemuck::{Pod, Zeroable};
+
+/// Bars across the width of the frame
+pub const SPECTRUM_BARS: usize = 64;
+
+/// Height of a full-scale bar (NDC; the frame is 2 tall)
+const SPECTRUM_HEIGHT_NDC: f32 = 0.5;
+
+/// Magnitudes from 1 (empty bar) to this many decibels above it (full bar)
+const SPECTRUM_DB_RANGE: f32 = 60.0;
+
+/// Share of each bar's slot left empty between bars
+const BAR_GAP: f32 = 0.2;
+
+/// Vertices per bar (two triangles)
+const BAR_VERTICES: usize = 6;
+
+/// Bar corner (must match `spectrum.wgsl`)
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Pod, Zeroable)]
+struct SpectrumVertex {
+    /// NDC position
+    position: [f32; 2],
+    /// Bar height at this corner (0..1, drives the color gradient)
+    level: f32,
+}
+
+/// Bar heights (0..1) for `spectrum`: the mean magnitude of each bar's bins on a dB scale
+///
+/// Bins are split evenly across `SPECTRUM_BARS`; bars without bins (short spectra) are empty.
+pub fn bar_heights(spectrum: &[f32]) -> Vec<f32> {
+    (0..SPECTRUM_BARS)
+        .map(|bar| {
+            let bins = &spectrum
+                [bar * spectrum.len() / SPECTRUM_BARS..(bar + 1) * spectrum.len() / SPECTRUM_BARS];
+            if bins.is_empty() {
+                return 0.0;
+            }
+            let magnitude = bins.iter().sum::<f32>() / bins.len() as f32;
+            (20.0 * magnitude.max(1.0).log10() / SPECTRUM_DB_RANGE).min(1.0)
+        })
+        .collect()
+}
+
+/// One quad per bar, standing on the bottom edge of the frame
+fn bar_vertices(heights: &[f32]) -> Vec<SpectrumVertex> {
+    let slot = 2.0 / heights.len().max(1) as f32;
+    heights
+        .iter()
+        .enumerate()
+        .flat_map(|(bar, &height)| {
+            let left = -1.0 + bar as f32 * slot + slot * BAR_GAP * 0.5;
+            let right = left + slot * (1.0 - BAR_GAP);
+            let top = -1.0 + height * SPECTRUM_HEIGHT_NDC;
+            let corner = |x, y, level| SpectrumVertex {
+                position: [x, y],
+                level,
+            };
+            [
+                corner(left, -1.0, 0.0),
+                corner(right, -1.0, 0.0),
+                corner(left, top, height),
+                corner(left, top, height),
+                corner(right, -1.0, 0.0),
+                corner(right, top, height),
+            ]
+        })
+        .collect()
+}
+
+/// Bar vertex buffer and the pipeline drawing it over the frame
+pub struct SpectrumGraph {
+    pipeline: wgpu::RenderPipeline,
+    vertex_buffer: wgpu::Buffer,
+}
+
+impl SpectrumGraph {
+    /// Create the graph for an output in `output_format` (empty until `update`)
+    pub fn new(device: &wgpu::Device, output_format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Spectrum Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../spectrum.wgsl").into()),
+        });
+
+        let vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Spectrum Vertex Buffer"),
+            size: (SPECTRUM_BARS * BAR_VERTICES * std::mem::size_of::<SpectrumVertex>())
+                as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Spectrum Pipeline Layout"),
+            bind_group_layouts: &[],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Spectrum Pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<SpectrumVertex>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &[
+                        wgpu::VertexAttribute {
+                            offset: 0,
+                            shader_location: 0,
+                            format: wgpu::VertexFormat::Float32x2,
+                        },
+                        wgpu::VertexAttribute {
+                            offset: 8, // After position (8 bytes)
+                            shader_location: 1,
+                            format: wgpu::VertexFormat::Float32,
+                        },
+                    ],
+                }],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: output_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            pipeline,
+            vertex_buffer,
+        }
+    }
+
+    /// Set the bars from this frame's FFT bin magnitudes
+    pub fn update(&self, queue: &wgpu::Queue, spectrum: &[f32]) {
+        let vertices = bar_vertices(&bar_heights(spectrum));
+        queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&vertices));
+    }
+
+    /// Draw the bars over `output_view` (after the scene and bloom)
+    pub fn draw(&self, encoder: &mut wgpu::CommandEncoder, output_view: &wgpu::TextureView) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Spectrum Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: output_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.draw(0..(SPECTRUM_BARS * BAR_VERTICES) as u32, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bar_heights_track_spectrum() {
+        // Magnitude rising across 512 bins: 8 bins per bar
+        let spectrum: Vec<f32> = (0..512).map(|bin| bin as f32).collect();
+        let heights = bar_heights(&spectrum);
+        assert_eq!(heights.len(), SPECTRUM_BARS);
+        assert_eq!(heights[0], 20.0 * 3.5f32.log10() / SPECTRUM_DB_RANGE);
+        assert!(heights.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(heights.iter().all(|&h| (0.0..=1.0).contains(&h)));
+
+        // Silence: empty bars; very loud: full bars; no spectrum: empty bars
+        assert!(bar_heights(&[0.0; 512]).iter().all(|&h| h == 0.0));
+        assert!(bar_heights(&[1e6; 512]).iter().all(|&h| h == 1.0));
+        assert_eq!(bar_heights(&[]), vec![0.0; SPECTRUM_BARS]);
+
+        // One quad per bar, standing on the bottom edge at the bar's height
+        let vertices = bar_vertices(&heights);
+        assert_eq!(vertices.len(), SPECTRUM_BARS * BAR_VERTICES);
+        for (bar, quad) in vertices.chunks(BAR_VERTICES).enumerate() {
+            let top = quad.iter().map(|v| v.position[1]).fold(-1.0, f32::max);
+            assert_eq!(top, -1.0 + heights[bar] * SPECTRUM_HEIGHT_NDC);
+            assert!(quad.iter().all(|v| (-1.0..=1.0).contains(&v.position[0])));
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//! GPU timestamp queries for the terrain compute and render passes.
+//!
+//! Each pass writes begin/end timestamps that are resolved in its own command encoder. After
+//! the render pass, both pairs are copied into a readback buffer and mapped with `map_async`;
+//! the result is picked up on a later poll, so timing never stalls the render loop.
+
+use std::sync::mpsc;
+use std::sync::Mutex;
+
+/// Query indices: compute begin/end, render begin/end
+const COMPUTE_BEGIN: u32 = 0;
+const RENDER_BEGIN: u32 = 2;
+const QUERY_COUNT: u32 = 4;
+
+/// Bytes per resolved timestamp
+const TIMESTAMP_SIZE: u64 = std::mem::size_of::<u64>() as u64;
+
+/// Bytes per resolved begin/end pair
+const PAIR_SIZE: u64 = 2 * TIMESTAMP_SIZE;
+
+/// Offset of a pair in the resolve buffer (resolve destinations must be 256-byte aligned)
+fn resolve_offset(begin: u32) -> u64 {
+    (begin / 2) as u64 * wgpu::QUERY_RESOLVE_BUFFER_ALIGNMENT
+}
+
+/// GPU time of the most recently measured frame (milliseconds; zeros when unsupported)
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct FrameTimings {
+    /// Terrain compute pass
+    pub compute_ms: f32,
+    /// Scene render pass (skybox + ocean, excluding bloom)
+    pub render_ms: f32,
+}
+
+/// Readback progress (copy submitted and waiting to map, plus the latest result)
+#[derive(Default)]
+struct TimerState {
+    mapped: Option<mpsc::Receiver<Result<(), wgpu::BufferAsyncError>>>,
+    timings: FrameTimings,
+}
+
+/// Timestamp query set with its resolve and readback buffers
+pub struct GpuTimer {
+    query_set: wgpu::QuerySet,
+    resolve_buffer: wgpu::Buffer,
+    readback_buffer: wgpu::Buffer,
+    /// Nanoseconds per timestamp tick
+    period_ns: f32,
+    state: Mutex<TimerState>,
+}
+
+impl GpuTimer {
+    /// Create a timer, or None if the device lacks `TIMESTAMP_QUERY`
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Self> {
+        if !device.features().contains(wgpu::Features::TIMESTAMP_QUERY) {
+            return None;
+        }
+
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("Frame Timestamp Queries"),
+            ty: wgpu::QueryType::Timestamp,
+            count: QUERY_COUNT,
+        });
+        let resolve_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Timestamp Resolve Buffer"),
+            size: resolve_offset(RENDER_BEGIN) + PAIR_SIZE,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Timestamp Readback Buffer"),
+            size: QUERY_COUNT as u64 * TIMESTAMP_SIZE,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        Some(Self {
+            query_set,
+            resolve_buffer,
+            readback_buffer,
+            period_ns: queue.get_timestamp_period(),
+            state: Mutex::new(TimerState::default()),
+        })
+    }
+
+    /// Timestamp writes for the terrain compute pass
+    pub fn compute_writes(&self) -> wgpu::ComputePassTimestampWrites<'_> {
+        wgpu::ComputePassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: Some(COMPUTE_BEGIN),
+            end_of_pass_write_index: Some(COMPUTE_BEGIN + 1),
+        }
+    }
+
+    /// Timestamp writes for the scene render pass
+    pub fn render_writes(&self) -> wgpu::RenderPassTimestampWrites<'_> {
+        wgpu::RenderPassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: Some(RENDER_BEGIN),
+            end_of_pass_write_index: Some(RENDER_BEGIN + 1),
+        }
+    }
+
+    /// Resolve the compute pass timestamps (after the compute pass, same encoder)
+    pub fn resolve_compute(&self, encoder: &mut wgpu::CommandEncoder) {
+        self.resolve(encoder, COMPUTE_BEGIN);
+    }
+
+    /// Resolve the render pass timestamps (after the render pass, same encoder)
+    ///
+    /// Also copies both pairs to the readback buffer unless a previous readback is still
+    /// waiting to map; call `map_readback` after submitting `encoder`.
+    pub fn resolve_render(&self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder) {
+        self.resolve(encoder, RENDER_BEGIN);
+
+        if self.poll(device) {
+            // Pack both pairs contiguously (query order) for the readback
+            for begin in [COMPUTE_BEGIN, RENDER_BEGIN] {
+                encoder.copy_buffer_to_buffer(
+                    &self.resolve_buffer,
+                    resolve_offset(begin),
+                    &self.readback_buffer,
+                    begin as u64 * TIMESTAMP_SIZE,
+                    PAIR_SIZE,
+                );
+            }
+        }
+    }
+
+    /// Start mapping the readback buffer copied by `resolve_render` (if it copied)
+    pub fn map_readback(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.mapped.is_some() {
+            return;
+        }
+
+        let (sender, mapped) = mpsc::channel();
+        self.readback_buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let _ = sender.send(result);
+            });
+        state.mapped = Some(mapped);
+    }
+
+    /// Latest measured timings (picks up a finished readback without waiting)
+    pub fn timings(&self, device: &wgpu::Device) -> FrameTimings {
+        self.poll(device);
+        self.state.lock().unwrap().timings
+    }
+
+    /// Read back a mapped result if ready; true when the readback buffer is free
+    fn poll(&self, device: &wgpu::Device) -> bool {
+        device.poll(wgpu::Maintain::Poll);
+
+        let mut state = self.state.lock().unwrap();
+        let Some(ref mapped) = state.mapped else {
+            return true;
+        };
+        let result = match mapped.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::TryRecvError::Empty) => return false,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                state.mapped = None;
+                return true;
+            }
+        };
+        state.mapped = None;
+
+        match result {
+            Ok(()) => {
+                let ticks: Vec<u64> = {
+                    let data = self.readback_buffer.slice(..).get_mapped_range();
+                    bytemuck::cast_slice(&data).to_vec()
+                };
+                self.readback_buffer.unmap();
+                state.timings = FrameTimings {
+                    compute_ms: self.elapsed_ms(&ticks, COMPUTE_BEGIN),
+                    render_ms: self.elapsed_ms(&ticks, RENDER_BEGIN),
+                };
+            }
+            Err(e) => eprintln!("Warning: Failed to map timestamp readback: {}", e),
+        }
+        true
+    }
+
+    /// Milliseconds between the begin/end pair starting at `begin`
+    fn elapsed_ms(&self, ticks: &[u64], begin: u32) -> f32 {
+        let begin = begin as usize;
+        let elapsed_ticks = ticks[begin + 1].saturating_sub(ticks[begin]);
+        elapsed_ticks as f32 * self.period_ns / 1_000_000.0
+    }
+
+    fn resolve(&self, encoder: &mut wgpu::CommandEncoder, begin: u32) {
+        encoder.resolve_query_set(
+            &self.query_set,
+            begin..begin + 2,
+            &self.resolve_buffer,
+            resolve_offset(begin),
+        );
+    }
+}
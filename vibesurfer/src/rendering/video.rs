@@ -0,0 +1,198 @@
+//! MP4 recording output through an `ffmpeg` child process.
+//!
+//! Raw RGBA frames are piped to ffmpeg's stdin and encoded to H.264 while recording. The
+//! audio track is only complete once recording stops, so it is muxed in afterwards.
+
+use std::io::Write;
+use std::process::{Child, ChildStdin, Command, Stdio};
+
+use crate::params::RecordingConfig;
+
+/// Whether an `ffmpeg` executable can be run from PATH
+pub fn ffmpeg_available() -> bool {
+    Command::new("ffmpeg")
+        .arg("-version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Spawn error with an install hint when ffmpeg is missing
+fn ffmpeg_spawn_error(e: std::io::Error) -> String {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        "ffmpeg not found on PATH (install ffmpeg or record a PNG sequence instead)".to_string()
+    } else {
+        format!("Failed to start ffmpeg: {}", e)
+    }
+}
+
+/// H.264 encoder fed with raw RGBA frames
+pub struct VideoEncoder {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    width: u32,
+    height: u32,
+}
+
+impl VideoEncoder {
+    /// Start ffmpeg writing `path` for `width`×`height` frames at `fps`
+    pub fn spawn(
+        path: &str,
+        width: u32,
+        height: u32,
+        fps: u32,
+        crf: u32,
+        preset: &str,
+    ) -> Result<Self, String> {
+        let mut child = Command::new("ffmpeg")
+            .args(["-y", "-loglevel", "error"])
+            .args(["-f", "rawvideo", "-pix_fmt", "rgba"])
+            .args(["-s", &format!("{}x{}", width, height)])
+            .args(["-r", &fps.to_string()])
+            .args(["-i", "-"])
+            // yuv420p needs even dimensions
+            .args(["-vf", "pad=ceil(iw/2)*2:ceil(ih/2)*2"])
+            .args(["-c:v", "libx264", "-pix_fmt", "yuv420p"])
+            .args(["-preset", preset, "-crf", &crf.to_string()])
+            .arg(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .map_err(ffmpeg_spawn_error)?;
+
+        let stdin = child.stdin.take();
+        Ok(Self {
+            child,
+            stdin,
+            width,
+            height,
+        })
+    }
+
+    /// Append one tightly packed RGBA frame
+    pub fn write_frame(&mut self, rgba: &[u8], width: u32, height: u32) -> Result<(), String> {
+        if (width, height) != (self.width, self.height) {
+            return Err(format!(
+                "Frame size {}x{} does not match video size {}x{}",
+                width, height, self.width, self.height
+            ));
+        }
+        let stdin = self.stdin.as_mut().ok_or("ffmpeg input already closed")?;
+        stdin
+            .write_all(rgba)
+            .map_err(|e| format!("Failed to write frame to ffmpeg: {}", e))
+    }
+
+    /// Close the input and wait for ffmpeg to finish the file
+    pub fn finish(mut self) -> Result<(), String> {
+        drop(self.stdin.take());
+        let status = self
+            .child
+            .wait()
+            .map_err(|e| format!("Failed to wait for ffmpeg: {}", e))?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(format!("ffmpeg exited with {}", status))
+        }
+    }
+}
+
+/// Mux the recorded audio into the encoded video, producing `video_path()`
+///
+/// Removes the intermediate silent video on success.
+pub fn mux_audio(config: &RecordingConfig) -> Result<(), String> {
+    let status = Command::new("ffmpeg")
+        .args(["-y", "-loglevel", "error"])
+        .args(["-i", &config.silent_video_path()])
+        .args(["-i", &config.audio_path()])
+        .args(["-c:v", "copy", "-c:a", "aac", "-b:a", "192k", "-shortest"])
+        .arg(config.video_path())
+        .stdout(Stdio::null())
+        .status()
+        .map_err(ffmpeg_spawn_error)?;
+    if !status.success() {
+        return Err(format!("ffmpeg audio mux exited with {}", status));
+    }
+
+    let _ = std::fs::remove_file(config.silent_video_path());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Container duration in seconds reported by ffprobe
+    fn probe_duration(path: &str) -> Option<f32> {
+        let output = Command::new("ffprobe")
+            .args(["-v", "error", "-show_entries", "format=duration"])
+            .args(["-of", "default=noprint_wrappers=1:nokey=1", path])
+            .output()
+            .ok()?;
+        String::from_utf8(output.stdout).ok()?.trim().parse().ok()
+    }
+
+    #[test]
+    fn test_one_second_recording_produces_mp4() {
+        if !ffmpeg_available() {
+            eprintln!("Skipping: ffmpeg not available");
+            return;
+        }
+
+        let output_dir =
+            std::env::temp_dir().join(format!("vibesurfer_video_test_{}", std::process::id()));
+        std::fs::create_dir_all(&output_dir).unwrap();
+        let config = RecordingConfig {
+            output_dir: output_dir.to_string_lossy().into_owned(),
+            fps: 30,
+            ..RecordingConfig::new(1.0)
+        };
+
+        // 1 s of frames (odd width exercises the even-size padding)
+        let (width, height) = (65, 48);
+        let mut encoder = VideoEncoder::spawn(
+            &config.silent_video_path(),
+            width,
+            height,
+            30,
+            23,
+            "veryfast",
+        )
+        .unwrap();
+        for frame in 0..config.total_frames() {
+            let level = (frame * 8) as u8;
+            let rgba = vec![level; (width * height * 4) as usize];
+            encoder.write_frame(&rgba, width, height).unwrap();
+        }
+        encoder.finish().unwrap();
+
+        // 1 s of stereo float audio (same format as AudioSystem writes)
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 44100,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let mut wav = hound::WavWriter::create(config.audio_path(), spec).unwrap();
+        for i in 0..44100 {
+            let sample = (i as f32 * 0.05).sin() * 0.2;
+            wav.write_sample(sample).unwrap();
+            wav.write_sample(sample).unwrap();
+        }
+        wav.finalize().unwrap();
+
+        mux_audio(&config).unwrap();
+
+        let duration = probe_duration(&config.video_path()).expect("ffprobe duration");
+        assert!(
+            (duration - 1.0).abs() < 0.1,
+            "video duration {} s, expected 1 s",
+            duration
+        );
+        assert!(!std::path::Path::new(&config.silent_video_path()).exists());
+
+        std::fs::remove_dir_all(&output_dir).unwrap();
+    }
+}
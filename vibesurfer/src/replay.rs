@@ -0,0 +1,155 @@
+//! Replay of a recorded session: its saved config and audio track, rendered again frame by frame.
+//!
+//! Every recording writes `config.toml` next to `audio.wav` (see `save_session_config`). A
+//! replay loads both, analyzes the audio offline like `--audio-file`, and renders on the
+//! recording's deterministic clock, so frame N sees the same parameters and bands as before.
+
+use std::path::{Path, PathBuf};
+
+use crate::audio::BandTrack;
+use crate::params::{load_from_toml, save_to_toml, AppConfig, RecordingConfig};
+
+/// Subdirectory of the session a replay records into (the original frames are kept)
+pub const REPLAY_SUBDIR: &str = "replay";
+
+/// Save the effective config of a recording run to its `config_path` (for `Session::load`)
+pub fn save_session_config(config: &AppConfig, recording: &RecordingConfig) -> Result<(), String> {
+    let session = AppConfig {
+        recording: Some(recording.clone()),
+        ..config.clone()
+    };
+    save_to_toml(&session, &recording.config_path())
+}
+
+/// Recording config whose output paths point into session directory `dir`
+fn session_paths(dir: &Path) -> RecordingConfig {
+    RecordingConfig {
+        output_dir: dir.to_string_lossy().into_owned(),
+        ..RecordingConfig::new(0.0)
+    }
+}
+
+/// A recorded session ready to replay
+pub struct Session {
+    /// Session directory (the original recording's `output_dir`)
+    pub dir: PathBuf,
+    /// Config the session was recorded with (including its `recording` section)
+    pub config: AppConfig,
+    /// Per-frame bands of the session's audio track at the recording's frame rate
+    pub band_track: BandTrack,
+}
+
+impl Session {
+    /// Load `config.toml` and analyze `audio.wav` in `dir`
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        let paths = session_paths(dir);
+        let config = load_from_toml(&paths.config_path())?;
+        let Some(ref recording) = config.recording else {
+            return Err(format!(
+                "{} has no [recording] section to replay",
+                paths.config_path()
+            ));
+        };
+        let band_track =
+            BandTrack::from_wav(Path::new(&paths.audio_path()), &config.fft, recording.fps)?;
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            config,
+            band_track,
+        })
+    }
+
+    /// Path of the session's audio track
+    pub fn audio_path(&self) -> String {
+        session_paths(&self.dir).audio_path()
+    }
+
+    /// The session's recording settings, writing into `REPLAY_SUBDIR` instead
+    pub fn replay_recording(&self) -> Option<RecordingConfig> {
+        self.config
+            .recording
+            .clone()
+            .map(|recording| RecordingConfig {
+                output_dir: self.dir.join(REPLAY_SUBDIR).to_string_lossy().into_owned(),
+                ..recording
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::{AudioBackend, AudioSystem};
+    use crate::ocean::OceanSystem;
+    use crate::params::{AudioReactiveMapping, OceanPhysics};
+
+    #[test]
+    fn test_replay_reproduces_recorded_amplitudes() {
+        let dir = std::env::temp_dir().join(format!("vibesurfer_session_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let recording = RecordingConfig {
+            output_dir: dir.to_string_lossy().into_owned(),
+            fps: 10,
+            ..RecordingConfig::new(0.5)
+        };
+        let config = AppConfig {
+            ocean: OceanPhysics {
+                grid_size: 2,
+                detail_amplitude_m: 3.5,
+                ..OceanPhysics::default()
+            },
+            audio_mapping: AudioReactiveMapping {
+                amplitude_decay_per_s: 0.7,
+                ..AudioReactiveMapping::default()
+            },
+            ..AppConfig::default()
+        };
+
+        // Record: offline audio per frame drives the ocean, like the app's recording loop
+        save_session_config(&config, &recording).unwrap();
+        let audio = AudioSystem::new(
+            config.fft.clone(),
+            Some(recording.clone()),
+            AudioBackend::Null,
+        )
+        .unwrap();
+        let mut ocean =
+            OceanSystem::new(config.ocean.clone(), config.audio_mapping.clone()).unwrap();
+        let recorded: Vec<f32> = (0..recording.total_frames())
+            .map(|frame| {
+                let bands = audio.bands_for_frame(frame);
+                ocean.modulate(recording.frame_time_s(frame), &bands).0
+            })
+            .collect();
+        audio.finish_recording().unwrap();
+        drop(audio);
+
+        // Replay: saved config and offline bands of the saved track
+        let session = Session::load(&dir).unwrap();
+        assert_eq!(
+            session.config,
+            AppConfig {
+                recording: Some(recording.clone()),
+                ..config
+            }
+        );
+        let mut ocean = OceanSystem::new(
+            session.config.ocean.clone(),
+            session.config.audio_mapping.clone(),
+        )
+        .unwrap();
+        let replayed: Vec<f32> = (0..recording.total_frames())
+            .map(|frame| {
+                let bands = session.band_track.bands_for_frame(frame);
+                ocean.modulate(recording.frame_time_s(frame), &bands).0
+            })
+            .collect();
+        assert_eq!(replayed, recorded);
+        assert!(replayed[0] >= 3.5, "Saved physics not used: {:?}", replayed);
+
+        let replay_dir = session.replay_recording().unwrap().output_dir;
+        assert_eq!(Path::new(&replay_dir), dir.join(REPLAY_SUBDIR));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
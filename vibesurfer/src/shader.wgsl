@@ -4,6 +4,18 @@ struct Uniforms {
     amplitude: f32,
     frequency: f32,
     time: f32,
+    fog_color: vec4<f32>,
+    fog_density: f32,
+    color_height_range_m: f32,
+    color_low: vec4<f32>,   // Tint at troughs (audio-reactive palette)
+    color_high: vec4<f32>,  // Tint at peaks
+    light_direction: vec4<f32>,  // xyz: toward the key light, w: Lambert intensity (0 = unlit)
+    edge_fade_start: f32,  // meters from the camera where the grid starts fading out
+    edge_fade_end: f32,    // meters where it is fully transparent (before the wrap boundary)
+    depth_color_range_m: f32,      // wave height above base terrain spanning the depth tint
+    depth_color_trough: vec4<f32>, // Tint below the base terrain (deep water)
+    depth_color_crest: vec4<f32>,  // Tint cresting above it
+    camera_xz: vec2<f32>,          // Camera world XZ: center of the edge fade
 }
 
 @group(0) @binding(0)
@@ -12,12 +24,19 @@ var<uniform> uniforms: Uniforms;
 struct VertexInput {
     @location(0) position: vec3<f32>,
     @location(1) uv: vec2<f32>,
+    @location(2) normal: vec3<f32>,
+    @location(3) foam: f32,
+    @location(4) base_height: f32,  // Base terrain height under the vertex
 }
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) uv: vec2<f32>,
     @location(1) world_pos: vec3<f32>,
+    @location(2) normal: vec3<f32>,
+    @location(3) foam: f32,
+    @location(4) view_depth: f32,
+    @location(5) wave_height: f32,  // Height above the base terrain
 }
 
 @vertex
@@ -26,9 +45,20 @@ fn vs_main(in: VertexInput) -> VertexOutput {
     out.clip_position = uniforms.view_proj * vec4<f32>(in.position, 1.0);
     out.uv = in.uv;
     out.world_pos = in.position;
+    out.normal = in.normal;
+    out.foam = in.foam;
+    out.view_depth = out.clip_position.w; // Perspective w = distance along the view axis
+    out.wave_height = in.position.y - in.base_height;
     return out;
 }
 
+// Trough-to-crest tint for a height above the base terrain
+fn depth_tint(wave_height: f32) -> vec3<f32> {
+    let range = max(uniforms.depth_color_range_m, 0.001);
+    let t = smoothstep(-range, range, wave_height);
+    return mix(uniforms.depth_color_trough.rgb, uniforms.depth_color_crest.rgb, t);
+}
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let hot_pink = vec3<f32>(1.0, 0.16, 0.46);
@@ -54,15 +84,36 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     // Smooth glow effect using AUDIO-REACTIVE line_width!
     let core_intensity = 1.0 - smoothstep(0.0, uniforms.line_width * 0.3, dist);
     let glow_intensity = 1.0 - smoothstep(0.0, uniforms.line_width * 3.0, dist);
-    let brightness = core_intensity * 2.5 + glow_intensity * 0.8;
+    var brightness = core_intensity * 2.5 + glow_intensity * 0.8;
+
+    // Peak/trough tint from the audio-reactive palette
+    let height_range = max(uniforms.color_height_range_m, 0.001);
+    let height_t = smoothstep(-height_range, height_range, in.world_pos.y);
+    color = color * mix(uniforms.color_low.rgb, uniforms.color_high.rgb, height_t);
+
+    // Depth tint: troughs below the base terrain vs crests above it
+    color = color * depth_tint(in.wave_height);
+
+    // Foam: whiten and brighten lines on steep crests
+    let foam_white = vec3<f32>(1.0, 0.95, 1.0);
+    color = mix(color, foam_white, in.foam * 0.7);
+    brightness = brightness * (1.0 + in.foam);
+
+    // Key light: Lambert on the surface normal, blended in by intensity (0 = flat, unlit)
+    let lambert = max(dot(normalize(in.normal), uniforms.light_direction.xyz), 0.0);
+    color = color * mix(1.0, lambert, uniforms.light_direction.w);
 
     color = color * brightness;
 
+    // Exponential-squared distance fog toward the configured color
+    let fog_distance = in.view_depth * uniforms.fog_density;
+    let fog_amount = 1.0 - exp(-fog_distance * fog_distance);
+    color = mix(color, uniforms.fog_color.rgb, fog_amount);
+
     // Distance-based fade to create circular ocean view AND hide wrap boundary
-    let dist_from_center = length(in.world_pos.xz);
-    let fade_start = 800.0;  // Start fading farther out (1024×1024 grid)
-    let fade_end = 1000.0;   // Complete fade before wrap boundary (1024m)
-    let distance_fade = 1.0 - smoothstep(fade_start, fade_end, dist_from_center);
+    let dist_from_center = length(in.world_pos.xz - uniforms.camera_xz);
+    let distance_fade =
+        1.0 - smoothstep(uniforms.edge_fade_start, uniforms.edge_fade_end, dist_from_center);
 
     // Output with translucency and distance fade
     let alpha = clamp(brightness, 0.0, 1.0) * distance_fade;
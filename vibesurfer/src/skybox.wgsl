@@ -1,6 +1,14 @@
 struct SkyboxUniforms {
     inv_view_proj: mat4x4<f32>,
     time: f32,
+    sky_enabled: f32,            // 1.0 = day-night sky gradient with sun, 0.0 = background only
+    background_top: vec4<f32>,
+    background_bottom: vec4<f32>,
+    sun_direction: vec3<f32>,    // unit vector toward the sun (world space)
+    horizon_color: vec4<f32>,    // daylight sky at the horizon
+    zenith_color: vec4<f32>,     // daylight sky straight up
+    horizon_glow_color: vec4<f32>,
+    horizon_glow_width: f32,     // glow half-width in ray elevation (0 = no glow)
 }
 
 @group(0) @binding(0)
@@ -19,7 +27,7 @@ fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
     let x = f32((vertex_index << 1u) & 2u);
     let y = f32(vertex_index & 2u);
 
-    output.position = vec4<f32>(x * 2.0 - 1.0, y * 2.0 - 1.0, 0.0, 1.0);
+    output.position = vec4<f32>(x * 2.0 - 1.0, y * 2.0 - 1.0, 1.0, 1.0);  // Far plane (depth 1.0)
     output.ndc_pos = vec2<f32>(x * 2.0 - 1.0, y * 2.0 - 1.0);
 
     return output;
@@ -32,6 +40,39 @@ fn hash3(p: vec3<f32>) -> f32 {
     return fract((p3.x + p3.y) * p3.z);
 }
 
+// Day-night sky: zenith/horizon gradient dimmed by sun elevation, plus sun disk and glow
+// Returns (sky color, daylight 0..1)
+fn day_sky(dir: vec3<f32>) -> vec4<f32> {
+    let sun = normalize(uniforms.sun_direction);
+
+    // Daylight fades out as the sun sinks below the horizon
+    let daylight = smoothstep(-0.15, 0.1, sun.y);
+
+    // Gradient: horizon at and below eye level, zenith overhead
+    let height = sqrt(clamp(dir.y, 0.0, 1.0));
+    var sky = mix(uniforms.horizon_color.rgb, uniforms.zenith_color.rgb, height) * daylight;
+
+    // Sun disk and glow (visible only while above the horizon)
+    let sun_dot = max(dot(dir, sun), 0.0);
+    let sun_visible = smoothstep(-0.02, 0.02, sun.y);
+    let disk = smoothstep(0.9995, 0.9998, sun_dot);
+    let glow = pow(sun_dot, 64.0) * 0.5;
+    let sun_color = mix(vec3<f32>(1.0, 0.5, 0.2), vec3<f32>(1.0, 0.95, 0.85), daylight);
+    sky += sun_color * (disk * 4.0 + glow) * sun_visible;
+
+    return vec4<f32>(sky, daylight);
+}
+
+// Thin glow band where the view ray crosses the world horizon (dir.y = 0)
+fn horizon_glow(dir: vec3<f32>) -> vec3<f32> {
+    let width = uniforms.horizon_glow_width;
+    if (width <= 0.0) {
+        return vec3<f32>(0.0);
+    }
+    let t = dir.y / width;
+    return uniforms.horizon_glow_color.rgb * exp(-t * t);
+}
+
 // Generate stars with twinkling
 fn stars(dir: vec3<f32>, density: f32, time: f32) -> f32 {
     let p = dir * 100.0;
@@ -87,8 +128,14 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
 
     let dir = normalize(world_pos.xyz);
 
-    // Pure black background
-    let sky_color = vec3<f32>(0.0, 0.0, 0.0);
+    // Configured background: vertical gradient in screen space (top = ndc y 1)
+    let gradient_t = input.ndc_pos.y * 0.5 + 0.5;
+    let background = mix(uniforms.background_bottom.rgb, uniforms.background_top.rgb, gradient_t);
+
+    // Optional day-night sky replaces the background; daylight hides the stars
+    let day = day_sky(dir);
+    let sky_color = mix(background, day.rgb, uniforms.sky_enabled);
+    let star_visibility = 1.0 - uniforms.sky_enabled * day.a;
 
     // Add stars everywhere with twinkling
     let star_density = 0.02; // Increased from 0.003 to 0.02 (much more stars)
@@ -101,10 +148,10 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
         1.0
     );
 
-    let star_color = star_tint * star_brightness * 100.0; // Much brighter stars
+    let star_color = star_tint * star_brightness * 100.0 * star_visibility; // Much brighter stars
 
-    // Combine sky and stars
-    let final_color = sky_color + star_color;
+    // Combine sky, stars and the horizon glow
+    let final_color = sky_color + star_color + horizon_glow(dir);
 
     return vec4<f32>(final_color, 1.0);
 }
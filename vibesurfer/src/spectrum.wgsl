@@ -0,0 +1,27 @@
+// Debug spectrum analyzer: one quad per bar along the bottom of the frame, drawn over the
+// finished frame
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,  // NDC
+    @location(1) level: f32,           // Bar height at this corner (0..1)
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) level: f32,
+}
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(in.position, 0.0, 1.0);
+    out.level = in.level;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Cyan at the base, magenta toward full scale
+    let color = mix(vec3<f32>(0.0, 0.8, 1.0), vec3<f32>(1.0, 0.2, 0.8), in.level);
+    return vec4<f32>(color, 0.8);
+}
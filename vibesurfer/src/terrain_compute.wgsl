@@ -1,12 +1,14 @@
 // GPU Terrain Generation Compute Shader
-// Generates procedural heightfield using 3D simplex noise
+// Generates procedural heightfield using 3D simplex (or Perlin / fBm) noise
 // Ported from toy2, adapted for vibesurfer's two-layer terrain model
 
 struct Vertex {
     position: vec3<f32>,
-    _padding1: f32,  // Align position to 16 bytes
+    base_height: f32,  // Base terrain height (position.y minus it is the detail height)
     uv: vec2<f32>,
-    _padding2: vec2<f32>,  // Pad struct to 32 bytes total for array alignment
+    _padding2: vec2<f32>,  // Align normal to 16 bytes
+    normal: vec3<f32>,     // Surface normal (filled by `compute_normals` after `main`)
+    foam: f32,             // Whitecap intensity (computed on CPU; untouched here)
 }
 
 struct TerrainParams {
@@ -19,7 +21,14 @@ struct TerrainParams {
     grid_size: u32,           // vertices per side (1024)
     grid_spacing: f32,        // meters between vertices (2.0)
     time: f32,                // seconds (for animation)
-    _padding2: f32,
+    noise_seed: u32,          // selects noise slice (see noise::seed_offset on CPU)
+    noise_kind: u32,          // 0 = Perlin, 1 = simplex, 2 = fBm (NoiseKind::shader_id)
+    base_octaves: u32,        // fBm octaves of the base layer
+    detail_octaves: u32,      // fBm octaves of the detail layer
+    noise_lacunarity: f32,    // fBm frequency multiplier per octave
+    noise_gain: f32,          // fBm amplitude multiplier per octave (negative = 0)
+    _padding2: u32,           // Align swell_offset to 8 bytes
+    swell_offset: vec2<f32>,  // meters the swell has carried the detail layer (XZ)
 }
 
 @group(0) @binding(0) var<storage, read_write> vertices: array<Vertex>;
@@ -109,6 +118,87 @@ fn simplex3d(v: vec3<f32>) -> f32 {
     return 42.0 * dot(m * m, vec4<f32>(dot(p0, x0), dot(p1, x1), dot(p2, x2), dot(p3, x3)));
 }
 
+// === 3D Perlin Noise (hashed lattice, mirrors noise::perlin3d on CPU) ===
+
+fn hash_lattice(p: vec3<i32>) -> u32 {
+    var h = (u32(p.x) * 0x8da6b343u) ^ (u32(p.y) * 0xd8163841u) ^ (u32(p.z) * 0xcb1ab31fu);
+    h ^= h >> 13u;
+    h *= 0x5bd1e995u;
+    return h ^ (h >> 15u);
+}
+
+// Dot product of offset `d` with one of Perlin's 12 cube-edge gradients
+fn perlin_gradient(hash: u32, d: vec3<f32>) -> f32 {
+    switch hash % 12u {
+        case 0u: { return d.x + d.y; }
+        case 1u: { return -d.x + d.y; }
+        case 2u: { return d.x - d.y; }
+        case 3u: { return -d.x - d.y; }
+        case 4u: { return d.x + d.z; }
+        case 5u: { return -d.x + d.z; }
+        case 6u: { return d.x - d.z; }
+        case 7u: { return -d.x - d.z; }
+        case 8u: { return d.y + d.z; }
+        case 9u: { return -d.y + d.z; }
+        case 10u: { return d.y - d.z; }
+        default: { return -d.y - d.z; }
+    }
+}
+
+fn perlin_corner(cell: vec3<i32>, f: vec3<f32>, offset: vec3<i32>) -> f32 {
+    return perlin_gradient(hash_lattice(cell + offset), f - vec3<f32>(offset));
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    return a + (b - a) * t;
+}
+
+fn perlin3d(v: vec3<f32>) -> f32 {
+    let cell_floor = floor(v);
+    let f = v - cell_floor;
+    let cell = vec3<i32>(cell_floor);
+
+    // Quintic fade curve (C2-continuous across cell boundaries)
+    let u = f * f * f * (f * (f * 6.0 - 15.0) + 10.0);
+
+    let x00 = lerp(perlin_corner(cell, f, vec3<i32>(0, 0, 0)), perlin_corner(cell, f, vec3<i32>(1, 0, 0)), u.x);
+    let x10 = lerp(perlin_corner(cell, f, vec3<i32>(0, 1, 0)), perlin_corner(cell, f, vec3<i32>(1, 1, 0)), u.x);
+    let x01 = lerp(perlin_corner(cell, f, vec3<i32>(0, 0, 1)), perlin_corner(cell, f, vec3<i32>(1, 0, 1)), u.x);
+    let x11 = lerp(perlin_corner(cell, f, vec3<i32>(0, 1, 1)), perlin_corner(cell, f, vec3<i32>(1, 1, 1)), u.x);
+    let y0 = lerp(x00, x10, u.y);
+    let y1 = lerp(x01, x11, u.y);
+
+    return clamp(lerp(y0, y1, u.z), -1.0, 1.0);
+}
+
+// === Noise Selection (mirrors NoiseGenerator::sample on CPU) ===
+
+// Fractal simplex: each octave scales XZ frequency by lacunarity and amplitude by gain,
+// normalized by the total amplitude so the result stays in [-1, 1]
+fn fbm(xz: vec2<f32>, t: f32, octaves: u32) -> f32 {
+    let gain = max(params.noise_gain, 0.0);
+    var sum = 0.0;
+    var total_amplitude = 0.0;
+    var amplitude = 1.0;
+    var frequency = 1.0;
+    for (var octave = 0u; octave < max(octaves, 1u); octave++) {
+        sum += simplex3d(vec3<f32>(xz * frequency, t)) * amplitude;
+        total_amplitude += amplitude;
+        amplitude *= gain;
+        frequency *= params.noise_lacunarity;
+    }
+    return sum / total_amplitude;
+}
+
+// Terrain noise at XZ (already scaled by the layer frequency) and noise time `t`
+fn terrain_noise(xz: vec2<f32>, t: f32, octaves: u32) -> f32 {
+    switch params.noise_kind {
+        case 0u: { return perlin3d(vec3<f32>(xz, t)); }
+        case 1u: { return simplex3d(vec3<f32>(xz, t)); }
+        default: { return fbm(xz, t, octaves); }
+    }
+}
+
 // === Main Compute Kernel ===
 
 @compute @workgroup_size(256)
@@ -126,39 +216,50 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let x = idx % grid_size;
     let z = idx / grid_size;
 
-    // Grid follows camera: keep camera centered in grid
+    // Grid follows camera: keep camera centered in grid (snapped to the world lattice)
     let grid_extent = f32(grid_size) * params.grid_spacing;
     let half_extent = grid_extent * 0.5;
 
-    // Local grid position (0 to grid_extent)
-    let local_x = f32(x) * params.grid_spacing;
-    let local_z = f32(z) * params.grid_spacing;
+    // World origin: first world lattice cell (multiple of grid_spacing) at or after
+    // camera - half_extent, so the grid covers [camera - half_extent, camera + half_extent)
+    // like OceanGrid::update's toroidal wrap. As the camera moves the grid shifts by whole
+    // cells (the row left behind reappears ahead), and every lattice point keeps the same
+    // world position and noise sample: no swimming, and no phase reset across a wrap.
+    let grid_origin = vec2<i32>(ceil((params.camera_pos.xz - half_extent) / params.grid_spacing));
 
-    // World position: camera is at center of grid
-    // Grid spans from (camera - half_extent) to (camera + half_extent)
-    // These are ACTUAL WORLD COORDINATES that move with the camera
-    let world_x = params.camera_pos.x - half_extent + local_x;
-    let world_z = params.camera_pos.z - half_extent + local_z;
+    // World position of this vertex's lattice point
+    let world_x = f32(grid_origin.x + i32(x)) * params.grid_spacing;
+    let world_z = f32(grid_origin.y + i32(z)) * params.grid_spacing;
 
     // For noise sampling, use the same world coordinates
     let sample_x = world_x;
     let sample_z = world_z;
 
+    // Seed offsets the third noise axis (must match noise::seed_offset on CPU)
+    var seed_hash = params.noise_seed;
+    seed_hash ^= seed_hash >> 16u;
+    seed_hash *= 0x7feb352du;
+    seed_hash ^= seed_hash >> 15u;
+    seed_hash *= 0x846ca68bu;
+    seed_hash ^= seed_hash >> 16u;
+    let seed_offset = f32(seed_hash >> 8u) * (289.0 / 16777216.0);
+
     // Sample base terrain using wrapped coordinates (creates the loop)
     let base_coord_x = sample_x * params.base_frequency;
     let base_coord_z = sample_z * params.base_frequency;
-    let base_height = simplex3d(vec3<f32>(base_coord_x, base_coord_z, 0.0)) * params.base_amplitude;
+    let base_height = terrain_noise(vec2<f32>(base_coord_x, base_coord_z), seed_offset, params.base_octaves) * params.base_amplitude;
 
-    // Sample detail layer (animated, audio-reactive)
-    let detail_coord_x = sample_x * params.detail_frequency;
-    let detail_coord_z = sample_z * params.detail_frequency;
-    let detail_height = simplex3d(vec3<f32>(detail_coord_x, detail_coord_z, params.time)) * params.detail_amplitude;
+    // Sample detail layer (animated, audio-reactive, advected by the swell)
+    let detail_coord_x = (sample_x - params.swell_offset.x) * params.detail_frequency;
+    let detail_coord_z = (sample_z - params.swell_offset.y) * params.detail_frequency;
+    let detail_height = terrain_noise(vec2<f32>(detail_coord_x, detail_coord_z), params.time + seed_offset, params.detail_octaves) * params.detail_amplitude;
 
     // Combine layers
     let height = base_height + detail_height;
 
     // Write vertex data
     vertices[idx].position = vec3<f32>(world_x, height, world_z);
+    vertices[idx].base_height = base_height;
     vertices[idx].uv = vec2<f32>(f32(x) / f32(grid_size), f32(z) / f32(grid_size));
 
     // DEBUG: Print first vertex position periodically
@@ -166,3 +267,31 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     //     // This won't actually print, but useful for understanding
     // }
 }
+
+// === Normals Kernel (second dispatch: reads the heights `main` wrote) ===
+
+@compute @workgroup_size(256)
+fn compute_normals(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+    let grid_size = params.grid_size;
+    if (idx >= grid_size * grid_size) {
+        return;
+    }
+    let x = idx % grid_size;
+    let z = idx / grid_size;
+
+    // Central differences, one-sided at the grid edges
+    let x0 = select(x - 1u, x, x == 0u);
+    let x1 = min(x + 1u, grid_size - 1u);
+    let z0 = select(z - 1u, z, z == 0u);
+    let z1 = min(z + 1u, grid_size - 1u);
+
+    let dx = vertices[z * grid_size + x1].position - vertices[z * grid_size + x0].position;
+    let dz = vertices[z1 * grid_size + x].position - vertices[z0 * grid_size + x].position;
+
+    // Height slope per meter along X and Z (a 1-vertex grid stays flat)
+    let slope_x = select(0.0, dx.y / dx.x, x1 != x0);
+    let slope_z = select(0.0, dz.y / dz.z, z1 != z0);
+
+    vertices[idx].normal = normalize(vec3<f32>(-slope_x, 1.0, -slope_z));
+}
@@ -0,0 +1,286 @@
+//! Fixed-timestep simulation clock.
+//!
+//! Real frame time is accumulated and consumed in whole steps of `1 / sim_hz`, so stateful
+//! simulation (audio envelopes, manual camera motion) behaves the same at any render rate.
+//! Rendering happens between the last two steps, `alpha` of the way to the latest one.
+//! `SimClock` decides how much real time is fed in, so interactive runs can pause and step.
+//! Frame pacing (`RenderConfig::max_fps`) only delays frames; it never changes simulated time.
+
+use std::time::{Duration, Instant};
+
+/// Most steps owed at once; longer stalls are dropped instead of replayed
+pub const MAX_PENDING_STEPS: u32 = 8;
+
+/// Slack when comparing the accumulator to a step, so e.g. 1/60 s advances at 60 Hz
+/// yield exactly one step despite floating-point rounding
+const STEP_EPSILON_S: f64 = 1e-9;
+
+/// Accumulator handing out fixed simulation steps
+#[derive(Clone, Debug)]
+pub struct FixedTimestep {
+    step_s: f64,
+    /// Real time not yet consumed by a step
+    accumulator_s: f64,
+    /// Steps taken so far
+    steps: u64,
+}
+
+impl FixedTimestep {
+    /// Clock stepping at `sim_hz` steps per second (at least 1)
+    pub fn new(sim_hz: f64) -> Self {
+        Self {
+            step_s: 1.0 / sim_hz.max(1.0),
+            accumulator_s: 0.0,
+            steps: 0,
+        }
+    }
+
+    /// Duration of one step (seconds)
+    pub fn step_s(&self) -> f64 {
+        self.step_s
+    }
+
+    /// Real time not yet consumed by a step (seconds, below one step)
+    pub fn remainder_s(&self) -> f64 {
+        self.accumulator_s
+    }
+
+    /// Fraction of a step accumulated since the latest step (0..1)
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator_s / self.step_s).clamp(0.0, 1.0) as f32
+    }
+
+    /// Simulation time to render (seconds): `alpha` between the previous and latest step
+    pub fn render_time_s(&self) -> f64 {
+        ((self.steps as f64 - 1.0) * self.step_s + self.accumulator_s).max(0.0)
+    }
+
+    /// Add elapsed real time (seconds; negative is ignored)
+    pub fn advance(&mut self, elapsed_s: f64) {
+        self.accumulator_s += elapsed_s.max(0.0);
+
+        // Keep the sub-step remainder but forget whole steps beyond the limit
+        let max_pending_s = MAX_PENDING_STEPS as f64 * self.step_s;
+        if self.accumulator_s >= max_pending_s + self.step_s {
+            self.accumulator_s = max_pending_s + self.accumulator_s.rem_euclid(self.step_s);
+        }
+    }
+
+    /// Feed real time up to a total of `time_s` since the start (seconds, never backwards)
+    ///
+    /// For offline recording, where each frame has an exact timestamp: unlike `advance`,
+    /// nothing is dropped however many steps a frame spans.
+    pub fn advance_to(&mut self, time_s: f64) {
+        let fed_s = self.steps as f64 * self.step_s + self.accumulator_s;
+        self.accumulator_s += (time_s - fed_s).max(0.0);
+    }
+
+    /// Take the next due step, returning its simulation time (None when none is due)
+    pub fn next_step(&mut self) -> Option<f64> {
+        if self.accumulator_s + STEP_EPSILON_S < self.step_s {
+            return None;
+        }
+        self.accumulator_s = (self.accumulator_s - self.step_s).max(0.0);
+        self.steps += 1;
+        Some(self.steps as f64 * self.step_s)
+    }
+}
+
+/// Pausable source of elapsed simulation time (interactive mode)
+#[derive(Clone, Debug)]
+pub struct SimClock {
+    paused: bool,
+    /// Time one manual step advances (seconds)
+    frame_period_s: f64,
+    /// Manual steps requested since the last frame
+    queued_steps: u32,
+}
+
+impl SimClock {
+    /// Running clock whose manual steps advance `frame_period_s`
+    pub fn new(frame_period_s: f64) -> Self {
+        Self {
+            paused: false,
+            frame_period_s,
+            queued_steps: 0,
+        }
+    }
+
+    /// Whether simulation time is frozen
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Freeze (true) or resume (false) simulation time
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        self.queued_steps = 0;
+    }
+
+    /// Advance one frame period at the next frame (pauses a running clock first)
+    pub fn step(&mut self) {
+        self.paused = true;
+        self.queued_steps += 1;
+    }
+
+    /// Simulation time to advance for a frame that took `real_elapsed_s` (seconds)
+    ///
+    /// Real time while running; while paused only the queued steps.
+    pub fn elapsed_s(&mut self, real_elapsed_s: f64) -> f64 {
+        if !self.paused {
+            return real_elapsed_s;
+        }
+        let elapsed_s = self.queued_steps as f64 * self.frame_period_s;
+        self.queued_steps = 0;
+        elapsed_s
+    }
+}
+
+/// Shortest time between frames under a frame rate cap (None: uncapped, also for 0)
+pub fn frame_interval(max_fps: Option<u32>) -> Option<Duration> {
+    max_fps
+        .filter(|&fps| fps > 0)
+        .map(|fps| Duration::from_secs_f64(1.0 / fps as f64))
+}
+
+/// How long to sleep at `now` so the next frame starts `interval` after `last_frame` started
+///
+/// Zero when the interval has already passed (a slow frame is not made up for).
+pub fn frame_pacing_sleep(last_frame: Instant, now: Instant, interval: Duration) -> Duration {
+    (last_frame + interval).saturating_duration_since(now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Take all due steps, returning how many there were
+    fn drain(timestep: &mut FixedTimestep) -> u32 {
+        std::iter::from_fn(|| timestep.next_step()).count() as u32
+    }
+
+    #[test]
+    fn test_elapsed_time_yields_whole_steps_and_remainder() {
+        let mut timestep = FixedTimestep::new(100.0);
+
+        timestep.advance(0.035);
+        assert_eq!(drain(&mut timestep), 3);
+        assert!((timestep.remainder_s() - 0.005).abs() < 1e-9);
+        assert!((timestep.alpha() - 0.5).abs() < 1e-6);
+
+        // The remainder carries into the next frame
+        timestep.advance(0.006);
+        assert_eq!(drain(&mut timestep), 1);
+        assert!((timestep.remainder_s() - 0.001).abs() < 1e-9);
+
+        // Frames shorter than a step take no step
+        timestep.advance(0.002);
+        assert_eq!(drain(&mut timestep), 0);
+        assert!((timestep.remainder_s() - 0.003).abs() < 1e-9);
+        assert!((timestep.render_time_s() - 0.033).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_render_rate_does_not_change_step_count() {
+        // One second at 30 fps and 144 fps both take exactly 60 steps at 60 Hz
+        for fps in [30.0, 60.0, 144.0] {
+            let mut timestep = FixedTimestep::new(60.0);
+            let mut steps = 0;
+            for _ in 0..fps as usize {
+                timestep.advance(1.0 / fps);
+                steps += drain(&mut timestep);
+            }
+            assert_eq!(steps, 60, "{} fps", fps);
+        }
+    }
+
+    #[test]
+    fn test_long_stall_is_capped() {
+        let mut timestep = FixedTimestep::new(100.0);
+        timestep.advance(2.0025);
+        assert_eq!(drain(&mut timestep), MAX_PENDING_STEPS);
+        assert!((timestep.remainder_s() - 0.0025).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_recording_below_step_cap_rate_keeps_time() {
+        // 10 fps recording at 120 Hz: 12 steps a frame, more than MAX_PENDING_STEPS
+        let (fps, sim_hz) = (10, 120.0);
+        let mut timestep = FixedTimestep::new(sim_hz);
+        for frame in 0..50 {
+            let frame_time_s = frame as f64 / fps as f64;
+            timestep.advance_to(frame_time_s);
+            let steps = drain(&mut timestep);
+            if frame > 0 {
+                assert_eq!(steps, 12, "frame {}", frame);
+            }
+            let lag_s = frame_time_s - timestep.render_time_s();
+            assert!(lag_s.abs() <= timestep.step_s() + 1e-9, "frame {}", frame);
+        }
+    }
+
+    #[test]
+    fn test_paused_clock_holds_time_and_steps_one_frame() {
+        let frame_period_s = 1.0 / 60.0;
+        let mut clock = SimClock::new(frame_period_s);
+        let mut timestep = FixedTimestep::new(120.0);
+        let mut frame = |clock: &mut SimClock, real_elapsed_s| {
+            timestep.advance(clock.elapsed_s(real_elapsed_s));
+            drain(&mut timestep);
+            timestep.render_time_s()
+        };
+
+        let running_time_s = frame(&mut clock, 0.05);
+        assert!((frame(&mut clock, 0.05) - running_time_s - 0.05).abs() < 1e-9);
+
+        // Paused: real time passes, simulation time doesn't
+        clock.set_paused(true);
+        let paused_time_s = frame(&mut clock, 0.1);
+        for _ in 0..5 {
+            assert_eq!(frame(&mut clock, 0.1), paused_time_s);
+        }
+
+        // One step advances exactly one frame period, then time holds again
+        clock.step();
+        let stepped_time_s = frame(&mut clock, 0.1);
+        assert!((stepped_time_s - paused_time_s - frame_period_s).abs() < 1e-9);
+        assert_eq!(frame(&mut clock, 0.1), stepped_time_s);
+
+        clock.set_paused(false);
+        assert!((frame(&mut clock, 0.05) - stepped_time_s - 0.05).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_step_times_are_consecutive() {
+        let mut timestep = FixedTimestep::new(50.0);
+        timestep.advance(0.05);
+        let times: Vec<f64> = std::iter::from_fn(|| timestep.next_step()).collect();
+        assert_eq!(times.len(), 2);
+        assert!((times[0] - 0.02).abs() < 1e-12 && (times[1] - 0.04).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_frame_pacing_sleeps_out_the_interval() {
+        let interval = frame_interval(Some(30)).unwrap();
+        assert_eq!(interval, Duration::from_secs_f64(1.0 / 30.0));
+        assert_eq!(frame_interval(None), None);
+        assert_eq!(frame_interval(Some(0)), None);
+
+        let last_frame = Instant::now();
+        let ms = Duration::from_millis;
+
+        // 10 ms into a ~33 ms frame: sleep the rest
+        let sleep = frame_pacing_sleep(last_frame, last_frame + ms(10), interval);
+        assert_eq!(sleep, interval - ms(10));
+
+        // Right on time or late: no sleep
+        assert_eq!(
+            frame_pacing_sleep(last_frame, last_frame + interval, interval),
+            Duration::ZERO
+        );
+        assert_eq!(
+            frame_pacing_sleep(last_frame, last_frame + ms(50), interval),
+            Duration::ZERO
+        );
+    }
+}